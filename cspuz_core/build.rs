@@ -61,6 +61,10 @@ fn build_glucose() {
     println!("cargo:rerun-if-changed=lib/glucose_bridge.cpp");
 }
 
+/// The version of the CaDiCaL submodule. `lib/cadical_bridge.cpp` implements the external
+/// propagator interface of this version, so this must be updated together with the submodule.
+const CADICAL_VERSION: &str = "1.5.3";
+
 fn build_cadical() {
     let mut build_target = vec![];
     for name in fs::read_dir("lib/cadical/src").unwrap() {
@@ -79,7 +83,7 @@ fn build_cadical() {
         .file("lib/cadical_bridge.cpp")
         .files(&build_target)
         .include("lib/cadical/src")
-        .flag("-DVERSION=\"1.5.3\"") // TODO
+        .flag(&format!("-DVERSION=\"{}\"", CADICAL_VERSION))
         .flag("-DNBUILD")
         .warnings(false)
         .compile("cadical");
@@ -1,5 +1,11 @@
 #include "cadical_bridge.h"
 
+#include <algorithm>
+#include <chrono>
+#include <climits>
+#include <memory>
+#include <vector>
+
 #include "cadical.hpp"
 #include "ext_subgraph_connectivity.hpp"
 
@@ -11,6 +17,119 @@ int to_cadical_lit(int l) {
     }
 }
 
+int from_cadical_lit(int l) {
+    if (l < 0) {
+        return ((-l - 1) << 1) | 1;
+    } else {
+        return (l - 1) << 1;
+    }
+}
+
+namespace {
+
+// Forwards the callbacks of the external propagator interface to `PropagatorHub` in Rust.
+// Literals are passed in the same representation as `Lit` in Rust, and -1 is used as a terminator.
+// This implements the interface of the CaDiCaL submodule (1.5.3, see `CADICAL_VERSION` in build.rs),
+// where assignments are notified one by one and clauses from the propagator are never forgettable.
+class RustPropagator : public CaDiCaL::ExternalPropagator {
+public:
+    RustPropagator(void* hub) : hub_(hub) {}
+
+    void notify_assignment(int lit, bool is_fixed) override {
+        CaDiCaL_RustPropagatorNotifyAssignment(hub_, from_cadical_lit(lit), is_fixed ? 1 : 0);
+    }
+
+    void notify_new_decision_level() override {
+        CaDiCaL_RustPropagatorNotifyNewDecisionLevel(hub_);
+    }
+
+    void notify_backtrack(size_t new_level) override {
+        CaDiCaL_RustPropagatorNotifyBacktrack(hub_, new_level);
+    }
+
+    bool cb_check_found_model(const std::vector<int>& model) override {
+        return CaDiCaL_RustPropagatorCheckFoundModel(hub_) != 0;
+    }
+
+    int cb_propagate() override {
+        return to_cadical_lit_or_zero(CaDiCaL_RustPropagatorPropagate(hub_));
+    }
+
+    int cb_add_reason_clause_lit(int propagated_lit) override {
+        return to_cadical_lit_or_zero(CaDiCaL_RustPropagatorAddReasonClauseLit(hub_, from_cadical_lit(propagated_lit)));
+    }
+
+    bool cb_has_external_clause() override {
+        return CaDiCaL_RustPropagatorHasExternalClause(hub_) != 0;
+    }
+
+    int cb_add_external_clause_lit() override {
+        return to_cadical_lit_or_zero(CaDiCaL_RustPropagatorAddExternalClauseLit(hub_));
+    }
+
+private:
+    static int to_cadical_lit_or_zero(int l) {
+        return l < 0 ? 0 : to_cadical_lit(l);
+    }
+
+    void* hub_;
+};
+
+// Asks CaDiCaL to stop the search once `deadline` is passed or `interrupt` is set.
+class LimitTerminator : public CaDiCaL::Terminator {
+public:
+    LimitTerminator(bool has_deadline, std::chrono::steady_clock::time_point deadline, const uint8_t* interrupt)
+        : has_deadline_(has_deadline), deadline_(deadline), interrupt_(interrupt) {}
+
+    bool terminate() override {
+        if (interrupt_ != nullptr && __atomic_load_n(interrupt_, __ATOMIC_RELAXED)) {
+            return true;
+        }
+        return has_deadline_ && std::chrono::steady_clock::now() >= deadline_;
+    }
+
+private:
+    bool has_deadline_;
+    std::chrono::steady_clock::time_point deadline_;
+    const uint8_t* interrupt_;
+};
+
+// CaDiCaL does not expose its statistics through the API, so the number of conflicts is
+// approximated by the number of learnt clauses.
+// Learnt clauses with at most `max_export_len` literals are also passed to `export_ctx` in Rust.
+class LearntObserver : public CaDiCaL::Learner {
+public:
+    LearntObserver(int32_t max_export_len, void* export_ctx)
+        : count_(0), max_export_len_(max_export_len), export_ctx_(export_ctx) {}
+
+    bool learning(int size) override {
+        ++count_;
+        if (export_ctx_ != nullptr && size <= max_export_len_) {
+            clause_.clear();
+            return true;
+        }
+        return false;
+    }
+
+    void learn(int lit) override {
+        if (lit == 0) {
+            CaDiCaL_RustExportLearntClause(export_ctx_, clause_.data(), (int32_t)clause_.size());
+        } else {
+            clause_.push_back(from_cadical_lit(lit));
+        }
+    }
+
+    int64_t count() const { return count_; }
+
+private:
+    int64_t count_;
+    int32_t max_export_len_;
+    void* export_ctx_;
+    std::vector<int32_t> clause_;
+};
+
+}
+
 extern "C" {
 
 CaDiCaL::Solver* CaDiCaL_CreateSolver() {
@@ -30,10 +149,40 @@ void CaDiCaL_AddClause(CaDiCaL::Solver* solver, int32_t* lits, int32_t n_lits) {
     solver->add(0);
 }
 
-int32_t CaDiCaL_Solve(CaDiCaL::Solver* solver) {
+int32_t CaDiCaL_SolveLimited(CaDiCaL::Solver* solver, const int32_t* assumptions, int32_t n_assumptions, int64_t conflicts, double time_limit, const uint8_t* interrupt, int32_t max_export_len, void* export_ctx, int64_t* conflicts_used) {
+    for (int i = 0; i < n_assumptions; ++i) {
+        solver->assume(to_cadical_lit(assumptions[i]));
+    }
+
+    // Limits set by `limit` are reset after each call of `solve`.
+    if (conflicts >= 0) {
+        solver->limit("conflicts", (int)std::min<int64_t>(conflicts, INT_MAX));
+    }
+
+    std::unique_ptr<LimitTerminator> terminator;
+    if (time_limit >= 0 || interrupt != nullptr) {
+        auto deadline = std::chrono::steady_clock::now();
+        if (time_limit >= 0) {
+            deadline += std::chrono::duration_cast<std::chrono::steady_clock::duration>(std::chrono::duration<double>(time_limit));
+        }
+        terminator = std::make_unique<LimitTerminator>(time_limit >= 0, deadline, interrupt);
+        solver->connect_terminator(terminator.get());
+    }
+
+    LearntObserver observer(max_export_len, export_ctx);
+    solver->connect_learner(&observer);
+
     int res = solver->solve();
+
+    solver->disconnect_learner();
+    if (terminator) {
+        solver->disconnect_terminator();
+    }
+    *conflicts_used = observer.count();
+
     if (res == 10) return 1;
-    return 0;
+    if (res == 20) return 0;
+    return -1;
 }
 
 int32_t CaDiCaL_GetModelValueVar(CaDiCaL::Solver* solver, int32_t var) {
@@ -53,4 +202,41 @@ void CaDiCaL_AddActiveVerticesConnected(CaDiCaL::Solver* solver, int32_t n_verti
     solver->add_extra(std::make_unique<CaDiCaL::SubgraphConnectivity>(std::move(c_lits), std::move(g_edges)));
 }
 
+void CaDiCaL_SetPhase(CaDiCaL::Solver* solver, int32_t lit) {
+    solver->phase(to_cadical_lit(lit));
+}
+
+void CaDiCaL_Set_random_seed(CaDiCaL::Solver* solver, int32_t random_seed) {
+    solver->set("seed", random_seed);
+}
+
+int32_t CaDiCaL_EnableProof(CaDiCaL::Solver* solver, const char* path) {
+    // DRAT proofs are written in the textual format so that they can be read by `check_drat`.
+    solver->set("binary", 0);
+    return solver->trace_proof(path) ? 1 : 0;
+}
+
+void CaDiCaL_FlushProof(CaDiCaL::Solver* solver) {
+    solver->flush_proof_trace();
+}
+
+void CaDiCaL_CloseProof(CaDiCaL::Solver* solver) {
+    solver->close_proof_trace();
+}
+
+CaDiCaL::ExternalPropagator* CaDiCaL_ConnectRustPropagator(CaDiCaL::Solver* solver, void* hub) {
+    CaDiCaL::ExternalPropagator* propagator = new RustPropagator(hub);
+    solver->connect_external_propagator(propagator);
+    return propagator;
+}
+
+void CaDiCaL_DisconnectRustPropagator(CaDiCaL::Solver* solver, CaDiCaL::ExternalPropagator* propagator) {
+    solver->disconnect_external_propagator();
+    delete propagator;
+}
+
+void CaDiCaL_AddObservedVar(CaDiCaL::Solver* solver, int32_t var) {
+    solver->add_observed_var(var + 1);
+}
+
 }
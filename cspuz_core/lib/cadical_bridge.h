@@ -5,6 +5,7 @@
 namespace CaDiCaL {
 
 class Solver;
+class ExternalPropagator;
 
 }
 
@@ -13,8 +14,34 @@ extern "C" {
 CaDiCaL::Solver* CaDiCaL_CreateSolver();
 void CaDiCaL_DestroySolver(CaDiCaL::Solver* solver);
 void CaDiCaL_AddClause(CaDiCaL::Solver* solver, int32_t* lits, int32_t n_lits);
-int32_t CaDiCaL_Solve(CaDiCaL::Solver* solver);
+// Returns 1 (SAT), 0 (UNSAT) or -1 (the limit is reached or `interrupt` is set).
+// Negative `conflicts` / `time_limit` mean no limit, and `interrupt` may be null.
+// If `export_ctx` is not null, learnt clauses of at most `max_export_len` literals are passed to
+// `CaDiCaL_RustExportLearntClause` with `export_ctx`.
+int32_t CaDiCaL_SolveLimited(CaDiCaL::Solver* solver, const int32_t* assumptions, int32_t n_assumptions, int64_t conflicts, double time_limit, const uint8_t* interrupt, int32_t max_export_len, void* export_ctx, int64_t* conflicts_used);
 int32_t CaDiCaL_GetModelValueVar(CaDiCaL::Solver* solver, int32_t var);
 void CaDiCaL_AddActiveVerticesConnected(CaDiCaL::Solver* solver, int32_t n_vertices, const int32_t* lits, int32_t n_edges, const int32_t* edges);
+void CaDiCaL_SetPhase(CaDiCaL::Solver* solver, int32_t lit);
+void CaDiCaL_Set_random_seed(CaDiCaL::Solver* solver, int32_t random_seed);
+
+// Must be called right after the solver is created. Returns 0 if the proof file cannot be opened.
+int32_t CaDiCaL_EnableProof(CaDiCaL::Solver* solver, const char* path);
+void CaDiCaL_FlushProof(CaDiCaL::Solver* solver);
+void CaDiCaL_CloseProof(CaDiCaL::Solver* solver);
+
+CaDiCaL::ExternalPropagator* CaDiCaL_ConnectRustPropagator(CaDiCaL::Solver* solver, void* hub);
+void CaDiCaL_DisconnectRustPropagator(CaDiCaL::Solver* solver, CaDiCaL::ExternalPropagator* propagator);
+void CaDiCaL_AddObservedVar(CaDiCaL::Solver* solver, int32_t var);
+
+// Implement functions below in Rust
+void CaDiCaL_RustPropagatorNotifyAssignment(void* hub, int32_t lit, int32_t is_fixed);
+void CaDiCaL_RustPropagatorNotifyNewDecisionLevel(void* hub);
+void CaDiCaL_RustPropagatorNotifyBacktrack(void* hub, uint64_t new_level);
+int32_t CaDiCaL_RustPropagatorCheckFoundModel(void* hub);
+int32_t CaDiCaL_RustPropagatorPropagate(void* hub);
+int32_t CaDiCaL_RustPropagatorAddReasonClauseLit(void* hub, int32_t propagated_lit);
+int32_t CaDiCaL_RustPropagatorHasExternalClause(void* hub);
+int32_t CaDiCaL_RustPropagatorAddExternalClauseLit(void* hub);
+void CaDiCaL_RustExportLearntClause(void* ctx, const int32_t* lits, int32_t n_lits);
 
 }
@@ -1,5 +1,16 @@
 #include "glucose_bridge.h"
 
+#include <algorithm>
+#include <chrono>
+#include <cstdio>
+#include <memory>
+
+#ifndef __wasm__
+#include <condition_variable>
+#include <mutex>
+#include <thread>
+#endif
+
 #include "core/Solver.h"
 #include "constraints/DirectEncodingExtension.h"
 #include "constraints/Graph.h"
@@ -25,6 +36,57 @@ void RustExtraConstraint::undo(Solver& solver, Lit p) {
     Glucose_CallCustomPropagatorUndo(&solver, trait_object_, p.x);
 }
 
+#ifndef __wasm__
+// Glucose has no callback for checking the wall clock, so a thread waits until the deadline (polling `interrupt`,
+// if given) and stops the search by the asynchronous interrupt of Glucose. The thread is stopped and joined
+// when the watcher is destroyed.
+class InterruptWatcher {
+public:
+    InterruptWatcher(Glucose::Solver* solver, bool has_deadline, std::chrono::steady_clock::time_point deadline, const uint8_t* interrupt)
+        : solver_(solver), has_deadline_(has_deadline), deadline_(deadline), interrupt_(interrupt), finished_(false) {
+        thread_ = std::thread([this]() { run(); });
+    }
+
+    ~InterruptWatcher() {
+        {
+            std::lock_guard<std::mutex> lock(mutex_);
+            finished_ = true;
+        }
+        cv_.notify_all();
+        thread_.join();
+    }
+
+private:
+    static constexpr std::chrono::milliseconds kPollInterval{10};
+
+    void run() {
+        std::unique_lock<std::mutex> lock(mutex_);
+        while (!finished_) {
+            auto now = std::chrono::steady_clock::now();
+            if ((has_deadline_ && now >= deadline_) || (interrupt_ != nullptr && __atomic_load_n(interrupt_, __ATOMIC_RELAXED))) {
+                solver_->interrupt();
+                return;
+            }
+            if (interrupt_ != nullptr) {
+                auto wake = now + kPollInterval;
+                cv_.wait_until(lock, has_deadline_ ? std::min(wake, deadline_) : wake);
+            } else {
+                cv_.wait_until(lock, deadline_);
+            }
+        }
+    }
+
+    Glucose::Solver* solver_;
+    bool has_deadline_;
+    std::chrono::steady_clock::time_point deadline_;
+    const uint8_t* interrupt_;
+    bool finished_;
+    std::mutex mutex_;
+    std::condition_variable cv_;
+    std::thread thread_;
+};
+#endif
+
 }
 
 extern "C" {
@@ -59,6 +121,77 @@ int32_t Glucose_Solve(Glucose::Solver* solver) {
     return solver->solve();
 }
 
+int32_t Glucose_SolveUnder(Glucose::Solver* solver, const int32_t* assumptions, int32_t n_assumptions) {
+    Glucose::vec<Glucose::Lit> assumptions_vec;
+    for (int i = 0; i < n_assumptions; ++i) {
+        assumptions_vec.push(Glucose::Lit{assumptions[i]});
+    }
+    return solver->solve(assumptions_vec);
+}
+
+int32_t Glucose_SolveLimited(Glucose::Solver* solver, const int32_t* assumptions, int32_t n_assumptions, int64_t conflicts, int64_t propagations, double time_limit, const uint8_t* interrupt) {
+    Glucose::vec<Glucose::Lit> assumptions_vec;
+    for (int i = 0; i < n_assumptions; ++i) {
+        assumptions_vec.push(Glucose::Lit{assumptions[i]});
+    }
+
+    auto deadline = std::chrono::steady_clock::now();
+    if (time_limit >= 0) {
+        deadline += std::chrono::duration_cast<std::chrono::steady_clock::duration>(std::chrono::duration<double>(time_limit));
+    }
+
+    Glucose::lbool res = l_Undef;
+#ifndef __wasm__
+    solver->budgetOff();
+    if (conflicts >= 0) solver->setConfBudget(conflicts);
+    if (propagations >= 0) solver->setPropBudget(propagations);
+    {
+        std::unique_ptr<Glucose::InterruptWatcher> watcher;
+        if (time_limit >= 0 || interrupt != nullptr) {
+            watcher = std::make_unique<Glucose::InterruptWatcher>(solver, time_limit >= 0, deadline, interrupt);
+        }
+        res = solver->solveLimited(assumptions_vec);
+    }
+    // The watcher may have interrupted the solver just after the search finished.
+    solver->clearInterrupt();
+#else
+    // Threads are not available in wasm, so the search is split into slices of `kConflictsPerSlice`
+    // conflicts and the deadline (and `interrupt`, if given) is checked between slices instead.
+    constexpr int64_t kConflictsPerSlice = 100;
+
+    uint64_t start_conflicts = solver->conflicts;
+    uint64_t start_propagations = solver->propagations;
+
+    while (true) {
+        int64_t used_conflicts = solver->conflicts - start_conflicts;
+        int64_t used_propagations = solver->propagations - start_propagations;
+        if (conflicts >= 0 && used_conflicts >= conflicts) break;
+        if (propagations >= 0 && used_propagations >= propagations) break;
+        if (time_limit >= 0 && std::chrono::steady_clock::now() >= deadline) break;
+        if (interrupt != nullptr && __atomic_load_n(interrupt, __ATOMIC_RELAXED)) break;
+
+        bool sliced = time_limit >= 0 || interrupt != nullptr;
+        solver->budgetOff();
+        if (sliced || conflicts >= 0) {
+            int64_t slice = sliced ? kConflictsPerSlice : conflicts - used_conflicts;
+            if (conflicts >= 0) slice = std::min(slice, conflicts - used_conflicts);
+            solver->setConfBudget(slice);
+        }
+        if (propagations >= 0) {
+            solver->setPropBudget(propagations - used_propagations);
+        }
+
+        res = solver->solveLimited(assumptions_vec);
+        if (res != l_Undef) break;
+    }
+#endif
+    solver->budgetOff();
+
+    if (res == l_True) return 1;
+    if (res == l_False) return 0;
+    return -1;
+}
+
 int32_t Glucose_NumVar(const Glucose::Solver* solver) {
     return solver->nVars();
 }
@@ -181,6 +314,39 @@ void Glucose_Set_dump_analysis_info(Glucose::Solver* solver, int32_t value) {
     solver->dump_analysis_info = value != 0;
 }
 
+int32_t Glucose_EnableProof(Glucose::Solver* solver, const char* path) {
+#ifdef GLUCOSE_UNUSE_STDIO
+    return 0;
+#else
+    FILE* fp = fopen(path, "w");
+    if (fp == nullptr) {
+        return 0;
+    }
+    solver->certifiedOutput = fp;
+    solver->certifiedUNSAT = true;
+    solver->vbyte = false;
+    return 1;
+#endif
+}
+
+void Glucose_FlushProof(Glucose::Solver* solver) {
+#ifndef GLUCOSE_UNUSE_STDIO
+    if (solver->certifiedUNSAT) {
+        fflush(solver->certifiedOutput);
+    }
+#endif
+}
+
+void Glucose_CloseProof(Glucose::Solver* solver) {
+#ifndef GLUCOSE_UNUSE_STDIO
+    if (solver->certifiedUNSAT) {
+        fclose(solver->certifiedOutput);
+        solver->certifiedOutput = nullptr;
+        solver->certifiedUNSAT = false;
+    }
+#endif
+}
+
 int32_t Glucose_AddRustExtraConstraint(Glucose::Solver* solver, void* trait_object) {
     return solver->addConstraint(std::make_unique<Glucose::RustExtraConstraint>(trait_object)) ? 1 : 0;
 }
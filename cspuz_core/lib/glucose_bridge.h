@@ -31,6 +31,8 @@ int32_t Glucose_NewVar(Glucose::Solver* solver);
 int32_t Glucose_NewNamedVar(Glucose::Solver* solver, const char* name);
 int32_t Glucose_AddClause(Glucose::Solver* solver, int32_t* lits, int32_t n_lits);
 int32_t Glucose_Solve(Glucose::Solver* solver);
+int32_t Glucose_SolveUnder(Glucose::Solver* solver, const int32_t* assumptions, int32_t n_assumptions);
+int32_t Glucose_SolveLimited(Glucose::Solver* solver, const int32_t* assumptions, int32_t n_assumptions, int64_t conflicts, int64_t propagations, double time_limit, const uint8_t* interrupt);
 int32_t Glucose_NumVar(const Glucose::Solver* solver);
 int32_t Glucose_GetModelValueVar(const Glucose::Solver* solver, int32_t var);
 void Glucose_SetPolarity(Glucose::Solver* solver, int32_t var, int32_t polarity);
@@ -42,6 +44,11 @@ void Glucose_Set_random_seed(Glucose::Solver* solver, double random_seed);
 void Glucose_Set_rnd_init_act(Glucose::Solver* solver, int32_t rnd_init_act);
 void Glucose_Set_dump_analysis_info(Glucose::Solver* solver, int32_t value);
 
+// Returns 0 if the proof file cannot be opened.
+int32_t Glucose_EnableProof(Glucose::Solver* solver, const char* path);
+void Glucose_FlushProof(Glucose::Solver* solver);
+void Glucose_CloseProof(Glucose::Solver* solver);
+
 int32_t Glucose_AddRustExtraConstraint(Glucose::Solver* solver, void* trait_object);
 void Glucose_CustomPropagatorCopyReason(void* reason_vec, int32_t n_lits, int32_t* lits);
 int32_t Glucose_SolverValue(Glucose::Solver* solver, int32_t lit);
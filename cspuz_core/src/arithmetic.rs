@@ -55,24 +55,59 @@ impl std::fmt::Display for CmpOp {
     }
 }
 
+/// The underlying integer type of `CheckedInt`.
+///
+/// By default, this is `i32`. With the `wide-int` feature, this is `i64` so that intermediate
+/// values (e.g. products of variables and weighted sums) may exceed the range of `i32`.
+/// In either case, the values of the variables and constants in the CSP API are `i32`.
+#[cfg(not(feature = "wide-int"))]
+pub type IntRepr = i32;
+#[cfg(feature = "wide-int")]
+pub type IntRepr = i64;
+
 /// Integer type for internal use.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
-pub struct CheckedInt(i32);
+pub struct CheckedInt(IntRepr);
 
 impl CheckedInt {
     pub fn new(value: i32) -> CheckedInt {
+        CheckedInt(value as IntRepr)
+    }
+
+    pub fn from_repr(value: IntRepr) -> CheckedInt {
         CheckedInt(value)
     }
 
     pub fn min_value() -> CheckedInt {
-        CheckedInt(i32::MIN)
+        CheckedInt(IntRepr::MIN)
     }
 
     pub fn max_value() -> CheckedInt {
-        CheckedInt(i32::MAX)
+        CheckedInt(IntRepr::MAX)
     }
 
+    /// Returns the value as `i32`. Panics if the value does not fit in `i32`, which is possible
+    /// only with the `wide-int` feature.
     pub fn get(self) -> i32 {
+        self.to_i32()
+            .unwrap_or_else(|| panic!("value {} does not fit in i32", self.0))
+    }
+
+    /// Returns the value as `i32` if it fits in `i32`.
+    #[allow(clippy::useless_conversion)] // `IntRepr` may be `i32`
+    pub fn to_i32(self) -> Option<i32> {
+        i32::try_from(self.0).ok()
+    }
+
+    /// Same as `to_i32`, but returns an `OverflowError` if the value does not fit in `i32`.
+    pub fn to_i32_checked(self) -> Result<i32, OverflowError> {
+        self.to_i32().ok_or(OverflowError {
+            magnitude: (self.0 as i128).abs(),
+            limit: i32::MAX as i128,
+        })
+    }
+
+    pub fn get_repr(self) -> IntRepr {
         self.0
     }
 
@@ -90,6 +125,14 @@ impl CheckedInt {
         )
     }
 
+    pub fn div_trunc(self, rhs: CheckedInt) -> CheckedInt {
+        CheckedInt(self.0.checked_div(rhs.0).unwrap())
+    }
+
+    pub fn rem_trunc(self, rhs: CheckedInt) -> CheckedInt {
+        CheckedInt(self.0.checked_rem(rhs.0).unwrap())
+    }
+
     pub fn abs(self) -> CheckedInt {
         CheckedInt(self.0.checked_abs().unwrap())
     }
@@ -147,28 +190,58 @@ impl Neg for CheckedInt {
 
 impl PartialEq<i32> for CheckedInt {
     fn eq(&self, other: &i32) -> bool {
-        self.0 == *other
+        self.0 == *other as IntRepr
     }
 }
 
 impl PartialEq<CheckedInt> for i32 {
     fn eq(&self, other: &CheckedInt) -> bool {
-        *self == other.0
+        *self as IntRepr == other.0
     }
 }
 
 impl PartialOrd<i32> for CheckedInt {
     fn partial_cmp(&self, other: &i32) -> Option<std::cmp::Ordering> {
-        Some(self.0.cmp(other))
+        Some(self.0.cmp(&(*other as IntRepr)))
     }
 }
 
 impl PartialOrd<CheckedInt> for i32 {
     fn partial_cmp(&self, other: &CheckedInt) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other.0))
+        Some((*self as IntRepr).cmp(&other.0))
     }
 }
 
+/// Values whose magnitudes exceed this limit are considered to overflow in `CSP::check_overflow`.
+/// Half of the maximum value is left as a margin for the intermediate values in the encoders.
+pub(crate) const MAGNITUDE_LIMIT: i128 = IntRepr::MAX as i128 / 2;
+
+/// An error indicating that an instance involves values which may not fit in `CheckedInt`, or
+/// values which must be passed as `i32` but do not fit in `i32` (possible only with the `wide-int` feature).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+    /// An upper bound of the magnitudes of the values in the instance.
+    pub magnitude: i128,
+    /// The largest magnitude allowed.
+    pub limit: i128,
+}
+
+impl std::fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "values up to {} in magnitude may appear, which exceeds the limit {}",
+            self.magnitude, self.limit
+        )?;
+        if cfg!(not(feature = "wide-int")) {
+            write!(f, " (consider enabling the `wide-int` feature)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Range {
     pub low: CheckedInt,
@@ -215,6 +288,62 @@ impl Range {
     pub fn is_empty(&self) -> bool {
         self.low > self.high
     }
+
+    pub fn contains(&self, c: CheckedInt) -> bool {
+        self.low <= c && c <= self.high
+    }
+
+    /// Returns a range containing `x / y` (rounded toward zero, or 0 if `y` is 0)
+    /// for all `x` in `self` and `y` in `rhs`.
+    pub fn div_trunc(self, rhs: Range) -> Range {
+        if self.is_empty() || rhs.is_empty() {
+            return Range::empty();
+        }
+        let one = CheckedInt::new(1);
+        let mut ret = Range::empty();
+        // `x / y` is monotone in each of `x` and `y` as long as the sign of `y` is fixed,
+        // so the extremes are attained at the endpoints of the positive / negative parts of `rhs`.
+        for divisor in [
+            Range::new(rhs.low.max(one), rhs.high),
+            Range::new(rhs.low, rhs.high.min(-one)),
+        ] {
+            if divisor.is_empty() {
+                continue;
+            }
+            for x in [self.low, self.high] {
+                for y in [divisor.low, divisor.high] {
+                    ret = ret | Range::constant(x.div_trunc(y));
+                }
+            }
+        }
+        if rhs.contains(CheckedInt::new(0)) {
+            ret = ret | Range::constant(CheckedInt::new(0));
+        }
+        ret
+    }
+
+    /// Returns a range containing `x % y` (with the sign of `x`, or `x` if `y` is 0)
+    /// for all `x` in `self` and `y` in `rhs`.
+    pub fn rem_trunc(self, rhs: Range) -> Range {
+        if self.is_empty() || rhs.is_empty() {
+            return Range::empty();
+        }
+        let zero = CheckedInt::new(0);
+        let max_divisor = rhs.low.abs().max(rhs.high.abs());
+        let mut ret = if max_divisor == 0 {
+            Range::empty()
+        } else {
+            let bound = max_divisor - CheckedInt::new(1);
+            Range::new(
+                self.low.max(-bound).min(zero),
+                self.high.min(bound).max(zero),
+            )
+        };
+        if rhs.contains(zero) {
+            ret = ret | self;
+        }
+        ret
+    }
 }
 
 impl Add<Range> for Range {
@@ -463,4 +592,28 @@ mod tests {
         assert_eq!(CheckedInt::new(-42).div_ceil(CheckedInt::new(4)), -10);
         assert_eq!(CheckedInt::new(-42).div_ceil(CheckedInt::new(3)), -14);
     }
+
+    #[test]
+    fn test_range_div_rem_trunc() {
+        for xl in -5..=5 {
+            for xh in xl..=5 {
+                for yl in -4..=4 {
+                    for yh in yl..=4 {
+                        let x = Range::new(CheckedInt::new(xl), CheckedInt::new(xh));
+                        let y = Range::new(CheckedInt::new(yl), CheckedInt::new(yh));
+                        let div = x.div_trunc(y);
+                        let rem = x.rem_trunc(y);
+
+                        for a in xl..=xh {
+                            for b in yl..=yh {
+                                let (q, r) = if b == 0 { (0, a) } else { (a / b, a % b) };
+                                assert!(div.contains(CheckedInt::new(q)));
+                                assert!(rem.contains(CheckedInt::new(r)));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,6 +1,18 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::{c_void, CString};
 use std::ops::Drop;
+use std::os::raw::c_char;
+use std::sync::atomic::AtomicBool;
 
-use crate::sat::{Lit, Var};
+use crate::proof::{Proof, ProofLog};
+use crate::propagators::active_vertices_connected::ActiveVerticesConnected;
+use crate::propagators::graph_division::{GraphDivision, GraphDivisionOptions};
+use crate::propagators::order_encoding_linear;
+use crate::propagators::single_cycle::ActiveEdgesSingleCycle;
+use crate::sat::{
+    CustomPropagator, Lit, OrderEncodingLinearMode, ResourceLimit, SolveResult, SolverManipulator,
+    Var,
+};
 
 #[repr(C)]
 struct Opaque {
@@ -11,7 +23,17 @@ extern "C" {
     fn CaDiCaL_CreateSolver() -> *mut Opaque;
     fn CaDiCaL_DestroySolver(solver: *mut Opaque);
     fn CaDiCaL_AddClause(solver: *mut Opaque, lits: *const i32, n_lits: i32);
-    fn CaDiCaL_Solve(solver: *mut Opaque) -> i32;
+    fn CaDiCaL_SolveLimited(
+        solver: *mut Opaque,
+        assumptions: *const Lit,
+        n_assumptions: i32,
+        conflicts: i64,
+        time_limit: f64,
+        interrupt: *const u8,
+        max_export_len: i32,
+        export_ctx: *mut c_void,
+        conflicts_used: *mut i64,
+    ) -> i32;
     fn CaDiCaL_GetModelValueVar(solver: *mut Opaque, var: i32) -> i32;
     fn CaDiCaL_AddActiveVerticesConnected(
         solver: *mut Opaque,
@@ -20,11 +42,32 @@ extern "C" {
         n_edges: i32,
         edges: *const i32,
     );
+    fn CaDiCaL_SetPhase(solver: *mut Opaque, lit: Lit);
+    fn CaDiCaL_Set_random_seed(solver: *mut Opaque, random_seed: i32);
+    fn CaDiCaL_ConnectRustPropagator(solver: *mut Opaque, hub: *mut c_void) -> *mut Opaque;
+    fn CaDiCaL_DisconnectRustPropagator(solver: *mut Opaque, propagator: *mut Opaque);
+    fn CaDiCaL_AddObservedVar(solver: *mut Opaque, var: i32);
+    fn CaDiCaL_EnableProof(solver: *mut Opaque, path: *const c_char) -> i32;
+    fn CaDiCaL_FlushProof(solver: *mut Opaque);
+    fn CaDiCaL_CloseProof(solver: *mut Opaque);
 }
 
 pub struct Solver {
     ptr: *mut Opaque,
     num_var: i32,
+
+    // All custom propagators share a single external propagator connected to CaDiCaL.
+    // `hub` is boxed so that its address, which is passed to the C++ code, is stable.
+    hub: Option<(Box<PropagatorHub>, *mut Opaque)>,
+
+    // The total number of conflicts (approximated by the number of learnt clauses) in all solves so far.
+    conflicts: u64,
+
+    // Learnt clauses with at most `max_export_len` literals are stored in `exported` (0 for disabling this).
+    max_export_len: usize,
+    exported: Vec<Vec<Lit>>,
+
+    proof: Option<ProofLog>,
 }
 
 const NUM_VAR_MAX: i32 = 0x3fffffff;
@@ -34,13 +77,47 @@ impl Solver {
         Solver {
             ptr: unsafe { CaDiCaL_CreateSolver() },
             num_var: 0,
+            hub: None,
+            conflicts: 0,
+            max_export_len: 0,
+            exported: vec![],
+            proof: None,
         }
     }
 
+    /// Makes the solver write a DRAT proof. This must be called right after the solver is created.
+    /// Returns `false` if the proof cannot be written.
+    pub fn enable_proof(&mut self) -> bool {
+        assert!(self.proof.is_none());
+        assert_eq!(self.num_var, 0);
+        let Ok(log) = ProofLog::new() else {
+            return false;
+        };
+        let Some(path) = log.path().to_str().and_then(|p| CString::new(p).ok()) else {
+            return false;
+        };
+        if unsafe { CaDiCaL_EnableProof(self.ptr, path.as_ptr()) } == 0 {
+            return false;
+        }
+        self.proof = Some(log);
+        true
+    }
+
+    /// Returns the proof written so far, which refutes the clauses added so far if the last solve
+    /// (without assumptions) reported unsatisfiability.
+    pub fn proof(&self) -> Result<Proof, String> {
+        let log = self.proof.as_ref().ok_or("proof is not enabled")?;
+        unsafe { CaDiCaL_FlushProof(self.ptr) };
+        log.proof(self.num_var as usize)
+    }
+
     pub fn new_var(&mut self) -> Var {
         assert!(self.num_var < NUM_VAR_MAX);
         let var_id = self.num_var;
         self.num_var += 1;
+        if let Some((hub, _)) = &mut self.hub {
+            hub.state.reserve_vars(self.num_var as usize);
+        }
         Var(var_id)
     }
 
@@ -54,6 +131,9 @@ impl Solver {
 
     pub fn add_clause(&mut self, clause: &[Lit]) {
         assert!(clause.len() <= i32::MAX as usize);
+        if let Some(proof) = &mut self.proof {
+            proof.add_clause(clause);
+        }
         let clause = unsafe { std::mem::transmute::<&[Lit], &[i32]>(clause) };
         for &c in clause {
             assert!(0 <= c && c < 2 * self.num_var);
@@ -61,10 +141,22 @@ impl Solver {
         unsafe { CaDiCaL_AddClause(self.ptr, clause.as_ptr(), clause.len() as i32) };
     }
 
-    pub fn add_active_vertices_connected(&mut self, lits: &[Lit], edges: &[(usize, usize)]) {
+    pub fn add_active_vertices_connected(
+        &mut self,
+        lits: &[Lit],
+        edges: &[(usize, usize)],
+    ) -> bool {
         assert!(lits.len() <= i32::MAX as usize);
         assert!(edges.len() <= i32::MAX as usize);
 
+        if self.proof.is_some() {
+            // The reasons of the C++ propagator cannot be logged in proofs.
+            return self.add_custom_constraint(Box::new(ActiveVerticesConnected::new(
+                lits.to_vec(),
+                edges.to_vec(),
+            )));
+        }
+
         let lits = unsafe { std::mem::transmute::<&[Lit], &[i32]>(lits) };
         for &l in lits {
             assert!(0 <= l && l < 2 * self.num_var);
@@ -87,6 +179,129 @@ impl Solver {
                 edges_flat.as_ptr(),
             )
         };
+        true
+    }
+
+    pub fn set_polarity(&mut self, var: Var, polarity: bool) {
+        assert!(0 <= var.0 && var.0 < self.num_var);
+        // Following Glucose, `polarity == true` means that `false` is preferred.
+        unsafe { CaDiCaL_SetPhase(self.ptr, var.as_lit(polarity)) };
+    }
+
+    pub fn set_seed(&mut self, seed: f64) {
+        // CaDiCaL accepts only integral seeds, so we use the bit pattern of `seed`.
+        let bits = seed.to_bits();
+        let seed = ((bits ^ (bits >> 32)) & 0x7fffffff) as i32;
+        unsafe { CaDiCaL_Set_random_seed(self.ptr, seed) };
+    }
+
+    pub fn add_order_encoding_linear(
+        &mut self,
+        lits: &[Vec<Lit>],
+        domain: &[Vec<i32>],
+        coefs: &[i32],
+        constant: i32,
+        mode: OrderEncodingLinearMode,
+    ) -> bool {
+        assert_eq!(lits.len(), domain.len());
+        assert_eq!(lits.len(), coefs.len());
+
+        let mut terms = vec![];
+        for i in 0..lits.len() {
+            assert_eq!(lits[i].len() + 1, domain[i].len());
+            terms.push(order_encoding_linear::LinearTerm::new(
+                lits[i].clone(),
+                domain[i].clone(),
+                coefs[i],
+            ));
+        }
+        // The C++ implementation is available only in Glucose, so the optimized Rust
+        // implementation is used instead for `OrderEncodingLinearMode::Cpp`.
+        let optimized = mode != OrderEncodingLinearMode::Rust;
+        self.add_custom_constraint(Box::new(order_encoding_linear::OrderEncodingLinear::new(
+            terms, constant, optimized,
+        )))
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_extension_supports(
+        &mut self,
+        vars: &[Vec<Lit>],
+        supports: &[Vec<Option<usize>>],
+    ) -> bool {
+        use crate::propagators::direct_encoding_extension::DirectEncodingExtensionSupports;
+
+        self.add_custom_constraint(Box::new(DirectEncodingExtensionSupports::new(
+            vars.to_vec(),
+            supports.to_vec(),
+        )))
+    }
+
+    pub fn add_active_edges_single_cycle(
+        &mut self,
+        lits: &[Lit],
+        edges: &[(usize, usize)],
+    ) -> bool {
+        self.add_custom_constraint(Box::new(ActiveEdgesSingleCycle::new(
+            lits.to_vec(),
+            edges.to_vec(),
+        )))
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        use crate::propagators::all_different::DirectEncodingAllDifferent;
+
+        self.add_custom_constraint(Box::new(DirectEncodingAllDifferent::new(
+            vars.to_vec(),
+            values.to_vec(),
+        )))
+    }
+
+    pub fn add_graph_division(
+        &mut self,
+        domains: &[Vec<i32>],
+        dom_lits: &[Vec<Lit>],
+        edges: &[(usize, usize)],
+        edge_lits: &[Lit],
+        opts: &GraphDivisionOptions,
+    ) -> bool {
+        // Only the Rust implementation is available in CaDiCaL, regardless of `GraphDivisionMode`.
+        let vertex_weights = vec![1; domains.len()];
+        let constr = GraphDivision::new(domains, dom_lits, &vertex_weights, edges, edge_lits, opts);
+        self.add_custom_constraint(Box::new(constr))
+    }
+
+    pub fn add_custom_constraint(
+        &mut self,
+        constraint: Box<dyn CustomPropagator<CaDiCaLSolverManipulator>>,
+    ) -> bool {
+        let constraint = match &self.proof {
+            Some(proof) => proof.wrap_propagator(constraint),
+            None => constraint,
+        };
+        if self.hub.is_none() {
+            let mut hub = Box::new(PropagatorHub::new(self.ptr));
+            hub.state.reserve_vars(self.num_var as usize);
+            let hub_ptr = hub.as_mut() as *mut PropagatorHub as *mut c_void;
+            let propagator = unsafe { CaDiCaL_ConnectRustPropagator(self.ptr, hub_ptr) };
+            self.hub = Some((hub, propagator));
+        }
+        let (hub, _) = self.hub.as_mut().unwrap();
+        let res = hub.add_propagator(constraint);
+        if !res {
+            // Make the solver inconsistent, as Glucose does for a failed constraint.
+            // The empty clause is not a consequence of the formula, so it is not a part of the proof.
+            unsafe { CaDiCaL_AddClause(self.ptr, std::ptr::null(), 0) };
+            if let Some(proof) = &mut self.proof {
+                proof.mark_refuted_before_solving();
+            }
+        }
+        res
     }
 
     pub fn solve(&mut self) -> Option<Model<'_>> {
@@ -98,8 +313,72 @@ impl Solver {
     }
 
     pub fn solve_without_model(&mut self) -> bool {
-        let res = unsafe { CaDiCaL_Solve(self.ptr) };
-        res != 0
+        self.solve_under_without_model(&[])
+    }
+
+    pub fn solve_under(&mut self, assumptions: &[Lit]) -> Option<Model<'_>> {
+        if self.solve_under_without_model(assumptions) {
+            Some(unsafe { self.model() })
+        } else {
+            None
+        }
+    }
+
+    pub fn solve_under_without_model(&mut self, assumptions: &[Lit]) -> bool {
+        self.solve_under_limited(assumptions, &ResourceLimit::unlimited(), None) == SolveResult::Sat
+    }
+
+    /// Solves the problem under `assumptions` within `limit`, giving up also when `interrupt` is set.
+    /// The propagation limit is ignored.
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+        interrupt: Option<&AtomicBool>,
+    ) -> SolveResult {
+        let mut conflicts_used = 0;
+        let export_ctx = if self.max_export_len > 0 {
+            &mut self.exported as *mut Vec<Vec<Lit>> as *mut c_void
+        } else {
+            std::ptr::null_mut()
+        };
+        let res = unsafe {
+            CaDiCaL_SolveLimited(
+                self.ptr,
+                assumptions.as_ptr(),
+                assumptions.len() as i32,
+                limit
+                    .conflicts
+                    .map_or(-1, |x| x.min(i64::MAX as u64) as i64),
+                limit.time.map_or(-1.0, |x| x.as_secs_f64()),
+                interrupt.map_or(std::ptr::null(), |x| x.as_ptr() as *const u8),
+                self.max_export_len.min(i32::MAX as usize) as i32,
+                export_ctx,
+                &mut conflicts_used,
+            )
+        };
+        self.conflicts += conflicts_used as u64;
+        match res {
+            1 => SolveResult::Sat,
+            0 => SolveResult::Unsat,
+            _ => SolveResult::Unknown,
+        }
+    }
+
+    pub fn stats_conflicts(&self) -> u64 {
+        self.conflicts
+    }
+
+    /// Makes the solver keep the clauses with at most `max_len` literals learnt in the subsequent solves,
+    /// which are retrieved by `take_exported_clauses`.
+    #[cfg(feature = "portfolio")]
+    pub(crate) fn set_max_export_len(&mut self, max_len: usize) {
+        self.max_export_len = max_len;
+    }
+
+    #[cfg(feature = "portfolio")]
+    pub(crate) fn take_exported_clauses(&mut self) -> Vec<Vec<Lit>> {
+        std::mem::take(&mut self.exported)
     }
 
     pub(crate) unsafe fn model(&self) -> Model<'_> {
@@ -110,6 +389,12 @@ impl Solver {
 impl Drop for Solver {
     fn drop(&mut self) {
         unsafe {
+            if let Some((_, propagator)) = self.hub.take() {
+                CaDiCaL_DisconnectRustPropagator(self.ptr, propagator);
+            }
+            if self.proof.is_some() {
+                CaDiCaL_CloseProof(self.ptr);
+            }
             CaDiCaL_DestroySolver(self.ptr);
         }
     }
@@ -126,3 +411,423 @@ impl Model<'_> {
         unsafe { CaDiCaL_GetModelValueVar(self.solver.ptr, var.0) != 0 }
     }
 }
+
+// Interface for implementing custom constraints in Rust
+//
+// CaDiCaL accepts only one external propagator, and its interface (IPASIR-UP) differs from that of
+// Glucose: assignments are notified in batches, and propagations and conflicts are reported
+// through callbacks. `PropagatorHub` bridges the gap by keeping its own copy of the trail,
+// dispatching assignments to `CustomPropagator`s in the same way as Glucose does.
+
+/// Returned to the C++ code when there is no literal to report.
+const LIT_NONE: i32 = -1;
+
+struct PropagatorState {
+    solver: *mut Opaque,
+
+    // indexed by `Lit.0`
+    watches: Vec<Vec<usize>>,
+    // indexed by `Var.0`
+    observed: Vec<bool>,
+    assignment: Vec<Option<bool>>,
+    level: Vec<usize>,
+
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+
+    // `trail[queue_head]` is the next literal to be passed to the propagators, and its first
+    // `queue_watch_pos` watchers have already been processed.
+    queue_head: usize,
+    queue_watch_pos: usize,
+    num_pending: Vec<i32>,
+
+    // (position in `trail`, literal, propagator index) for each call of `propagate`
+    undo_list: Vec<(usize, Lit, usize)>,
+
+    propagations: VecDeque<Lit>,
+    // reason clauses of propagated literals (the propagated literal comes first), removed on backtracking
+    reasons: BTreeMap<Lit, Vec<Lit>>,
+    reason_output: Option<(Vec<Lit>, usize)>,
+
+    external_clauses: VecDeque<Vec<Lit>>,
+    external_clause_pos: usize,
+
+    failed_enqueue: Option<Lit>,
+}
+
+impl PropagatorState {
+    fn reserve_vars(&mut self, num_var: usize) {
+        if self.assignment.len() < num_var {
+            self.watches.resize(num_var * 2, vec![]);
+            self.observed.resize(num_var, false);
+            self.assignment.resize(num_var, None);
+            self.level.resize(num_var, 0);
+        }
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assignment[lit.var().0 as usize].map(|v| v ^ lit.is_negated())
+    }
+
+    fn assign(&mut self, lit: Lit) {
+        let var = lit.var().0 as usize;
+        self.assignment[var] = Some(!lit.is_negated());
+        self.level[var] = self.decision_level();
+        for &i in &self.watches[lit.0 as usize] {
+            self.num_pending[i] += 1;
+        }
+        self.trail.push(lit);
+    }
+
+    fn next_external_clause_lit(&mut self) -> i32 {
+        let clause = &self.external_clauses[0];
+        if self.external_clause_pos < clause.len() {
+            self.external_clause_pos += 1;
+            clause[self.external_clause_pos - 1].0
+        } else {
+            self.external_clauses.pop_front();
+            self.external_clause_pos = 0;
+            LIT_NONE
+        }
+    }
+}
+
+struct PropagatorHub {
+    state: PropagatorState,
+    propagators: Vec<Box<dyn CustomPropagator<CaDiCaLSolverManipulator>>>,
+}
+
+impl PropagatorHub {
+    fn new(solver: *mut Opaque) -> PropagatorHub {
+        PropagatorHub {
+            state: PropagatorState {
+                solver,
+                watches: vec![],
+                observed: vec![],
+                assignment: vec![],
+                level: vec![],
+                trail: vec![],
+                trail_lim: vec![],
+                queue_head: 0,
+                queue_watch_pos: 0,
+                num_pending: vec![],
+                undo_list: vec![],
+                propagations: VecDeque::new(),
+                reasons: BTreeMap::new(),
+                reason_output: None,
+                external_clauses: VecDeque::new(),
+                external_clause_pos: 0,
+                failed_enqueue: None,
+            },
+            propagators: vec![],
+        }
+    }
+
+    fn manipulator(&mut self, idx: usize) -> CaDiCaLSolverManipulator {
+        CaDiCaLSolverManipulator {
+            state: &mut self.state as *mut PropagatorState,
+            idx,
+        }
+    }
+
+    fn add_propagator(
+        &mut self,
+        mut propagator: Box<dyn CustomPropagator<CaDiCaLSolverManipulator>>,
+    ) -> bool {
+        // Propagators are added only at the root level, while the local trail may still hold the
+        // assignment of the last `solve` call.
+        self.backtrack(0);
+
+        let idx = self.propagators.len();
+        self.state.num_pending.push(0);
+        let trail_size = self.state.trail.len();
+        let mut manipulator = self.manipulator(idx);
+        let res = propagator.initialize(&mut manipulator);
+        self.propagators.push(propagator);
+        self.collect_propagations(idx, trail_size);
+        res
+    }
+
+    /// Computes the reasons of the literals enqueued by the `idx`-th propagator, which are
+    /// `trail[trail_size..]`, and schedules them to be reported to CaDiCaL.
+    fn collect_propagations(&mut self, idx: usize, trail_size: usize) {
+        for pos in trail_size..self.state.trail.len() {
+            let lit = self.state.trail[pos];
+            let mut manipulator = self.manipulator(idx);
+            let reason = self.propagators[idx].calc_reason(&mut manipulator, Some(lit), None);
+            let mut clause = vec![lit];
+            clause.extend(reason.into_iter().map(|l| !l));
+            self.state.reasons.insert(lit, clause);
+            self.state.propagations.push_back(lit);
+        }
+    }
+
+    /// Passes the assigned literals to the propagators.
+    /// Returns `false` if a conflict is found, in which case the conflict clause is stored in
+    /// `external_clauses`.
+    fn process_queue(&mut self) -> bool {
+        if !self.state.external_clauses.is_empty() {
+            return false;
+        }
+
+        while self.state.queue_head < self.state.trail.len() {
+            let p = self.state.trail[self.state.queue_head];
+            let watchers = &self.state.watches[p.0 as usize];
+            if self.state.queue_watch_pos >= watchers.len() {
+                self.state.queue_head += 1;
+                self.state.queue_watch_pos = 0;
+                continue;
+            }
+            let idx = watchers[self.state.queue_watch_pos];
+            self.state.queue_watch_pos += 1;
+            self.state.num_pending[idx] -= 1;
+            self.state.undo_list.push((self.state.queue_head, p, idx));
+            self.state.failed_enqueue = None;
+
+            let num_pending = self.state.num_pending[idx];
+            let trail_size = self.state.trail.len();
+            let mut manipulator = self.manipulator(idx);
+            let res = self.propagators[idx].propagate(&mut manipulator, p, num_pending);
+            self.collect_propagations(idx, trail_size);
+
+            if !res {
+                let extra = self.state.failed_enqueue.take().map(|l| !l);
+                let reason = self.propagators[idx].calc_reason(&mut manipulator, None, extra);
+                self.state
+                    .external_clauses
+                    .push_back(reason.into_iter().map(|l| !l).collect());
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Called when CaDiCaL assigns `lit`. `is_fixed` is set if `lit` is assigned at the root level,
+    /// which may happen while the current decision level is not 0 (e.g. for learnt units), or
+    /// after `lit` was already notified at a higher level.
+    fn notify_assignment(&mut self, lit: Lit, is_fixed: bool) {
+        match self.state.value(lit) {
+            None => self.state.assign(lit),
+            // Literals propagated by this hub are already assigned.
+            Some(true) => (),
+            Some(false) => {
+                // `!lit` was propagated by this hub, but CaDiCaL assigned `lit` before the
+                // propagation was reported. The reason clause of `!lit` is now falsified, so it is
+                // reported as a conflict, and `!lit` is no longer reported as a propagation.
+                let clause = self.state.reasons[&!lit].clone();
+                self.state.propagations.retain(|&l| l != !lit);
+                self.state.external_clauses.push_back(clause);
+                return;
+            }
+        }
+        if is_fixed {
+            self.state.level[lit.var().0 as usize] = 0;
+        }
+    }
+
+    fn backtrack(&mut self, new_level: usize) {
+        if new_level >= self.state.decision_level() {
+            return;
+        }
+        let new_size = self.state.trail_lim[new_level];
+        self.state.trail_lim.truncate(new_level);
+
+        while let Some(&(pos, lit, idx)) = self.state.undo_list.last() {
+            if pos < new_size {
+                break;
+            }
+            self.state.undo_list.pop();
+            let mut manipulator = self.manipulator(idx);
+            self.propagators[idx].undo(&mut manipulator, lit);
+        }
+
+        let state = &mut self.state;
+        // Literals fixed at a level not greater than `new_level` (see `notify_assignment`) remain
+        // assigned. They are put back on the trail and passed to the propagators again, since the
+        // propagators have undone everything after `new_size`.
+        let mut kept = vec![];
+        for pos in (new_size..state.trail.len()).rev() {
+            let lit = state.trail[pos];
+            if pos >= state.queue_head {
+                let start = if pos == state.queue_head {
+                    state.queue_watch_pos
+                } else {
+                    0
+                };
+                for &i in &state.watches[lit.0 as usize][start..] {
+                    state.num_pending[i] -= 1;
+                }
+            }
+            let var = lit.var().0 as usize;
+            state.assignment[var] = None;
+            if state.level[var] <= new_level {
+                kept.push((lit, state.level[var]));
+            } else {
+                state.reasons.remove(&lit);
+            }
+        }
+        state.trail.truncate(new_size);
+        if state.queue_head >= new_size {
+            state.queue_head = new_size;
+            state.queue_watch_pos = 0;
+        }
+        for &(lit, level) in kept.iter().rev() {
+            state.assign(lit);
+            state.level[lit.var().0 as usize] = level;
+        }
+
+        let mut propagations = std::mem::take(&mut state.propagations);
+        propagations.retain(|&lit| state.value(lit) == Some(true));
+        state.propagations = propagations;
+        state.reason_output = None;
+    }
+
+    fn propagate(&mut self) -> i32 {
+        if self.state.propagations.is_empty() {
+            self.process_queue();
+        }
+        match self.state.propagations.pop_front() {
+            Some(lit) => lit.0,
+            None => LIT_NONE,
+        }
+    }
+
+    fn add_reason_clause_lit(&mut self, propagated_lit: Lit) -> i32 {
+        let state = &mut self.state;
+        if state.reason_output.is_none() {
+            let clause = state.reasons[&propagated_lit].clone();
+            state.reason_output = Some((clause, 0));
+        }
+        let (clause, pos) = state.reason_output.as_mut().unwrap();
+        if *pos < clause.len() {
+            *pos += 1;
+            clause[*pos - 1].0
+        } else {
+            state.reason_output = None;
+            LIT_NONE
+        }
+    }
+
+    fn check_found_model(&mut self) -> bool {
+        self.process_queue() && self.state.propagations.is_empty()
+    }
+}
+
+pub struct CaDiCaLSolverManipulator {
+    state: *mut PropagatorState,
+    idx: usize,
+}
+
+unsafe impl SolverManipulator for CaDiCaLSolverManipulator {
+    unsafe fn value(&self, lit: Lit) -> Option<bool> {
+        (*self.state).value(lit)
+    }
+
+    unsafe fn add_watch(&mut self, lit: Lit) {
+        let state = &mut *self.state;
+        state.watches[lit.0 as usize].push(self.idx);
+        let var = lit.var().0 as usize;
+        if !state.observed[var] {
+            state.observed[var] = true;
+            CaDiCaL_AddObservedVar(state.solver, var as i32);
+        }
+    }
+
+    unsafe fn enqueue(&mut self, lit: Lit) -> bool {
+        let state = &mut *self.state;
+        debug_assert!(
+            state.observed[lit.var().0 as usize],
+            "propagated literals must be watched"
+        );
+        match state.value(lit) {
+            Some(true) => true,
+            Some(false) => {
+                state.failed_enqueue = Some(lit);
+                false
+            }
+            None => {
+                state.assign(lit);
+                true
+            }
+        }
+    }
+
+    unsafe fn is_current_level(&self, lit: Lit) -> bool {
+        let state = &*self.state;
+        state.level[lit.var().0 as usize] == state.decision_level()
+    }
+}
+
+unsafe fn hub_from_ptr<'a>(hub: *mut c_void) -> &'a mut PropagatorHub {
+    &mut *hub.cast::<PropagatorHub>()
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorNotifyAssignment(hub: *mut c_void, lit: Lit, is_fixed: i32) {
+    unsafe { hub_from_ptr(hub) }.notify_assignment(lit, is_fixed != 0);
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorNotifyNewDecisionLevel(hub: *mut c_void) {
+    let hub = unsafe { hub_from_ptr(hub) };
+    hub.state.trail_lim.push(hub.state.trail.len());
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorNotifyBacktrack(hub: *mut c_void, new_level: u64) {
+    unsafe { hub_from_ptr(hub) }.backtrack(new_level as usize);
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorCheckFoundModel(hub: *mut c_void) -> i32 {
+    if unsafe { hub_from_ptr(hub) }.check_found_model() {
+        1
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorPropagate(hub: *mut c_void) -> i32 {
+    unsafe { hub_from_ptr(hub) }.propagate()
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorAddReasonClauseLit(
+    hub: *mut c_void,
+    propagated_lit: Lit,
+) -> i32 {
+    unsafe { hub_from_ptr(hub) }.add_reason_clause_lit(propagated_lit)
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorHasExternalClause(hub: *mut c_void) -> i32 {
+    if unsafe { hub_from_ptr(hub) }
+        .state
+        .external_clauses
+        .is_empty()
+    {
+        0
+    } else {
+        1
+    }
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustExportLearntClause(ctx: *mut c_void, lits: *const Lit, n_lits: i32) {
+    let exported = unsafe { &mut *ctx.cast::<Vec<Vec<Lit>>>() };
+    let lits = unsafe { std::slice::from_raw_parts(lits, n_lits as usize) };
+    exported.push(lits.to_vec());
+}
+
+#[no_mangle]
+extern "C" fn CaDiCaL_RustPropagatorAddExternalClauseLit(hub: *mut c_void) -> i32 {
+    unsafe { hub_from_ptr(hub) }
+        .state
+        .next_external_clause_lit()
+}
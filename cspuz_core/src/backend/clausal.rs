@@ -0,0 +1,669 @@
+//! Clausal encodings of the constraints which are usually handled by native propagators.
+//!
+//! These are used by the backends which accept only clauses (e.g. the external backend).
+//! The encodings are much weaker and larger than the propagators, so they are meant as fallbacks.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::propagators::graph_division::GraphDivisionOptions;
+use crate::sat::{Lit, Var};
+
+/// A SAT solver which accepts only clauses.
+///
+/// The constraints are added by the clausal encodings below, and those which cannot be expressed by
+/// the clauses are reported by `mark_unsupported`. The methods return `false` if the constraint is
+/// found to be unsatisfiable, like the corresponding methods of `SAT`.
+pub(crate) trait ClauseSink: Sized {
+    fn new_var(&mut self) -> Var;
+    fn add_clause(&mut self, clause: &[Lit]);
+
+    /// Marks that a constraint which cannot be expressed by the clauses (`name`) was added.
+    fn mark_unsupported(&mut self, name: &'static str);
+
+    fn add_active_vertices_connected(&mut self, lits: &[Lit], edges: &[(usize, usize)]) -> bool {
+        add_active_vertices_connected(self, lits, edges);
+        true
+    }
+
+    fn add_active_edges_single_cycle(&mut self, lits: &[Lit], edges: &[(usize, usize)]) -> bool {
+        add_active_edges_single_cycle(self, lits, edges);
+        true
+    }
+
+    fn add_order_encoding_linear(
+        &mut self,
+        lits: &[Vec<Lit>],
+        domain: &[Vec<i32>],
+        coefs: &[i32],
+        constant: i32,
+    ) -> bool {
+        add_order_encoding_linear(self, lits, domain, coefs, constant)
+    }
+
+    fn add_graph_division(
+        &mut self,
+        domains: &[Vec<i32>],
+        dom_lits: &[Vec<Lit>],
+        edges: &[(usize, usize)],
+        edge_lits: &[Lit],
+        opts: &GraphDivisionOptions,
+    ) -> bool {
+        if opts.require_extra_constraints() {
+            self.mark_unsupported("add_graph_division with extra constraints");
+        } else {
+            add_graph_division(self, domains, dom_lits, edges, edge_lits, opts);
+        }
+        true
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    fn add_direct_encoding_extension_supports(
+        &mut self,
+        vars: &[Vec<Lit>],
+        supports: &[Vec<Option<usize>>],
+    ) -> bool {
+        add_direct_encoding_extension_supports(self, vars, supports);
+        true
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        add_direct_encoding_all_different(self, vars, values);
+        true
+    }
+}
+
+fn new_lit<S: ClauseSink>(sink: &mut S) -> Lit {
+    sink.new_var().as_lit(false)
+}
+
+/// Returns a literal which is always true.
+fn true_lit<S: ClauseSink>(sink: &mut S) -> Lit {
+    let ret = new_lit(sink);
+    sink.add_clause(&[ret]);
+    ret
+}
+
+/// Returns the literal meaning `x >= value`, where `x` is order-encoded by `domain` and `lits`
+/// (`lits[i]` means `x >= domain[i + 1]`). Constants are represented by `t` and `!t`.
+fn order_encoding_ge(domain: &[i32], lits: &[Lit], value: i32, t: Lit) -> Lit {
+    let idx = domain.partition_point(|&d| d < value);
+    if idx == 0 {
+        t
+    } else if idx == domain.len() {
+        !t
+    } else {
+        lits[idx - 1]
+    }
+}
+
+/// Returns `count` such that `count[k]` is true if and only if at least `k` of `lits` are true,
+/// for `k` in `0..=max_count`.
+fn add_counter<S: ClauseSink>(sink: &mut S, lits: &[Lit], max_count: usize, t: Lit) -> Vec<Lit> {
+    let mut count = vec![!t; max_count + 1];
+    count[0] = t;
+    for (i, &lit) in lits.iter().enumerate() {
+        let mut next = count.clone();
+        for k in 1..=max_count.min(i + 1) {
+            let c = new_lit(sink);
+            sink.add_clause(&[!count[k], c]);
+            sink.add_clause(&[!lit, !count[k - 1], c]);
+            sink.add_clause(&[!c, count[k], lit]);
+            sink.add_clause(&[!c, count[k], count[k - 1]]);
+            next[k] = c;
+        }
+        count = next;
+    }
+    count
+}
+
+/// Requires that every active vertex is reachable from the active vertex with the smallest id
+/// within `max_dist` steps. `neighbors[v]` lists `(u, cond)` meaning that `v` can be reached from `u`
+/// in one step if `cond` (if any) is true.
+fn add_reachability<S: ClauseSink>(
+    sink: &mut S,
+    active: &[Lit],
+    neighbors: &[Vec<(usize, Option<Lit>)>],
+    max_dist: usize,
+) {
+    let n = active.len();
+    if n <= 1 {
+        return;
+    }
+
+    // `before[v]` must be true if any of vertices `0..v` is active
+    let before = (0..n).map(|_| new_lit(sink)).collect::<Vec<_>>();
+    sink.add_clause(&[!before[0]]);
+    for v in 0..(n - 1) {
+        sink.add_clause(&[!active[v], before[v + 1]]);
+        sink.add_clause(&[!before[v], before[v + 1]]);
+    }
+
+    // `reached[v]` can be true only if `v` is reachable from the root within the current number of steps
+    let mut reached = (0..n)
+        .map(|v| {
+            let r = new_lit(sink);
+            sink.add_clause(&[!r, active[v]]);
+            sink.add_clause(&[!r, !before[v]]);
+            r
+        })
+        .collect::<Vec<_>>();
+    for _ in 0..max_dist {
+        let mut next = vec![];
+        for v in 0..n {
+            let r = new_lit(sink);
+            sink.add_clause(&[!r, active[v]]);
+            let mut clause = vec![!r, reached[v]];
+            for &(u, cond) in &neighbors[v] {
+                match cond {
+                    None => clause.push(reached[u]),
+                    Some(cond) => {
+                        let step = new_lit(sink);
+                        sink.add_clause(&[!step, reached[u]]);
+                        sink.add_clause(&[!step, cond]);
+                        clause.push(step);
+                    }
+                }
+            }
+            sink.add_clause(&clause);
+            next.push(r);
+        }
+        reached = next;
+    }
+
+    for v in 0..n {
+        sink.add_clause(&[!active[v], reached[v]]);
+    }
+}
+
+/// Encodes `SAT::add_active_vertices_connected`.
+fn add_active_vertices_connected<S: ClauseSink>(
+    sink: &mut S,
+    lits: &[Lit],
+    edges: &[(usize, usize)],
+) {
+    let n = lits.len();
+    let mut neighbors = vec![vec![]; n];
+    for &(u, v) in edges {
+        neighbors[u].push((v, None));
+        neighbors[v].push((u, None));
+    }
+    add_reachability(sink, lits, &neighbors, n.saturating_sub(1));
+}
+
+/// Encodes `SAT::add_active_edges_single_cycle`: the active edges are connected and each vertex is
+/// incident to at most two active edges.
+fn add_active_edges_single_cycle<S: ClauseSink>(
+    sink: &mut S,
+    lits: &[Lit],
+    edges: &[(usize, usize)],
+) {
+    assert_eq!(lits.len(), edges.len());
+
+    let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let mut incident = vec![vec![]; n];
+    let mut neighbors = vec![vec![]; n];
+    for (&lit, &(u, v)) in lits.iter().zip(edges) {
+        incident[u].push(lit);
+        incident[v].push(lit);
+        neighbors[u].push((v, Some(lit)));
+        neighbors[v].push((u, Some(lit)));
+    }
+
+    let mut active = vec![];
+    for edge_lits in &incident {
+        for i in 0..edge_lits.len() {
+            for j in (i + 1)..edge_lits.len() {
+                for k in (j + 1)..edge_lits.len() {
+                    sink.add_clause(&[!edge_lits[i], !edge_lits[j], !edge_lits[k]]);
+                }
+            }
+        }
+
+        let a = new_lit(sink);
+        let mut clause = vec![!a];
+        for &e in edge_lits {
+            sink.add_clause(&[!e, a]);
+            clause.push(e);
+        }
+        sink.add_clause(&clause);
+        active.push(a);
+    }
+
+    add_reachability(sink, &active, &neighbors, n.saturating_sub(1));
+}
+
+/// Encodes `SAT::add_order_encoding_linear` (`sum(coefs[i] * x_i) + constant >= 0`) by introducing
+/// order-encoded partial sums `x_0 + ... + x_k` whose domains are all the reachable values.
+/// Returns `false` if the constraint is trivially unsatisfiable.
+fn add_order_encoding_linear<S: ClauseSink>(
+    sink: &mut S,
+    lits: &[Vec<Lit>],
+    domain: &[Vec<i32>],
+    coefs: &[i32],
+    constant: i32,
+) -> bool {
+    assert_eq!(lits.len(), domain.len());
+    assert_eq!(lits.len(), coefs.len());
+
+    // Normalize each term `coef * x` into an order-encoded variable with ascending domain
+    let mut terms = vec![];
+    for i in 0..lits.len() {
+        assert_eq!(lits[i].len() + 1, domain[i].len());
+        let coef = coefs[i];
+        assert_ne!(coef, 0);
+        if coef > 0 {
+            let dom = domain[i].iter().map(|&d| d * coef).collect::<Vec<_>>();
+            terms.push((dom, lits[i].clone()));
+        } else {
+            let dom = domain[i]
+                .iter()
+                .rev()
+                .map(|&d| d * coef)
+                .collect::<Vec<_>>();
+            let l = lits[i].iter().rev().map(|&l| !l).collect::<Vec<_>>();
+            terms.push((dom, l));
+        }
+    }
+
+    let t = true_lit(sink);
+    let (mut sum_domain, mut sum_lits) = match terms.first() {
+        Some(term) => term.clone(),
+        None => (vec![0], vec![]),
+    };
+    for (term_domain, term_lits) in terms.iter().skip(1) {
+        let mut next_domain = BTreeSet::new();
+        for &a in &sum_domain {
+            for &b in term_domain {
+                next_domain.insert(a + b);
+            }
+        }
+        let next_domain = next_domain.into_iter().collect::<Vec<_>>();
+
+        // `next_lits[j]` implies that the partial sum is at least `next_domain[j + 1]`:
+        // if the previous partial sum is at most `d`, the term must be at least `v - d`.
+        let mut next_lits = vec![];
+        for &v in &next_domain[1..] {
+            let l = new_lit(sink);
+            for (j, &d) in sum_domain.iter().enumerate() {
+                let prev_gt = if j + 1 < sum_domain.len() {
+                    sum_lits[j]
+                } else {
+                    !t
+                };
+                let term_ge = order_encoding_ge(term_domain, term_lits, v - d, t);
+                sink.add_clause(&[!l, prev_gt, term_ge]);
+            }
+            next_lits.push(l);
+        }
+
+        sum_domain = next_domain;
+        sum_lits = next_lits;
+    }
+
+    let goal = order_encoding_ge(&sum_domain, &sum_lits, -constant, t);
+    if goal == !t {
+        sink.add_clause(&[]);
+        return false;
+    }
+    sink.add_clause(&[goal]);
+    true
+}
+
+/// Encodes `SAT::add_graph_division` without extra constraints (i.e. `opts.require_extra_constraints()`
+/// must be `false`). `edge_lits[i]` is true if the `i`-th edge is disconnected.
+///
+/// For each vertex `u`, a set closed under connected edges and containing `u` is introduced, which
+/// must not contain the other end of any disconnected edge incident to `u`. For a vertex with size,
+/// this set bounds the size of the region from above, and the vertices reachable from it within
+/// the maximum size bounds it from below.
+fn add_graph_division<S: ClauseSink>(
+    sink: &mut S,
+    domains: &[Vec<i32>],
+    dom_lits: &[Vec<Lit>],
+    edges: &[(usize, usize)],
+    edge_lits: &[Lit],
+    opts: &GraphDivisionOptions,
+) {
+    assert!(!opts.require_extra_constraints());
+    assert_eq!(domains.len(), dom_lits.len());
+    assert_eq!(edges.len(), edge_lits.len());
+
+    let n = domains.len();
+    let mut adj = vec![vec![]; n];
+    for (&(u, v), &lit) in edges.iter().zip(edge_lits) {
+        adj[u].push((v, lit));
+        adj[v].push((u, lit));
+    }
+
+    let t = true_lit(sink);
+    for u in 0..n {
+        if adj[u].is_empty() && domains[u].is_empty() {
+            continue;
+        }
+
+        let closure = (0..n)
+            .map(|v| if v == u { t } else { new_lit(sink) })
+            .collect::<Vec<_>>();
+        for (&(a, b), &lit) in edges.iter().zip(edge_lits) {
+            sink.add_clause(&[!closure[a], lit, closure[b]]);
+            sink.add_clause(&[!closure[b], lit, closure[a]]);
+        }
+        for &(v, lit) in &adj[u] {
+            sink.add_clause(&[!lit, !closure[v]]);
+        }
+
+        if domains[u].is_empty() {
+            continue;
+        }
+        let domain = &domains[u];
+        let lits = &dom_lits[u];
+        let max_size = (*domain.last().unwrap()).clamp(0, n as i32) as usize;
+
+        let upper = add_counter(sink, &closure, max_size + 1, t);
+        for (k, &c) in upper.iter().enumerate().skip(1) {
+            sink.add_clause(&[!c, order_encoding_ge(domain, lits, k as i32, t)]);
+        }
+
+        // `reached[v]` implies that `v` is connected to `u` by a path of at most `max_size - 1` edges
+        let mut dist = vec![usize::MAX; n];
+        let mut queue = VecDeque::new();
+        dist[u] = 0;
+        queue.push_back(u);
+        while let Some(v) = queue.pop_front() {
+            for &(w, _) in &adj[v] {
+                if dist[w] == usize::MAX {
+                    dist[w] = dist[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        let mut reached = (0..n)
+            .map(|v| if v == u { t } else { !t })
+            .collect::<Vec<_>>();
+        for step in 1..max_size {
+            let mut next = reached.clone();
+            for v in 0..n {
+                if v == u || dist[v] > step {
+                    continue;
+                }
+                let r = new_lit(sink);
+                let mut clause = vec![!r, reached[v]];
+                for &(w, lit) in &adj[v] {
+                    if dist[w] < step {
+                        let s = new_lit(sink);
+                        sink.add_clause(&[!s, reached[w]]);
+                        sink.add_clause(&[!s, !lit]);
+                        clause.push(s);
+                    }
+                }
+                sink.add_clause(&clause);
+                next[v] = r;
+            }
+            reached = next;
+        }
+
+        let lower = add_counter(sink, &reached, max_size, t);
+        for (k, &c) in lower.iter().enumerate().skip(1) {
+            sink.add_clause(&[!order_encoding_ge(domain, lits, k as i32, t), c]);
+        }
+    }
+}
+
+/// Encodes `SAT::add_direct_encoding_extension_supports` by introducing a selector for each support.
+#[cfg(feature = "csp-extra-constraints")]
+fn add_direct_encoding_extension_supports<S: ClauseSink>(
+    sink: &mut S,
+    vars: &[Vec<Lit>],
+    supports: &[Vec<Option<usize>>],
+) {
+    let mut clause = vec![];
+    for support in supports {
+        let s = new_lit(sink);
+        for (lits, &val) in vars.iter().zip(support) {
+            if let Some(val) = val {
+                sink.add_clause(&[!s, lits[val]]);
+            }
+        }
+        clause.push(s);
+    }
+    sink.add_clause(&clause);
+}
+
+/// Encodes `SAT::add_direct_encoding_all_different` by at-most-one clauses for each value.
+#[cfg(feature = "csp-extra-constraints")]
+fn add_direct_encoding_all_different<S: ClauseSink>(
+    sink: &mut S,
+    vars: &[Vec<Lit>],
+    values: &[Vec<i32>],
+) {
+    let mut lits_by_value = std::collections::BTreeMap::<i32, Vec<Lit>>::new();
+    for (lits, vals) in vars.iter().zip(values) {
+        for (&lit, &val) in lits.iter().zip(vals) {
+            lits_by_value.entry(val).or_default().push(lit);
+        }
+    }
+    for lits in lits_by_value.values() {
+        for i in 0..lits.len() {
+            for j in (i + 1)..lits.len() {
+                sink.add_clause(&[!lits[i], !lits[j]]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::glucose;
+    use crate::test_utils;
+
+    impl ClauseSink for glucose::Solver {
+        fn new_var(&mut self) -> Var {
+            glucose::Solver::new_var(self)
+        }
+
+        fn add_clause(&mut self, clause: &[Lit]) {
+            glucose::Solver::add_clause(self, clause);
+        }
+
+        fn mark_unsupported(&mut self, name: &'static str) {
+            panic!("unsupported: {}", name);
+        }
+    }
+
+    /// Checks that `encode` accepts exactly the assignments of `n` literals accepted by `checker`.
+    fn check_all_assignments<E, C>(n: usize, encode: E, checker: C)
+    where
+        E: Fn(&mut glucose::Solver, &[Lit]),
+        C: Fn(&[bool]) -> bool,
+    {
+        let mut solver = glucose::Solver::new();
+        let lits = (0..n)
+            .map(|_| solver.new_var().as_lit(false))
+            .collect::<Vec<_>>();
+        encode(&mut solver, &lits);
+
+        for mask in 0..(1u32 << n) {
+            let values = (0..n).map(|i| (mask >> i) & 1 != 0).collect::<Vec<_>>();
+            let assumptions = lits
+                .iter()
+                .zip(&values)
+                .map(|(&l, &v)| if v { l } else { !l })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                solver.solve_under(&assumptions).is_some(),
+                checker(&values),
+                "{:?}",
+                values
+            );
+        }
+    }
+
+    fn grid_edges(height: usize, width: usize) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                if y + 1 < height {
+                    edges.push((y * width + x, (y + 1) * width + x));
+                }
+                if x + 1 < width {
+                    edges.push((y * width + x, y * width + x + 1));
+                }
+            }
+        }
+        edges
+    }
+
+    #[test]
+    fn test_active_vertices_connected() {
+        let edges = grid_edges(3, 3);
+        check_all_assignments(
+            9,
+            |solver, lits| add_active_vertices_connected(solver, lits, &edges),
+            |values| test_utils::check_graph_active_vertices_connected(values, &edges),
+        );
+    }
+
+    #[test]
+    fn test_active_edges_single_cycle() {
+        let edges = grid_edges(3, 3);
+        check_all_assignments(
+            edges.len(),
+            |solver, lits| add_active_edges_single_cycle(solver, lits, &edges),
+            |values| test_utils::check_graph_active_edges_single_path_or_cycle(values, &edges),
+        );
+
+        // a vertex with degree 4
+        let edges = vec![(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (3, 4)];
+        check_all_assignments(
+            edges.len(),
+            |solver, lits| add_active_edges_single_cycle(solver, lits, &edges),
+            |values| test_utils::check_graph_active_edges_single_path_or_cycle(values, &edges),
+        );
+    }
+
+    /// Returns the value represented by the order encoding `values` over `domain`, or `None` if
+    /// `values` is not a valid order encoding.
+    fn order_encoding_value(domain: &[i32], values: &[bool]) -> Option<i32> {
+        let k = values.iter().take_while(|&&v| v).count();
+        if values[k..].iter().any(|&v| v) {
+            None
+        } else {
+            Some(domain[k])
+        }
+    }
+
+    fn add_order_encoding_clauses(solver: &mut glucose::Solver, lits: &[Lit]) {
+        for i in 1..lits.len() {
+            solver.add_clause(&[!lits[i], lits[i - 1]]);
+        }
+    }
+
+    #[test]
+    fn test_order_encoding_linear() {
+        let domain = vec![vec![0, 1, 3], vec![-1, 0, 2, 3], vec![1, 2]];
+
+        for (coefs, constant) in [
+            (vec![1, 1, 1], -4),
+            (vec![2, -1, 1], -1),
+            (vec![-1, -2, 3], 0),
+            (vec![1, 1, 1], -10),
+        ] {
+            check_all_assignments(
+                6,
+                |solver, lits| {
+                    let lits = vec![
+                        lits[0..2].to_vec(),
+                        lits[2..5].to_vec(),
+                        lits[5..6].to_vec(),
+                    ];
+                    for l in &lits {
+                        add_order_encoding_clauses(solver, l);
+                    }
+                    add_order_encoding_linear(solver, &lits, &domain, &coefs, constant);
+                },
+                |values| {
+                    let x = order_encoding_value(&domain[0], &values[0..2]);
+                    let y = order_encoding_value(&domain[1], &values[2..5]);
+                    let z = order_encoding_value(&domain[2], &values[5..6]);
+                    match (x, y, z) {
+                        (Some(x), Some(y), Some(z)) => {
+                            x * coefs[0] + y * coefs[1] + z * coefs[2] + constant >= 0
+                        }
+                        _ => false,
+                    }
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_graph_division() {
+        // 2x3 grid with sizes on the top-left and the bottom-right vertices
+        let edges = grid_edges(2, 3);
+        let size_domain = vec![1, 2, 3, 4];
+        let num_edges = edges.len();
+
+        check_all_assignments(
+            num_edges + 6,
+            |solver, lits| {
+                let mut domains = vec![vec![]; 6];
+                let mut dom_lits = vec![vec![]; 6];
+                domains[0] = size_domain.clone();
+                dom_lits[0] = lits[num_edges..(num_edges + 3)].to_vec();
+                domains[5] = size_domain.clone();
+                dom_lits[5] = lits[(num_edges + 3)..].to_vec();
+                add_order_encoding_clauses(solver, &dom_lits[0]);
+                add_order_encoding_clauses(solver, &dom_lits[5]);
+                add_graph_division(
+                    solver,
+                    &domains,
+                    &dom_lits,
+                    &edges,
+                    &lits[..num_edges],
+                    &GraphDivisionOptions::default(),
+                );
+            },
+            |values| {
+                let mut sizes = vec![None; 6];
+                sizes[0] = order_encoding_value(&size_domain, &values[num_edges..(num_edges + 3)]);
+                sizes[5] = order_encoding_value(&size_domain, &values[(num_edges + 3)..]);
+                if sizes[0].is_none() || sizes[5].is_none() {
+                    return false;
+                }
+                test_utils::check_graph_division(&sizes, &edges, &values[..num_edges])
+            },
+        );
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    #[test]
+    fn test_direct_encoding_extension_supports() {
+        let supports = vec![
+            vec![Some(0), Some(1)],
+            vec![Some(1), None],
+            vec![Some(2), Some(0)],
+        ];
+        check_all_assignments(
+            6,
+            |solver, lits| {
+                let vars = vec![lits[0..3].to_vec(), lits[3..6].to_vec()];
+                add_direct_encoding_extension_supports(solver, &vars, &supports)
+            },
+            |values| {
+                // `values[3 * i + j]` means that the `i`-th variable takes the `j`-th value
+                supports.iter().any(|support| {
+                    support
+                        .iter()
+                        .enumerate()
+                        .all(|(i, &v)| v.is_none_or(|v| values[3 * i + v]))
+                })
+            },
+        );
+    }
+}
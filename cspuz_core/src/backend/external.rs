@@ -1,20 +1,137 @@
+//! Backend which runs an external SAT solver process on the DIMACS CNF of the problem.
+//!
+//! The solver is expected to follow the output format of the SAT competition: a line `s SATISFIABLE`,
+//! `s UNSATISFIABLE` or `s UNKNOWN`, followed by the model in `v` lines if the problem is satisfiable.
+//! If no `s` line is printed, the conventional exit codes (10 for SAT and 20 for UNSAT) are used instead.
+
+use std::collections::hash_map::RandomState;
+use std::fs::OpenOptions;
+use std::hash::{BuildHasher, Hasher};
 use std::io::{Read, Write};
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::backend::clausal::ClauseSink;
+use crate::proof::lit_to_dimacs;
+use crate::sat::{ExternalSolverConfig, Lit, ResourceLimit, SolveResult, Var};
+
+/// The environment variable from which the solver command is taken if `ExternalSolverConfig::command` is not given.
+pub const SOLVER_COMMAND_ENV: &str = "ENIGMA_CSP_EXTERNAL_SOLVER";
+
+/// A failure in running the external solver.
+#[derive(Debug)]
+pub enum ExternalSolverError {
+    /// Neither `ExternalSolverConfig::command` nor `ENIGMA_CSP_EXTERNAL_SOLVER` is set.
+    NoCommand,
+    /// The problem contains a constraint which cannot be expressed by clauses.
+    Unsupported(&'static str),
+    /// The solver could not be started, or the communication with it failed.
+    Io(std::io::Error),
+    /// The solver printed something which is not a valid answer.
+    InvalidOutput(String),
+}
+
+impl std::fmt::Display for ExternalSolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExternalSolverError::NoCommand => write!(
+                f,
+                "external solver command is not specified (set {})",
+                SOLVER_COMMAND_ENV
+            ),
+            ExternalSolverError::Unsupported(name) => {
+                write!(f, "{} is not supported in external backend", name)
+            }
+            ExternalSolverError::Io(e) => write!(f, "failed to run external solver: {}", e),
+            ExternalSolverError::InvalidOutput(msg) => {
+                write!(f, "invalid output from external solver: {}", msg)
+            }
+        }
+    }
+}
 
-use crate::sat::{Lit, Var};
+impl std::error::Error for ExternalSolverError {}
+
+impl From<std::io::Error> for ExternalSolverError {
+    fn from(e: std::io::Error) -> ExternalSolverError {
+        ExternalSolverError::Io(e)
+    }
+}
+
+static INPUT_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary file holding the input of the solver, which is removed when dropped.
+struct InputFile {
+    path: PathBuf,
+}
+
+impl InputFile {
+    /// Creates a file with `content` in the temporary directory.
+    ///
+    /// As in `ProofLog::new`, the file name contains a random part and the file is created only if it
+    /// does not exist yet, so that other users cannot plant a file or a symlink at its path beforehand.
+    fn create(content: &[u8]) -> std::io::Result<InputFile> {
+        let id = INPUT_FILE_ID.fetch_add(1, Ordering::Relaxed);
+        loop {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_usize(id);
+            let path = std::env::temp_dir().join(format!(
+                "cspuz_core_external_{}_{}_{:016x}.cnf",
+                std::process::id(),
+                id,
+                hasher.finish()
+            ));
+
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut file = match options.open(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            };
+            let ret = InputFile { path };
+            file.write_all(content)?;
+            return Ok(ret);
+        }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for InputFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
 
 pub struct Solver {
+    config: ExternalSolverConfig,
     num_vars: i32,
     clauses: Vec<Vec<Lit>>,
     model: Vec<bool>,
+    unsupported: Option<&'static str>,
+    last_error: Option<ExternalSolverError>,
 }
 
 impl Solver {
     pub fn new() -> Solver {
+        Solver::with_config(ExternalSolverConfig::default())
+    }
+
+    pub fn with_config(config: ExternalSolverConfig) -> Solver {
         Solver {
+            config,
             num_vars: 0,
             clauses: vec![],
             model: vec![],
+            unsupported: None,
+            last_error: None,
         }
     }
 
@@ -37,6 +154,43 @@ impl Solver {
         true
     }
 
+    /// Marks that a constraint which cannot be expressed by the clauses (`name`) was added to the solver.
+    /// After this, the solver refuses to solve the problem or to write it.
+    pub fn mark_unsupported(&mut self, name: &'static str) {
+        if self.unsupported.is_none() {
+            self.unsupported = Some(name);
+        }
+    }
+
+    /// Returns the first constraint passed to `mark_unsupported`, if any.
+    pub fn unsupported(&self) -> Option<&'static str> {
+        self.unsupported
+    }
+
+    /// Returns why the last call of `solve_under_limited` returned `SolveResult::Unknown`.
+    /// This is `None` if the solver ran successfully (e.g. it reported `s UNKNOWN` or hit the time limit).
+    pub fn last_error(&self) -> Option<&ExternalSolverError> {
+        self.last_error.as_ref()
+    }
+
+    /// Writes the clauses added so far in the DIMACS CNF format. `assumptions` are written as unit clauses.
+    pub fn write_dimacs<W: Write>(&self, out: &mut W, assumptions: &[Lit]) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "p cnf {} {}",
+            self.num_vars,
+            self.clauses.len() + assumptions.len()
+        )?;
+        let unit_clauses = assumptions.iter().map(std::slice::from_ref);
+        for clause in self.clauses.iter().map(|c| &c[..]).chain(unit_clauses) {
+            for &l in clause {
+                write!(out, "{} ", lit_to_dimacs(l))?;
+            }
+            writeln!(out, "0")?;
+        }
+        Ok(())
+    }
+
     pub fn solve(&mut self) -> Option<Model<'_>> {
         if self.solve_without_model() {
             Some(unsafe { self.model() })
@@ -46,63 +200,122 @@ impl Solver {
     }
 
     pub fn solve_without_model(&mut self) -> bool {
-        let mut description = String::new();
-        description.push_str(&format!("p cnf {} {}\n", self.num_vars, self.clauses.len()));
-        for clause in &self.clauses {
-            for l in clause {
-                let n = (l.var().0 + 1) * if l.is_negated() { -1 } else { 1 };
-                description.push_str(&(n.to_string()));
-                description.push(' ');
-            }
-            description.push_str("0\n");
+        self.solve_under_without_model(&[])
+    }
+
+    pub fn solve_under(&mut self, assumptions: &[Lit]) -> Option<Model<'_>> {
+        if self.solve_under_without_model(assumptions) {
+            Some(unsafe { self.model() })
+        } else {
+            None
         }
+    }
 
-        let solver_command = std::env::var("ENIGMA_CSP_EXTERNAL_SOLVER").unwrap();
-        let process = Command::new(solver_command)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
-            .unwrap();
-
-        process
-            .stdin
-            .unwrap()
-            .write_all(description.as_bytes())
-            .unwrap();
-
-        let mut output = String::new();
-        process.stdout.unwrap().read_to_string(&mut output).unwrap();
-
-        let mut is_sat: Option<bool> = None;
-        let mut model = vec![false; self.num_vars as usize];
-
-        for line in output.split(&['\r', '\n']) {
-            if line.starts_with("s ") {
-                if line.starts_with("s UNSAT") {
-                    is_sat = Some(false);
-                } else if line.starts_with("s SAT") {
-                    is_sat = Some(true);
-                } else {
-                    panic!();
-                }
-            } else if line.starts_with("v ") {
-                for toks in line.split(' ').skip(1) {
-                    let n = toks.parse::<i32>().unwrap();
-                    if n == 0 {
-                        break;
-                    }
-                    if n > 0 {
-                        model[(n - 1) as usize] = true;
-                    }
+    /// Solve the problem under `assumptions`.
+    /// Since this cannot report failures of the solver, it panics if the solver does not give an answer.
+    /// Use `solve_under_limited` to handle them.
+    pub fn solve_under_without_model(&mut self, assumptions: &[Lit]) -> bool {
+        match self.solve_under_limited(assumptions, &ResourceLimit::unlimited()) {
+            SolveResult::Sat => true,
+            SolveResult::Unsat => false,
+            SolveResult::Unknown => match &self.last_error {
+                Some(e) => panic!("{}", e),
+                None => panic!("external solver could not solve the problem"),
+            },
+        }
+    }
+
+    /// Solve the problem under `assumptions`, killing the solver when the time limit in `limit` is exceeded.
+    /// The other limits are ignored.
+    ///
+    /// Since external solvers are invoked from scratch every time, assumptions are simply passed as unit clauses.
+    /// If the solver fails, `SolveResult::Unknown` is returned and the reason is available from `last_error`.
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+    ) -> SolveResult {
+        self.last_error = None;
+        match self.run(assumptions, limit.time) {
+            Ok((result, model)) => {
+                if result == SolveResult::Sat {
+                    self.model = model;
                 }
+                result
+            }
+            Err(e) => {
+                self.last_error = Some(e);
+                SolveResult::Unknown
             }
         }
+    }
 
-        if is_sat == Some(true) {
-            self.model = model;
+    fn run(
+        &self,
+        assumptions: &[Lit],
+        time_limit: Option<Duration>,
+    ) -> Result<(SolveResult, Vec<bool>), ExternalSolverError> {
+        if let Some(name) = self.unsupported {
+            return Err(ExternalSolverError::Unsupported(name));
         }
+        let command = match &self.config.command {
+            Some(command) => command.clone(),
+            None => {
+                std::env::var(SOLVER_COMMAND_ENV).map_err(|_| ExternalSolverError::NoCommand)?
+            }
+        };
+
+        let deadline = time_limit.map(|t| Instant::now() + t);
+        let mut description = vec![];
+        self.write_dimacs(&mut description, assumptions)?;
 
-        is_sat.unwrap()
+        let mut process = Command::new(command);
+        process.args(&self.config.args).stdout(Stdio::piped());
+        let input_file = if self.config.use_input_file {
+            let file = InputFile::create(&description)?;
+            process.arg(file.path()).stdin(Stdio::null());
+            Some(file)
+        } else {
+            process.stdin(Stdio::piped());
+            None
+        };
+        let mut child = process.spawn()?;
+
+        // The input and the output are transferred in separate threads so that a solver which starts
+        // writing before reading the whole input does not block.
+        let writer = child.stdin.take().map(|mut stdin| {
+            std::thread::spawn(move || {
+                // The solver may exit without reading the whole input, which is not an error by itself.
+                let _ = stdin.write_all(&description);
+            })
+        });
+        let mut stdout = child.stdout.take().unwrap();
+        let reader = std::thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+
+        let status = match deadline {
+            None => child.wait()?,
+            Some(deadline) => loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Ok((SolveResult::Unknown, vec![]));
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            },
+        };
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+        let output = reader.join().unwrap()?;
+        drop(input_file);
+
+        parse_output(&output, status, self.num_vars as usize)
     }
 
     pub(crate) unsafe fn model(&self) -> Model<'_> {
@@ -110,6 +323,91 @@ impl Solver {
     }
 }
 
+impl ClauseSink for Solver {
+    fn new_var(&mut self) -> Var {
+        Solver::new_var(self)
+    }
+
+    fn add_clause(&mut self, clause: &[Lit]) {
+        Solver::add_clause(self, clause);
+    }
+
+    fn mark_unsupported(&mut self, name: &'static str) {
+        Solver::mark_unsupported(self, name);
+    }
+}
+
+fn parse_output(
+    output: &str,
+    status: ExitStatus,
+    num_vars: usize,
+) -> Result<(SolveResult, Vec<bool>), ExternalSolverError> {
+    let mut result = None;
+    let mut model = vec![false; num_vars];
+    let mut has_model = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(answer) = line.strip_prefix("s ") {
+            let r = match answer.trim() {
+                "SATISFIABLE" => SolveResult::Sat,
+                "UNSATISFIABLE" => SolveResult::Unsat,
+                "UNKNOWN" | "INDETERMINATE" => SolveResult::Unknown,
+                _ => {
+                    return Err(ExternalSolverError::InvalidOutput(format!(
+                        "unknown answer: {}",
+                        line
+                    )))
+                }
+            };
+            if result.is_some_and(|prev| prev != r) {
+                return Err(ExternalSolverError::InvalidOutput(String::from(
+                    "conflicting answers",
+                )));
+            }
+            result = Some(r);
+        } else if let Some(values) = line.strip_prefix("v ") {
+            has_model = true;
+            for tok in values.split_whitespace() {
+                let n = tok.parse::<i32>().map_err(|_| {
+                    ExternalSolverError::InvalidOutput(format!("invalid literal: {}", tok))
+                })?;
+                if n == 0 {
+                    break;
+                }
+                let idx = n.unsigned_abs() as usize - 1;
+                if idx >= num_vars {
+                    return Err(ExternalSolverError::InvalidOutput(format!(
+                        "unknown variable: {}",
+                        n
+                    )));
+                }
+                model[idx] = n > 0;
+            }
+        }
+    }
+
+    let result = match result {
+        Some(result) => result,
+        None => match status.code() {
+            Some(10) => SolveResult::Sat,
+            Some(20) => SolveResult::Unsat,
+            _ => {
+                return Err(ExternalSolverError::InvalidOutput(format!(
+                    "no answer is given (exit status: {})",
+                    status
+                )))
+            }
+        },
+    };
+    if result == SolveResult::Sat && !has_model && num_vars > 0 {
+        return Err(ExternalSolverError::InvalidOutput(String::from(
+            "no model is given for a satisfiable problem",
+        )));
+    }
+    Ok((result, model))
+}
+
 pub struct Model<'a> {
     solver: &'a Solver,
 }
@@ -119,3 +417,145 @@ impl Model<'_> {
         self.solver.model[var.0 as usize]
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Creates a solver running `script` by `sh` in place of a SAT solver.
+    fn mock_solver(script: &str, use_input_file: bool) -> Solver {
+        Solver::with_config(ExternalSolverConfig {
+            command: Some(String::from("sh")),
+            args: vec![String::from("-c"), String::from(script), String::from("sh")],
+            use_input_file,
+        })
+    }
+
+    fn add_problem(solver: &mut Solver) -> (Var, Var) {
+        let x = solver.new_var();
+        let y = solver.new_var();
+        solver.add_clause(&[Lit::new(x, false), Lit::new(y, true)]);
+        (x, y)
+    }
+
+    #[test]
+    fn test_external_sat() {
+        let mut solver = mock_solver(
+            "cat > /dev/null; echo 'c comment'; echo 's SATISFIABLE'; echo 'v 1 -2'; echo 'v 0'",
+            false,
+        );
+        let (x, y) = add_problem(&mut solver);
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+            SolveResult::Sat
+        );
+        let model = unsafe { solver.model() };
+        assert!(model.assignment(x));
+        assert!(!model.assignment(y));
+    }
+
+    #[test]
+    fn test_external_input() {
+        // The solver answers UNSAT only if it receives the expected problem.
+        let script = "grep -q '^1 -2 0$' && echo 's UNSATISFIABLE'";
+        let mut solver = mock_solver(script, false);
+        add_problem(&mut solver);
+        assert!(!solver.solve_without_model());
+
+        let script = "grep -q '^p cnf 2 2$' \"$1\" && echo 's UNSATISFIABLE'";
+        let mut solver = mock_solver(script, true);
+        let (x, _) = add_problem(&mut solver);
+        assert!(!solver.solve_under_without_model(&[Lit::new(x, true)]));
+    }
+
+    #[test]
+    fn test_external_exit_code() {
+        let mut solver = mock_solver("cat > /dev/null; exit 20", false);
+        add_problem(&mut solver);
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+            SolveResult::Unsat
+        );
+    }
+
+    #[test]
+    fn test_external_unknown() {
+        let mut solver = mock_solver("cat > /dev/null; echo 's UNKNOWN'", false);
+        add_problem(&mut solver);
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+            SolveResult::Unknown
+        );
+        assert!(solver.last_error().is_none());
+    }
+
+    #[test]
+    fn test_external_time_limit() {
+        let mut solver = mock_solver("sleep 10", false);
+        add_problem(&mut solver);
+        let limit = ResourceLimit {
+            time: Some(Duration::from_millis(100)),
+            ..ResourceLimit::unlimited()
+        };
+        let start = Instant::now();
+        assert_eq!(
+            solver.solve_under_limited(&[], &limit),
+            SolveResult::Unknown
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(solver.last_error().is_none());
+    }
+
+    #[test]
+    fn test_external_errors() {
+        let scripts = [
+            "cat > /dev/null; echo 's SATISFIABLE'",
+            "cat > /dev/null; echo 's SATISFIABLE'; echo 'v 1 3 0'",
+            "cat > /dev/null; echo 's SATISFIABLE'; echo 'v 1 x 0'",
+            "cat > /dev/null; echo 's MAYBE'",
+            "cat > /dev/null; echo 'segmentation fault'; exit 1",
+        ];
+        for script in scripts {
+            let mut solver = mock_solver(script, false);
+            add_problem(&mut solver);
+            assert_eq!(
+                solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+                SolveResult::Unknown
+            );
+            assert!(
+                matches!(
+                    solver.last_error(),
+                    Some(ExternalSolverError::InvalidOutput(_))
+                ),
+                "{}",
+                script
+            );
+        }
+
+        let mut solver = Solver::with_config(ExternalSolverConfig {
+            command: Some(String::from("/nonexistent/sat_solver")),
+            ..ExternalSolverConfig::default()
+        });
+        add_problem(&mut solver);
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+            SolveResult::Unknown
+        );
+        assert!(matches!(
+            solver.last_error(),
+            Some(ExternalSolverError::Io(_))
+        ));
+
+        let mut solver = mock_solver("echo 's UNSATISFIABLE'", false);
+        add_problem(&mut solver);
+        solver.mark_unsupported("add_graph_division");
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited()),
+            SolveResult::Unknown
+        );
+        assert!(matches!(
+            solver.last_error(),
+            Some(ExternalSolverError::Unsupported("add_graph_division"))
+        ));
+    }
+}
@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::ffi::{c_void, CString};
 use std::ops::Drop;
 use std::os::raw::c_char;
+use std::sync::atomic::AtomicBool;
 
+use crate::proof::{Proof, ProofLog};
+use crate::propagators::active_vertices_connected::ActiveVerticesConnected;
 use crate::propagators::graph_division::{GraphDivision, GraphDivisionOptions};
 use crate::propagators::order_encoding_linear;
+use crate::propagators::single_cycle::ActiveEdgesSingleCycle;
 use crate::sat::{
-    CustomPropagator, GraphDivisionMode, Lit, OrderEncodingLinearMode, SolverManipulator, Var,
+    CustomPropagator, GraphDivisionMode, Lit, OrderEncodingLinearMode, ResourceLimit, SolveResult,
+    SolverManipulator, Var,
 };
 
 #[repr(C)]
@@ -20,6 +26,16 @@ extern "C-unwind" {
     fn Glucose_NewNamedVar(solver: *mut Opaque, name: *const c_char) -> i32;
     fn Glucose_AddClause(solver: *mut Opaque, lits: *const Lit, n_lits: i32) -> i32;
     fn Glucose_Solve(solver: *mut Opaque) -> i32;
+    fn Glucose_SolveUnder(solver: *mut Opaque, assumptions: *const Lit, n_assumptions: i32) -> i32;
+    fn Glucose_SolveLimited(
+        solver: *mut Opaque,
+        assumptions: *const Lit,
+        n_assumptions: i32,
+        conflicts: i64,
+        propagations: i64,
+        time_limit: f64,
+        interrupt: *const u8,
+    ) -> i32;
     fn Glucose_NumVar(solver: *mut Opaque) -> i32;
     fn Glucose_GetModelValueVar(solver: *mut Opaque, var: i32) -> i32;
     fn Glucose_SetPolarity(solver: *mut Opaque, var: i32, polarity: i32);
@@ -63,6 +79,9 @@ extern "C-unwind" {
     fn Glucose_Set_random_seed(solver: *mut Opaque, random_seed: f64);
     fn Glucose_Set_rnd_init_act(solver: *mut Opaque, rnd_init_act: i32);
     fn Glucose_Set_dump_analysis_info(solver: *mut Opaque, value: i32);
+    fn Glucose_EnableProof(solver: *mut Opaque, path: *const c_char) -> i32;
+    fn Glucose_FlushProof(solver: *mut Opaque);
+    fn Glucose_CloseProof(solver: *mut Opaque);
 }
 
 pub struct Solver {
@@ -74,6 +93,8 @@ pub struct Solver {
     // https://doc.rust-lang.org/reference/type-layout.html#r-layout.pointer.unsized
     #[allow(clippy::vec_box)]
     custom_constraints: Vec<Box<Box<dyn CustomPropagator<GlucoseSolverManipulator>>>>,
+
+    proof: Option<ProofLog>,
 }
 
 const NUM_VAR_MAX: i32 = 0x3fffffff;
@@ -83,9 +104,35 @@ impl Solver {
         Solver {
             ptr: unsafe { Glucose_CreateSolver() },
             custom_constraints: vec![],
+            proof: None,
         }
     }
 
+    /// Makes the solver write a DRAT proof. This must be called before any clause is added.
+    /// Returns `false` if the proof cannot be written.
+    pub fn enable_proof(&mut self) -> bool {
+        assert!(self.proof.is_none());
+        let Ok(log) = ProofLog::new() else {
+            return false;
+        };
+        let Some(path) = log.path().to_str().and_then(|p| CString::new(p).ok()) else {
+            return false;
+        };
+        if unsafe { Glucose_EnableProof(self.ptr, path.as_ptr()) } == 0 {
+            return false;
+        }
+        self.proof = Some(log);
+        true
+    }
+
+    /// Returns the proof written so far, which refutes the clauses added so far if the last solve
+    /// (without assumptions) reported unsatisfiability.
+    pub fn proof(&self) -> Result<Proof, String> {
+        let log = self.proof.as_ref().ok_or("proof is not enabled")?;
+        unsafe { Glucose_FlushProof(self.ptr) };
+        log.proof(self.num_var() as usize)
+    }
+
     pub fn new_var(&mut self) -> Var {
         let var_id = unsafe { Glucose_NewVar(self.ptr) };
         assert!((0..=NUM_VAR_MAX).contains(&var_id));
@@ -114,6 +161,9 @@ impl Solver {
 
     pub fn add_clause(&mut self, clause: &[Lit]) -> bool {
         assert!(clause.len() <= i32::MAX as usize);
+        if let Some(proof) = &mut self.proof {
+            proof.add_clause(clause);
+        }
         let res = unsafe { Glucose_AddClause(self.ptr, clause.as_ptr(), clause.len() as i32) };
         res != 0
     }
@@ -135,6 +185,13 @@ impl Solver {
             assert_eq!(lits[i].len() + 1, domain[i].len());
         }
 
+        // The reasons of C++ propagators cannot be logged in proofs.
+        let mode = if self.proof.is_some() && mode == OrderEncodingLinearMode::Cpp {
+            OrderEncodingLinearMode::RustOptimized
+        } else {
+            mode
+        };
+
         match mode {
             OrderEncodingLinearMode::Cpp => {
                 let n_terms = lits.len() as i32;
@@ -179,6 +236,14 @@ impl Solver {
         assert!(lits.len() <= i32::MAX as usize);
         assert!(edges.len() <= i32::MAX as usize);
 
+        if self.proof.is_some() {
+            // The reasons of the C++ propagator cannot be logged in proofs.
+            return self.add_custom_constraint(Box::new(ActiveVerticesConnected::new(
+                lits.to_vec(),
+                edges.to_vec(),
+            )));
+        }
+
         let mut edges_flat = vec![];
         for &(u, v) in edges {
             assert!(u < lits.len());
@@ -204,6 +269,16 @@ impl Solver {
         vars: &[Vec<Lit>],
         supports: &[Vec<Option<usize>>],
     ) -> bool {
+        #[cfg(feature = "csp-extra-constraints")]
+        if self.proof.is_some() {
+            use crate::propagators::direct_encoding_extension::DirectEncodingExtensionSupports;
+
+            return self.add_custom_constraint(Box::new(DirectEncodingExtensionSupports::new(
+                vars.to_vec(),
+                supports.to_vec(),
+            )));
+        }
+
         let mut len_total = 0;
         for v in vars {
             len_total += v.len();
@@ -239,6 +314,31 @@ impl Solver {
         res != 0
     }
 
+    pub fn add_active_edges_single_cycle(
+        &mut self,
+        lits: &[Lit],
+        edges: &[(usize, usize)],
+    ) -> bool {
+        self.add_custom_constraint(Box::new(ActiveEdgesSingleCycle::new(
+            lits.to_vec(),
+            edges.to_vec(),
+        )))
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        use crate::propagators::all_different::DirectEncodingAllDifferent;
+
+        self.add_custom_constraint(Box::new(DirectEncodingAllDifferent::new(
+            vars.to_vec(),
+            values.to_vec(),
+        )))
+    }
+
     pub fn add_graph_division(
         &mut self,
         domains: &[Vec<i32>],
@@ -248,7 +348,7 @@ impl Solver {
         mode: GraphDivisionMode,
         opts: &GraphDivisionOptions,
     ) -> bool {
-        if mode == GraphDivisionMode::Rust {
+        if mode == GraphDivisionMode::Rust || self.proof.is_some() {
             let vertex_weights = vec![1; domains.len()];
             let constr =
                 GraphDivision::new(domains, dom_lits, &vertex_weights, edges, edge_lits, opts);
@@ -301,12 +401,21 @@ impl Solver {
         &mut self,
         constraint: Box<dyn CustomPropagator<GlucoseSolverManipulator>>,
     ) -> bool {
+        let constraint = match &self.proof {
+            Some(proof) => Box::new(EagerReasons::new(proof.wrap_propagator(constraint))),
+            None => constraint,
+        };
         self.custom_constraints.push(Box::new(constraint));
         #[allow(clippy::borrowed_box)]
         let c: &Box<dyn CustomPropagator<GlucoseSolverManipulator>> =
             &self.custom_constraints[self.custom_constraints.len() - 1];
         let c = unsafe { std::mem::transmute::<_, *mut c_void>(c) };
         let res = unsafe { Glucose_AddRustExtraConstraint(self.ptr, c) };
+        if res == 0 {
+            if let Some(proof) = &mut self.proof {
+                proof.mark_refuted_before_solving();
+            }
+        }
         res != 0
     }
 
@@ -339,10 +448,69 @@ impl Solver {
         res != 0
     }
 
+    pub fn solve_under(&mut self, assumptions: &[Lit]) -> Option<Model<'_>> {
+        if self.solve_under_without_model(assumptions) {
+            Some(unsafe { self.model() })
+        } else {
+            None
+        }
+    }
+
+    pub fn solve_under_without_model(&mut self, assumptions: &[Lit]) -> bool {
+        let res =
+            unsafe { Glucose_SolveUnder(self.ptr, assumptions.as_ptr(), assumptions.len() as i32) };
+        res != 0
+    }
+
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+        interrupt: Option<&AtomicBool>,
+    ) -> SolveResult {
+        let res = unsafe {
+            Glucose_SolveLimited(
+                self.ptr,
+                assumptions.as_ptr(),
+                assumptions.len() as i32,
+                limit
+                    .conflicts
+                    .map_or(-1, |x| x.min(i64::MAX as u64) as i64),
+                limit
+                    .propagations
+                    .map_or(-1, |x| x.min(i64::MAX as u64) as i64),
+                limit.time.map_or(-1.0, |x| x.as_secs_f64()),
+                interrupt.map_or(std::ptr::null(), |x| x.as_ptr() as *const u8),
+            )
+        };
+        match res {
+            1 => SolveResult::Sat,
+            0 => SolveResult::Unsat,
+            _ => SolveResult::Unknown,
+        }
+    }
+
     pub(crate) unsafe fn model(&self) -> Model<'_> {
         Model { solver: self }
     }
 
+    /// Returns the literals assigned at the root level, which are the consequences of the clauses and
+    /// the constraints. Glucose backtracks to the root level at the end of every solve, so this must be
+    /// called between solves.
+    #[cfg(feature = "portfolio")]
+    pub(crate) fn root_level_lits(&self) -> Vec<Lit> {
+        let mut ret = vec![];
+        for v in 0..self.num_var() {
+            let lit = Var(v).as_lit(false);
+            match unsafe { Glucose_SolverValue(self.ptr, lit) } {
+                0 => ret.push(lit),
+                1 => ret.push(!lit),
+                _ => (),
+            }
+        }
+        ret
+    }
+
     pub fn stats_decisions(&self) -> u64 {
         unsafe { Glucose_SolverStats_decisions(self.ptr) }
     }
@@ -359,6 +527,9 @@ impl Solver {
 impl Drop for Solver {
     fn drop(&mut self) {
         unsafe {
+            if self.proof.is_some() {
+                Glucose_CloseProof(self.ptr);
+            }
             Glucose_DestroySolver(self.ptr);
         }
     }
@@ -390,10 +561,18 @@ extern "C-unwind" {
     fn Glucose_IsCurrentLevel(solver: *mut Opaque, lit: Lit) -> i32;
 }
 
+/// The literals enqueued by a propagator, recorded by `GlucoseSolverManipulator` for `EagerReasons`.
+#[derive(Default)]
+struct EnqueueLog {
+    enqueued: Vec<Lit>,
+    failed: Option<Lit>,
+}
+
 #[derive(Clone, Copy)]
 pub struct GlucoseSolverManipulator {
     ptr: *mut Opaque,
     wrapper_object: Option<*mut c_void>,
+    enqueue_log: Option<*mut EnqueueLog>,
 }
 
 unsafe impl SolverManipulator for GlucoseSolverManipulator {
@@ -414,7 +593,18 @@ unsafe impl SolverManipulator for GlucoseSolverManipulator {
 
     unsafe fn enqueue(&mut self, lit: Lit) -> bool {
         assert!(self.wrapper_object.is_some());
-        Glucose_SolverEnqueue(self.ptr, lit, self.wrapper_object.unwrap()) != 0
+        let Some(log) = self.enqueue_log else {
+            return Glucose_SolverEnqueue(self.ptr, lit, self.wrapper_object.unwrap()) != 0;
+        };
+        let is_new = self.value(lit).is_none();
+        let res = Glucose_SolverEnqueue(self.ptr, lit, self.wrapper_object.unwrap()) != 0;
+        let log = &mut *log;
+        if !res {
+            log.failed = Some(lit);
+        } else if is_new {
+            log.enqueued.push(lit);
+        }
+        res
     }
 
     unsafe fn is_current_level(&self, lit: Lit) -> bool {
@@ -422,6 +612,92 @@ unsafe impl SolverManipulator for GlucoseSolverManipulator {
     }
 }
 
+/// Wraps a propagator so that the reasons of its propagations and conflicts are computed as soon as
+/// they are made, which makes the wrapped `ReasonLogger` record them as theory lemmas even if Glucose
+/// never asks for them (as for the propagations at the top level).
+struct EagerReasons {
+    inner: Box<dyn CustomPropagator<GlucoseSolverManipulator>>,
+    // The latest reason of each literal enqueued by `inner`
+    reasons: HashMap<Lit, Vec<Lit>>,
+    conflict: Option<Vec<Lit>>,
+}
+
+impl EagerReasons {
+    fn new(inner: Box<dyn CustomPropagator<GlucoseSolverManipulator>>) -> EagerReasons {
+        EagerReasons {
+            inner,
+            reasons: HashMap::new(),
+            conflict: None,
+        }
+    }
+
+    fn run<F>(&mut self, solver: &mut GlucoseSolverManipulator, f: F) -> bool
+    where
+        F: FnOnce(
+            &mut dyn CustomPropagator<GlucoseSolverManipulator>,
+            &mut GlucoseSolverManipulator,
+        ) -> bool,
+    {
+        let mut log = EnqueueLog::default();
+        let mut logging_solver = GlucoseSolverManipulator {
+            enqueue_log: Some(&mut log as *mut EnqueueLog),
+            ..*solver
+        };
+        let res = f(self.inner.as_mut(), &mut logging_solver);
+
+        for lit in log.enqueued {
+            let reason = self.inner.calc_reason(solver, Some(lit), None);
+            self.reasons.insert(lit, reason);
+        }
+        self.conflict = if res {
+            None
+        } else {
+            Some(self.inner.calc_reason(solver, None, log.failed.map(|l| !l)))
+        };
+        res
+    }
+}
+
+unsafe impl CustomPropagator<GlucoseSolverManipulator> for EagerReasons {
+    fn initialize(&mut self, solver: &mut GlucoseSolverManipulator) -> bool {
+        self.run(solver, |inner, solver| inner.initialize(solver))
+    }
+
+    fn propagate(
+        &mut self,
+        solver: &mut GlucoseSolverManipulator,
+        p: Lit,
+        num_pending_propagations: i32,
+    ) -> bool {
+        self.run(solver, |inner, solver| {
+            inner.propagate(solver, p, num_pending_propagations)
+        })
+    }
+
+    fn calc_reason(
+        &mut self,
+        _: &mut GlucoseSolverManipulator,
+        p: Option<Lit>,
+        extra: Option<Lit>,
+    ) -> Vec<Lit> {
+        let mut ret = match p {
+            Some(p) => self.reasons[&p].clone(),
+            None => self.conflict.clone().unwrap(),
+        };
+        if let Some(extra) = extra {
+            if !ret.contains(&extra) {
+                ret.push(extra);
+            }
+        }
+        ret
+    }
+
+    fn undo(&mut self, solver: &mut GlucoseSolverManipulator, p: Lit) {
+        self.conflict = None;
+        self.inner.undo(solver, p)
+    }
+}
+
 #[no_mangle]
 extern "C-unwind" fn Glucose_CallCustomPropagatorInitialize(
     solver: *mut Opaque,
@@ -433,6 +709,7 @@ extern "C-unwind" fn Glucose_CallCustomPropagatorInitialize(
     let res = trait_object.initialize(&mut GlucoseSolverManipulator {
         ptr: solver,
         wrapper_object: Some(wrapper_object),
+        enqueue_log: None,
     });
     if res {
         1
@@ -455,6 +732,7 @@ extern "C-unwind" fn Glucose_CallCustomPropagatorPropagate(
         &mut GlucoseSolverManipulator {
             ptr: solver,
             wrapper_object: Some(wrapper_object),
+            enqueue_log: None,
         },
         p,
         num_pending_propagations,
@@ -480,6 +758,7 @@ extern "C-unwind" fn Glucose_CallCustomPropagatorCalcReason(
         &mut GlucoseSolverManipulator {
             ptr: solver,
             wrapper_object: None,
+            enqueue_log: None,
         },
         if p != LIT_UNDEF { Some(p) } else { None },
         if extra != LIT_UNDEF {
@@ -505,6 +784,7 @@ extern "C-unwind" fn Glucose_CallCustomPropagatorUndo(
         &mut GlucoseSolverManipulator {
             ptr: solver,
             wrapper_object: None,
+            enqueue_log: None,
         },
         p,
     );
@@ -9,6 +9,7 @@ use glucose_rs::types::{LBool as RawLBool, Lit as RawLit};
 
 use crate::propagators::graph_division::{GraphDivision, GraphDivisionOptions};
 use crate::propagators::order_encoding_linear;
+use crate::propagators::single_cycle::ActiveEdgesSingleCycle;
 use crate::sat::{
     CustomPropagator, GraphDivisionMode, Lit, OrderEncodingLinearMode, SolverManipulator, Var,
 };
@@ -221,6 +222,31 @@ impl Solver {
             )))
     }
 
+    pub fn add_active_edges_single_cycle(
+        &mut self,
+        lits: &[Lit],
+        edges: &[(usize, usize)],
+    ) -> bool {
+        self.add_custom_constraint(Box::new(ActiveEdgesSingleCycle::new(
+            lits.to_vec(),
+            edges.to_vec(),
+        )))
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        use crate::propagators::all_different::DirectEncodingAllDifferent;
+
+        self.add_custom_constraint(Box::new(DirectEncodingAllDifferent::new(
+            vars.to_vec(),
+            values.to_vec(),
+        )))
+    }
+
     pub fn add_graph_division(
         &mut self,
         domains: &[Vec<i32>],
@@ -0,0 +1,486 @@
+//! Backend which loads an IPASIR-compliant SAT solver from a shared library at runtime.
+//!
+//! IPASIR is the incremental interface of the SAT competition (`ipasir_init`, `ipasir_add`,
+//! `ipasir_assume`, `ipasir_solve`, `ipasir_val`, ...), which is implemented by many solvers.
+//! For example, the vendored CaDiCaL can be built as such a library (on Linux) by
+//!
+//! ```sh
+//! cd lib/cadical && CXXFLAGS=-fPIC ./configure && make
+//! c++ -shared -o libcadical.so -Wl,--whole-archive build/libcadical.a -Wl,--no-whole-archive
+//! ```
+//!
+//! and used by `--backend ipasir:/path/to/libcadical.so`.
+//!
+//! Since IPASIR accepts only clauses, the other constraints are encoded by the clausal fallbacks
+//! in `crate::backend::clausal`.
+
+use std::collections::BTreeMap;
+use std::ffi::{c_int, c_void, CStr};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::backend::clausal::ClauseSink;
+use crate::proof::lit_to_dimacs;
+use crate::sat::{Lit, ResourceLimit, SolveResult, Var};
+
+/// A failure in using an IPASIR library.
+#[derive(Debug)]
+pub enum IpasirError {
+    /// The library could not be loaded, or it does not provide a required function.
+    Load(String),
+    /// The problem contains a constraint which cannot be expressed by clauses.
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for IpasirError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IpasirError::Load(msg) => write!(f, "failed to load IPASIR library: {}", msg),
+            IpasirError::Unsupported(name) => {
+                write!(f, "{} is not supported in IPASIR backend", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IpasirError {}
+
+#[cfg(unix)]
+mod dl {
+    use std::ffi::{c_char, c_int, c_void, CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    // These are provided by the C library (or libdl, which is linked by `std` where needed).
+    extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+        fn dlerror() -> *mut c_char;
+    }
+
+    const RTLD_NOW: c_int = 2;
+
+    fn last_error() -> String {
+        let msg = unsafe { dlerror() };
+        if msg.is_null() {
+            String::from("unknown error")
+        } else {
+            unsafe { CStr::from_ptr(msg) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    pub fn open(path: &Path) -> Result<*mut c_void, String> {
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+        let handle = unsafe { dlopen(path.as_ptr(), RTLD_NOW) };
+        if handle.is_null() {
+            Err(last_error())
+        } else {
+            Ok(handle)
+        }
+    }
+
+    pub fn symbol(handle: *mut c_void, name: &CStr) -> Result<*mut c_void, String> {
+        let ptr = unsafe { dlsym(handle, name.as_ptr()) };
+        if ptr.is_null() {
+            Err(format!("symbol {} not found", name.to_string_lossy()))
+        } else {
+            Ok(ptr)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod dl {
+    use std::ffi::{c_void, CStr};
+    use std::path::Path;
+
+    pub fn open(_path: &Path) -> Result<*mut c_void, String> {
+        Err(String::from("dynamic loading is supported only on Unix"))
+    }
+
+    pub fn symbol(_handle: *mut c_void, _name: &CStr) -> Result<*mut c_void, String> {
+        unreachable!();
+    }
+}
+
+type TerminateCallback = unsafe extern "C" fn(data: *mut c_void) -> c_int;
+
+/// The functions of a loaded IPASIR library.
+struct Library {
+    signature: String,
+    init: unsafe extern "C" fn() -> *mut c_void,
+    release: unsafe extern "C" fn(solver: *mut c_void),
+    add: unsafe extern "C" fn(solver: *mut c_void, lit_or_zero: i32),
+    assume: unsafe extern "C" fn(solver: *mut c_void, lit: i32),
+    solve: unsafe extern "C" fn(solver: *mut c_void) -> c_int,
+    val: unsafe extern "C" fn(solver: *mut c_void, lit: i32) -> i32,
+    set_terminate: unsafe extern "C" fn(
+        solver: *mut c_void,
+        data: *mut c_void,
+        terminate: Option<TerminateCallback>,
+    ),
+}
+
+// IPASIR functions are safe to call from any thread as long as each solver instance is used by
+// a single thread at a time, which is guaranteed by `Solver` not being `Send`.
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+// Libraries are never unloaded, since solvers written in C++ may not be unloaded safely.
+static LIBRARIES: Mutex<BTreeMap<PathBuf, &'static Library>> = Mutex::new(BTreeMap::new());
+
+impl Library {
+    fn get(path: &Path) -> Result<&'static Library, IpasirError> {
+        let mut libraries = LIBRARIES.lock().unwrap();
+        if let Some(&library) = libraries.get(path) {
+            return Ok(library);
+        }
+        let library = Box::leak(Box::new(Library::load(path).map_err(IpasirError::Load)?));
+        libraries.insert(path.to_owned(), library);
+        Ok(library)
+    }
+
+    fn load(path: &Path) -> Result<Library, String> {
+        let handle = dl::open(path)?;
+        let signature: unsafe extern "C" fn() -> *const std::ffi::c_char =
+            unsafe { symbol(handle, c"ipasir_signature")? };
+        let signature = unsafe { CStr::from_ptr(signature()) }
+            .to_string_lossy()
+            .into_owned();
+
+        Ok(Library {
+            signature,
+            init: unsafe { symbol(handle, c"ipasir_init")? },
+            release: unsafe { symbol(handle, c"ipasir_release")? },
+            add: unsafe { symbol(handle, c"ipasir_add")? },
+            assume: unsafe { symbol(handle, c"ipasir_assume")? },
+            solve: unsafe { symbol(handle, c"ipasir_solve")? },
+            val: unsafe { symbol(handle, c"ipasir_val")? },
+            set_terminate: unsafe { symbol(handle, c"ipasir_set_terminate")? },
+        })
+    }
+}
+
+/// Returns the function `name` in the library `handle`.
+///
+/// # Safety
+/// `T` must be the function pointer type matching the signature of the function.
+unsafe fn symbol<T: Copy>(handle: *mut c_void, name: &CStr) -> Result<T, String> {
+    assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<*mut c_void>());
+    let ptr = dl::symbol(handle, name)?;
+    Ok(unsafe { std::mem::transmute_copy::<*mut c_void, T>(&ptr) })
+}
+
+/// The conditions on which `terminate_callback` asks the solver to stop.
+struct Termination<'a> {
+    deadline: Option<Instant>,
+    interrupt: Option<&'a AtomicBool>,
+}
+
+unsafe extern "C" fn terminate_callback(data: *mut c_void) -> c_int {
+    let termination = unsafe { &*(data as *const Termination) };
+    if termination
+        .interrupt
+        .is_some_and(|interrupt| interrupt.load(Ordering::Relaxed))
+    {
+        return 1;
+    }
+    if termination
+        .deadline
+        .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        return 1;
+    }
+    0
+}
+
+pub struct Solver {
+    library: &'static Library,
+    ptr: *mut c_void,
+    path: PathBuf,
+    num_vars: i32,
+
+    // The largest DIMACS variable passed to the library so far. IPASIR solvers know only these variables.
+    max_known_var: i32,
+    unsupported: Option<&'static str>,
+    last_error: Option<IpasirError>,
+}
+
+impl Solver {
+    /// Creates a solver by the IPASIR library at `path`.
+    /// The library is loaded only once even if several solvers are created.
+    pub fn new(path: &Path) -> Result<Solver, IpasirError> {
+        let library = Library::get(path)?;
+        let ptr = unsafe { (library.init)() };
+        if ptr.is_null() {
+            return Err(IpasirError::Load(String::from("ipasir_init failed")));
+        }
+        Ok(Solver {
+            library,
+            ptr,
+            path: path.to_owned(),
+            num_vars: 0,
+            max_known_var: 0,
+            unsupported: None,
+            last_error: None,
+        })
+    }
+
+    /// Returns the path of the library this solver was created from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name and the version of the solver reported by `ipasir_signature`.
+    pub fn signature(&self) -> &str {
+        &self.library.signature
+    }
+
+    pub fn new_var(&mut self) -> Var {
+        let ret = Var(self.num_vars);
+        self.num_vars += 1;
+        ret
+    }
+
+    pub fn num_var(&self) -> i32 {
+        self.num_vars
+    }
+
+    pub fn all_vars(&self) -> Vec<Var> {
+        (0..self.num_var()).map(Var).collect()
+    }
+
+    fn register_lit(&mut self, lit: Lit) -> i32 {
+        let ret = lit_to_dimacs(lit);
+        self.max_known_var = self.max_known_var.max(ret.abs());
+        ret
+    }
+
+    pub fn add_clause(&mut self, clause: &[Lit]) -> bool {
+        for &lit in clause {
+            let lit = self.register_lit(lit);
+            unsafe { (self.library.add)(self.ptr, lit) };
+        }
+        unsafe { (self.library.add)(self.ptr, 0) };
+        true
+    }
+
+    /// Marks that a constraint which cannot be expressed by the clauses (`name`) was added to the solver.
+    /// After this, the solver refuses to solve the problem.
+    pub fn mark_unsupported(&mut self, name: &'static str) {
+        if self.unsupported.is_none() {
+            self.unsupported = Some(name);
+        }
+    }
+
+    /// Returns why the last call of `solve_under_limited` returned `SolveResult::Unknown`.
+    /// This is `None` if the solver was terminated by the limit or the interruption.
+    pub fn last_error(&self) -> Option<&IpasirError> {
+        self.last_error.as_ref()
+    }
+
+    pub fn solve(&mut self) -> Option<Model<'_>> {
+        if self.solve_without_model() {
+            Some(unsafe { self.model() })
+        } else {
+            None
+        }
+    }
+
+    pub fn solve_without_model(&mut self) -> bool {
+        self.solve_under_without_model(&[])
+    }
+
+    pub fn solve_under(&mut self, assumptions: &[Lit]) -> Option<Model<'_>> {
+        if self.solve_under_without_model(assumptions) {
+            Some(unsafe { self.model() })
+        } else {
+            None
+        }
+    }
+
+    /// Solve the problem under `assumptions`.
+    /// Since this cannot report failures, it panics if the problem contains unsupported constraints.
+    pub fn solve_under_without_model(&mut self, assumptions: &[Lit]) -> bool {
+        match self.solve_under_limited(assumptions, &ResourceLimit::unlimited(), None) {
+            SolveResult::Sat => true,
+            SolveResult::Unsat => false,
+            SolveResult::Unknown => match &self.last_error {
+                Some(e) => panic!("{}", e),
+                None => panic!("IPASIR solver could not solve the problem"),
+            },
+        }
+    }
+
+    /// Solve the problem under `assumptions`, terminating the solver when the time limit in `limit`
+    /// is exceeded or `interrupt` is set. The other limits are ignored, since IPASIR does not support them.
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+        interrupt: Option<&AtomicBool>,
+    ) -> SolveResult {
+        self.last_error = None;
+        if let Some(name) = self.unsupported {
+            self.last_error = Some(IpasirError::Unsupported(name));
+            return SolveResult::Unknown;
+        }
+
+        for &lit in assumptions {
+            let lit = self.register_lit(lit);
+            unsafe { (self.library.assume)(self.ptr, lit) };
+        }
+
+        let termination = Termination {
+            deadline: limit.time.map(|t| Instant::now() + t),
+            interrupt,
+        };
+        let use_termination = termination.deadline.is_some() || termination.interrupt.is_some();
+        let res = unsafe {
+            if use_termination {
+                (self.library.set_terminate)(
+                    self.ptr,
+                    &termination as *const Termination as *mut c_void,
+                    Some(terminate_callback),
+                );
+            }
+            let res = (self.library.solve)(self.ptr);
+            if use_termination {
+                (self.library.set_terminate)(self.ptr, std::ptr::null_mut(), None);
+            }
+            res
+        };
+
+        match res {
+            10 => SolveResult::Sat,
+            20 => SolveResult::Unsat,
+            _ => SolveResult::Unknown,
+        }
+    }
+
+    pub(crate) unsafe fn model(&self) -> Model<'_> {
+        Model { solver: self }
+    }
+}
+
+impl Drop for Solver {
+    fn drop(&mut self) {
+        unsafe { (self.library.release)(self.ptr) };
+    }
+}
+
+impl ClauseSink for Solver {
+    fn new_var(&mut self) -> Var {
+        Solver::new_var(self)
+    }
+
+    fn add_clause(&mut self, clause: &[Lit]) {
+        Solver::add_clause(self, clause);
+    }
+
+    fn mark_unsupported(&mut self, name: &'static str) {
+        Solver::mark_unsupported(self, name);
+    }
+}
+
+pub struct Model<'a> {
+    solver: &'a Solver,
+}
+
+impl Model<'_> {
+    pub fn assignment(&self, var: Var) -> bool {
+        assert!(0 <= var.0 && var.0 < self.solver.num_var());
+        let v = var.0 + 1;
+        if v > self.solver.max_known_var {
+            // The variable does not appear in any clause, so its value does not matter.
+            return false;
+        }
+        unsafe { (self.solver.library.val)(self.solver.ptr, v) > 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The environment variable specifying the IPASIR library used in the tests.
+    /// The tests are skipped if this is not set.
+    const TEST_LIBRARY_ENV: &str = "CSPUZ_CORE_TEST_IPASIR_LIBRARY";
+
+    fn test_solver() -> Option<Solver> {
+        let path = std::env::var(TEST_LIBRARY_ENV).ok()?;
+        Some(Solver::new(Path::new(&path)).unwrap())
+    }
+
+    #[test]
+    fn test_ipasir_load_failure() {
+        assert!(Solver::new(Path::new("/nonexistent/libipasir.so")).is_err());
+    }
+
+    #[test]
+    fn test_ipasir_incremental() {
+        let Some(mut solver) = test_solver() else {
+            return;
+        };
+        let x = solver.new_var();
+        let y = solver.new_var();
+        let z = solver.new_var();
+        solver.add_clause(&[x.as_lit(false), y.as_lit(false)]);
+        solver.add_clause(&[x.as_lit(true), y.as_lit(true)]);
+
+        {
+            let model = solver.solve_under(&[x.as_lit(false)]).unwrap();
+            assert!(model.assignment(x));
+            assert!(!model.assignment(y));
+            assert!(!model.assignment(z));
+        }
+        assert!(solver
+            .solve_under(&[x.as_lit(false), y.as_lit(false)])
+            .is_none());
+
+        solver.add_clause(&[y.as_lit(true)]);
+        assert!(solver.solve_under(&[x.as_lit(true)]).is_none());
+        {
+            let model = solver.solve().unwrap();
+            assert!(model.assignment(x));
+        }
+
+        solver.add_clause(&[x.as_lit(true)]);
+        assert!(solver.solve().is_none());
+    }
+
+    #[test]
+    fn test_ipasir_interrupt() {
+        let Some(mut solver) = test_solver() else {
+            return;
+        };
+        let x = solver.new_var();
+        solver.add_clause(&[x.as_lit(false)]);
+
+        // The solver may or may not notice the interruption for such an easy problem
+        let interrupt = AtomicBool::new(true);
+        let res = solver.solve_under_limited(&[], &ResourceLimit::unlimited(), Some(&interrupt));
+        assert_ne!(res, SolveResult::Unsat);
+        assert!(solver.last_error().is_none());
+    }
+
+    #[test]
+    fn test_ipasir_unsupported() {
+        let Some(mut solver) = test_solver() else {
+            return;
+        };
+        solver.mark_unsupported("foo");
+        assert_eq!(
+            solver.solve_under_limited(&[], &ResourceLimit::unlimited(), None),
+            SolveResult::Unknown
+        );
+        assert!(matches!(
+            solver.last_error(),
+            Some(IpasirError::Unsupported("foo"))
+        ));
+    }
+}
@@ -1,6 +1,10 @@
-#[cfg(feature = "backend-external")]
+pub(crate) mod clausal;
+
 pub mod external;
 
+#[cfg(feature = "backend-ipasir")]
+pub mod ipasir;
+
 #[cfg(feature = "backend-cadical")]
 pub mod cadical;
 
@@ -8,3 +12,6 @@ pub mod cadical;
 pub mod glucose_rs;
 
 pub mod glucose;
+
+#[cfg(feature = "portfolio")]
+pub mod portfolio;
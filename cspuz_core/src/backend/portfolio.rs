@@ -0,0 +1,583 @@
+// Portfolio of SAT solvers racing on threads.
+//
+// The problem is encoded only once, into the primary solver living in the caller's thread.
+// Every operation applied to the primary solver is also recorded, and the recorded operations are
+// replayed by the workers, each of which owns a differently configured SAT solver in its own thread.
+// On solving, the primary solver and all the workers run simultaneously, and the first one to
+// reach a definite answer interrupts the others.
+//
+// Clauses added after a solve (e.g. refutations added by `decide_irrefutable_facts`) are replayed
+// as well. After each solve, the short clauses learned by each solver (the literals fixed at the root
+// level for Glucose, and the learnt clauses of at most `MAX_SHARED_CLAUSE_LEN` literals for CaDiCaL)
+// are passed to all the other solvers before the next solve.
+//
+// Custom constraints cannot be replayed, so the workers solve a relaxation of the problem after one
+// is added. An `Unsat` answer of the relaxation is still valid for the problem, while a `Sat` answer
+// is not, so only the `Unsat` answers of the workers are used from then on. Sharing learned clauses
+// is still sound in this case, since every learned clause is a consequence of the whole problem.
+
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::propagators::graph_division::GraphDivisionOptions;
+use crate::sat::{
+    Backend, BackendConfig, GraphDivisionMode, Lit, OrderEncodingLinearMode, ResourceLimit,
+    SATModel, SATSolverStats, SolveResult, Var, SAT,
+};
+
+/// Learnt clauses of at most this number of literals are shared between the solvers.
+const MAX_SHARED_CLAUSE_LEN: usize = 2;
+
+/// Configuration of a solver in the portfolio.
+#[derive(Clone)]
+pub struct WorkerConfig {
+    pub backend: Backend,
+    pub seed: Option<f64>,
+    pub rnd_init_act: bool,
+}
+
+impl WorkerConfig {
+    /// Returns `num_workers` configurations which are expected to behave differently from each other.
+    /// Both Glucose and CaDiCaL (if enabled) are used, with different random seeds.
+    pub fn diversified(num_workers: usize, base_seed: f64) -> Vec<WorkerConfig> {
+        (0..num_workers)
+            .map(|i| {
+                let backend = if cfg!(feature = "backend-cadical") && i % 2 == 0 {
+                    Backend::CaDiCaL
+                } else {
+                    Backend::Glucose
+                };
+                WorkerConfig {
+                    backend,
+                    seed: Some(worker_seed(base_seed, i)),
+                    rnd_init_act: i % 4 >= 2,
+                }
+            })
+            .collect()
+    }
+
+    fn create_solver(&self) -> SAT {
+        // the workers are Glucose or CaDiCaL, which are not configured by `BackendConfig`
+        let mut sat = SAT::new_with_backend(self.backend, &BackendConfig::initial_default());
+        if let Some(seed) = self.seed {
+            sat.set_seed(seed);
+        }
+        sat.set_rnd_init_act(self.rnd_init_act);
+        sat.set_max_export_len(MAX_SHARED_CLAUSE_LEN);
+        sat
+    }
+}
+
+/// The seed of the `index`-th worker when the seed of the portfolio is `base_seed`.
+fn worker_seed(base_seed: f64, index: usize) -> f64 {
+    base_seed + (index + 1) as f64
+}
+
+/// An operation on the primary solver which is replayed by the workers.
+enum Op {
+    NewVar,
+    AddClause(Vec<Lit>),
+    SetPolarity(Var, bool),
+    SetSeed(f64),
+    SetRndInitAct(bool),
+    OrderEncodingLinear {
+        lits: Vec<Vec<Lit>>,
+        domain: Vec<Vec<i32>>,
+        coefs: Vec<i32>,
+        constant: i32,
+        mode: OrderEncodingLinearMode,
+    },
+    ActiveVerticesConnected {
+        lits: Vec<Lit>,
+        edges: Vec<(usize, usize)>,
+    },
+    ActiveEdgesSingleCycle {
+        lits: Vec<Lit>,
+        edges: Vec<(usize, usize)>,
+    },
+    #[cfg(feature = "csp-extra-constraints")]
+    DirectEncodingExtensionSupports {
+        vars: Vec<Vec<Lit>>,
+        supports: Vec<Vec<Option<usize>>>,
+    },
+    #[cfg(feature = "csp-extra-constraints")]
+    DirectEncodingAllDifferent {
+        vars: Vec<Vec<Lit>>,
+        values: Vec<Vec<i32>>,
+    },
+    GraphDivision {
+        domains: Vec<Vec<i32>>,
+        dom_lits: Vec<Vec<Lit>>,
+        edges: Vec<(usize, usize)>,
+        edge_lits: Vec<Lit>,
+        mode: GraphDivisionMode,
+        opts: GraphDivisionOptions,
+    },
+}
+
+impl Op {
+    fn apply(&self, sat: &mut SAT) {
+        match self {
+            #[cfg(not(feature = "sat-analyzer"))]
+            Op::NewVar => {
+                sat.new_var();
+            }
+            #[cfg(feature = "sat-analyzer")]
+            Op::NewVar => {
+                sat.new_var("");
+            }
+            Op::AddClause(clause) => sat.add_clause(clause),
+            &Op::SetPolarity(var, polarity) => sat.set_polarity(var, polarity),
+            &Op::SetSeed(seed) => sat.set_seed(seed),
+            &Op::SetRndInitAct(rnd_init_act) => sat.set_rnd_init_act(rnd_init_act),
+            Op::OrderEncodingLinear {
+                lits,
+                domain,
+                coefs,
+                constant,
+                mode,
+            } => {
+                sat.add_order_encoding_linear(
+                    lits.clone(),
+                    domain.clone(),
+                    coefs.clone(),
+                    *constant,
+                    *mode,
+                );
+            }
+            Op::ActiveVerticesConnected { lits, edges } => {
+                sat.add_active_vertices_connected(lits.clone(), edges.clone());
+            }
+            Op::ActiveEdgesSingleCycle { lits, edges } => {
+                sat.add_active_edges_single_cycle(lits.clone(), edges.clone());
+            }
+            #[cfg(feature = "csp-extra-constraints")]
+            Op::DirectEncodingExtensionSupports { vars, supports } => {
+                sat.add_direct_encoding_extension_supports(vars, supports);
+            }
+            #[cfg(feature = "csp-extra-constraints")]
+            Op::DirectEncodingAllDifferent { vars, values } => {
+                sat.add_direct_encoding_all_different(vars, values);
+            }
+            Op::GraphDivision {
+                domains,
+                dom_lits,
+                edges,
+                edge_lits,
+                mode,
+                opts,
+            } => {
+                sat.add_graph_division(domains, dom_lits, edges, edge_lits, *mode, opts);
+            }
+        }
+    }
+
+    /// Applies the operation to the solver of the `index`-th worker, which is configured by `config`.
+    /// The settings of the search are diversified in the same way as `WorkerConfig::diversified`.
+    fn apply_to_worker(&self, sat: &mut SAT, config: &WorkerConfig, index: usize) {
+        match *self {
+            Op::SetSeed(seed) => sat.set_seed(worker_seed(seed, index)),
+            Op::SetRndInitAct(rnd_init_act) => {
+                sat.set_rnd_init_act(rnd_init_act || config.rnd_init_act)
+            }
+            _ => self.apply(sat),
+        }
+    }
+}
+
+struct SolveRequest {
+    assumptions: Vec<Lit>,
+    // Whether the worker lacks some constraint of the problem, so that its `Sat` answer must be discarded.
+    relaxed: bool,
+    limit: ResourceLimit,
+    interrupt: Arc<AtomicBool>,
+    reply: mpsc::Sender<SolveReply>,
+}
+
+struct SolveReply {
+    result: SolveResult,
+    model: Vec<bool>,
+    learnt_clauses: Vec<Vec<Lit>>,
+}
+
+enum Command {
+    Apply(Arc<Vec<Op>>),
+    Solve(SolveRequest),
+}
+
+struct Worker {
+    sender: mpsc::Sender<Command>,
+    handle: JoinHandle<()>,
+}
+
+impl Worker {
+    fn spawn(config: WorkerConfig, index: usize) -> Worker {
+        let (sender, receiver) = mpsc::channel::<Command>();
+        let handle = std::thread::spawn(move || {
+            let mut sat = config.create_solver();
+            while let Ok(command) = receiver.recv() {
+                match command {
+                    Command::Apply(ops) => {
+                        for op in ops.iter() {
+                            op.apply_to_worker(&mut sat, &config, index);
+                        }
+                    }
+                    Command::Solve(request) => {
+                        let mut result = sat.solve_under_interruptible(
+                            &request.assumptions,
+                            &request.limit,
+                            Some(&request.interrupt),
+                        );
+                        if request.relaxed && result == SolveResult::Sat {
+                            result = SolveResult::Unknown;
+                        }
+                        let mut model = vec![];
+                        if result != SolveResult::Unknown {
+                            request.interrupt.store(true, Ordering::Relaxed);
+                        }
+                        if result == SolveResult::Sat {
+                            let sat_model = unsafe { sat.model() };
+                            model = sat
+                                .all_vars()
+                                .into_iter()
+                                .map(|v| sat_model.assignment(v))
+                                .collect();
+                        }
+                        let _ = request.reply.send(SolveReply {
+                            result,
+                            model,
+                            learnt_clauses: sat.take_learnt_clauses(),
+                        });
+                    }
+                }
+            }
+        });
+        Worker { sender, handle }
+    }
+}
+
+pub struct Solver {
+    primary: Box<SAT>,
+    workers: Vec<Worker>,
+
+    // Operations applied to `primary` which are not sent to the workers yet.
+    pending: Vec<Op>,
+
+    // The model found by a worker in the last solve, or `None` if the primary solver found it.
+    worker_model: Option<Vec<bool>>,
+
+    // Whether a custom constraint, which the workers do not have, has been added.
+    relaxed: bool,
+
+    // Learnt clauses which have been shared, so that each of them is shared only once.
+    shared_clauses: BTreeSet<Vec<Lit>>,
+
+    // Clauses learnt by the workers which are added to `primary` before the next solve.
+    // They cannot be added right after a solve, since CaDiCaL discards its model on adding a clause.
+    primary_pending: Vec<Vec<Lit>>,
+}
+
+impl Solver {
+    pub fn new(mut primary: SAT, workers: &[WorkerConfig]) -> Solver {
+        match primary.get_backend() {
+            Backend::Glucose | Backend::CaDiCaL => (),
+            _ => panic!("the primary solver of a portfolio must be Glucose or CaDiCaL"),
+        }
+        if !workers.is_empty() {
+            primary.set_max_export_len(MAX_SHARED_CLAUSE_LEN);
+        }
+        Solver {
+            primary: Box::new(primary),
+            workers: workers
+                .iter()
+                .enumerate()
+                .map(|(i, config)| Worker::spawn(config.clone(), i))
+                .collect(),
+            pending: vec![],
+            worker_model: None,
+            relaxed: false,
+            shared_clauses: BTreeSet::new(),
+            primary_pending: vec![],
+        }
+    }
+
+    pub fn primary(&self) -> &SAT {
+        &self.primary
+    }
+
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    fn record(&mut self, op: Op) {
+        if !self.workers.is_empty() {
+            self.pending.push(op);
+        }
+    }
+
+    fn stop_workers(&mut self) {
+        self.pending.clear();
+        for worker in self.workers.drain(..) {
+            drop(worker.sender);
+            let _ = worker.handle.join();
+        }
+    }
+
+    #[cfg(not(feature = "sat-analyzer"))]
+    pub fn new_var(&mut self) -> Var {
+        self.record(Op::NewVar);
+        self.primary.new_var()
+    }
+
+    #[cfg(feature = "sat-analyzer")]
+    pub fn new_named_var(&mut self, name: &str) -> Var {
+        self.record(Op::NewVar);
+        self.primary.new_var(name)
+    }
+
+    pub fn add_clause(&mut self, clause: &[Lit]) {
+        self.record(Op::AddClause(clause.to_vec()));
+        self.primary.add_clause(clause);
+    }
+
+    pub fn set_polarity(&mut self, var: Var, polarity: bool) {
+        self.record(Op::SetPolarity(var, polarity));
+        self.primary.set_polarity(var, polarity);
+    }
+
+    pub fn add_order_encoding_linear(
+        &mut self,
+        lits: Vec<Vec<Lit>>,
+        domain: Vec<Vec<i32>>,
+        coefs: Vec<i32>,
+        constant: i32,
+        mode: OrderEncodingLinearMode,
+    ) -> bool {
+        if !self.workers.is_empty() {
+            self.record(Op::OrderEncodingLinear {
+                lits: lits.clone(),
+                domain: domain.clone(),
+                coefs: coefs.clone(),
+                constant,
+                mode,
+            });
+        }
+        self.primary
+            .add_order_encoding_linear(lits, domain, coefs, constant, mode)
+    }
+
+    pub fn add_active_vertices_connected(
+        &mut self,
+        lits: Vec<Lit>,
+        edges: Vec<(usize, usize)>,
+    ) -> bool {
+        if !self.workers.is_empty() {
+            self.record(Op::ActiveVerticesConnected {
+                lits: lits.clone(),
+                edges: edges.clone(),
+            });
+        }
+        self.primary.add_active_vertices_connected(lits, edges)
+    }
+
+    pub fn add_active_edges_single_cycle(
+        &mut self,
+        lits: Vec<Lit>,
+        edges: Vec<(usize, usize)>,
+    ) -> bool {
+        if !self.workers.is_empty() {
+            self.record(Op::ActiveEdgesSingleCycle {
+                lits: lits.clone(),
+                edges: edges.clone(),
+            });
+        }
+        self.primary.add_active_edges_single_cycle(lits, edges)
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_extension_supports(
+        &mut self,
+        vars: &[Vec<Lit>],
+        supports: &[Vec<Option<usize>>],
+    ) -> bool {
+        self.record(Op::DirectEncodingExtensionSupports {
+            vars: vars.to_vec(),
+            supports: supports.to_vec(),
+        });
+        self.primary
+            .add_direct_encoding_extension_supports(vars, supports)
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        self.record(Op::DirectEncodingAllDifferent {
+            vars: vars.to_vec(),
+            values: values.to_vec(),
+        });
+        self.primary.add_direct_encoding_all_different(vars, values)
+    }
+
+    pub fn add_graph_division(
+        &mut self,
+        domains: &[Vec<i32>],
+        dom_lits: &[Vec<Lit>],
+        edges: &[(usize, usize)],
+        edge_lits: &[Lit],
+        mode: GraphDivisionMode,
+        opts: &GraphDivisionOptions,
+    ) -> bool {
+        self.record(Op::GraphDivision {
+            domains: domains.to_vec(),
+            dom_lits: dom_lits.to_vec(),
+            edges: edges.to_vec(),
+            edge_lits: edge_lits.to_vec(),
+            mode,
+            opts: *opts,
+        });
+        self.primary
+            .add_graph_division(domains, dom_lits, edges, edge_lits, mode, opts)
+    }
+
+    /// Adds a custom constraint to the primary solver.
+    /// Since custom constraints cannot be replayed, only `Unsat` answers of the workers are used afterwards.
+    pub fn add_custom_constraint(&mut self, add: impl FnOnce(&mut SAT) -> bool) -> bool {
+        self.relaxed = true;
+        add(&mut self.primary)
+    }
+
+    /// Sets the seed of the primary solver to `seed`. The workers get different seeds derived from `seed`.
+    pub fn set_seed(&mut self, seed: f64) {
+        self.record(Op::SetSeed(seed));
+        self.primary.set_seed(seed);
+    }
+
+    /// Sets `rnd_init_act` of the primary solver. The workers which are configured with `rnd_init_act`
+    /// keep using it even if `rnd_init_act` is `false`.
+    pub fn set_rnd_init_act(&mut self, rnd_init_act: bool) {
+        self.record(Op::SetRndInitAct(rnd_init_act));
+        self.primary.set_rnd_init_act(rnd_init_act);
+    }
+
+    pub fn set_dump_analysis_info(&mut self, dump_analysis_info: bool) {
+        self.primary.set_dump_analysis_info(dump_analysis_info);
+    }
+
+    /// Solves the problem under `assumptions` with all the solvers in the portfolio, and returns the
+    /// first definite answer. `Unknown` is returned only if all the solvers reach `limit` (or, after a custom
+    /// constraint is added, if the primary solver reaches `limit` and no worker refutes the problem).
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+    ) -> SolveResult {
+        self.worker_model = None;
+
+        if self.workers.is_empty() {
+            return self.primary.solve_under_limited(assumptions, limit);
+        }
+
+        for clause in std::mem::take(&mut self.primary_pending) {
+            self.primary.add_clause(&clause);
+        }
+        if !self.pending.is_empty() {
+            let ops = Arc::new(std::mem::take(&mut self.pending));
+            for worker in &self.workers {
+                let _ = worker.sender.send(Command::Apply(ops.clone()));
+            }
+        }
+
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let (reply_sender, reply_receiver) = mpsc::channel();
+        for worker in &self.workers {
+            let _ = worker.sender.send(Command::Solve(SolveRequest {
+                assumptions: assumptions.to_vec(),
+                relaxed: self.relaxed,
+                limit: *limit,
+                interrupt: interrupt.clone(),
+                reply: reply_sender.clone(),
+            }));
+        }
+        drop(reply_sender);
+
+        let primary_result =
+            self.primary
+                .solve_under_interruptible(assumptions, limit, Some(&interrupt));
+        if primary_result != SolveResult::Unknown {
+            interrupt.store(true, Ordering::Relaxed);
+        }
+
+        // Wait for all the workers so that they are idle before the next operation.
+        // Replies are received in the order in which the workers finished.
+        let mut result = primary_result;
+        for reply in reply_receiver.iter() {
+            if result == SolveResult::Unknown && reply.result != SolveResult::Unknown {
+                result = reply.result;
+                if reply.result == SolveResult::Sat {
+                    self.worker_model = Some(reply.model);
+                }
+            }
+            for clause in reply.learnt_clauses {
+                if let Some(clause) = self.new_shared_clause(clause) {
+                    self.primary_pending.push(clause.clone());
+                    self.pending.push(Op::AddClause(clause));
+                }
+            }
+        }
+        for clause in self.primary.take_learnt_clauses() {
+            if let Some(clause) = self.new_shared_clause(clause) {
+                self.pending.push(Op::AddClause(clause));
+            }
+        }
+        result
+    }
+
+    /// Normalizes a learnt clause, and returns it if it has not been shared yet.
+    fn new_shared_clause(&mut self, mut clause: Vec<Lit>) -> Option<Vec<Lit>> {
+        clause.sort();
+        clause.dedup();
+        if self.shared_clauses.insert(clause.clone()) {
+            Some(clause)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) unsafe fn model(&self) -> Model<'_> {
+        match &self.worker_model {
+            Some(model) => Model::Worker(model),
+            None => Model::Primary(Box::new(self.primary.model())),
+        }
+    }
+
+    pub fn stats(&self) -> SATSolverStats {
+        // Only the statistics of the primary solver are reported.
+        self.primary.stats()
+    }
+}
+
+impl Drop for Solver {
+    fn drop(&mut self) {
+        self.stop_workers();
+    }
+}
+
+pub enum Model<'a> {
+    Primary(Box<SATModel<'a>>),
+    Worker(&'a [bool]),
+}
+
+impl Model<'_> {
+    pub fn assignment(&self, var: Var) -> bool {
+        match self {
+            Model::Primary(model) => model.assignment(var),
+            Model::Worker(model) => model[var.0 as usize],
+        }
+    }
+}
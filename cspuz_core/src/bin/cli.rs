@@ -7,9 +7,14 @@ use std::io;
 fn main() {
     let stdin = io::stdin();
     let mut lock = stdin.lock();
-    let config = cspuz_core::config::Config::parse_from_args();
-    let (res, _) = cspuz_core::csugar_cli::csugar_cli(&mut lock, config);
-    print!("{}", res);
+    let (config, backend_config) = cspuz_core::config::Config::parse_from_args();
+    match cspuz_core::csugar_cli::csugar_cli(&mut lock, config, &backend_config) {
+        Ok((res, _)) => print!("{}", res),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(not(feature = "parser"))]
@@ -0,0 +1,81 @@
+extern crate cspuz_core;
+
+#[allow(unused)]
+use std::io::{self, Read};
+
+#[cfg(feature = "cli")]
+fn main() {
+    use cspuz_core::flatzinc::{flatzinc_cli, FlatZincOptions};
+
+    let (mut config, backend_config, matches) =
+        cspuz_core::config::Config::parse_from_args_with_extra_options(|opts| {
+            opts.optflag("a", "all-solutions", "Print all solutions.");
+            opts.optopt(
+                "n",
+                "num-solutions",
+                "Stop after printing N solutions.",
+                "N",
+            );
+            opts.optflag(
+                "f",
+                "free-search",
+                "Ignore search annotations (always the case).",
+            );
+            opts.optopt(
+                "t",
+                "",
+                "Specify the time limit in milliseconds.",
+                "MILLISECONDS",
+            );
+        });
+
+    let mut options = FlatZincOptions {
+        all_solutions: matches.opt_present("a"),
+        num_solutions: None,
+    };
+    if let Some(n) = matches.opt_str("n") {
+        match n.parse::<usize>() {
+            Ok(n) => options.num_solutions = Some(n),
+            Err(_) => {
+                eprintln!("error: invalid number of solutions: {}", n);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(t) = matches.opt_str("t") {
+        match t.parse::<u64>() {
+            Ok(t) => config.resource_limit.time = Some(std::time::Duration::from_millis(t)),
+            Err(_) => {
+                eprintln!("error: invalid time limit: {}", t);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut input = String::new();
+    let read_result = match matches.free.len() {
+        0 => io::stdin().read_to_string(&mut input).map(|_| ()),
+        1 => std::fs::read_to_string(&matches.free[0]).map(|s| input = s),
+        _ => {
+            eprintln!("error: at most one input file can be specified");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = read_result {
+        eprintln!("error: I/O error: {}", e);
+        std::process::exit(1);
+    }
+
+    match flatzinc_cli(&input, config, &backend_config, &options) {
+        Ok(res) => print!("{}", res),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+fn main() {
+    panic!("cli feature not enabled");
+}
@@ -1,4 +1,8 @@
-pub use crate::sat::{Backend, GraphDivisionMode, OrderEncodingLinearMode};
+pub use crate::sat::{
+    Backend, BackendConfig, ExternalSolverConfig, GraphDivisionMode, OrderEncodingLinearMode,
+    ResourceLimit,
+};
+use std::path::PathBuf;
 
 // Single source of truth for `bool` config options that have been migrated to the
 // macro-based definition below (currently just `use_constant_folding`, as a trial).
@@ -19,6 +23,10 @@ macro_rules! bool_config_options {
             direct_encoding_for_binary_vars: bool = false, doc = "use direct encoding for binary variables";
             merge_equivalent_variables: bool = false, doc = "merge equivalent variables (which is caused by, for example, (iff x y))";
             alldifferent_bijection_constraints: bool = false, doc = "add auxiliary constraints for bijective alldifferent constraints";
+            use_native_all_different: bool = false, doc = "use native propagator for alldifferent constraints";
+            use_native_single_cycle: bool = false, doc = "use native propagator for single-cycle constraints (instead of the connectivity of the line graph)";
+            use_pseudo_boolean_encoding: bool = true, doc = "use totalizer / sequential counter encodings for cardinality and pseudo-Boolean constraints if they are estimated to be smaller than the order encoding";
+            force_pseudo_boolean_encoding: bool = false, doc = "use totalizer / sequential counter encodings for all cardinality and pseudo-Boolean constraints";
             dump_analysis_info: bool = false, doc = "dump analysis info in Glucose";
             glucose_rnd_init_act: bool = false, doc = "rnd_init_act in Glucose";
             optimize_polarity: bool = false, doc = "use polarity-based optimization in decide_irrefutable_facts";
@@ -35,10 +43,14 @@ macro_rules! __config_struct_def {
             pub domain_product_threshold: usize,
             pub native_linear_encoding_terms: usize,
             pub native_linear_encoding_domain_product_threshold: usize,
+            pub pseudo_boolean_encoding_min_terms: usize,
             pub glucose_random_seed: Option<f64>,
             pub backend: Backend,
             pub order_encoding_linear_mode: OrderEncodingLinearMode,
             pub graph_division_mode: GraphDivisionMode,
+            pub resource_limit: ResourceLimit,
+            pub portfolio_size: usize,
+            pub produce_proof: bool,
         }
     };
 }
@@ -53,10 +65,14 @@ macro_rules! __config_initial_default_impl {
                     domain_product_threshold: 1000,
                     native_linear_encoding_terms: 4,
                     native_linear_encoding_domain_product_threshold: 20,
+                    pseudo_boolean_encoding_min_terms: 4,
                     glucose_random_seed: None,
-                    backend: default_backend_from_env(),
+                    backend: default_backend_from_env().0,
                     order_encoding_linear_mode: OrderEncodingLinearMode::Cpp,
                     graph_division_mode: GraphDivisionMode::Cpp,
+                    resource_limit: ResourceLimit::unlimited(),
+                    portfolio_size: 1,
+                    produce_proof: false,
                 }
             }
         }
@@ -68,22 +84,27 @@ thread_local! {
     static DEFAULT_CONFIG: std::cell::Cell<Config> = {
         std::cell::Cell::new(Config::initial_default())
     };
+    static DEFAULT_BACKEND_CONFIG: std::cell::RefCell<BackendConfig> = {
+        std::cell::RefCell::new(BackendConfig::initial_default())
+    };
 }
 
 #[cfg(target_arch = "wasm32")]
-fn default_backend_from_env() -> Backend {
+fn default_backend_from_env() -> (Backend, Option<PathBuf>) {
     // In wasm, we cannot use environment variables, so we just return the default backend.
-    Backend::Glucose
+    (Backend::Glucose, None)
 }
 
+/// Returns the backend specified by `CSPUZ_CORE_DEFAULT_BACKEND`, together with the library of the IPASIR backend
+/// given as `ipasir:PATH`.
 #[cfg(not(target_arch = "wasm32"))]
-fn default_backend_from_env() -> Backend {
+fn default_backend_from_env() -> (Backend, Option<PathBuf>) {
     if let Ok(s) = std::env::var("CSPUZ_CORE_DEFAULT_BACKEND") {
-        s.parse().unwrap_or_else(|_| {
+        Backend::parse_with_library(&s).unwrap_or_else(|_| {
             panic!("error: unknown backend specified in CSPUZ_CORE_DEFAULT_BACKEND");
         })
     } else {
-        Backend::Glucose
+        (Backend::Glucose, None)
     }
 }
 
@@ -91,6 +112,24 @@ fn to_config_name(s: &str) -> String {
     s.replace('-', "_")
 }
 
+impl BackendConfig {
+    pub fn initial_default() -> BackendConfig {
+        BackendConfig {
+            external_solver: ExternalSolverConfig::default(),
+            ipasir_library: default_backend_from_env().1,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> BackendConfig {
+        DEFAULT_BACKEND_CONFIG.with(|f| f.borrow().clone())
+    }
+
+    pub fn set_default(new_default: BackendConfig) {
+        DEFAULT_BACKEND_CONFIG.with(|f| *f.borrow_mut() = new_default);
+    }
+}
+
 impl Config {
     pub fn default() -> Config {
         DEFAULT_CONFIG.with(|f| f.get())
@@ -100,8 +139,22 @@ impl Config {
         DEFAULT_CONFIG.with(|f| f.set(new_default));
     }
 
+    /// Parses the command line arguments.
+    /// The settings of the SAT backends (such as the command of the external solver) are returned as a
+    /// separate `BackendConfig`, since they are not `Copy`.
     #[cfg(feature = "cli")]
-    pub fn parse_from_args() -> Config {
+    pub fn parse_from_args() -> (Config, BackendConfig) {
+        let (config, backend_config, _) = Config::parse_from_args_with_extra_options(|_| ());
+        (config, backend_config)
+    }
+
+    /// Same as `parse_from_args`, but also accepts the options registered by `add_options`.
+    /// The parse result is returned as well so that the caller can read its own options and the
+    /// free arguments.
+    #[cfg(feature = "cli")]
+    pub fn parse_from_args_with_extra_options<F: FnOnce(&mut getopts::Options)>(
+        add_options: F,
+    ) -> (Config, BackendConfig, getopts::Matches) {
         use getopts::Options;
         use std::str::FromStr;
 
@@ -145,8 +198,31 @@ impl Config {
         opts.optopt("", "domain-product-threshold", "Specify the threshold of domain product for introducing an auxiliary variable by Tseitin transformation.", "THRESHOLD");
         opts.optopt("", "native-linear-encoding-terms", "Specify the maximum number of terms in a linear sum which is encoded by the native linear constraint (0 for disabling this).", "TERMS");
         opts.optopt("", "native-linear-encoding-domain-product", "Specify the minimum domain product of linear sums which are encoded by the native linear constraint.", "DOMAIN_PRODUCT");
+        opts.optopt("", "pseudo-boolean-encoding-min-terms", "Specify the minimum number of terms in a cardinality / pseudo-Boolean constraint which is encoded by the dedicated encodings.", "TERMS");
 
-        opts.optopt("", "backend", "Specify the SAT backend", "BACKEND");
+        opts.optopt(
+            "",
+            "backend",
+            "Specify the SAT backend (glucose, cadical, external or ipasir:PATH).",
+            "BACKEND",
+        );
+        opts.optopt(
+            "",
+            "external-solver",
+            "Specify the command of the SAT solver for the external backend (default: $ENIGMA_CSP_EXTERNAL_SOLVER).",
+            "COMMAND",
+        );
+        opts.optmulti(
+            "",
+            "external-solver-arg",
+            "Specify an argument passed to the external solver (can be repeated).",
+            "ARG",
+        );
+        opts.optflag(
+            "",
+            "external-solver-input-file",
+            "Pass the problem to the external solver as a file instead of the standard input.",
+        );
         opts.optopt(
             "",
             "order-encoding-linear-mode",
@@ -154,6 +230,33 @@ impl Config {
             "MODE",
         );
 
+        opts.optopt(
+            "",
+            "time-limit",
+            "Specify the time limit of SAT solving in seconds.",
+            "SECONDS",
+        );
+        opts.optopt(
+            "",
+            "conflict-limit",
+            "Specify the maximum number of conflicts in SAT solving.",
+            "CONFLICTS",
+        );
+        opts.optopt(
+            "",
+            "propagation-limit",
+            "Specify the maximum number of propagations in SAT solving (Glucose only).",
+            "PROPAGATIONS",
+        );
+
+        opts.optopt(
+            "",
+            "portfolio",
+            "Specify the number of SAT solvers racing on threads (1 for disabling portfolio).",
+            "SOLVERS",
+        );
+
+        add_options(&mut opts);
         opts.optflag("h", "help", "Display this help");
 
         let matches = match opts.parse(&args[1..]) {
@@ -216,13 +319,65 @@ impl Config {
             &mut config.native_linear_encoding_domain_product_threshold,
             "native-linear-encoding-domain-product",
         );
-        maybe_set_option(&matches, &mut config.backend, "backend");
+        maybe_set_option(
+            &matches,
+            &mut config.pseudo_boolean_encoding_min_terms,
+            "pseudo-boolean-encoding-min-terms",
+        );
+        let mut backend_config = BackendConfig::default();
+        if let Some(backend) = matches.opt_str("backend") {
+            match Backend::parse_with_library(&backend) {
+                Ok((backend, library)) => {
+                    config.backend = backend;
+                    if library.is_some() {
+                        backend_config.ipasir_library = library;
+                    }
+                }
+                Err(_) => {
+                    println!("error: parse failed for --backend: {}", backend);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if let Some(command) = matches.opt_str("external-solver") {
+            backend_config.external_solver.command = Some(command);
+        }
+        backend_config
+            .external_solver
+            .args
+            .extend(matches.opt_strs("external-solver-arg"));
+        if matches.opt_present("external-solver-input-file") {
+            backend_config.external_solver.use_input_file = true;
+        }
         maybe_set_option(
             &matches,
             &mut config.order_encoding_linear_mode,
             "order-encoding-linear-mode",
         );
 
-        config
+        maybe_set_option(&matches, &mut config.portfolio_size, "portfolio");
+        if matches.opt_present("time-limit") {
+            let mut seconds = 0.0f64;
+            maybe_set_option(&matches, &mut seconds, "time-limit");
+            match std::time::Duration::try_from_secs_f64(seconds) {
+                Ok(d) => config.resource_limit.time = Some(d),
+                Err(_) => {
+                    println!("error: invalid time limit: {}", seconds);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if matches.opt_present("conflict-limit") {
+            let mut conflicts = 0u64;
+            maybe_set_option(&matches, &mut conflicts, "conflict-limit");
+            config.resource_limit.conflicts = Some(conflicts);
+        }
+        if matches.opt_present("propagation-limit") {
+            let mut propagations = 0u64;
+            maybe_set_option(&matches, &mut propagations, "propagation-limit");
+            config.resource_limit.propagations = Some(propagations);
+        }
+
+        (config, backend_config, matches)
     }
 }
@@ -3,7 +3,7 @@ mod repr;
 #[cfg(test)]
 pub mod test_utils;
 
-use crate::arithmetic::CheckedInt;
+use crate::arithmetic::{CheckedInt, OverflowError, Range, MAGNITUDE_LIMIT};
 use crate::domain::Domain;
 use crate::util::{ConvertMapIndex, UpdateStatus};
 use std::collections::{btree_map, BTreeMap};
@@ -92,6 +92,135 @@ impl CSPVars {
         &self.int_var[var.to_index()]
     }
 
+    fn int_expr_range(&self, expr: &IntExpr) -> Range {
+        match expr {
+            &IntExpr::Const(c) => Range::constant(CheckedInt::new(c)),
+            IntExpr::Var(v) => {
+                let domain = &self.int_var(*v).domain;
+                Range::new(domain.lower_bound_checked(), domain.upper_bound_checked())
+            }
+            IntExpr::NVar(_) => unreachable!(),
+            IntExpr::Linear(terms) => terms
+                .iter()
+                .fold(Range::constant(CheckedInt::new(0)), |acc, (e, coef)| {
+                    acc + self.int_expr_range(e) * CheckedInt::new(*coef)
+                }),
+            IntExpr::If(_, t, f) => self.int_expr_range(t) | self.int_expr_range(f),
+            IntExpr::Abs(x) => {
+                let x = self.int_expr_range(x);
+                if x.low >= 0 {
+                    x
+                } else if x.high <= 0 {
+                    Range::new(-x.high, -x.low)
+                } else {
+                    Range::new(CheckedInt::new(0), (-x.low).max(x.high))
+                }
+            }
+            IntExpr::Mul(x, y) => {
+                let x = self.int_expr_range(x);
+                let y = self.int_expr_range(y);
+                let cands = [
+                    x.low * y.low,
+                    x.low * y.high,
+                    x.high * y.low,
+                    x.high * y.high,
+                ];
+                Range::new(*cands.iter().min().unwrap(), *cands.iter().max().unwrap())
+            }
+            IntExpr::Div(x, y) => self.int_expr_range(x).div_trunc(self.int_expr_range(y)),
+            IntExpr::Mod(x, y) => self.int_expr_range(x).rem_trunc(self.int_expr_range(y)),
+            IntExpr::Min(x, y) => {
+                let x = self.int_expr_range(x);
+                let y = self.int_expr_range(y);
+                Range::new(x.low.min(y.low), x.high.min(y.high))
+            }
+            IntExpr::Max(x, y) => {
+                let x = self.int_expr_range(x);
+                let y = self.int_expr_range(y);
+                Range::new(x.low.max(y.low), x.high.max(y.high))
+            }
+            IntExpr::Element(index, values) => {
+                let index = self.int_expr_range(index);
+                let mut ret = Range::empty();
+                for (i, v) in values.iter().enumerate() {
+                    if index.contains(CheckedInt::new(i as i32)) {
+                        ret = ret | self.int_expr_range(v);
+                    }
+                }
+                if index.low < 0 || index.high >= values.len() as i32 {
+                    ret = ret | Range::constant(CheckedInt::new(0));
+                }
+                ret
+            }
+        }
+    }
+
+    /// Returns an upper bound of the absolute values of `expr`, and updates `max_seen` with the
+    /// magnitudes of the values which may appear while `expr` is normalized.
+    fn int_expr_magnitude(&self, expr: &IntExpr, max_seen: &mut i128) -> i128 {
+        let ret = match expr {
+            &IntExpr::Const(c) => (c as i128).abs(),
+            IntExpr::Var(v) => {
+                let domain = &self.int_var(*v).domain;
+                (domain.lower_bound_checked().get_repr() as i128)
+                    .abs()
+                    .max((domain.upper_bound_checked().get_repr() as i128).abs())
+            }
+            IntExpr::NVar(_) => unreachable!(),
+            IntExpr::Linear(terms) => terms.iter().fold(0i128, |acc, (e, coef)| {
+                acc.saturating_add(
+                    self.int_expr_magnitude(e, max_seen)
+                        .saturating_mul((*coef as i128).abs()),
+                )
+            }),
+            IntExpr::If(c, t, f) => {
+                self.bool_expr_magnitude(c, max_seen);
+                self.int_expr_magnitude(t, max_seen)
+                    .max(self.int_expr_magnitude(f, max_seen))
+            }
+            IntExpr::Abs(x) => self.int_expr_magnitude(x, max_seen),
+            IntExpr::Mul(x, y) => self
+                .int_expr_magnitude(x, max_seen)
+                .saturating_mul(self.int_expr_magnitude(y, max_seen)),
+            IntExpr::Div(x, y) | IntExpr::Mod(x, y) | IntExpr::Min(x, y) | IntExpr::Max(x, y) => {
+                self.int_expr_magnitude(x, max_seen)
+                    .max(self.int_expr_magnitude(y, max_seen))
+            }
+            IntExpr::Element(index, values) => {
+                let mut ret = self.int_expr_magnitude(index, max_seen);
+                for v in values {
+                    ret = ret.max(self.int_expr_magnitude(v, max_seen));
+                }
+                ret
+            }
+        };
+        *max_seen = (*max_seen).max(ret);
+        ret
+    }
+
+    /// Updates `max_seen` with the magnitudes of the values which may appear while `expr` is normalized.
+    fn bool_expr_magnitude(&self, expr: &BoolExpr, max_seen: &mut i128) {
+        match expr {
+            BoolExpr::Const(_) | BoolExpr::Var(_) | BoolExpr::NVar(_) => (),
+            BoolExpr::And(es) | BoolExpr::Or(es) => {
+                es.iter()
+                    .for_each(|e| self.bool_expr_magnitude(e, max_seen));
+            }
+            BoolExpr::Not(e) => self.bool_expr_magnitude(e, max_seen),
+            BoolExpr::Xor(a, b) | BoolExpr::Iff(a, b) | BoolExpr::Imp(a, b) => {
+                self.bool_expr_magnitude(a, max_seen);
+                self.bool_expr_magnitude(b, max_seen);
+            }
+            BoolExpr::Cmp(_, a, b) => {
+                // `a op b` is normalized into `a - b op 0`
+                let m = self
+                    .int_expr_magnitude(a, max_seen)
+                    .saturating_add(self.int_expr_magnitude(b, max_seen));
+                *max_seen = (*max_seen).max(m);
+            }
+        }
+    }
+
     fn constant_folding_bool(&self, expr: &mut BoolExpr) {
         match expr {
             BoolExpr::Const(_) => (),
@@ -271,6 +400,47 @@ impl CSPVars {
                     *expr = IntExpr::Linear(vec![(Box::new(x_val), *c)]);
                 }
             }
+            IntExpr::Div(x, y) | IntExpr::Mod(x, y) => {
+                self.constant_folding_int(x);
+                self.constant_folding_int(y);
+
+                if let (&IntExpr::Const(a), &IntExpr::Const(b)) = (x.as_ref(), y.as_ref()) {
+                    let is_div = matches!(expr, IntExpr::Div(_, _));
+                    let value = match (is_div, b) {
+                        (true, 0) => Some(0),
+                        (false, 0) => Some(a),
+                        (true, _) => a.checked_div(b),
+                        (false, _) => a.checked_rem(b),
+                    };
+                    if let Some(value) = value {
+                        *expr = IntExpr::Const(value);
+                    }
+                }
+            }
+            IntExpr::Min(x, y) | IntExpr::Max(x, y) => {
+                self.constant_folding_int(x);
+                self.constant_folding_int(y);
+
+                if let (&IntExpr::Const(a), &IntExpr::Const(b)) = (x.as_ref(), y.as_ref()) {
+                    if matches!(expr, IntExpr::Min(_, _)) {
+                        *expr = IntExpr::Const(a.min(b));
+                    } else {
+                        *expr = IntExpr::Const(a.max(b));
+                    }
+                }
+            }
+            IntExpr::Element(index, values) => {
+                self.constant_folding_int(index);
+                values.iter_mut().for_each(|e| self.constant_folding_int(e));
+
+                if let &IntExpr::Const(i) = index.as_ref() {
+                    if 0 <= i && (i as usize) < values.len() {
+                        *expr = values.swap_remove(i as usize);
+                    } else {
+                        *expr = IntExpr::Const(0);
+                    }
+                }
+            }
         }
     }
 
@@ -411,10 +581,86 @@ impl CSP {
         self.inconsistent
     }
 
+    pub fn num_bool_vars(&self) -> usize {
+        self.vars.bool_var.len()
+    }
+
+    pub fn num_int_vars(&self) -> usize {
+        self.vars.int_var.len()
+    }
+
     pub fn get_bool_var_status(&self, var: BoolVar) -> BoolVarStatus {
         self.vars[var].get_status()
     }
 
+    /// Returns a range which contains all the possible values of `expr`.
+    pub(crate) fn int_expr_range(&self, expr: &IntExpr) -> Range {
+        self.vars.int_expr_range(expr)
+    }
+
+    /// Checks that the values appearing in the normalization and encoding of the constraints fit
+    /// in `CheckedInt`.
+    ///
+    /// The check is conservative: an instance may be rejected even if no overflow actually occurs.
+    pub fn check_overflow(&self) -> Result<(), OverflowError> {
+        let vars = &self.vars;
+        let mut max_seen = 0i128;
+        let bools = |es: &[BoolExpr], max_seen: &mut i128| {
+            es.iter()
+                .for_each(|e| vars.bool_expr_magnitude(e, max_seen));
+        };
+        let ints = |es: &[IntExpr], max_seen: &mut i128| {
+            es.iter().for_each(|e| {
+                vars.int_expr_magnitude(e, max_seen);
+            });
+        };
+        for stmt in &self.constraints {
+            match stmt {
+                Stmt::Expr(e) => vars.bool_expr_magnitude(e, &mut max_seen),
+                Stmt::AllDifferent(exprs)
+                | Stmt::Circuit(exprs)
+                | Stmt::ValuePrecedeChain(exprs, _)
+                | Stmt::ExtensionSupports(exprs, _)
+                | Stmt::Regular(exprs, _)
+                | Stmt::CustomIntConstraint(exprs, _) => ints(exprs, &mut max_seen),
+                Stmt::LexLessEq(lhs, rhs) => {
+                    ints(lhs, &mut max_seen);
+                    ints(rhs, &mut max_seen);
+                }
+                Stmt::ActiveVerticesConnected(exprs, _)
+                | Stmt::ActiveEdgesSingleCycle(exprs, _)
+                | Stmt::CustomConstraint(exprs, _) => bools(exprs, &mut max_seen),
+                Stmt::Cardinality(exprs, _, rhs) => {
+                    bools(exprs, &mut max_seen);
+                    max_seen = max_seen.max((exprs.len() as i128).max((*rhs as i128).abs()));
+                }
+                Stmt::PseudoBoolean(terms, _, rhs) => {
+                    let mut total = (*rhs as i128).abs();
+                    for (e, coef) in terms {
+                        vars.bool_expr_magnitude(e, &mut max_seen);
+                        total = total.saturating_add((*coef as i128).abs());
+                    }
+                    max_seen = max_seen.max(total);
+                }
+                Stmt::GraphDivision(sizes, _, edges, _) => {
+                    for size in sizes.iter().flatten() {
+                        vars.int_expr_magnitude(size, &mut max_seen);
+                    }
+                    bools(edges, &mut max_seen);
+                }
+            }
+        }
+
+        if max_seen > MAGNITUDE_LIMIT {
+            Err(OverflowError {
+                magnitude: max_seen,
+                limit: MAGNITUDE_LIMIT,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn get_int_var_status(&self, var: IntVar) -> IntVarStatus {
         let data = self.vars.int_var(var);
         let domain = &data.domain;
@@ -440,12 +686,33 @@ impl CSP {
                         .iter_mut()
                         .for_each(|e| vars.constant_folding_bool(e));
                 }
+                Stmt::ActiveEdgesSingleCycle(edges, _) => {
+                    edges.iter_mut().for_each(|e| vars.constant_folding_bool(e));
+                }
                 Stmt::Circuit(exprs) => {
                     exprs.iter_mut().for_each(|e| vars.constant_folding_int(e));
                 }
+                Stmt::Cardinality(exprs, _, _) => {
+                    exprs.iter_mut().for_each(|e| vars.constant_folding_bool(e));
+                }
+                Stmt::PseudoBoolean(terms, _, _) => {
+                    terms
+                        .iter_mut()
+                        .for_each(|(e, _)| vars.constant_folding_bool(e));
+                }
+                Stmt::LexLessEq(lhs, rhs) => {
+                    lhs.iter_mut().for_each(|e| vars.constant_folding_int(e));
+                    rhs.iter_mut().for_each(|e| vars.constant_folding_int(e));
+                }
+                Stmt::ValuePrecedeChain(exprs, _) => {
+                    exprs.iter_mut().for_each(|e| vars.constant_folding_int(e));
+                }
                 Stmt::ExtensionSupports(exprs, _) => {
                     exprs.iter_mut().for_each(|e| vars.constant_folding_int(e));
                 }
+                Stmt::Regular(exprs, _) => {
+                    exprs.iter_mut().for_each(|e| vars.constant_folding_int(e));
+                }
                 Stmt::GraphDivision(sizes, _edges, edge_lits, _opts) => {
                     sizes.iter_mut().for_each(|e| {
                         if let Some(e) = e {
@@ -459,6 +726,9 @@ impl CSP {
                 Stmt::CustomConstraint(exprs, _) => {
                     exprs.iter_mut().for_each(|e| vars.constant_folding_bool(e));
                 }
+                Stmt::CustomIntConstraint(exprs, _) => {
+                    exprs.iter_mut().for_each(|e| vars.constant_folding_int(e));
+                }
             }
         }
     }
@@ -1178,6 +1448,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_check_overflow() {
+        let mut csp = CSP::new();
+        let x = csp.new_int_var(Domain::range(-2000, 2000));
+        let y = csp.new_int_var(Domain::range(0, 2000));
+        let z = csp.new_int_var(Domain::range(-2000, 0));
+
+        csp.add_constraint(Stmt::Expr(
+            IntExpr::Linear(vec![
+                (Box::new(x.expr()), 1000),
+                (Box::new(y.expr()), -1000),
+            ])
+            .ge(z.expr()),
+        ));
+        assert_eq!(csp.check_overflow(), Ok(()));
+
+        let xyz = IntExpr::Mul(
+            Box::new(IntExpr::Mul(Box::new(x.expr()), Box::new(y.expr()))),
+            Box::new(z.expr()),
+        );
+        csp.add_constraint(Stmt::Expr(xyz.ne(IntExpr::Const(1))));
+        if cfg!(feature = "wide-int") {
+            assert_eq!(csp.check_overflow(), Ok(()));
+        } else {
+            assert_eq!(
+                csp.check_overflow(),
+                Err(OverflowError {
+                    magnitude: 8_000_000_001,
+                    limit: MAGNITUDE_LIMIT,
+                })
+            );
+        }
+    }
+
     #[test]
     fn test_assignment() {
         let mut assignment = Assignment::new();
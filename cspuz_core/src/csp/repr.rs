@@ -1,9 +1,10 @@
 use crate::util::ConvertMapIndex;
 use std::io::Write;
-use std::ops::{Add, BitAnd, BitOr, BitXor, Mul, Not, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Sub};
 
 use crate::arithmetic::CmpOp;
-use crate::custom_constraints::PropagatorGenerator;
+use crate::custom_constraints::{PropagatorGenerator, SimpleCustomIntConstraint};
+use crate::dfa::Dfa;
 use crate::propagators::graph_division::GraphDivisionOptions;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
@@ -48,8 +49,22 @@ pub enum Stmt {
     Expr(BoolExpr),
     AllDifferent(Vec<IntExpr>),
     ActiveVerticesConnected(Vec<BoolExpr>, Vec<(usize, usize)>),
+    /// The active edges form a single cycle, or no edge is active.
+    ActiveEdgesSingleCycle(Vec<BoolExpr>, Vec<(usize, usize)>),
     Circuit(Vec<IntExpr>),
+    /// The number of true expressions satisfies `op` against the constant.
+    Cardinality(Vec<BoolExpr>, CmpOp, i32),
+    /// The sum of the coefficients of the true expressions satisfies `op` against the constant.
+    PseudoBoolean(Vec<(BoolExpr, i32)>, CmpOp, i32),
+    /// The sequence of the values of the first expressions is lexicographically less than or equal
+    /// to that of the second ones (a proper prefix is smaller).
+    LexLessEq(Vec<IntExpr>, Vec<IntExpr>),
+    /// For each pair of consecutive values `s` and `t` in the list, `t` never appears in the
+    /// sequence of the values of the expressions before the first occurrence of `s`.
+    ValuePrecedeChain(Vec<IntExpr>, Vec<i32>),
     ExtensionSupports(Vec<IntExpr>, Vec<Vec<Option<i32>>>),
+    /// The sequence of the values of the expressions is accepted by the automaton.
+    Regular(Vec<IntExpr>, Dfa),
     GraphDivision(
         Vec<Option<IntExpr>>,
         Vec<(usize, usize)>,
@@ -57,6 +72,9 @@ pub enum Stmt {
         GraphDivisionOptions,
     ),
     CustomConstraint(Vec<BoolExpr>, Box<dyn PropagatorGenerator>),
+    /// A custom constraint whose inputs are the values of the expressions.
+    /// Currently, the expressions must not be log-encoded.
+    CustomIntConstraint(Vec<IntExpr>, Box<dyn SimpleCustomIntConstraint>),
 }
 
 impl std::fmt::Debug for Stmt {
@@ -67,10 +85,24 @@ impl std::fmt::Debug for Stmt {
             Stmt::ActiveVerticesConnected(exprs, edges) => {
                 write!(f, "ActiveVerticesConnected({:?}, {:?})", exprs, edges)
             }
+            Stmt::ActiveEdgesSingleCycle(exprs, edges) => {
+                write!(f, "ActiveEdgesSingleCycle({:?}, {:?})", exprs, edges)
+            }
             Stmt::Circuit(exprs) => write!(f, "Circuit({:?})", exprs),
+            Stmt::Cardinality(exprs, op, rhs) => {
+                write!(f, "Cardinality({:?}, {:?}, {})", exprs, op, rhs)
+            }
+            Stmt::PseudoBoolean(terms, op, rhs) => {
+                write!(f, "PseudoBoolean({:?}, {:?}, {})", terms, op, rhs)
+            }
+            Stmt::LexLessEq(lhs, rhs) => write!(f, "LexLessEq({:?}, {:?})", lhs, rhs),
+            Stmt::ValuePrecedeChain(exprs, values) => {
+                write!(f, "ValuePrecedeChain({:?}, {:?})", exprs, values)
+            }
             Stmt::ExtensionSupports(exprs, supports) => {
                 write!(f, "ExtensionSupports({:?}, {:?})", exprs, supports)
             }
+            Stmt::Regular(exprs, dfa) => write!(f, "Regular({:?}, {:?})", exprs, dfa),
             Stmt::GraphDivision(sizes, edges, edges_lit, _opts) => {
                 write!(
                     f,
@@ -79,6 +111,7 @@ impl std::fmt::Debug for Stmt {
                 )
             }
             Stmt::CustomConstraint(_, _) => write!(f, "CustomConstraint"),
+            Stmt::CustomIntConstraint(_, _) => write!(f, "CustomIntConstraint"),
         }
     }
 }
@@ -113,6 +146,14 @@ impl Stmt {
                 }
                 write!(out, "])")?;
             }
+            Stmt::ActiveEdgesSingleCycle(exprs, edges) => {
+                write!(out, "(active-edges-single-cycle")?;
+                for (i, (expr, &(u, v))) in exprs.iter().zip(edges).enumerate() {
+                    write!(out, " {}:{}--{}:", i, u, v)?;
+                    expr.pretty_print(out)?;
+                }
+                write!(out, ")")?;
+            }
             Stmt::Circuit(exprs) => {
                 write!(out, "(circuit")?;
                 for e in exprs {
@@ -121,6 +162,54 @@ impl Stmt {
                 }
                 write!(out, ")")?;
             }
+            Stmt::Cardinality(exprs, op, rhs) => {
+                write!(out, "(cardinality")?;
+                for e in exprs {
+                    write!(out, " ")?;
+                    e.pretty_print(out)?;
+                }
+                write!(out, " {} {})", op, rhs)?;
+            }
+            Stmt::PseudoBoolean(terms, op, rhs) => {
+                write!(out, "(pseudo-boolean")?;
+                for (e, coef) in terms {
+                    write!(out, " {}*", coef)?;
+                    e.pretty_print(out)?;
+                }
+                write!(out, " {} {})", op, rhs)?;
+            }
+            Stmt::LexLessEq(lhs, rhs) => {
+                write!(out, "(lex-less-eq (")?;
+                for (i, e) in lhs.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, " ")?;
+                    }
+                    e.pretty_print(out)?;
+                }
+                write!(out, ") (")?;
+                for (i, e) in rhs.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, " ")?;
+                    }
+                    e.pretty_print(out)?;
+                }
+                write!(out, "))")?;
+            }
+            Stmt::ValuePrecedeChain(exprs, values) => {
+                write!(out, "(value-precede-chain")?;
+                for e in exprs {
+                    write!(out, " ")?;
+                    e.pretty_print(out)?;
+                }
+                write!(out, " values=[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, " ")?;
+                    }
+                    write!(out, "{}", v)?;
+                }
+                write!(out, "])")?;
+            }
             Stmt::ExtensionSupports(exprs, supports) => {
                 write!(out, "(extension-supports")?;
                 for expr in exprs {
@@ -152,6 +241,38 @@ impl Stmt {
                 }
                 write!(out, "])")?;
             }
+            Stmt::Regular(exprs, dfa) => {
+                write!(out, "(regular")?;
+                for expr in exprs {
+                    write!(out, " ")?;
+                    expr.pretty_print(out)?;
+                }
+                write!(out, " initial={} accepting=[", dfa.initial_state())?;
+                let mut is_first = true;
+                for s in 0..dfa.num_states() {
+                    if dfa.is_accepting(s) {
+                        if !is_first {
+                            write!(out, " ")?;
+                        } else {
+                            is_first = false;
+                        }
+                        write!(out, "{}", s)?;
+                    }
+                }
+                write!(out, "] transitions=[")?;
+                let mut is_first = true;
+                for s in 0..dfa.num_states() {
+                    for &(symbol, t) in dfa.transitions(s) {
+                        if !is_first {
+                            write!(out, " ")?;
+                        } else {
+                            is_first = false;
+                        }
+                        write!(out, "{}-{}->{}", s, symbol, t)?;
+                    }
+                }
+                write!(out, "])")?;
+            }
             Stmt::GraphDivision(sizes, edges, edges_lit, _opts) => {
                 write!(out, "(graph-division")?;
                 write!(out, " sizes=[")?;
@@ -199,6 +320,14 @@ impl Stmt {
                 }
                 write!(out, ")")?;
             }
+            Stmt::CustomIntConstraint(exprs, _) => {
+                write!(out, "(custom-int-constraint")?;
+                for expr in exprs {
+                    write!(out, " ")?;
+                    expr.pretty_print(out)?;
+                }
+                write!(out, ")")?;
+            }
         }
         Ok(())
     }
@@ -344,6 +473,14 @@ pub enum IntExpr {
     If(Box<BoolExpr>, Box<IntExpr>, Box<IntExpr>),
     Abs(Box<IntExpr>),
     Mul(Box<IntExpr>, Box<IntExpr>),
+    /// `x / y` rounded toward zero, or 0 if `y` is 0.
+    Div(Box<IntExpr>, Box<IntExpr>),
+    /// `x % y` with the sign of `x` (so that `x == (x / y) * y + x % y`), or `x` if `y` is 0.
+    Mod(Box<IntExpr>, Box<IntExpr>),
+    Min(Box<IntExpr>, Box<IntExpr>),
+    Max(Box<IntExpr>, Box<IntExpr>),
+    /// The `index`-th (0-based) expression of the list, or 0 if `index` is out of range.
+    Element(Box<IntExpr>, Vec<IntExpr>),
 }
 
 impl IntExpr {
@@ -375,6 +512,18 @@ impl IntExpr {
         IntExpr::Abs(Box::new(self))
     }
 
+    pub fn min(self, rhs: IntExpr) -> IntExpr {
+        IntExpr::Min(Box::new(self), Box::new(rhs))
+    }
+
+    pub fn max(self, rhs: IntExpr) -> IntExpr {
+        IntExpr::Max(Box::new(self), Box::new(rhs))
+    }
+
+    pub fn element(index: IntExpr, values: Vec<IntExpr>) -> IntExpr {
+        IntExpr::Element(Box::new(index), values)
+    }
+
     pub fn pretty_print<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
         match self {
             &IntExpr::Const(c) => write!(out, "{}", c)?,
@@ -415,6 +564,29 @@ impl IntExpr {
                 y.pretty_print(out)?;
                 write!(out, ")")?;
             }
+            IntExpr::Div(x, y) | IntExpr::Mod(x, y) | IntExpr::Min(x, y) | IntExpr::Max(x, y) => {
+                let name = match self {
+                    IntExpr::Div(_, _) => "div",
+                    IntExpr::Mod(_, _) => "mod",
+                    IntExpr::Min(_, _) => "min",
+                    IntExpr::Max(_, _) => "max",
+                    _ => unreachable!(),
+                };
+                write!(out, "({} ", name)?;
+                x.pretty_print(out)?;
+                write!(out, " ")?;
+                y.pretty_print(out)?;
+                write!(out, ")")?;
+            }
+            IntExpr::Element(index, values) => {
+                write!(out, "(element ")?;
+                index.pretty_print(out)?;
+                for v in values {
+                    write!(out, " ")?;
+                    v.pretty_print(out)?;
+                }
+                write!(out, ")")?;
+            }
         }
         Ok(())
     }
@@ -451,3 +623,19 @@ impl Mul<IntExpr> for IntExpr {
         IntExpr::Mul(Box::new(self), Box::new(rhs))
     }
 }
+
+impl Div<IntExpr> for IntExpr {
+    type Output = IntExpr;
+
+    fn div(self, rhs: IntExpr) -> IntExpr {
+        IntExpr::Div(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl Rem<IntExpr> for IntExpr {
+    type Output = IntExpr;
+
+    fn rem(self, rhs: IntExpr) -> IntExpr {
+        IntExpr::Mod(Box::new(self), Box::new(rhs))
+    }
+}
@@ -53,6 +53,34 @@ pub fn eval_int_expr(assignment: &Assignment, expr: &IntExpr) -> i32 {
         ),
         IntExpr::Abs(x) => eval_int_expr(assignment, x).abs(),
         IntExpr::Mul(x, y) => eval_int_expr(assignment, x) * eval_int_expr(assignment, y),
+        IntExpr::Div(x, y) => {
+            let x = eval_int_expr(assignment, x);
+            let y = eval_int_expr(assignment, y);
+            if y == 0 {
+                0
+            } else {
+                x / y
+            }
+        }
+        IntExpr::Mod(x, y) => {
+            let x = eval_int_expr(assignment, x);
+            let y = eval_int_expr(assignment, y);
+            if y == 0 {
+                x
+            } else {
+                x % y
+            }
+        }
+        IntExpr::Min(x, y) => eval_int_expr(assignment, x).min(eval_int_expr(assignment, y)),
+        IntExpr::Max(x, y) => eval_int_expr(assignment, x).max(eval_int_expr(assignment, y)),
+        IntExpr::Element(index, values) => {
+            let index = eval_int_expr(assignment, index);
+            if 0 <= index && (index as usize) < values.len() {
+                eval_int_expr(assignment, &values[index as usize])
+            } else {
+                0
+            }
+        }
     }
 }
 
@@ -63,16 +91,29 @@ pub fn clone_stmt(stmt: &Stmt) -> Stmt {
         Stmt::ActiveVerticesConnected(exprs, edges) => {
             Stmt::ActiveVerticesConnected(exprs.clone(), edges.clone())
         }
+        Stmt::ActiveEdgesSingleCycle(exprs, edges) => {
+            Stmt::ActiveEdgesSingleCycle(exprs.clone(), edges.clone())
+        }
         Stmt::Circuit(exprs) => Stmt::Circuit(exprs.clone()),
+        Stmt::Cardinality(exprs, op, rhs) => Stmt::Cardinality(exprs.clone(), *op, *rhs),
+        Stmt::PseudoBoolean(terms, op, rhs) => Stmt::PseudoBoolean(terms.clone(), *op, *rhs),
+        Stmt::LexLessEq(lhs, rhs) => Stmt::LexLessEq(lhs.clone(), rhs.clone()),
+        Stmt::ValuePrecedeChain(exprs, values) => {
+            Stmt::ValuePrecedeChain(exprs.clone(), values.clone())
+        }
         Stmt::ExtensionSupports(exprs, supports) => {
             Stmt::ExtensionSupports(exprs.clone(), supports.clone())
         }
+        Stmt::Regular(exprs, dfa) => Stmt::Regular(exprs.clone(), dfa.clone()),
         Stmt::GraphDivision(sizes, edges, edges_lit, opts) => {
             Stmt::GraphDivision(sizes.clone(), edges.clone(), edges_lit.clone(), *opts)
         }
         Stmt::CustomConstraint(_, _) => {
             panic!("CustomConstraint cannot be cloned");
         }
+        Stmt::CustomIntConstraint(_, _) => {
+            panic!("CustomIntConstraint cannot be cloned");
+        }
     };
     cloned
 }
@@ -101,6 +142,13 @@ pub fn is_stmt_satisfied(assignment: &Assignment, stmt: &Stmt) -> bool {
                 .collect::<Vec<_>>();
             crate::test_utils::check_graph_active_vertices_connected(&is_active, &edges)
         }
+        Stmt::ActiveEdgesSingleCycle(is_active, edges) => {
+            let is_active = is_active
+                .iter()
+                .map(|v| crate::csp::test_utils::eval_bool_expr(assignment, v))
+                .collect::<Vec<_>>();
+            crate::test_utils::check_graph_active_edges_single_cycle(&is_active, edges)
+        }
         Stmt::Circuit(values) => {
             let values = values
                 .iter()
@@ -108,6 +156,47 @@ pub fn is_stmt_satisfied(assignment: &Assignment, stmt: &Stmt) -> bool {
                 .collect::<Vec<_>>();
             crate::test_utils::check_circuit(&values)
         }
+        Stmt::Cardinality(exprs, op, rhs) => {
+            let count = exprs
+                .iter()
+                .filter(|e| crate::csp::test_utils::eval_bool_expr(assignment, e))
+                .count() as i32;
+            op.compare(count, *rhs)
+        }
+        Stmt::PseudoBoolean(terms, op, rhs) => {
+            let sum = terms
+                .iter()
+                .filter(|(e, _)| crate::csp::test_utils::eval_bool_expr(assignment, e))
+                .map(|&(_, coef)| coef)
+                .sum::<i32>();
+            op.compare(sum, *rhs)
+        }
+        Stmt::LexLessEq(lhs, rhs) => {
+            let lhs = lhs
+                .iter()
+                .map(|e| crate::csp::test_utils::eval_int_expr(assignment, e))
+                .collect::<Vec<_>>();
+            let rhs = rhs
+                .iter()
+                .map(|e| crate::csp::test_utils::eval_int_expr(assignment, e))
+                .collect::<Vec<_>>();
+            lhs <= rhs
+        }
+        Stmt::ValuePrecedeChain(exprs, values) => {
+            let seq = exprs
+                .iter()
+                .map(|e| crate::csp::test_utils::eval_int_expr(assignment, e))
+                .collect::<Vec<_>>();
+            values.windows(2).all(|w| {
+                let first_s = seq.iter().position(|&x| x == w[0]);
+                let first_t = seq.iter().position(|&x| x == w[1]);
+                match (first_s, first_t) {
+                    (_, None) => true,
+                    (None, Some(_)) => false,
+                    (Some(s), Some(t)) => s < t,
+                }
+            })
+        }
         Stmt::ExtensionSupports(vars, supports) => {
             let values = vars
                 .iter()
@@ -129,6 +218,13 @@ pub fn is_stmt_satisfied(assignment: &Assignment, stmt: &Stmt) -> bool {
             }
             isok
         }
+        Stmt::Regular(exprs, dfa) => {
+            let values = exprs
+                .iter()
+                .map(|e| crate::csp::test_utils::eval_int_expr(assignment, e))
+                .collect::<Vec<_>>();
+            dfa.accepts(&values)
+        }
         Stmt::GraphDivision(sizes, edges, edges_lit, opts) => {
             assert!(!opts.require_extra_constraints());
             let sizes = sizes
@@ -148,6 +244,9 @@ pub fn is_stmt_satisfied(assignment: &Assignment, stmt: &Stmt) -> bool {
         Stmt::CustomConstraint(_, _) => {
             todo!();
         }
+        Stmt::CustomIntConstraint(_, _) => {
+            todo!();
+        }
     }
 }
 
@@ -375,5 +474,40 @@ mod tests {
             ),
             42 * 100
         );
+
+        assert_eq!(eval_int_expr(&assignment, &(b.expr() / a.expr())), 2);
+        assert_eq!(eval_int_expr(&assignment, &(b.expr() % a.expr())), 16);
+        assert_eq!(
+            eval_int_expr(&assignment, &(a.expr() * -1 / IntExpr::Const(5))),
+            -8
+        );
+        assert_eq!(
+            eval_int_expr(&assignment, &(a.expr() * -1 % IntExpr::Const(5))),
+            -2
+        );
+        assert_eq!(
+            eval_int_expr(&assignment, &(a.expr() / IntExpr::Const(0))),
+            0
+        );
+        assert_eq!(
+            eval_int_expr(&assignment, &(a.expr() % IntExpr::Const(0))),
+            42
+        );
+        assert_eq!(eval_int_expr(&assignment, &a.expr().min(b.expr())), 42);
+        assert_eq!(eval_int_expr(&assignment, &a.expr().max(b.expr())), 100);
+        assert_eq!(
+            eval_int_expr(
+                &assignment,
+                &IntExpr::element(IntExpr::Const(1), vec![a.expr(), b.expr()])
+            ),
+            100
+        );
+        assert_eq!(
+            eval_int_expr(
+                &assignment,
+                &IntExpr::element(IntExpr::Const(2), vec![a.expr(), b.expr()])
+            ),
+            0
+        );
     }
 }
@@ -2,43 +2,92 @@
 use std::fmt::Write;
 use std::io::BufRead;
 
-use super::config::Config;
-use super::integration::{IntegratedSolver, PerfStats};
-use super::parser::{parse, ParseResult, Var, VarMap};
+use super::arithmetic::OverflowError;
+use super::config::{BackendConfig, Config};
+use super::csp::IntExpr;
+use super::integration::{
+    take_resource_limit_exceeded, take_sat_solver_error, IntegratedSolver, Model, PerfStats,
+    SolveOutcome,
+};
+use super::parser::{parse, ObjectiveSense, ParseError, ParseResult, Var, VarMap};
 
-pub fn csugar_cli<R: BufRead>(input: &mut R, config: Config) -> (String, PerfStats) {
+/// An error which prevents `csugar_cli` from solving the input.
+#[derive(Debug)]
+pub enum CliError {
+    Io(std::io::Error),
+    /// The input is malformed. The line number in the error refers to the whole input.
+    Parse(ParseError),
+    Overflow(OverflowError),
+    /// The SAT solver failed (see `take_sat_solver_error`).
+    Solver(String),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CliError::Io(e) => write!(f, "I/O error: {}", e),
+            CliError::Parse(e) => write!(f, "parse error at {}", e),
+            CliError::Overflow(e) => write!(f, "overflow: {}", e),
+            CliError::Solver(e) => write!(f, "SAT solver error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub fn csugar_cli<R: BufRead>(
+    input: &mut R,
+    config: Config,
+    backend_config: &BackendConfig,
+) -> Result<(String, PerfStats), CliError> {
     let mut var_map = VarMap::new();
-    let mut solver = IntegratedSolver::with_config(config);
+    let mut solver = IntegratedSolver::with_backend_config(config, backend_config);
 
     let perf_stats = PerfStats::new();
     solver.set_perf_stats(&perf_stats);
 
+    // clear the flags left by the solvers used before in this thread
+    take_resource_limit_exceeded();
+    take_sat_solver_error();
+
     let mut buffer = String::new();
 
     let mut target_vars: Option<Vec<String>> = None;
+    let mut objective: Option<(ObjectiveSense, IntExpr)> = None;
 
     let mut ret = String::new();
 
+    let mut line_no = 0;
+    let mut target_line_no = 0;
     loop {
         buffer.clear();
-        let num_bytes = input.read_line(&mut buffer).unwrap(); // TODO
+        let num_bytes = input.read_line(&mut buffer).map_err(CliError::Io)?;
         if num_bytes == 0 {
             // EOF
             break;
         }
+        line_no += 1;
         let line = buffer.trim_end();
 
         if line.starts_with("#") {
-            assert!(target_vars.is_none());
+            if target_vars.is_some() {
+                return Err(CliError::Parse(ParseError {
+                    line: line_no,
+                    column: 1,
+                    message: String::from("target variables are specified more than once"),
+                }));
+            }
             target_vars = Some(
                 line.trim_start_matches("#")
                     .split(" ")
                     .map(String::from)
                     .collect(),
             );
+            target_line_no = line_no;
             continue;
         }
-        let result = parse(&var_map, line);
+        let result = parse(&var_map, line)
+            .map_err(|e| CliError::Parse(ParseError { line: line_no, ..e }))?;
         match result {
             ParseResult::BoolVarDecl(name) => {
                 let var = solver.new_bool_var();
@@ -53,21 +102,59 @@ pub fn csugar_cli<R: BufRead>(input: &mut R, config: Config) -> (String, PerfSta
                 var_map.add_int_var(name, var);
             }
             ParseResult::Stmt(stmt) => solver.add_constraint(stmt),
+            ParseResult::Objective(sense, expr) => {
+                if objective.is_some() {
+                    return Err(CliError::Parse(ParseError {
+                        line: line_no,
+                        column: 1,
+                        message: String::from("objective is specified more than once"),
+                    }));
+                }
+                objective = Some((sense, expr));
+            }
         }
     }
 
     match target_vars {
         Some(target_vars) => {
+            if let Some((sense, expr)) = objective {
+                // restrict the solutions to optimal ones
+                let optimum = match sense {
+                    ObjectiveSense::Minimize => solver.try_minimize_under(expr.clone(), &[]),
+                    ObjectiveSense::Maximize => solver.try_maximize_under(expr.clone(), &[]),
+                }
+                .map(|(value, _)| value);
+                match optimum {
+                    SolveOutcome::Sat(value) => solver.add_expr(expr.eq(IntExpr::Const(value))),
+                    SolveOutcome::Unsat => {
+                        writeln!(&mut ret, "unsat").unwrap();
+                        return Ok((ret, perf_stats));
+                    }
+                    SolveOutcome::Unknown => {
+                        writeln!(&mut ret, "unknown").unwrap();
+                        return Ok((ret, perf_stats));
+                    }
+                    SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
+                }
+            }
+
             let mut bool_target = vec![];
             let mut int_target = vec![];
             for target in &target_vars {
-                match var_map.get_var(target).unwrap() {
-                    Var::Bool(var) => bool_target.push(var),
-                    Var::Int(var) => int_target.push(var),
+                match var_map.get_var(target) {
+                    Some(Var::Bool(var)) => bool_target.push(var),
+                    Some(Var::Int(var)) => int_target.push(var),
+                    None => {
+                        return Err(CliError::Parse(ParseError {
+                            line: target_line_no,
+                            column: 1,
+                            message: format!("unknown variable: {}", target),
+                        }))
+                    }
                 }
             }
-            match solver.decide_irrefutable_facts(&bool_target, &int_target) {
-                Some(result) => {
+            match solver.try_decide_irrefutable_facts(&bool_target, &int_target) {
+                SolveOutcome::Sat(result) => {
                     writeln!(&mut ret, "sat").unwrap();
                     for target in &target_vars {
                         match var_map.get_var(target).unwrap() {
@@ -84,27 +171,52 @@ pub fn csugar_cli<R: BufRead>(input: &mut R, config: Config) -> (String, PerfSta
                         }
                     }
                 }
-                None => writeln!(&mut ret, "unsat").unwrap(),
+                SolveOutcome::Unsat => writeln!(&mut ret, "unsat").unwrap(),
+                SolveOutcome::Unknown => writeln!(&mut ret, "unknown").unwrap(),
+                SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
             }
         }
-        None => match solver.solve() {
-            Some(model) => {
-                writeln!(&mut ret, "s SATISFIABLE").unwrap();
-                for (name, &var) in var_map.iter() {
-                    match var {
-                        Var::Bool(var) => {
-                            writeln!(&mut ret, "a {}\t{}", name, model.get_bool(var)).unwrap()
-                        }
-                        Var::Int(var) => {
-                            writeln!(&mut ret, "a {}\t{}", name, model.get_int(var)).unwrap()
-                        }
+        None => match objective {
+            Some((sense, expr)) => {
+                let result = match sense {
+                    ObjectiveSense::Minimize => solver.try_minimize_under(expr, &[]),
+                    ObjectiveSense::Maximize => solver.try_maximize_under(expr, &[]),
+                };
+                match result {
+                    SolveOutcome::Sat((value, model)) => {
+                        writeln!(&mut ret, "o {}", value).unwrap();
+                        writeln!(&mut ret, "s OPTIMUM FOUND").unwrap();
+                        write_model(&mut ret, &var_map, &model);
                     }
+                    SolveOutcome::Unsat => writeln!(&mut ret, "s UNSATISFIABLE").unwrap(),
+                    SolveOutcome::Unknown => writeln!(&mut ret, "s UNKNOWN").unwrap(),
+                    SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
                 }
-                writeln!(&mut ret, "a").unwrap();
             }
-            None => writeln!(&mut ret, "s UNSATISFIABLE").unwrap(),
+            None => match solver.try_solve() {
+                SolveOutcome::Sat(model) => {
+                    writeln!(&mut ret, "s SATISFIABLE").unwrap();
+                    write_model(&mut ret, &var_map, &model);
+                }
+                SolveOutcome::Unsat => writeln!(&mut ret, "s UNSATISFIABLE").unwrap(),
+                SolveOutcome::Unknown => writeln!(&mut ret, "s UNKNOWN").unwrap(),
+                SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
+            },
         },
     }
 
-    (ret, perf_stats)
+    if let Some(e) = take_sat_solver_error() {
+        return Err(CliError::Solver(e));
+    }
+    Ok((ret, perf_stats))
+}
+
+fn write_model(out: &mut String, var_map: &VarMap, model: &Model) {
+    for (name, &var) in var_map.iter() {
+        match var {
+            Var::Bool(var) => writeln!(out, "a {}\t{}", name, model.get_bool(var)).unwrap(),
+            Var::Int(var) => writeln!(out, "a {}\t{}", name, model.get_int(var)).unwrap(),
+        }
+    }
+    writeln!(out, "a").unwrap();
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "backend-cadical")]
+use crate::backend::cadical::CaDiCaLSolverManipulator;
 use crate::backend::glucose::GlucoseSolverManipulator;
 #[cfg(feature = "experimental-backend-glucose-rs")]
 use crate::backend::glucose_rs::GlucoseSolverManipulator as GlucoseRsSolverManipulator;
@@ -5,10 +7,11 @@ use crate::sat::Lit;
 use crate::sat::{CustomPropagator, SolverManipulator};
 
 /// To make the CSP solver more flexible, we provide a way to define custom constraints (propagators).
-/// Currently, custom constraints are supported only in the Glucose backend.
-/// We provide two ways to define custom constraints:
+/// Currently, custom constraints are supported in the Glucose, GlucoseRs and CaDiCaL backends.
+/// We provide three ways to define custom constraints:
 /// - `PropagatorGenerator` trait (more flexible but hard to implement)
 /// - `SimpleCustomConstraint` trait (less flexible but easy to implement)
+/// - `SimpleCustomIntConstraint` trait (`SimpleCustomConstraint` on int values)
 ///
 /// In custom constraints, we abstract a constraint as a predicate on N boolean values, and the constraint requires that the predicate is satisfied.
 /// These N boolean values are represented as `BoolExpr`s, which are passed to `Stmt::CustomConstraint` along with the custom constraint itself.
@@ -44,7 +47,25 @@ use crate::sat::{CustomPropagator, SolverManipulator};
 /// For this constraint, we can state that the constraint is inconsistent immediately when two values are decided to be true.
 /// Even though, it is permissive that the constraint does not find the inconsistency until all values are decided.
 ///
-/// TODO: support int values
+/// # `SimpleCustomIntConstraint` trait
+/// `SimpleCustomIntConstraint` is the counterpart of `SimpleCustomConstraint` whose inputs are `IntExpr`s,
+/// which are passed to `Stmt::CustomIntConstraint`.
+/// `initialize_sat` receives the domain of each input in ascending order.
+///
+/// Instead of boolean values, `notify` receives domain changes of the inputs as `IntInputLit`s.
+/// Which kind of changes are reported depends on how the input is encoded in the SAT solver:
+/// - for a direct-encoded input, `Eq(v)` (the value is fixed to `v`) and `Ne(v)` (`v` is excluded),
+/// - for an order-encoded input, `Ge(v)` and `Le(v)` (the bounds of the value are updated).
+///
+/// Inputs are never log-encoded (nor the variables sharing constraints with them).
+/// `find_inconsistency` returns a list of tuples (index, `IntInputLit`) in the same manner as `SimpleCustomConstraint`.
+/// Any kind of `IntInputLit` can be used in the reason regardless of the encoding of the input,
+/// but every literal in the reason must be implied by the notified changes.
+/// An inconsistency whose reason contains a literal which can never hold (e.g. `Eq(v)` for `v` out of the domain)
+/// is ignored.
+/// It is recommended to cite the notified changes themselves: otherwise, a literal implied only by
+/// changes in earlier decision levels may be chosen, which can violate the condition that
+/// the reason must contain a literal in the current level.
 pub trait PropagatorGenerator {
     fn generate<'a>(
         self: Box<Self>,
@@ -60,6 +81,14 @@ pub trait PropagatorGenerator {
     ) -> Box<dyn CustomPropagator<GlucoseRsSolverManipulator> + 'a>
     where
         Self: 'a;
+
+    #[cfg(feature = "backend-cadical")]
+    fn generate_for_cadical<'a>(
+        self: Box<Self>,
+        proxy_map: Vec<Lit>,
+    ) -> Box<dyn CustomPropagator<CaDiCaLSolverManipulator> + 'a>
+    where
+        Self: 'a;
 }
 
 pub trait SimpleCustomConstraint {
@@ -73,6 +102,241 @@ pub trait SimpleCustomConstraint {
     }
 }
 
+/// A domain change of an int input of `SimpleCustomIntConstraint`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IntInputLit {
+    /// The value is equal to the specified value.
+    Eq(i32),
+    /// The value is not equal to the specified value.
+    Ne(i32),
+    /// The value is at least the specified value.
+    Ge(i32),
+    /// The value is at most the specified value.
+    Le(i32),
+}
+
+pub trait SimpleCustomIntConstraint {
+    fn initialize_sat(&mut self, domains: &[Vec<i32>]);
+    fn notify(&mut self, index: usize, lit: IntInputLit);
+    fn find_inconsistency(&mut self) -> Option<Vec<(usize, IntInputLit)>>;
+    fn undo(&mut self);
+
+    fn lazy_propagation(&self) -> bool {
+        false
+    }
+}
+
+/// The SAT literals representing an int input of `SimpleCustomIntConstraint`.
+pub(crate) enum IntInputEncoding {
+    /// `lits[i]` is `x == domain[i]`.
+    Direct { domain: Vec<i32>, lits: Vec<Lit> },
+    /// `lits[i]` is `x >= domain[i + 1]`.
+    Order { domain: Vec<i32>, lits: Vec<Lit> },
+}
+
+/// Adapts `SimpleCustomIntConstraint` to `SimpleCustomConstraint` whose inputs are the SAT literals
+/// of all the int inputs.
+pub(crate) struct IntCustomConstraintAdapter {
+    constraint: Box<dyn SimpleCustomIntConstraint>,
+    domains: Vec<Vec<i32>>,
+    is_direct: Vec<bool>,
+    offsets: Vec<usize>,
+    // For each boolean input, the index of the int input and the `IntInputLit`s meaning the input is true / false.
+    bool_inputs: Vec<(usize, IntInputLit, IntInputLit)>,
+    assignment: Vec<Option<bool>>,
+    decision_stack: Vec<usize>,
+}
+
+impl IntCustomConstraintAdapter {
+    /// Returns the adapter and the SAT literals to be passed to the underlying `SimpleCustomConstraint`.
+    pub(crate) fn new(
+        constraint: Box<dyn SimpleCustomIntConstraint>,
+        inputs: Vec<IntInputEncoding>,
+    ) -> (IntCustomConstraintAdapter, Vec<Lit>) {
+        let mut domains = vec![];
+        let mut is_direct = vec![];
+        let mut offsets = vec![];
+        let mut bool_inputs = vec![];
+        let mut all_lits = vec![];
+
+        for (i, input) in inputs.into_iter().enumerate() {
+            offsets.push(all_lits.len());
+            match input {
+                IntInputEncoding::Direct { domain, lits } => {
+                    assert_eq!(domain.len(), lits.len());
+                    for &v in &domain {
+                        bool_inputs.push((i, IntInputLit::Eq(v), IntInputLit::Ne(v)));
+                    }
+                    all_lits.extend(lits);
+                    domains.push(domain);
+                    is_direct.push(true);
+                }
+                IntInputEncoding::Order { domain, lits } => {
+                    assert_eq!(domain.len(), lits.len() + 1);
+                    for j in 1..domain.len() {
+                        bool_inputs.push((
+                            i,
+                            IntInputLit::Ge(domain[j]),
+                            IntInputLit::Le(domain[j - 1]),
+                        ));
+                    }
+                    all_lits.extend(lits);
+                    domains.push(domain);
+                    is_direct.push(false);
+                }
+            }
+        }
+
+        let adapter = IntCustomConstraintAdapter {
+            constraint,
+            domains,
+            is_direct,
+            offsets,
+            bool_inputs,
+            assignment: vec![],
+            decision_stack: vec![],
+        };
+        (adapter, all_lits)
+    }
+
+    fn holds(&self, lits: &[(usize, bool)]) -> bool {
+        lits.iter()
+            .all(|&(idx, value)| self.assignment[idx] == Some(value))
+    }
+
+    /// Converts `lit` on the `index`-th int input into a conjunction of the boolean inputs.
+    /// Returns `None` if `lit` can never hold (e.g. `Eq(v)` for `v` out of the domain).
+    fn explain(&self, index: usize, lit: IntInputLit) -> Option<Vec<(usize, bool)>> {
+        let domain = &self.domains[index];
+        let offset = self.offsets[index];
+
+        let ret = if self.is_direct[index] {
+            // If the value is fixed, `x == w` explains any bound satisfied by `w`; the exclusions of
+            // the other values may not be assigned yet.
+            let fixed = (0..domain.len()).find(|&j| self.assignment[offset + j] == Some(true));
+            match lit {
+                IntInputLit::Eq(v) => vec![(offset + domain.binary_search(&v).ok()?, true)],
+                IntInputLit::Ne(v) => match (domain.binary_search(&v), fixed) {
+                    (Ok(j), Some(k)) if j != k => vec![(offset + k, true)],
+                    (Ok(j), _) => vec![(offset + j, false)],
+                    (Err(_), _) => vec![],
+                },
+                IntInputLit::Ge(v) => match fixed {
+                    Some(j) if domain[j] >= v => vec![(offset + j, true)],
+                    _ => (0..domain.partition_point(|&x| x < v))
+                        .map(|j| (offset + j, false))
+                        .collect(),
+                },
+                IntInputLit::Le(v) => match fixed {
+                    Some(j) if domain[j] <= v => vec![(offset + j, true)],
+                    _ => (domain.partition_point(|&x| x <= v)..domain.len())
+                        .map(|j| (offset + j, false))
+                        .collect(),
+                },
+            }
+        } else {
+            match lit {
+                IntInputLit::Eq(v) => {
+                    domain.binary_search(&v).ok()?;
+                    let mut ret = self.explain(index, IntInputLit::Ge(v))?;
+                    ret.extend(self.explain(index, IntInputLit::Le(v))?);
+                    ret
+                }
+                IntInputLit::Ne(v) => {
+                    let j = match domain.binary_search(&v) {
+                        Ok(j) => j,
+                        Err(_) => return Some(vec![]),
+                    };
+                    // x != domain[j]  <=>  x < domain[j] or x > domain[j]; cite the one which holds
+                    let below = if j > 0 {
+                        Some(vec![(offset + j - 1, false)])
+                    } else {
+                        None
+                    };
+                    let above = if j + 1 < domain.len() {
+                        Some(vec![(offset + j, true)])
+                    } else {
+                        None
+                    };
+                    match (below, above) {
+                        (Some(below), Some(above)) => {
+                            if self.holds(&below) {
+                                below
+                            } else {
+                                above
+                            }
+                        }
+                        (Some(below), None) => below,
+                        (None, Some(above)) => above,
+                        (None, None) => return None,
+                    }
+                }
+                IntInputLit::Ge(v) => {
+                    // x >= v  <=>  x >= domain[j] where domain[j] is the smallest value at least v
+                    let j = domain.partition_point(|&x| x < v);
+                    if j == 0 {
+                        vec![]
+                    } else if j == domain.len() {
+                        return None;
+                    } else {
+                        vec![(offset + j - 1, true)]
+                    }
+                }
+                IntInputLit::Le(v) => {
+                    // x <= v  <=>  !(x >= domain[j]) where domain[j] is the smallest value greater than v
+                    let j = domain.partition_point(|&x| x <= v);
+                    if j == domain.len() {
+                        vec![]
+                    } else if j == 0 {
+                        return None;
+                    } else {
+                        vec![(offset + j - 1, false)]
+                    }
+                }
+            }
+        };
+        Some(ret)
+    }
+}
+
+impl SimpleCustomConstraint for IntCustomConstraintAdapter {
+    fn initialize_sat(&mut self, num_inputs: usize) {
+        assert_eq!(num_inputs, self.bool_inputs.len());
+        self.assignment = vec![None; num_inputs];
+        self.constraint.initialize_sat(&self.domains);
+    }
+
+    fn notify(&mut self, index: usize, value: bool) {
+        self.assignment[index] = Some(value);
+        self.decision_stack.push(index);
+
+        let (i, lit_true, lit_false) = self.bool_inputs[index];
+        self.constraint
+            .notify(i, if value { lit_true } else { lit_false });
+    }
+
+    fn find_inconsistency(&mut self) -> Option<Vec<(usize, bool)>> {
+        let inconsistency = self.constraint.find_inconsistency()?;
+        let mut ret = vec![];
+        for (index, lit) in inconsistency {
+            // A literal which can never hold is not implied by the notified changes, so the
+            // reason is wrong. Such a report is ignored rather than turned into an unsound clause.
+            ret.extend(self.explain(index, lit)?);
+        }
+        Some(ret)
+    }
+
+    fn undo(&mut self) {
+        let index = self.decision_stack.pop().unwrap();
+        self.assignment[index] = None;
+        self.constraint.undo();
+    }
+
+    fn lazy_propagation(&self) -> bool {
+        self.constraint.lazy_propagation()
+    }
+}
+
 impl<T: SimpleCustomConstraint> PropagatorGenerator for T {
     fn generate<'a>(
         self: Box<Self>,
@@ -94,6 +358,17 @@ impl<T: SimpleCustomConstraint> PropagatorGenerator for T {
     {
         Box::new(CustomConstraintWrapperForGlucose::new(*self, proxy_map))
     }
+
+    #[cfg(feature = "backend-cadical")]
+    fn generate_for_cadical<'a>(
+        self: Box<Self>,
+        proxy_map: Vec<Lit>,
+    ) -> Box<dyn CustomPropagator<CaDiCaLSolverManipulator> + 'a>
+    where
+        Self: 'a,
+    {
+        Box::new(CustomConstraintWrapperForGlucose::new(*self, proxy_map))
+    }
 }
 
 pub(crate) struct CustomConstraintWrapperForGlucose<T: SimpleCustomConstraint> {
@@ -229,7 +504,9 @@ unsafe impl<T: SimpleCustomConstraint, M: SolverManipulator> CustomPropagator<M>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::csp::Stmt;
+    use crate::config::Config;
+    use crate::csp::{IntExpr, Stmt};
+    use crate::domain::Domain;
     use crate::integration::IntegratedSolver;
 
     #[derive(PartialEq, Eq)]
@@ -370,6 +647,52 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "portfolio")]
+    #[test]
+    fn test_custom_constraints_atmost_portfolio() {
+        // The workers of the portfolio do not have the custom constraint, so their answers must not be used.
+        let n = 10;
+        let mut config = Config::default();
+        config.portfolio_size = 3;
+        let mut solver = IntegratedSolver::with_config(config);
+
+        let mut vars = vec![];
+        let mut vars_expr = vec![];
+        for _ in 0..n {
+            let var = solver.new_bool_var();
+            vars.push(var);
+            vars_expr.push(var.expr());
+        }
+
+        let at_most = AtMost {
+            k: 2,
+            decision_stack: vec![],
+        };
+        solver.add_constraint(Stmt::CustomConstraint(vars_expr, Box::new(at_most)));
+
+        let iter = solver.answer_iter(&vars, &[]);
+        assert_eq!(iter.count(), 1 + n + n * (n - 1) / 2);
+    }
+
+    #[test]
+    fn test_custom_constraints_export_cnf() {
+        let mut solver = IntegratedSolver::new();
+        let vars_expr = (0..3)
+            .map(|_| solver.new_bool_var().expr())
+            .collect::<Vec<_>>();
+        let at_most = AtMost {
+            k: 2,
+            decision_stack: vec![],
+        };
+        solver.add_constraint(Stmt::CustomConstraint(vars_expr, Box::new(at_most)));
+
+        let mut dimacs = vec![];
+        assert!(matches!(
+            solver.export_cnf(&mut dimacs),
+            Err(crate::export::ExportError::Unsupported(_))
+        ));
+    }
+
     #[test]
     fn test_custom_constraints_atmost_same_lit() {
         for n in [2, 6, 10, 20, 30] {
@@ -526,4 +849,281 @@ mod tests {
 
         let _ = solver.enumerate_valid_assignments();
     }
+
+    struct IntSumAtMost {
+        k: i32,
+        domains: Vec<Vec<i32>>,
+        decision_stack: Vec<(usize, IntInputLit)>,
+    }
+
+    impl SimpleCustomIntConstraint for IntSumAtMost {
+        fn initialize_sat(&mut self, domains: &[Vec<i32>]) {
+            self.domains = domains.to_vec();
+        }
+
+        fn notify(&mut self, index: usize, lit: IntInputLit) {
+            self.decision_stack.push((index, lit));
+        }
+
+        fn find_inconsistency(&mut self) -> Option<Vec<(usize, IntInputLit)>> {
+            let mut lb = self.domains.iter().map(|d| d[0]).collect::<Vec<_>>();
+            let mut lb_source = vec![None; self.domains.len()];
+            for &(idx, lit) in &self.decision_stack {
+                if let IntInputLit::Eq(v) | IntInputLit::Ge(v) = lit {
+                    if v > lb[idx] {
+                        lb[idx] = v;
+                        lb_source[idx] = Some((idx, lit));
+                    }
+                }
+            }
+
+            if lb.iter().sum::<i32>() > self.k {
+                Some(lb_source.into_iter().flatten().collect())
+            } else {
+                None
+            }
+        }
+
+        fn undo(&mut self) {
+            assert!(!self.decision_stack.is_empty());
+            self.decision_stack.pop();
+        }
+    }
+
+    struct IntAllDifferent {
+        domains: Vec<Vec<i32>>,
+        decision_stack: Vec<(usize, IntInputLit)>,
+    }
+
+    impl SimpleCustomIntConstraint for IntAllDifferent {
+        fn initialize_sat(&mut self, domains: &[Vec<i32>]) {
+            self.domains = domains.to_vec();
+        }
+
+        fn notify(&mut self, index: usize, lit: IntInputLit) {
+            self.decision_stack.push((index, lit));
+        }
+
+        fn find_inconsistency(&mut self) -> Option<Vec<(usize, IntInputLit)>> {
+            let mut lb = self.domains.iter().map(|d| d[0]).collect::<Vec<_>>();
+            let mut ub = self
+                .domains
+                .iter()
+                .map(|d| d[d.len() - 1])
+                .collect::<Vec<_>>();
+            for &(idx, lit) in &self.decision_stack {
+                match lit {
+                    IntInputLit::Eq(v) => {
+                        lb[idx] = v;
+                        ub[idx] = v;
+                    }
+                    IntInputLit::Ge(v) => lb[idx] = lb[idx].max(v),
+                    IntInputLit::Le(v) => ub[idx] = ub[idx].min(v),
+                    IntInputLit::Ne(_) => (),
+                }
+            }
+
+            for i in 0..self.domains.len() {
+                for j in (i + 1)..self.domains.len() {
+                    if lb[i] == ub[i] && lb[j] == ub[j] && lb[i] == lb[j] {
+                        return Some(vec![
+                            (i, IntInputLit::Eq(lb[i])),
+                            (j, IntInputLit::Eq(lb[j])),
+                        ]);
+                    }
+                }
+            }
+            None
+        }
+
+        fn undo(&mut self) {
+            assert!(!self.decision_stack.is_empty());
+            self.decision_stack.pop();
+        }
+    }
+
+    #[test]
+    fn test_custom_int_constraints_sum_at_most() {
+        for use_direct_encoding in [false, true] {
+            for n in [1, 3, 5] {
+                let mut config = Config::default();
+                config.use_direct_encoding = use_direct_encoding;
+                let mut solver = IntegratedSolver::with_config(config);
+
+                let vars = (0..n)
+                    .map(|_| solver.new_int_var(Domain::range(0, 2)))
+                    .collect::<Vec<_>>();
+                let vars_expr = vars.iter().map(|v| v.expr()).collect::<Vec<_>>();
+
+                let k = 3;
+                let sum_at_most = IntSumAtMost {
+                    k,
+                    domains: vec![],
+                    decision_stack: vec![],
+                };
+                solver.add_constraint(Stmt::CustomIntConstraint(vars_expr, Box::new(sum_at_most)));
+
+                let mut expected = 0;
+                for mask in 0..3i32.pow(n as u32) {
+                    let mut sum = 0;
+                    let mut m = mask;
+                    for _ in 0..n {
+                        sum += m % 3;
+                        m /= 3;
+                    }
+                    if sum <= k {
+                        expected += 1;
+                    }
+                }
+
+                let iter = solver.answer_iter(&[], &vars);
+                assert_eq!(iter.count(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_int_constraints_all_different() {
+        for use_direct_encoding in [false, true] {
+            for (n, m) in [(2, 2), (3, 4), (4, 4), (4, 5)] {
+                let mut config = Config::default();
+                config.use_direct_encoding = use_direct_encoding;
+                let mut solver = IntegratedSolver::with_config(config);
+
+                let vars = (0..n)
+                    .map(|_| solver.new_int_var(Domain::range(1, m)))
+                    .collect::<Vec<_>>();
+                let vars_expr = vars.iter().map(|v| v.expr()).collect::<Vec<_>>();
+
+                let all_different = IntAllDifferent {
+                    domains: vec![],
+                    decision_stack: vec![],
+                };
+                solver.add_constraint(Stmt::CustomIntConstraint(
+                    vars_expr,
+                    Box::new(all_different),
+                ));
+
+                let expected = ((m - n + 1)..=m).product::<i32>() as usize;
+                let iter = solver.answer_iter(&[], &vars);
+                assert_eq!(iter.count(), expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    #[test]
+    fn test_custom_int_constraints_log_encoding() {
+        let mut config = Config::default();
+        config.force_use_log_encoding = true;
+        let mut solver = IntegratedSolver::with_config(config);
+
+        let vars = (0..3)
+            .map(|_| solver.new_int_var(Domain::range(1, 3)))
+            .collect::<Vec<_>>();
+        let a = solver.new_int_var(Domain::range(0, 3));
+        let b = solver.new_int_var(Domain::range(0, 3));
+        let all_different = IntAllDifferent {
+            domains: vec![],
+            decision_stack: vec![],
+        };
+        solver.add_constraint(Stmt::CustomIntConstraint(
+            vars.iter().map(|v| v.expr()).collect(),
+            Box::new(all_different),
+        ));
+        // `b` shares a constraint with an input of the custom constraint, while `a` does not
+        solver.add_expr((vars[0].expr() + b.expr()).eq(IntExpr::Const(3)));
+        solver.add_expr(a.expr().ge(IntExpr::Const(2)));
+
+        let iter = solver.answer_iter(&[], &[vars[0], vars[1], vars[2], a, b]);
+        assert_eq!(iter.count(), 12);
+    }
+
+    #[test]
+    fn test_custom_int_constraints_explain() {
+        let inputs = vec![
+            IntInputEncoding::Direct {
+                domain: vec![1, 3, 4],
+                lits: (0..3)
+                    .map(|i| Lit::new(crate::sat::Var(i), false))
+                    .collect(),
+            },
+            IntInputEncoding::Order {
+                domain: vec![1, 3, 4],
+                lits: (3..5)
+                    .map(|i| Lit::new(crate::sat::Var(i), false))
+                    .collect(),
+            },
+        ];
+        let constraint = IntAllDifferent {
+            domains: vec![],
+            decision_stack: vec![],
+        };
+        let (mut adapter, lits) = IntCustomConstraintAdapter::new(Box::new(constraint), inputs);
+        assert_eq!(lits.len(), 5);
+        adapter.initialize_sat(lits.len());
+
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Eq(3)),
+            Some(vec![(1, true)])
+        );
+        assert_eq!(adapter.explain(0, IntInputLit::Ne(2)), Some(vec![]));
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Ge(2)),
+            Some(vec![(0, false)])
+        );
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Le(3)),
+            Some(vec![(2, false)])
+        );
+
+        assert_eq!(
+            adapter.explain(1, IntInputLit::Ge(2)),
+            Some(vec![(3, true)])
+        );
+        assert_eq!(adapter.explain(1, IntInputLit::Ge(1)), Some(vec![]));
+        assert_eq!(
+            adapter.explain(1, IntInputLit::Le(3)),
+            Some(vec![(4, false)])
+        );
+        assert_eq!(
+            adapter.explain(1, IntInputLit::Eq(3)),
+            Some(vec![(3, true), (4, false)])
+        );
+
+        // literals which can never hold
+        assert_eq!(adapter.explain(0, IntInputLit::Eq(2)), None);
+        assert_eq!(adapter.explain(1, IntInputLit::Eq(2)), None);
+        assert_eq!(adapter.explain(1, IntInputLit::Ge(5)), None);
+        assert_eq!(adapter.explain(1, IntInputLit::Le(0)), None);
+
+        // x != 3 is explained by the bound which currently holds
+        adapter.notify(4, true);
+        assert_eq!(
+            adapter.explain(1, IntInputLit::Ne(3)),
+            Some(vec![(4, true)])
+        );
+        adapter.undo();
+        adapter.notify(3, false);
+        assert_eq!(
+            adapter.explain(1, IntInputLit::Ne(3)),
+            Some(vec![(3, false)])
+        );
+        adapter.undo();
+
+        // bounds of a fixed direct-encoded input are explained by the fixed value
+        adapter.notify(2, true);
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Ge(2)),
+            Some(vec![(2, true)])
+        );
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Ne(3)),
+            Some(vec![(2, true)])
+        );
+        assert_eq!(
+            adapter.explain(0, IntInputLit::Le(5)),
+            Some(vec![(2, true)])
+        );
+    }
 }
@@ -0,0 +1,153 @@
+/// A deterministic finite automaton over integer symbols, used by `Stmt::Regular`.
+///
+/// States are numbered from 0 to `num_states() - 1`. A symbol without a transition from a state
+/// rejects the sequence.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Dfa {
+    initial_state: usize,
+    accepting: Vec<bool>,
+    transitions: Vec<Vec<(i32, usize)>>,
+}
+
+impl Dfa {
+    pub fn new(num_states: usize, initial_state: usize) -> Dfa {
+        assert!(initial_state < num_states);
+        Dfa {
+            initial_state,
+            accepting: vec![false; num_states],
+            transitions: vec![vec![]; num_states],
+        }
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn initial_state(&self) -> usize {
+        self.initial_state
+    }
+
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting[state]
+    }
+
+    pub fn set_accepting(&mut self, state: usize, accepting: bool) {
+        self.accepting[state] = accepting;
+    }
+
+    /// Adds a transition from `from` to `to` by `symbol`.
+    /// Panics if `from` already has a transition by `symbol`.
+    pub fn add_transition(&mut self, from: usize, symbol: i32, to: usize) {
+        assert!(to < self.num_states());
+        assert!(
+            self.next_state(from, symbol).is_none(),
+            "duplicate transition from state {} by symbol {}",
+            from,
+            symbol
+        );
+        self.transitions[from].push((symbol, to));
+    }
+
+    /// Returns the transitions `(symbol, to)` from `state`.
+    pub fn transitions(&self, state: usize) -> &[(i32, usize)] {
+        &self.transitions[state]
+    }
+
+    pub fn next_state(&self, state: usize, symbol: i32) -> Option<usize> {
+        self.transitions[state]
+            .iter()
+            .find(|&&(s, _)| s == symbol)
+            .map(|&(_, to)| to)
+    }
+
+    pub fn accepts(&self, sequence: &[i32]) -> bool {
+        let mut state = self.initial_state;
+        for &symbol in sequence {
+            match self.next_state(state, symbol) {
+                Some(s) => state = s,
+                None => return false,
+            }
+        }
+        self.accepting[state]
+    }
+
+    /// Returns, for each `i` in `0..=candidates.len()`, the states which can be reached after reading
+    /// `i` symbols and from which an accepting state can be reached, when the `i`-th symbol is
+    /// restricted to `candidates[i]`.
+    pub(crate) fn live_states(&self, candidates: &[Vec<i32>]) -> Vec<Vec<bool>> {
+        let n = candidates.len();
+        let num_states = self.num_states();
+
+        let mut reachable = vec![vec![false; num_states]; n + 1];
+        reachable[0][self.initial_state] = true;
+        for i in 0..n {
+            for s in 0..num_states {
+                if !reachable[i][s] {
+                    continue;
+                }
+                for &c in &candidates[i] {
+                    if let Some(t) = self.next_state(s, c) {
+                        reachable[i + 1][t] = true;
+                    }
+                }
+            }
+        }
+
+        let mut live = vec![vec![false; num_states]; n + 1];
+        for s in 0..num_states {
+            live[n][s] = reachable[n][s] && self.accepting[s];
+        }
+        for i in (0..n).rev() {
+            for s in 0..num_states {
+                if !reachable[i][s] {
+                    continue;
+                }
+                live[i][s] = candidates[i]
+                    .iter()
+                    .any(|&c| self.next_state(s, c).is_some_and(|t| live[i + 1][t]));
+            }
+        }
+
+        live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // accepts sequences over {0, 1} with an even number of 1's
+    fn even_ones() -> Dfa {
+        let mut dfa = Dfa::new(2, 0);
+        dfa.set_accepting(0, true);
+        dfa.add_transition(0, 0, 0);
+        dfa.add_transition(0, 1, 1);
+        dfa.add_transition(1, 0, 1);
+        dfa.add_transition(1, 1, 0);
+        dfa
+    }
+
+    #[test]
+    fn test_dfa_accepts() {
+        let dfa = even_ones();
+        assert!(dfa.accepts(&[]));
+        assert!(dfa.accepts(&[1, 0, 1]));
+        assert!(!dfa.accepts(&[0, 1, 0]));
+        assert!(!dfa.accepts(&[0, 2]));
+    }
+
+    #[test]
+    fn test_dfa_live_states() {
+        let dfa = even_ones();
+        let live = dfa.live_states(&[vec![0, 1], vec![1], vec![0]]);
+        assert_eq!(
+            live,
+            vec![
+                vec![true, false],
+                vec![false, true],
+                vec![true, false],
+                vec![true, false],
+            ]
+        );
+    }
+}
@@ -38,16 +38,25 @@ impl Domain {
 
     pub(crate) fn enumerate(&self) -> Vec<CheckedInt> {
         match self {
-            Domain::Range(low, high) => (low.get()..=high.get()).map(CheckedInt::new).collect(),
+            Domain::Range(low, high) => (low.get_repr()..=high.get_repr())
+                .map(CheckedInt::from_repr)
+                .collect(),
             Domain::Enumerative(cands) => cands.clone(),
         }
     }
 
+    pub(crate) fn contains(&self, v: CheckedInt) -> bool {
+        match self {
+            &Domain::Range(low, high) => low <= v && v <= high,
+            Domain::Enumerative(cands) => cands.binary_search(&v).is_ok(),
+        }
+    }
+
     pub(crate) fn num_candidates(&self) -> usize {
         match self {
             &Domain::Range(low, high) => {
                 if low <= high {
-                    (high - low).get() as usize + 1
+                    (high - low).get_repr() as usize + 1
                 } else {
                     0
                 }
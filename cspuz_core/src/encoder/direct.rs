@@ -94,7 +94,9 @@ pub(super) fn encode_var_direct(
             {
                 let mut tmp = vec![];
                 for i in 0..domain.len() {
-                    tmp.push(new_var!(sat, "{}.dir=={}", var.id(), domain[i].get()).as_lit(false));
+                    tmp.push(
+                        new_var!(sat, "{}.dir=={}", var.id(), domain[i].get_repr()).as_lit(false),
+                    );
                 }
                 lits = tmp;
             }
@@ -6,7 +6,7 @@ use super::direct::{
 use super::mixed::{encode_linear_eq_mixed_from_info, encode_linear_ge_mixed_from_info};
 use super::order::{LinearInfoForOrderEncoding, OrderEncoding};
 use super::{new_var, new_vars_as_lits, ClauseSet, EncodeMap, EncoderEnv, LinearInfo, LinearLit};
-use crate::arithmetic::{CheckedInt, CmpOp, Range};
+use crate::arithmetic::{CheckedInt, CmpOp, IntRepr, Range};
 use crate::norm_csp::{IntVar, IntVarRepresentation, LinearSum, NormCSPVars};
 use crate::sat::{Lit, SAT};
 
@@ -35,14 +35,14 @@ pub fn encode_var_log(
             } else {
                 (ori_low, ori_high, CheckedInt::new(0))
             };
-            let n_bits = (32 - high.get().leading_zeros()) as usize;
+            let n_bits = (IntRepr::BITS - high.get_repr().leading_zeros()) as usize;
             let lits = new_vars_as_lits!(sat, n_bits, "{}.log", var.id());
 
             for i in 0..n_bits {
-                if ((low.get() >> i) & 1) != 0 {
+                if ((low.get_repr() >> i) & 1) != 0 {
                     let mut clause = vec![lits[i]];
                     for j in (i + 1)..n_bits {
-                        clause.push(if (low.get() >> j) & 1 != 0 {
+                        clause.push(if (low.get_repr() >> j) & 1 != 0 {
                             !lits[j]
                         } else {
                             lits[j]
@@ -53,10 +53,10 @@ pub fn encode_var_log(
             }
 
             for i in 0..n_bits {
-                if (high.get() >> i) & 1 == 0 {
+                if (high.get_repr() >> i) & 1 == 0 {
                     let mut clause = vec![!lits[i]];
                     for j in (i + 1)..n_bits {
-                        clause.push(if (high.get() >> j) & 1 != 0 {
+                        clause.push(if (high.get_repr() >> j) & 1 != 0 {
                             !lits[j]
                         } else {
                             lits[j]
@@ -68,8 +68,8 @@ pub fn encode_var_log(
 
             let domain = domain.enumerate();
             for i in 1..domain.len() {
-                let gap_low = (domain[i - 1].get() + 1) - offset.get();
-                let gap_high = domain[i].get() - offset.get();
+                let gap_low = (domain[i - 1].get_repr() + 1) - offset.get_repr();
+                let gap_high = domain[i].get_repr() - offset.get_repr();
                 for n in gap_low..gap_high {
                     let mut clause = vec![];
                     for j in 0..n_bits {
@@ -99,11 +99,11 @@ pub fn encode_var_log(
             };
 
             let mut lits = vec![];
-            let n_bits = (32 - vt.get().leading_zeros()) as usize;
+            let n_bits = (IntRepr::BITS - vt.get_repr().leading_zeros()) as usize;
 
             for i in 0..n_bits {
-                let bf = (vf.get() >> i) & 1;
-                let bt = (vt.get() >> i) & 1;
+                let bf = (vf.get_repr() >> i) & 1;
+                let bt = (vt.get_repr() >> i) & 1;
 
                 match (bf, bt) {
                     (0, 0) => {
@@ -248,7 +248,7 @@ fn encode_linear_log_from_encodings(
         for (lits, coef) in encodings {
             let coef = *coef;
             if coef > 0 {
-                let mut coef = coef.get() as u32;
+                let mut coef = coef.get_repr().unsigned_abs();
                 for i in 0usize.. {
                     if (coef & 1) == 1 {
                         for j in 0..lits.len() {
@@ -261,7 +261,7 @@ fn encode_linear_log_from_encodings(
                     }
                 }
             } else {
-                let mut coef = (-coef).get() as u32;
+                let mut coef = coef.get_repr().unsigned_abs();
                 for i in 0usize.. {
                     if (coef & 1) == 1 {
                         for j in 0..lits.len() {
@@ -284,7 +284,7 @@ fn encode_linear_log_from_encodings(
     for (lits, coef) in encodings {
         let coef = *coef;
         if coef > 0 {
-            let mut coef = coef.get() as u32;
+            let mut coef = coef.get_repr().unsigned_abs();
             for i in 0usize.. {
                 if (coef & 1) == 1 {
                     values_positive.push((i, lits.clone()));
@@ -296,7 +296,7 @@ fn encode_linear_log_from_encodings(
             }
         } else {
             assert!(coef < 0);
-            let mut coef = -coef.get() as u32;
+            let mut coef = coef.get_repr().unsigned_abs();
             for i in 0usize.. {
                 if (coef & 1) == 1 {
                     values_negative.push((i, lits.clone()));
@@ -445,8 +445,8 @@ fn log_encoding_adder(
                     pos_constant[i + 1] += v;
                 }
             }
-            let v = pos_constant[i].get() & 1;
-            pos_constant[i] = CheckedInt::new(v);
+            let v = pos_constant[i].get_repr() & 1;
+            pos_constant[i] = CheckedInt::from_repr(v);
             i += 1;
         }
     }
@@ -495,11 +495,11 @@ fn log_encoding_adder(
         }));
 
         let mut carry_next_domain = vec![];
-        for j in 0..=(cnt.get() / 2) {
-            carry_next_domain.push(CheckedInt::new(j));
+        for j in 0..=(cnt.get_repr() / 2) {
+            carry_next_domain.push(CheckedInt::from_repr(j));
         }
         let mut carry_next = vec![];
-        for _ in 0..(cnt.get() / 2) {
+        for _ in 0..(cnt.get_repr() / 2) {
             let var = new_var!(env.sat);
             carry_next.push(var.as_lit(false));
         }
@@ -601,8 +601,8 @@ fn log_encoding_adder2(
 
         assert_eq!(carry_high - carry_low, carry_lits.len() as i32);
         {
-            let domain = (carry_low.get()..=carry_high.get())
-                .map(CheckedInt::new)
+            let domain = (carry_low.get_repr()..=carry_high.get_repr())
+                .map(CheckedInt::from_repr)
                 .collect::<Vec<_>>();
             order_encodings.push(OrderEncoding {
                 domain,
@@ -611,9 +611,9 @@ fn log_encoding_adder2(
         }
 
         let target = if i + 1 == max_ofs {
-            CheckedInt::new(constant.get() >> i)
+            CheckedInt::from_repr(constant.get_repr() >> i)
         } else {
-            CheckedInt::new((constant.get() >> i) & 1)
+            CheckedInt::from_repr((constant.get_repr() >> i) & 1)
         };
 
         let new_carry_low;
@@ -633,7 +633,7 @@ fn log_encoding_adder2(
         }
 
         let mut new_carry_lits = vec![];
-        for _ in 0..(new_carry_high - new_carry_low).get() {
+        for _ in 0..(new_carry_high - new_carry_low).get_repr() {
             new_carry_lits.push(new_var!(env.sat).as_lit(false));
         }
         for i in 1..new_carry_lits.len() {
@@ -642,9 +642,9 @@ fn log_encoding_adder2(
         }
 
         {
-            let domain = (new_carry_low.get()..=new_carry_high.get())
+            let domain = (new_carry_low.get_repr()..=new_carry_high.get_repr())
                 .rev()
-                .map(|x| CheckedInt::new(x) * CheckedInt::new(-2))
+                .map(|x| CheckedInt::from_repr(x) * CheckedInt::new(-2))
                 .collect::<Vec<_>>();
             let lits = new_carry_lits.iter().rev().map(|x| !*x).collect();
             order_encodings.push(OrderEncoding { domain, lits });
@@ -723,8 +723,8 @@ fn log_encoding_adder2_direct(
 
         assert_eq!(carry_high - carry_low, carry_lits.len() as i32 - 1);
         {
-            let domain = (carry_low.get()..=carry_high.get())
-                .map(CheckedInt::new)
+            let domain = (carry_low.get_repr()..=carry_high.get_repr())
+                .map(CheckedInt::from_repr)
                 .collect::<Vec<_>>();
             direct_encodings.push(DirectEncoding {
                 domain,
@@ -733,9 +733,9 @@ fn log_encoding_adder2_direct(
         }
 
         let target = if i + 1 == max_ofs {
-            CheckedInt::new(constant.get() >> i)
+            CheckedInt::from_repr(constant.get_repr() >> i)
         } else {
-            CheckedInt::new((constant.get() >> i) & 1)
+            CheckedInt::from_repr((constant.get_repr() >> i) & 1)
         };
 
         let new_carry_low;
@@ -755,7 +755,7 @@ fn log_encoding_adder2_direct(
         }
 
         let mut new_carry_lits = vec![];
-        for _ in 0..=(new_carry_high - new_carry_low).get() {
+        for _ in 0..=(new_carry_high - new_carry_low).get_repr() {
             new_carry_lits.push(new_var!(env.sat).as_lit(false));
         }
         env.sat.add_clause(&new_carry_lits);
@@ -767,9 +767,9 @@ fn log_encoding_adder2_direct(
         }
 
         {
-            let domain = (new_carry_low.get()..=new_carry_high.get())
+            let domain = (new_carry_low.get_repr()..=new_carry_high.get_repr())
                 .rev()
-                .map(|x| CheckedInt::new(x) * CheckedInt::new(-2))
+                .map(|x| CheckedInt::from_repr(x) * CheckedInt::new(-2))
                 .collect::<Vec<_>>();
             let lits = new_carry_lits.iter().rev().copied().collect();
             direct_encodings.push(DirectEncoding { domain, lits });
@@ -3,18 +3,22 @@ mod direct;
 mod log;
 mod mixed;
 mod order;
+mod pseudo_boolean;
+
+pub(crate) use pseudo_boolean::is_pseudo_boolean_encoding_smaller;
 
 use std::cmp::Reverse;
 use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
 use std::ops::Index;
 
 use super::config::Config;
+use super::custom_constraints::{IntCustomConstraintAdapter, IntInputEncoding};
 use super::norm_csp::{
     BoolLit, BoolVar, Constraint, ExtraConstraint, IntVar, IntVarRepresentation, LinearLit,
     LinearSum, NormCSP, NormCSPVars,
 };
-use super::sat::{Backend, Lit, SATModel, SAT};
-use crate::arithmetic::{CheckedInt, CmpOp, Range};
+use super::sat::{Lit, SATModel, SAT};
+use crate::arithmetic::{CheckedInt, CmpOp, OverflowError, Range};
 use crate::util::ConvertMap;
 
 struct ClauseSet {
@@ -204,6 +208,18 @@ use new_var;
 #[allow(unused)]
 use new_vars_as_lits;
 
+/// The SAT literals representing an integer variable of a `NormCSP`.
+/// Values are given in pairs with the literals, and a variable may have several encodings at the same time.
+#[derive(Clone, Debug, Default)]
+pub struct IntVarLits {
+    /// Order encoding: `(v, l)` means that `l` is true iff the variable is at least `v`.
+    pub order: Vec<(i32, Lit)>,
+    /// Direct encoding: `(v, l)` means that `l` is true iff the variable is `v`.
+    pub direct: Vec<(i32, Lit)>,
+    /// Log encoding: `(offset, bits)` means that the variable is `offset + sum_i 2^i * bits[i]`.
+    pub log: Option<(i32, Vec<Lit>)>,
+}
+
 pub struct EncodeMap {
     bool_map: ConvertMap<BoolVar, Option<Lit>>, // mapped to Lit rather than Var so that further optimization can be done
     int_map: ConvertMap<IntVar, Option<Encoding>>,
@@ -357,6 +373,35 @@ impl EncodeMap {
         }
     }
 
+    /// Returns the literals representing `var`, or `None` if `var` is not encoded.
+    /// An `OverflowError` is returned if some value in the domain of `var` does not fit in `i32`.
+    pub fn get_int_var_lits(&self, var: IntVar) -> Result<Option<IntVarLits>, OverflowError> {
+        let Some(encoding) = self.int_map[var].as_ref() else {
+            return Ok(None);
+        };
+        let mut ret = IntVarLits::default();
+        if let Some(encoding) = &encoding.order_encoding {
+            ret.order = encoding.domain[1..]
+                .iter()
+                .zip(&encoding.lits)
+                .map(|(v, &l)| Ok((v.to_i32_checked()?, l)))
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(encoding) = &encoding.direct_encoding {
+            ret.direct = encoding
+                .domain
+                .iter()
+                .zip(&encoding.lits)
+                .map(|(v, &l)| Ok((v.to_i32_checked()?, l)))
+                .collect::<Result<_, _>>()?;
+        }
+        #[cfg(feature = "csp-extra-constraints")]
+        if let Some(encoding) = &encoding.log_encoding {
+            ret.log = Some((encoding.offset.to_i32_checked()?, encoding.lits.clone()));
+        }
+        Ok(Some(ret))
+    }
+
     pub fn get_int_value(&self, model: &SATModel, var: IntVar) -> Option<i32> {
         self.get_int_value_checked(model, var).map(CheckedInt::get)
     }
@@ -382,6 +427,7 @@ impl EncoderEnv<'_, '_, '_, '_> {
     }
 }
 
+/// If the SAT solver detects that a constraint cannot be satisfied when it is added, `norm` is marked as inconsistent.
 pub fn encode(norm: &mut NormCSP, sat: &mut SAT, map: &mut EncodeMap, config: &Config) {
     let new_vars = norm.unencoded_int_vars().collect::<Vec<_>>();
     let constrs = std::mem::replace(&mut norm.constraints, vec![]);
@@ -413,6 +459,7 @@ pub fn encode(norm: &mut NormCSP, sat: &mut SAT, map: &mut EncodeMap, config: &C
         encode_constraint(&mut env, constr);
     }
 
+    let mut inconsistent = false;
     for constr in extra_constrs {
         match constr {
             ExtraConstraint::ActiveVerticesConnected(vertices, edges) => {
@@ -423,6 +470,14 @@ pub fn encode(norm: &mut NormCSP, sat: &mut SAT, map: &mut EncodeMap, config: &C
                 // TODO: handle failure of addition of constraint
                 env.sat.add_active_vertices_connected(lits, edges);
             }
+            ExtraConstraint::ActiveEdgesSingleCycle(edge_lits, edges) => {
+                let lits = edge_lits
+                    .into_iter()
+                    .map(|l| env.convert_bool_lit(l))
+                    .collect::<Vec<_>>();
+                // TODO: handle failure of addition of constraint
+                env.sat.add_active_edges_single_cycle(lits, edges);
+            }
             #[cfg(feature = "csp-extra-constraints")]
             ExtraConstraint::Mul(x, y, m) => {
                 let x_log = env.map.int_map[x].as_ref().unwrap().log_encoding.is_some();
@@ -495,6 +550,23 @@ pub fn encode(norm: &mut NormCSP, sat: &mut SAT, map: &mut EncodeMap, config: &C
             ExtraConstraint::ExtensionSupports(_, _) => {
                 panic!("feature not enabled");
             }
+            #[cfg(feature = "csp-extra-constraints")]
+            ExtraConstraint::AllDifferent(vars) => {
+                if !encode_all_different(&mut env, &vars) {
+                    inconsistent = true;
+                }
+            }
+            #[cfg(not(feature = "csp-extra-constraints"))]
+            ExtraConstraint::AllDifferent(_) => {
+                panic!("feature not enabled");
+            }
+            ExtraConstraint::PseudoBoolean(terms, op, rhs) => {
+                let terms = terms
+                    .into_iter()
+                    .map(|(l, coef)| (env.convert_bool_lit(l), coef))
+                    .collect::<Vec<_>>();
+                pseudo_boolean::encode_pseudo_boolean(&mut env, &terms, op, rhs);
+            }
             ExtraConstraint::GraphDivision(sizes, edges, edge_lits, opts) => {
                 let mut domains = vec![];
                 let mut dom_lits = vec![];
@@ -529,25 +601,41 @@ pub fn encode(norm: &mut NormCSP, sat: &mut SAT, map: &mut EncodeMap, config: &C
                     .into_iter()
                     .map(|l| env.convert_bool_lit(l))
                     .collect::<Vec<_>>();
-                let backend = env.sat.get_backend();
-                match backend {
-                    Backend::Glucose => {
-                        env.sat.add_custom_constraint(lits, constr);
-                    }
-                    #[cfg(feature = "experimental-backend-glucose-rs")]
-                    Backend::GlucoseRs => {
-                        env.sat.add_custom_constraint(lits, constr);
-                    }
-                    _ => {
-                        todo!(
-                            "custom constraints are only supported with Glucose/GlucoseRs backend"
-                        );
-                    }
+                if !env.sat.add_custom_constraint(lits, constr) {
+                    inconsistent = true;
+                }
+            }
+            ExtraConstraint::CustomIntConstraint(vars, constr) => {
+                let inputs = vars
+                    .iter()
+                    .map(|&v| {
+                        let encoding = env.map.int_map[v].as_ref().unwrap();
+                        if let Some(direct) = &encoding.direct_encoding {
+                            IntInputEncoding::Direct {
+                                domain: direct.domain.iter().map(|x| x.get()).collect(),
+                                lits: direct.lits.clone(),
+                            }
+                        } else if let Some(order) = &encoding.order_encoding {
+                            IntInputEncoding::Order {
+                                domain: order.domain.iter().map(|x| x.get()).collect(),
+                                lits: order.lits.clone(),
+                            }
+                        } else {
+                            panic!("inputs of custom int constraints must not be log-encoded");
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let (adapter, lits) = IntCustomConstraintAdapter::new(constr, inputs);
+                if !env.sat.add_custom_constraint(lits, Box::new(adapter)) {
+                    inconsistent = true;
                 }
             }
         }
     }
     norm.num_encoded_vars = norm.vars.num_int_vars();
+    if inconsistent {
+        norm.inconsistent = true;
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -568,11 +656,19 @@ fn decide_encode_schemes(
     // TODO: consider already encoded variables
     // TODO: ExtensionSupports requires direct encoding for efficient propagation
 
+    #[cfg(feature = "csp-extra-constraints")]
+    let non_log_vars = non_log_encodable_vars(new_constraints, new_ext_constraints);
+
     #[cfg(feature = "csp-extra-constraints")]
     if config.force_use_log_encoding {
         let mut ret = BTreeMap::new();
         for &var in new_vars {
-            ret.insert(var, EncodeScheme::Log);
+            let scheme = if non_log_vars.contains(&var) {
+                EncodeScheme::Order
+            } else {
+                EncodeScheme::Log
+            };
+            ret.insert(var, scheme);
         }
         return ret;
     }
@@ -602,16 +698,23 @@ fn decide_encode_schemes(
                     complex_constraints_vars.insert(m);
                 }
                 ExtraConstraint::ActiveVerticesConnected(_, _) => (),
+                ExtraConstraint::ActiveEdgesSingleCycle(_, _) => (),
                 ExtraConstraint::ExtensionSupports(_, _) => (),
+                ExtraConstraint::AllDifferent(_) => (),
+                ExtraConstraint::PseudoBoolean(_, _, _) => (),
                 ExtraConstraint::GraphDivision(_, _, _, _) => (),
                 ExtraConstraint::CustomConstraint(_, _) => (),
+                ExtraConstraint::CustomIntConstraint(_, _) => (),
             }
         }
 
         for &var in new_vars {
             let repr = norm_vars.int_var(var);
             if let IntVarRepresentation::Domain(domain) = repr {
-                if domain.num_candidates() > 500 && complex_constraints_vars.contains(&var) {
+                if domain.num_candidates() > 500
+                    && complex_constraints_vars.contains(&var)
+                    && !non_log_vars.contains(&var)
+                {
                     // TODO: make this configurable
                     scheme.insert(var, EncodeScheme::Log);
                 }
@@ -641,6 +744,7 @@ fn decide_encode_schemes(
             for ext_constraint in new_ext_constraints {
                 match ext_constraint {
                     ExtraConstraint::ActiveVerticesConnected(_, _) => (),
+                    ExtraConstraint::ActiveEdgesSingleCycle(_, _) => (),
                     ExtraConstraint::Mul(a, b, m) => {
                         let vars = [*a, *b, *m];
                         let has_log = vars
@@ -656,8 +760,11 @@ fn decide_encode_schemes(
                         }
                     }
                     ExtraConstraint::ExtensionSupports(_, _) => (),
+                    ExtraConstraint::AllDifferent(_) => (),
+                    ExtraConstraint::PseudoBoolean(_, _, _) => (),
                     ExtraConstraint::GraphDivision(_, _, _, _) => (),
                     ExtraConstraint::CustomConstraint(_, _) => (),
+                    ExtraConstraint::CustomIntConstraint(_, _) => (),
                 }
             }
 
@@ -722,6 +829,50 @@ fn decide_encode_schemes(
     ret
 }
 
+/// Returns the variables which must not be log-encoded, that is, the inputs of custom int constraints
+/// and the variables cooccurring with them in some constraints (since a constraint cannot mix log-encoded
+/// variables with the others).
+#[cfg(feature = "csp-extra-constraints")]
+fn non_log_encodable_vars(
+    new_constraints: &[Constraint],
+    new_ext_constraints: &[ExtraConstraint],
+) -> BTreeSet<IntVar> {
+    let mut ret = BTreeSet::new();
+    for ext_constraint in new_ext_constraints {
+        if let ExtraConstraint::CustomIntConstraint(vars, _) = ext_constraint {
+            ret.extend(vars.iter().copied());
+        }
+    }
+    if ret.is_empty() {
+        return ret;
+    }
+
+    loop {
+        let mut updated = false;
+        let mut add_group = |vars: &[IntVar]| {
+            if vars.iter().any(|v| ret.contains(v)) {
+                for &v in vars {
+                    updated |= ret.insert(v);
+                }
+            }
+        };
+        for constraint in new_constraints {
+            for lit in &constraint.linear_lit {
+                add_group(&lit.sum.iter().map(|(&v, _)| v).collect::<Vec<_>>());
+            }
+        }
+        for ext_constraint in new_ext_constraints {
+            if let &ExtraConstraint::Mul(x, y, m) = ext_constraint {
+                add_group(&[x, y, m]);
+            }
+        }
+        if !updated {
+            break;
+        }
+    }
+    ret
+}
+
 fn is_unsatisfiable_linear(env: &EncoderEnv, linear_lit: &LinearLit) -> bool {
     let mut range = Range::constant(linear_lit.sum.constant);
     for (&var, &coef) in linear_lit.sum.iter() {
@@ -1094,10 +1245,10 @@ fn encode_mul_naive(env: &mut EncoderEnv, x: IntVar, y: IntVar, m: IntVar) {
     let x_range = env.map.int_map[x].as_ref().unwrap().range();
     let y_range = env.map.int_map[y].as_ref().unwrap().range();
 
-    for i in x_range.low.get()..=x_range.high.get() {
-        let i = CheckedInt::new(i);
-        for j in y_range.low.get()..=y_range.high.get() {
-            let j = CheckedInt::new(j);
+    for i in x_range.low.get_repr()..=x_range.high.get_repr() {
+        let i = CheckedInt::from_repr(i);
+        for j in y_range.low.get_repr()..=y_range.high.get_repr() {
+            let j = CheckedInt::from_repr(j);
 
             let mut c = Constraint::new();
             c.add_linear(LinearLit::new(
@@ -1118,6 +1269,65 @@ fn encode_mul_naive(env: &mut EncoderEnv, x: IntVar, y: IntVar, m: IntVar) {
     }
 }
 
+#[cfg(feature = "csp-extra-constraints")]
+/// Encodes an all-different constraint over `vars`.
+/// Returns `false` if the SAT solver detected that the constraint cannot be satisfied.
+fn encode_all_different(env: &mut EncoderEnv, vars: &[IntVar]) -> bool {
+    let has_log_encoding = vars
+        .iter()
+        .any(|&v| env.map.int_map[v].as_ref().unwrap().log_encoding.is_some());
+    if has_log_encoding {
+        // The native propagator requires literals for each value, which log encoding lacks
+        for i in 0..vars.len() {
+            for j in (i + 1)..vars.len() {
+                let mut c = Constraint::new();
+                c.add_linear(LinearLit::new(
+                    LinearSum::singleton(vars[i]) - LinearSum::singleton(vars[j]),
+                    CmpOp::Ne,
+                ));
+                encode_constraint(env, c);
+            }
+        }
+        return true;
+    }
+
+    let mut vars_encoded = vec![];
+    let mut values = vec![];
+    for &var in vars {
+        let encoding = env.map.int_map[var].as_ref().unwrap();
+        if let Some(direct) = &encoding.direct_encoding {
+            vars_encoded.push(direct.lits.clone());
+            values.push(direct.domain.iter().map(|x| x.get()).collect::<Vec<_>>());
+            continue;
+        }
+
+        // Introduce literals for (var == domain[i]) channeled with the order encoding
+        let order = encoding.as_order_encoding();
+        let domain = order.domain.clone();
+        let order_lits = order.lits.clone();
+        let mut lits = vec![];
+        for i in 0..domain.len() {
+            let lit = new_var!(env.sat).as_lit(false);
+            let mut clause = vec![lit];
+            if i > 0 {
+                env.sat.add_clause(&[!lit, order_lits[i - 1]]);
+                clause.push(!order_lits[i - 1]);
+            }
+            if i + 1 < domain.len() {
+                env.sat.add_clause(&[!lit, !order_lits[i]]);
+                clause.push(order_lits[i]);
+            }
+            env.sat.add_clause(&clause);
+            lits.push(lit);
+        }
+        vars_encoded.push(lits);
+        values.push(domain.iter().map(|x| x.get()).collect::<Vec<_>>());
+    }
+
+    env.sat
+        .add_direct_encoding_all_different(&vars_encoded, &values)
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::{
@@ -1165,6 +1375,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pseudo_boolean_encoding_heuristic() {
+        let coefs = |n: usize, coef: i32| vec![CheckedInt::new(coef); n];
+
+        // at most 2 of 10 (e.g. a row of star battle): 120 clauses in the order encoding
+        assert!(is_pseudo_boolean_encoding_smaller(
+            &coefs(10, 1),
+            CmpOp::Le,
+            CheckedInt::new(2)
+        ));
+        assert!(is_pseudo_boolean_encoding_smaller(
+            &coefs(10, 1),
+            CmpOp::Eq,
+            CheckedInt::new(2)
+        ));
+        // at least 1 of 10 is a single clause
+        assert!(!is_pseudo_boolean_encoding_smaller(
+            &coefs(10, 1),
+            CmpOp::Ge,
+            CheckedInt::new(1)
+        ));
+        // at most 1 of 3
+        assert!(!is_pseudo_boolean_encoding_smaller(
+            &coefs(3, 1),
+            CmpOp::Le,
+            CheckedInt::new(1)
+        ));
+        // trivial constraints
+        assert!(!is_pseudo_boolean_encoding_smaller(
+            &coefs(10, 1),
+            CmpOp::Le,
+            CheckedInt::new(10)
+        ));
+        assert!(!is_pseudo_boolean_encoding_smaller(
+            &[CheckedInt::new(3), CheckedInt::new(-2), CheckedInt::new(2)],
+            CmpOp::Ne,
+            CheckedInt::new(1)
+        ));
+        // general coefficients
+        let weights = [5, 3, 4, 2, 6, 3, 1, 4, 2, 5, 3, 4]
+            .into_iter()
+            .map(CheckedInt::new)
+            .collect::<Vec<_>>();
+        assert!(is_pseudo_boolean_encoding_smaller(
+            &weights,
+            CmpOp::Le,
+            CheckedInt::new(12)
+        ));
+    }
+
     pub(super) struct EncoderTester {
         norm_csp: NormCSP,
         sat: SAT,
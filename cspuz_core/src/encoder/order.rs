@@ -107,7 +107,8 @@ pub(super) fn encode_var_order(
                 let mut tmp = vec![];
                 for i in 0..domain.len() - 1 {
                     tmp.push(
-                        new_var!(sat, "{}.ord>={}", var.id(), domain[i + 1].get()).as_lit(false),
+                        new_var!(sat, "{}.ord>={}", var.id(), domain[i + 1].get_repr())
+                            .as_lit(false),
                     );
                 }
                 lits = tmp;
@@ -137,6 +138,9 @@ pub(super) fn encode_var_order(
 }
 
 pub(super) fn is_ge_order_encoding_native_applicable(env: &EncoderEnv, sum: &LinearSum) -> bool {
+    if !env.sat.has_native_propagators() {
+        return false;
+    }
     for (&var, _) in sum.iter() {
         if env.map.int_map[var]
             .as_ref()
@@ -150,14 +154,21 @@ pub(super) fn is_ge_order_encoding_native_applicable(env: &EncoderEnv, sum: &Lin
     if sum.len() > env.config.native_linear_encoding_terms {
         return false;
     }
+    // The native propagator works on `i32` values, which may be exceeded with `wide-int` feature
+    if sum.constant.to_i32().is_none() {
+        return false;
+    }
     let mut domain_product = 1usize;
     for (&var, _) in sum.iter() {
-        domain_product *= env.map.int_map[var]
+        let domain = &env.map.int_map[var]
             .as_ref()
             .unwrap()
             .as_order_encoding()
-            .domain
-            .len();
+            .domain;
+        if domain[0].to_i32().is_none() || domain[domain.len() - 1].to_i32().is_none() {
+            return false;
+        }
+        domain_product *= domain.len();
     }
     domain_product >= env.config.native_linear_encoding_domain_product_threshold
 }
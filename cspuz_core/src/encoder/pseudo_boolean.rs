@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{new_vars_as_lits, EncoderEnv};
+use crate::arithmetic::{CheckedInt, CmpOp};
+use crate::sat::Lit;
+
+/// Encodes `sum coef * [lit] op rhs` into clauses. `op` must not be `Ne`.
+///
+/// Cardinality constraints (all coefficients are equal after normalization) are encoded by either
+/// the totalizer or the sequential counter, whichever is estimated to yield fewer clauses.
+/// Other constraints are encoded by the generalized totalizer.
+pub(super) fn encode_pseudo_boolean(
+    env: &mut EncoderEnv,
+    terms: &[(Lit, CheckedInt)],
+    op: CmpOp,
+    rhs: CheckedInt,
+) {
+    // Merge the terms so that each variable appears (positively) at most once
+    let mut rhs = rhs;
+    let mut coefs = BTreeMap::<Lit, CheckedInt>::new();
+    for &(lit, coef) in terms {
+        if lit.is_negated() {
+            // coef * [!x] == coef - coef * [x]
+            rhs -= coef;
+            *coefs.entry(!lit).or_insert(CheckedInt::new(0)) -= coef;
+        } else {
+            *coefs.entry(lit).or_insert(CheckedInt::new(0)) += coef;
+        }
+    }
+    let terms = coefs
+        .into_iter()
+        .filter(|&(_, coef)| coef != 0)
+        .collect::<Vec<_>>();
+
+    match op {
+        CmpOp::Eq => {
+            encode_le(env, &terms, rhs);
+            encode_ge(env, &terms, rhs);
+        }
+        CmpOp::Le => encode_le(env, &terms, rhs),
+        CmpOp::Lt => encode_le(env, &terms, rhs - CheckedInt::new(1)),
+        CmpOp::Ge => encode_ge(env, &terms, rhs),
+        CmpOp::Gt => encode_ge(env, &terms, rhs + CheckedInt::new(1)),
+        CmpOp::Ne => panic!("Ne is not supported in pseudo-Boolean encoding"),
+    }
+}
+
+/// Encodes `sum coef * [lit] >= rhs`.
+fn encode_ge(env: &mut EncoderEnv, terms: &[(Lit, CheckedInt)], rhs: CheckedInt) {
+    // sum coef * [x] >= rhs  <=>  sum coef * [!x] <= sum coef - rhs
+    let mut total = CheckedInt::new(0);
+    let mut negated = vec![];
+    for &(lit, coef) in terms {
+        total += coef;
+        negated.push((!lit, coef));
+    }
+    encode_le(env, &negated, total - rhs);
+}
+
+/// Encodes `sum coef * [lit] <= rhs`.
+fn encode_le(env: &mut EncoderEnv, terms: &[(Lit, CheckedInt)], rhs: CheckedInt) {
+    // Make all the coefficients positive: coef * [x] == coef + (-coef) * [!x]
+    let mut rhs = rhs;
+    let mut positive_terms = vec![];
+    for &(lit, coef) in terms {
+        if coef < 0 {
+            rhs -= coef;
+            positive_terms.push((!lit, -coef));
+        } else {
+            positive_terms.push((lit, coef));
+        }
+    }
+
+    if rhs < 0 {
+        env.sat.add_clause(&[]);
+        return;
+    }
+
+    // Literals whose coefficients exceed `rhs` can never be true
+    let mut rest = vec![];
+    let mut total = CheckedInt::new(0);
+    for (lit, coef) in positive_terms {
+        if coef > rhs {
+            env.sat.add_clause(&[!lit]);
+        } else {
+            total += coef;
+            rest.push((lit, coef));
+        }
+    }
+    if total <= rhs {
+        return;
+    }
+
+    let unit = rest[0].1;
+    if rest.iter().all(|&(_, coef)| coef == unit) {
+        // at most `k` of `rest` are true (1 <= k < rest.len())
+        let k = rhs.div_floor(unit).get_repr() as usize;
+        if sequential_counter_size(rest.len(), k) < totalizer_size(rest.len(), k + 1) {
+            let lits = rest.iter().map(|&(lit, _)| lit).collect::<Vec<_>>();
+            encode_sequential_counter(env, &lits, k);
+            return;
+        }
+    }
+
+    let cap = rhs + CheckedInt::new(1);
+    for (sum, lit) in encode_totalizer(env, &rest, cap) {
+        if sum == cap {
+            env.sat.add_clause(&[!lit]);
+        }
+    }
+}
+
+/// Returns whether the encoding by `encode_pseudo_boolean` is estimated to yield fewer clauses than the
+/// order encoding of the linear sum `sum coef * [x] op rhs` (over distinct variables).
+///
+/// The order encoding of `sum coef * [x] <= rhs` needs a clause for each minimal set of variables whose
+/// coefficients sum up beyond `rhs`. If at most `k` terms can be true at once, this is estimated by
+/// `binom(n, k + 1)`, which grows much faster than the sizes of the totalizer and the sequential counter.
+pub(crate) fn is_pseudo_boolean_encoding_smaller(
+    coefs: &[CheckedInt],
+    op: CmpOp,
+    rhs: CheckedInt,
+) -> bool {
+    let negated = coefs.iter().map(|&coef| -coef).collect::<Vec<_>>();
+
+    // sum coef * [x] >= rhs  <=>  sum (-coef) * [x] <= -rhs
+    let (order, pb) = match op {
+        CmpOp::Eq => {
+            let (order_le, pb_le) = estimate_le(coefs, rhs);
+            let (order_ge, pb_ge) = estimate_le(&negated, -rhs);
+            (order_le.saturating_add(order_ge), pb_le + pb_ge)
+        }
+        CmpOp::Le => estimate_le(coefs, rhs),
+        CmpOp::Lt => estimate_le(coefs, rhs - CheckedInt::new(1)),
+        CmpOp::Ge => estimate_le(&negated, -rhs),
+        CmpOp::Gt => estimate_le(&negated, -rhs - CheckedInt::new(1)),
+        CmpOp::Ne => return false,
+    };
+    pb < order
+}
+
+/// Estimates the numbers of clauses of `sum coef * [x] <= rhs` in the order encoding and in `encode_le`.
+fn estimate_le(coefs: &[CheckedInt], rhs: CheckedInt) -> (usize, usize) {
+    // Make all the coefficients positive as in `encode_le`
+    let mut rhs = rhs;
+    let mut positive = vec![];
+    for &coef in coefs {
+        if coef < 0 {
+            rhs -= coef;
+            positive.push(-coef);
+        } else if coef > 0 {
+            positive.push(coef);
+        }
+    }
+    if rhs < 0 {
+        return (1, 1);
+    }
+
+    // Terms exceeding `rhs` by themselves are encoded as unit clauses in both encodings
+    let mut rest = positive
+        .into_iter()
+        .filter(|&coef| coef <= rhs)
+        .collect::<Vec<_>>();
+    rest.sort();
+    let total = rest
+        .iter()
+        .fold(CheckedInt::new(0), |acc, &coef| acc + coef);
+    if total <= rhs {
+        return (0, 0);
+    }
+
+    // at most `k` terms can be true at once
+    let mut k = 0;
+    let mut sum = CheckedInt::new(0);
+    for &coef in &rest {
+        sum += coef;
+        if sum > rhs {
+            break;
+        }
+        k += 1;
+    }
+    let order = binomial_saturating(rest.len(), k + 1);
+
+    let n = rest.len();
+    let pb = if rest.iter().all(|&coef| coef == rest[0]) {
+        sequential_counter_size(n, k).min(totalizer_size(n, k + 1))
+    } else {
+        generalized_totalizer_size(&rest, rhs + CheckedInt::new(1)).0
+    };
+    (order, pb)
+}
+
+fn binomial_saturating(n: usize, k: usize) -> usize {
+    let k = k.min(n - k);
+    let mut ret = 1usize;
+    for i in 0..k {
+        // ret * (n - i) / (i + 1) is always an integer
+        ret = match ret.checked_mul(n - i) {
+            Some(x) => x / (i + 1),
+            None => return usize::MAX,
+        };
+    }
+    ret
+}
+
+/// Counts the clauses of `encode_totalizer` over the terms with `coefs`, together with the set of its outputs.
+fn generalized_totalizer_size(
+    coefs: &[CheckedInt],
+    cap: CheckedInt,
+) -> (usize, BTreeSet<CheckedInt>) {
+    if coefs.len() == 1 {
+        return (0, BTreeSet::from([coefs[0].min(cap)]));
+    }
+
+    let mid = coefs.len() / 2;
+    let (left_size, left) = generalized_totalizer_size(&coefs[..mid], cap);
+    let (right_size, right) = generalized_totalizer_size(&coefs[mid..], cap);
+
+    let mut sums = left.clone();
+    sums.extend(right.iter().copied());
+    for &a in &left {
+        for &b in &right {
+            sums.insert((a + b).min(cap));
+        }
+    }
+    let size = left_size + right_size + left.len() + right.len() + left.len() * right.len();
+    (size, sums)
+}
+
+/// Builds a generalized totalizer over `terms` and returns its outputs `(s, o_s)`.
+/// If the sum of the coefficients of the true literals is `s` (> 0), the output for `min(s, cap)`
+/// is forced to be true.
+fn encode_totalizer(
+    env: &mut EncoderEnv,
+    terms: &[(Lit, CheckedInt)],
+    cap: CheckedInt,
+) -> Vec<(CheckedInt, Lit)> {
+    if terms.len() == 1 {
+        let (lit, coef) = terms[0];
+        return vec![(coef.min(cap), lit)];
+    }
+
+    let mid = terms.len() / 2;
+    let left = encode_totalizer(env, &terms[..mid], cap);
+    let right = encode_totalizer(env, &terms[mid..], cap);
+
+    let mut sums = BTreeSet::new();
+    for &(a, _) in &left {
+        sums.insert(a);
+        for &(b, _) in &right {
+            sums.insert((a + b).min(cap));
+        }
+    }
+    for &(b, _) in &right {
+        sums.insert(b);
+    }
+
+    let output_lits = new_vars_as_lits!(env.sat, sums.len(), "pb.totalizer");
+    let outputs = sums
+        .into_iter()
+        .zip(output_lits)
+        .collect::<BTreeMap<_, _>>();
+
+    for &(a, la) in &left {
+        env.sat.add_clause(&[!la, outputs[&a]]);
+        for &(b, lb) in &right {
+            env.sat.add_clause(&[!la, !lb, outputs[&(a + b).min(cap)]]);
+        }
+    }
+    for &(b, lb) in &right {
+        env.sat.add_clause(&[!lb, outputs[&b]]);
+    }
+
+    outputs.into_iter().collect()
+}
+
+/// Estimates the number of clauses of the totalizer for a cardinality constraint over `n`
+/// literals whose outputs are capped at `cap`.
+fn totalizer_size(n: usize, cap: usize) -> usize {
+    if n <= 1 {
+        return 0;
+    }
+    let mid = n / 2;
+    let p = mid.min(cap);
+    let q = (n - mid).min(cap);
+    p + q + p * q + totalizer_size(mid, cap) + totalizer_size(n - mid, cap)
+}
+
+/// Encodes "at most `k` of `lits` are true" (`k >= 1`) by the sequential counter.
+/// After processing `i` literals, `counter[j]` is forced to be true if at least `j + 1` of them are
+/// true.
+fn encode_sequential_counter(env: &mut EncoderEnv, lits: &[Lit], k: usize) {
+    let mut counter: Vec<Lit> = vec![];
+    for (i, &x) in lits.iter().enumerate() {
+        if counter.len() == k {
+            env.sat.add_clause(&[!x, !counter[k - 1]]);
+        }
+        if i + 1 == lits.len() {
+            break;
+        }
+
+        let next = new_vars_as_lits!(env.sat, (counter.len() + 1).min(k), "pb.counter");
+        env.sat.add_clause(&[!x, next[0]]);
+        for (&c, &n) in counter.iter().zip(&next) {
+            env.sat.add_clause(&[!c, n]);
+        }
+        for (&c, &n) in counter.iter().zip(&next[1..]) {
+            env.sat.add_clause(&[!x, !c, n]);
+        }
+        counter = next;
+    }
+}
+
+/// Counts the clauses produced by `encode_sequential_counter` for `n` literals.
+fn sequential_counter_size(n: usize, k: usize) -> usize {
+    let mut ret = 0;
+    let mut counter_len = 0;
+    for i in 0..n {
+        if counter_len == k {
+            ret += 1;
+        }
+        if i + 1 == n {
+            break;
+        }
+        let next_len = (counter_len + 1).min(k);
+        ret += 1 + counter_len + (next_len - 1);
+        counter_len = next_len;
+    }
+    ret
+}
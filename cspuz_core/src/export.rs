@@ -0,0 +1,163 @@
+//! Export of the CNF formula encoding a problem, together with the correspondence between the SAT
+//! variables and the CSP variables (see `IntegratedSolver::export_cnf`).
+//!
+//! Literals are given in the DIMACS representation, in which variables are 1-indexed and negative
+//! numbers denote negated literals.
+
+use std::io::Write;
+
+use crate::arithmetic::OverflowError;
+use crate::csp::{BoolVar, IntVar};
+use crate::util::ConvertMapIndex;
+
+/// An error in exporting a problem.
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    /// The problem contains a constraint which is handled only by a native propagator and
+    /// cannot be written as clauses (e.g. `Stmt::GraphDivision` with extra options or custom constraints).
+    Unsupported(&'static str),
+    /// The problem involves values which do not fit in the internal arithmetic or in `i32`
+    /// (see `OverflowError`).
+    Overflow(OverflowError),
+    /// The problem has already been solved, so the encoding is no longer available.
+    AlreadySolved,
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "I/O error: {}", e),
+            ExportError::Unsupported(name) => {
+                write!(f, "{} cannot be expressed by clauses", name)
+            }
+            ExportError::Overflow(e) => write!(f, "overflow: {}", e),
+            ExportError::AlreadySolved => {
+                write!(f, "the problem cannot be exported after it is solved")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> ExportError {
+        ExportError::Io(e)
+    }
+}
+
+impl From<OverflowError> for ExportError {
+    fn from(e: OverflowError) -> ExportError {
+        ExportError::Overflow(e)
+    }
+}
+
+/// How a `BoolVar` is represented in the exported CNF.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoolVarMapping {
+    /// The variable is equal to the literal.
+    Lit(i32),
+    /// The variable was fixed before the encoding.
+    Fixed(bool),
+    /// The variable does not affect the problem, so it can take any value.
+    Unused,
+}
+
+/// How an `IntVar` is represented in the exported CNF.
+///
+/// A variable may have several encodings at the same time. If it has none, its value is always
+/// `lower_bound`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntVarMapping {
+    /// The value of the variable when none of the order encoding literals is true.
+    pub lower_bound: i32,
+    /// Order encoding: `(v, l)` means that `l` is true iff the variable is at least `v`.
+    pub order: Vec<(i32, i32)>,
+    /// Direct encoding: `(v, l)` means that `l` is true iff the variable is `v`.
+    pub direct: Vec<(i32, i32)>,
+    /// Log encoding: `(offset, bits)` means that the variable is `offset + sum_i 2^i * bits[i]`.
+    pub log: Option<(i32, Vec<i32>)>,
+}
+
+/// The correspondence between the variables of an exported CNF and the CSP variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CnfVarMap {
+    pub num_vars: usize,
+    pub(crate) bool_vars: Vec<BoolVarMapping>,
+    pub(crate) int_vars: Vec<IntVarMapping>,
+}
+
+impl CnfVarMap {
+    pub fn bool_var(&self, var: BoolVar) -> &BoolVarMapping {
+        &self.bool_vars[var.to_index()]
+    }
+
+    pub fn int_var(&self, var: IntVar) -> &IntVarMapping {
+        &self.int_vars[var.to_index()]
+    }
+
+    /// Writes the map in JSON. `bool_vars` and `int_vars` are indexed by the ids of the CSP variables:
+    ///
+    /// ```text
+    /// {
+    ///   "num_vars": 5,
+    ///   "bool_vars": [{"lit": -3}, {"value": true}, {}],
+    ///   "int_vars": [{"lower_bound": 0, "order": [[1, 1], [2, 2]], "direct": [], "log": null}]
+    /// }
+    /// ```
+    ///
+    /// `{}` in `bool_vars` stands for `BoolVarMapping::Unused`, and `log` is either `null` or
+    /// `{"offset": 0, "bits": [4, 5]}`.
+    pub fn write_json<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        fn pairs(pairs: &[(i32, i32)]) -> String {
+            let elems = pairs
+                .iter()
+                .map(|(v, l)| format!("[{}, {}]", v, l))
+                .collect::<Vec<_>>();
+            format!("[{}]", elems.join(", "))
+        }
+
+        writeln!(out, "{{")?;
+        writeln!(out, "  \"num_vars\": {},", self.num_vars)?;
+
+        let bool_vars = self
+            .bool_vars
+            .iter()
+            .map(|m| match m {
+                BoolVarMapping::Lit(l) => format!("{{\"lit\": {}}}", l),
+                BoolVarMapping::Fixed(b) => format!("{{\"value\": {}}}", b),
+                BoolVarMapping::Unused => String::from("{}"),
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "  \"bool_vars\": [{}],", bool_vars.join(", "))?;
+
+        let int_vars = self
+            .int_vars
+            .iter()
+            .map(|m| {
+                let log = match &m.log {
+                    Some((offset, bits)) => format!(
+                        "{{\"offset\": {}, \"bits\": [{}]}}",
+                        offset,
+                        bits.iter()
+                            .map(|l| l.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    None => String::from("null"),
+                };
+                format!(
+                    "{{\"lower_bound\": {}, \"order\": {}, \"direct\": {}, \"log\": {}}}",
+                    m.lower_bound,
+                    pairs(&m.order),
+                    pairs(&m.direct),
+                    log
+                )
+            })
+            .collect::<Vec<_>>();
+        writeln!(out, "  \"int_vars\": [{}]", int_vars.join(", "))?;
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1444 @@
+/// FlatZinc front-end
+///
+/// Reads a FlatZinc model (`.fzn`), translates its constraints into `Stmt`s of an
+/// `IntegratedSolver`, and prints solutions in the MiniZinc output format.
+///
+/// Only integer and Boolean variables with finite domains are supported. Search annotations are
+/// accepted but ignored.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::config::{BackendConfig, Config};
+use super::csp::{Assignment, BoolExpr, BoolVar, IntExpr, IntVar, Stmt};
+use super::csugar_cli::CliError;
+use super::domain::Domain;
+use super::integration::{
+    take_resource_limit_exceeded, take_sat_solver_error, IntegratedSolver, Model, SolveOutcome,
+};
+use super::parser::{ObjectiveSense, ParseError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Int(i32),
+    Str(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "`{}`", s),
+            Token::Int(n) => write!(f, "`{}`", n),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Symbol(s) => write!(f, "`{}`", s),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// 1-based line and column of a token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    fn error(self, message: String) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.column,
+            message,
+        }
+    }
+}
+
+// Longer symbols must come first so that `::` is not read as two `:`s.
+const SYMBOLS: [&str; 12] = ["::", "..", ":", ";", ",", "[", "]", "(", ")", "{", "}", "="];
+
+fn tokenize(input: &str) -> Result<Vec<(Token, Position)>, ParseError> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut ret = vec![];
+    let mut idx = 0;
+    let mut pos = Position { line: 1, column: 1 };
+
+    let advance = |idx: &mut usize, pos: &mut Position, n: usize| {
+        for _ in 0..n {
+            if chars[*idx] == '\n' {
+                pos.line += 1;
+                pos.column = 1;
+            } else {
+                pos.column += 1;
+            }
+            *idx += 1;
+        }
+    };
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        if c.is_whitespace() {
+            advance(&mut idx, &mut pos, 1);
+            continue;
+        }
+        if c == '%' {
+            while idx < chars.len() && chars[idx] != '\n' {
+                advance(&mut idx, &mut pos, 1);
+            }
+            continue;
+        }
+
+        let start = pos;
+        if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = idx;
+            while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+                end += 1;
+            }
+            ret.push((Token::Ident(chars[idx..end].iter().collect()), start));
+            let n = end - idx;
+            advance(&mut idx, &mut pos, n);
+        } else if c.is_ascii_digit()
+            || (c == '-' && idx + 1 < chars.len() && chars[idx + 1].is_ascii_digit())
+        {
+            let mut end = if c == '-' { idx + 1 } else { idx };
+            let radix = if end + 1 < chars.len() && chars[end] == '0' && chars[end + 1] == 'x' {
+                end += 2;
+                16
+            } else if end + 1 < chars.len() && chars[end] == '0' && chars[end + 1] == 'o' {
+                end += 2;
+                8
+            } else {
+                10
+            };
+            let digits_start = end;
+            while end < chars.len() && chars[end].is_digit(radix) {
+                end += 1;
+            }
+            if end + 1 < chars.len() && chars[end] == '.' && chars[end + 1].is_ascii_digit() {
+                return Err(start.error(String::from("floats are not supported")));
+            }
+            let digits = chars[digits_start..end].iter().collect::<String>();
+            let value = i64::from_str_radix(&digits, radix)
+                .ok()
+                .map(|v| if c == '-' { -v } else { v })
+                .and_then(|v| i32::try_from(v).ok())
+                .ok_or_else(|| start.error(String::from("integer literal out of range")))?;
+            ret.push((Token::Int(value), start));
+            let n = end - idx;
+            advance(&mut idx, &mut pos, n);
+        } else if c == '"' {
+            let mut end = idx + 1;
+            let mut content = String::new();
+            while end < chars.len() && chars[end] != '"' {
+                if chars[end] == '\\' && end + 1 < chars.len() {
+                    end += 1;
+                }
+                content.push(chars[end]);
+                end += 1;
+            }
+            if end >= chars.len() {
+                return Err(start.error(String::from("unterminated string literal")));
+            }
+            ret.push((Token::Str(content), start));
+            let n = end + 1 - idx;
+            advance(&mut idx, &mut pos, n);
+        } else {
+            let symbol = SYMBOLS.iter().find(|s| {
+                s.chars()
+                    .enumerate()
+                    .all(|(i, sc)| idx + i < chars.len() && chars[idx + i] == sc)
+            });
+            match symbol {
+                Some(&s) => {
+                    ret.push((Token::Symbol(s), start));
+                    advance(&mut idx, &mut pos, s.len());
+                }
+                None => return Err(start.error(format!("unexpected character: {}", c))),
+            }
+        }
+    }
+    ret.push((Token::Eof, pos));
+
+    Ok(ret)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum IntSet {
+    Range(i32, i32),
+    List(Vec<i32>),
+}
+
+impl IntSet {
+    fn contains(&self, x: IntExpr) -> BoolExpr {
+        match self {
+            &IntSet::Range(low, high) => {
+                x.clone().ge(IntExpr::Const(low)) & x.le(IntExpr::Const(high))
+            }
+            IntSet::List(values) => BoolExpr::Or(
+                values
+                    .iter()
+                    .map(|&v| Box::new(x.clone().eq(IntExpr::Const(v))))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn to_domain(&self) -> Domain {
+        match self {
+            &IntSet::Range(low, high) => Domain::range(low, high),
+            IntSet::List(values) => {
+                // set literals in FlatZinc are not necessarily sorted nor free of duplicates
+                let mut values = values.clone();
+                values.sort();
+                values.dedup();
+                Domain::enumerative(values)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expr {
+    Bool(bool),
+    Int(i32),
+    Set(IntSet),
+    Str(String),
+    Ident(String),
+    /// `name[index]` (1-based).
+    Index(String, i32),
+    Array(Vec<Expr>),
+    /// Function-call-like expressions, which only appear in annotations.
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum BaseType {
+    Bool,
+    Int(Option<IntSet>),
+    SetOfInt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Type {
+    is_var: bool,
+    array_len: Option<usize>,
+    base: BaseType,
+}
+
+#[derive(Debug)]
+enum Item {
+    Decl {
+        ty: Type,
+        name: String,
+        annotations: Vec<Expr>,
+        value: Option<Expr>,
+    },
+    Constraint {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Solve(Option<(ObjectiveSense, Expr)>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    idx: usize,
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.idx].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.idx].1
+    }
+
+    fn next(&mut self) -> Token {
+        let ret = self.tokens[self.idx].0.clone();
+        if ret != Token::Eof {
+            self.idx += 1;
+        }
+        ret
+    }
+
+    fn unexpected<T>(&self) -> ParseResult<T> {
+        Err(self.position().error(format!("unexpected {}", self.peek())))
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Token::Symbol(s) if *s == symbol) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Token::Ident(s) if s == keyword) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> ParseResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self
+                .position()
+                .error(format!("`{}` expected, but {} found", symbol, self.peek())))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self
+                .position()
+                .error(format!("`{}` expected, but {} found", keyword, self.peek())))
+        }
+    }
+
+    fn expect_ident(&mut self) -> ParseResult<String> {
+        match self.peek() {
+            Token::Ident(_) => match self.next() {
+                Token::Ident(s) => Ok(s),
+                _ => unreachable!(),
+            },
+            _ => Err(self
+                .position()
+                .error(format!("identifier expected, but {} found", self.peek()))),
+        }
+    }
+
+    fn expect_int(&mut self) -> ParseResult<i32> {
+        match *self.peek() {
+            Token::Int(n) => {
+                self.idx += 1;
+                Ok(n)
+            }
+            _ => Err(self
+                .position()
+                .error(format!("integer expected, but {} found", self.peek()))),
+        }
+    }
+
+    /// Parses a comma-separated list of `T` terminated by `close` (a trailing comma is allowed).
+    fn parse_list<T, F: FnMut(&mut Parser) -> ParseResult<T>>(
+        &mut self,
+        close: &str,
+        mut parse_elem: F,
+    ) -> ParseResult<Vec<T>> {
+        let mut ret = vec![];
+        loop {
+            if self.eat_symbol(close) {
+                return Ok(ret);
+            }
+            ret.push(parse_elem(self)?);
+            if !self.eat_symbol(",") {
+                self.expect_symbol(close)?;
+                return Ok(ret);
+            }
+        }
+    }
+
+    fn parse_int_set(&mut self) -> ParseResult<IntSet> {
+        if self.eat_symbol("{") {
+            Ok(IntSet::List(self.parse_list("}", |p| p.expect_int())?))
+        } else {
+            let low = self.expect_int()?;
+            self.expect_symbol("..")?;
+            let high = self.expect_int()?;
+            Ok(IntSet::Range(low, high))
+        }
+    }
+
+    fn parse_expr(&mut self) -> ParseResult<Expr> {
+        match self.peek().clone() {
+            Token::Ident(name) => {
+                self.idx += 1;
+                if name == "true" {
+                    Ok(Expr::Bool(true))
+                } else if name == "false" {
+                    Ok(Expr::Bool(false))
+                } else if self.eat_symbol("[") {
+                    let index = self.expect_int()?;
+                    self.expect_symbol("]")?;
+                    Ok(Expr::Index(name, index))
+                } else if self.eat_symbol("(") {
+                    let args = self.parse_list(")", |p| p.parse_expr())?;
+                    Ok(Expr::Call(name, args))
+                } else {
+                    Ok(Expr::Ident(name))
+                }
+            }
+            Token::Int(n) => {
+                if matches!(self.tokens[self.idx + 1].0, Token::Symbol("..")) {
+                    Ok(Expr::Set(self.parse_int_set()?))
+                } else {
+                    self.idx += 1;
+                    Ok(Expr::Int(n))
+                }
+            }
+            Token::Str(s) => {
+                self.idx += 1;
+                Ok(Expr::Str(s))
+            }
+            Token::Symbol("{") => Ok(Expr::Set(self.parse_int_set()?)),
+            Token::Symbol("[") => {
+                self.idx += 1;
+                Ok(Expr::Array(self.parse_list("]", |p| p.parse_expr())?))
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn parse_annotations(&mut self) -> ParseResult<Vec<Expr>> {
+        let mut ret = vec![];
+        while self.eat_symbol("::") {
+            ret.push(self.parse_expr()?);
+        }
+        Ok(ret)
+    }
+
+    fn parse_base_type(&mut self) -> ParseResult<BaseType> {
+        match self.peek().clone() {
+            Token::Ident(name) => match name.as_str() {
+                "bool" => {
+                    self.idx += 1;
+                    Ok(BaseType::Bool)
+                }
+                "int" => {
+                    self.idx += 1;
+                    Ok(BaseType::Int(None))
+                }
+                "float" => Err(self
+                    .position()
+                    .error(String::from("floats are not supported"))),
+                "set" => {
+                    self.idx += 1;
+                    self.expect_keyword("of")?;
+                    match self.parse_base_type()? {
+                        BaseType::Int(_) => Ok(BaseType::SetOfInt),
+                        _ => Err(self
+                            .position()
+                            .error(String::from("only sets of integers are supported"))),
+                    }
+                }
+                _ => self.unexpected(),
+            },
+            Token::Int(_) | Token::Symbol("{") => Ok(BaseType::Int(Some(self.parse_int_set()?))),
+            _ => self.unexpected(),
+        }
+    }
+
+    fn parse_type(&mut self) -> ParseResult<Type> {
+        let mut array_len = None;
+        if self.eat_keyword("array") {
+            self.expect_symbol("[")?;
+            let position = self.position();
+            match self.parse_int_set()? {
+                IntSet::Range(1, n) => array_len = Some(n.max(0) as usize),
+                _ => {
+                    return Err(position.error(String::from("array index set must be 1..n")));
+                }
+            }
+            self.expect_symbol("]")?;
+            self.expect_keyword("of")?;
+        }
+        let is_var = self.eat_keyword("var");
+        let base = self.parse_base_type()?;
+        Ok(Type {
+            is_var,
+            array_len,
+            base,
+        })
+    }
+
+    /// Parses the next item. Predicate declarations are skipped and `None` is returned at the end
+    /// of the input.
+    fn parse_item(&mut self) -> ParseResult<Option<(Item, Position)>> {
+        loop {
+            let position = self.position();
+            let item = match self.peek() {
+                Token::Eof => return Ok(None),
+                Token::Ident(s) if s == "predicate" => {
+                    while !matches!(self.peek(), Token::Symbol(";") | Token::Eof) {
+                        self.next();
+                    }
+                    self.expect_symbol(";")?;
+                    continue;
+                }
+                Token::Ident(s) if s == "constraint" => {
+                    self.idx += 1;
+                    let name = self.expect_ident()?;
+                    self.expect_symbol("(")?;
+                    let args = self.parse_list(")", |p| p.parse_expr())?;
+                    self.parse_annotations()?;
+                    Item::Constraint { name, args }
+                }
+                Token::Ident(s) if s == "solve" => {
+                    self.idx += 1;
+                    self.parse_annotations()?;
+                    if self.eat_keyword("satisfy") {
+                        Item::Solve(None)
+                    } else if self.eat_keyword("minimize") {
+                        Item::Solve(Some((ObjectiveSense::Minimize, self.parse_expr()?)))
+                    } else if self.eat_keyword("maximize") {
+                        Item::Solve(Some((ObjectiveSense::Maximize, self.parse_expr()?)))
+                    } else {
+                        return self.unexpected();
+                    }
+                }
+                _ => {
+                    let ty = self.parse_type()?;
+                    self.expect_symbol(":")?;
+                    let name = self.expect_ident()?;
+                    let annotations = self.parse_annotations()?;
+                    let value = if self.eat_symbol("=") {
+                        Some(self.parse_expr()?)
+                    } else {
+                        None
+                    };
+                    Item::Decl {
+                        ty,
+                        name,
+                        annotations,
+                        value,
+                    }
+                }
+            };
+            self.expect_symbol(";")?;
+            return Ok(Some((item, position)));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Bool(BoolExpr),
+    Int(IntExpr),
+    Set(IntSet),
+    BoolArray(Vec<BoolExpr>),
+    IntArray(Vec<IntExpr>),
+    SetArray(Vec<IntSet>),
+}
+
+/// A Boolean or integer which is either a constant or a variable.
+#[derive(Clone, Debug)]
+enum Term {
+    Bool(BoolExpr),
+    Int(IntExpr),
+}
+
+#[derive(Clone, Debug)]
+struct Output {
+    name: String,
+    /// Index sets of the array, or `None` for scalars.
+    dims: Option<Vec<(i32, i32)>>,
+    terms: Vec<Term>,
+}
+
+/// A FlatZinc model whose variables and constraints are added to an `IntegratedSolver`.
+pub struct FlatZincModel {
+    outputs: Vec<Output>,
+    pub objective: Option<(ObjectiveSense, IntExpr)>,
+}
+
+impl FlatZincModel {
+    /// Returns the variables whose values are printed by `format_solution`.
+    pub fn output_vars(&self) -> (Vec<BoolVar>, Vec<IntVar>) {
+        let mut bool_vars = vec![];
+        let mut int_vars = vec![];
+        for output in &self.outputs {
+            for term in &output.terms {
+                match term {
+                    Term::Bool(BoolExpr::Var(v)) => bool_vars.push(*v),
+                    Term::Int(IntExpr::Var(v)) => int_vars.push(*v),
+                    _ => (),
+                }
+            }
+        }
+        (bool_vars, int_vars)
+    }
+
+    /// Extracts the values of the output variables from `model`.
+    pub fn output_assignment(&self, model: &Model) -> Assignment {
+        let (bool_vars, int_vars) = self.output_vars();
+        let mut ret = Assignment::new();
+        for var in bool_vars {
+            ret.set_bool(var, model.get_bool(var));
+        }
+        for var in int_vars {
+            ret.set_int(var, model.get_int(var));
+        }
+        ret
+    }
+
+    /// Formats a solution in the MiniZinc output format (without the `----------` separator).
+    /// `assignment` must contain all the variables returned by `output_vars`.
+    pub fn format_solution(&self, assignment: &Assignment) -> String {
+        let mut ret = String::new();
+        for output in &self.outputs {
+            let values = output
+                .terms
+                .iter()
+                .map(|term| match term {
+                    Term::Bool(BoolExpr::Const(b)) => b.to_string(),
+                    &Term::Bool(BoolExpr::Var(v)) => assignment.get_bool(v).unwrap().to_string(),
+                    Term::Int(IntExpr::Const(n)) => n.to_string(),
+                    &Term::Int(IntExpr::Var(v)) => assignment.get_int(v).unwrap().to_string(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>();
+            match &output.dims {
+                None => writeln!(&mut ret, "{} = {};", output.name, values[0]).unwrap(),
+                Some(dims) => {
+                    write!(&mut ret, "{} = array{}d(", output.name, dims.len()).unwrap();
+                    for &(low, high) in dims {
+                        write!(&mut ret, "{}..{}, ", low, high).unwrap();
+                    }
+                    writeln!(&mut ret, "[{}]);", values.join(", ")).unwrap();
+                }
+            }
+        }
+        ret
+    }
+}
+
+fn check_args(args: &[Expr], n: usize) -> Result<(), String> {
+    if args.len() == n {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} argument(s) expected, but {} given",
+            n,
+            args.len()
+        ))
+    }
+}
+
+fn get_elem<T: Clone>(array: &[T], name: &str, index: i32) -> Result<T, String> {
+    if 1 <= index && index as usize <= array.len() {
+        Ok(array[index as usize - 1].clone())
+    } else {
+        Err(format!("index out of range: {}[{}]", name, index))
+    }
+}
+
+fn linear_sum(coefs: Vec<i32>, terms: Vec<IntExpr>) -> Result<IntExpr, String> {
+    if coefs.len() != terms.len() {
+        return Err(String::from(
+            "coefficients and variables have different lengths",
+        ));
+    }
+    Ok(IntExpr::Linear(
+        terms
+            .into_iter()
+            .zip(coefs)
+            .map(|(t, c)| (Box::new(t), c))
+            .collect(),
+    ))
+}
+
+fn bool_to_int(b: BoolExpr) -> IntExpr {
+    b.ite(IntExpr::Const(1), IntExpr::Const(0))
+}
+
+struct Translator<'s, 'a> {
+    solver: &'s mut IntegratedSolver<'a>,
+    values: BTreeMap<String, Value>,
+    outputs: Vec<Output>,
+    objective: Option<(ObjectiveSense, IntExpr)>,
+    has_solve_item: bool,
+}
+
+type TranslateResult<T> = Result<T, String>;
+
+impl Translator<'_, '_> {
+    fn lookup(&self, name: &str) -> TranslateResult<&Value> {
+        self.values
+            .get(name)
+            .ok_or_else(|| format!("unknown identifier: {}", name))
+    }
+
+    fn bool_arg(&self, expr: &Expr) -> TranslateResult<BoolExpr> {
+        match expr {
+            &Expr::Bool(b) => Ok(BoolExpr::Const(b)),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::Bool(x) => Ok(x.clone()),
+                _ => Err(format!("{} is not a bool", name)),
+            },
+            Expr::Index(name, index) => match self.lookup(name)? {
+                Value::BoolArray(xs) => get_elem(xs, name, *index),
+                _ => Err(format!("{} is not an array of bool", name)),
+            },
+            _ => Err(String::from("bool expected")),
+        }
+    }
+
+    fn int_arg(&self, expr: &Expr) -> TranslateResult<IntExpr> {
+        match expr {
+            &Expr::Int(n) => Ok(IntExpr::Const(n)),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::Int(x) => Ok(x.clone()),
+                _ => Err(format!("{} is not an int", name)),
+            },
+            Expr::Index(name, index) => match self.lookup(name)? {
+                Value::IntArray(xs) => get_elem(xs, name, *index),
+                _ => Err(format!("{} is not an array of int", name)),
+            },
+            _ => Err(String::from("int expected")),
+        }
+    }
+
+    fn const_int_arg(&self, expr: &Expr) -> TranslateResult<i32> {
+        match self.int_arg(expr)? {
+            IntExpr::Const(n) => Ok(n),
+            _ => Err(String::from("constant int expected")),
+        }
+    }
+
+    fn set_arg(&self, expr: &Expr) -> TranslateResult<IntSet> {
+        match expr {
+            Expr::Set(s) => Ok(s.clone()),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::Set(s) => Ok(s.clone()),
+                _ => Err(format!("{} is not a set of int", name)),
+            },
+            Expr::Index(name, index) => match self.lookup(name)? {
+                Value::SetArray(xs) => get_elem(xs, name, *index),
+                _ => Err(format!("{} is not an array of set of int", name)),
+            },
+            _ => Err(String::from("set of int expected")),
+        }
+    }
+
+    fn bool_array_arg(&self, expr: &Expr) -> TranslateResult<Vec<BoolExpr>> {
+        match expr {
+            Expr::Array(elems) => elems.iter().map(|e| self.bool_arg(e)).collect(),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::BoolArray(xs) => Ok(xs.clone()),
+                _ => Err(format!("{} is not an array of bool", name)),
+            },
+            _ => Err(String::from("array of bool expected")),
+        }
+    }
+
+    fn int_array_arg(&self, expr: &Expr) -> TranslateResult<Vec<IntExpr>> {
+        match expr {
+            Expr::Array(elems) => elems.iter().map(|e| self.int_arg(e)).collect(),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::IntArray(xs) => Ok(xs.clone()),
+                _ => Err(format!("{} is not an array of int", name)),
+            },
+            _ => Err(String::from("array of int expected")),
+        }
+    }
+
+    fn const_int_array_arg(&self, expr: &Expr) -> TranslateResult<Vec<i32>> {
+        self.int_array_arg(expr)?
+            .into_iter()
+            .map(|x| match x {
+                IntExpr::Const(n) => Ok(n),
+                _ => Err(String::from("array of constant int expected")),
+            })
+            .collect()
+    }
+
+    fn set_array_arg(&self, expr: &Expr) -> TranslateResult<Vec<IntSet>> {
+        match expr {
+            Expr::Array(elems) => elems.iter().map(|e| self.set_arg(e)).collect(),
+            Expr::Ident(name) => match self.lookup(name)? {
+                Value::SetArray(xs) => Ok(xs.clone()),
+                _ => Err(format!("{} is not an array of set of int", name)),
+            },
+            _ => Err(String::from("array of set of int expected")),
+        }
+    }
+
+    fn new_int_var(&mut self, domain: &IntSet) -> IntExpr {
+        self.solver.new_int_var(domain.to_domain()).expr()
+    }
+
+    fn declare(
+        &mut self,
+        ty: Type,
+        name: String,
+        annotations: Vec<Expr>,
+        value: Option<Expr>,
+    ) -> TranslateResult<()> {
+        if self.values.contains_key(&name) {
+            return Err(format!("redeclaration of {}", name));
+        }
+        if ty.is_var && ty.base == BaseType::SetOfInt {
+            return Err(String::from("set variables are not supported"));
+        }
+        if !ty.is_var && value.is_none() {
+            return Err(format!("missing value of parameter {}", name));
+        }
+
+        let value = match (ty.array_len, &ty.base) {
+            (None, BaseType::Bool) => Value::Bool(match &value {
+                Some(e) => self.bool_arg(e)?,
+                None => self.solver.new_bool_var().expr(),
+            }),
+            (None, BaseType::Int(domain)) => Value::Int(match (&value, domain) {
+                (Some(e), _) => {
+                    let x = self.int_arg(e)?;
+                    if let Some(domain) = domain {
+                        self.solver.add_expr(domain.contains(x.clone()));
+                    }
+                    x
+                }
+                (None, Some(domain)) => self.new_int_var(domain),
+                (None, None) => {
+                    return Err(format!(
+                        "unbounded int variables are not supported: {}",
+                        name
+                    ))
+                }
+            }),
+            (None, BaseType::SetOfInt) => Value::Set(self.set_arg(value.as_ref().unwrap())?),
+            (Some(len), BaseType::Bool) => Value::BoolArray(match &value {
+                Some(e) => self.bool_array_arg(e)?,
+                None => (0..len)
+                    .map(|_| self.solver.new_bool_var().expr())
+                    .collect(),
+            }),
+            (Some(len), BaseType::Int(domain)) => Value::IntArray(match (&value, domain) {
+                (Some(e), _) => {
+                    let xs = self.int_array_arg(e)?;
+                    if let Some(domain) = domain {
+                        for x in &xs {
+                            self.solver.add_expr(domain.contains(x.clone()));
+                        }
+                    }
+                    xs
+                }
+                (None, Some(domain)) => (0..len).map(|_| self.new_int_var(domain)).collect(),
+                (None, None) => {
+                    return Err(format!(
+                        "unbounded int variables are not supported: {}",
+                        name
+                    ))
+                }
+            }),
+            (Some(_), BaseType::SetOfInt) => {
+                Value::SetArray(self.set_array_arg(value.as_ref().unwrap())?)
+            }
+        };
+
+        let len = match &value {
+            Value::BoolArray(xs) => Some(xs.len()),
+            Value::IntArray(xs) => Some(xs.len()),
+            Value::SetArray(xs) => Some(xs.len()),
+            _ => None,
+        };
+        if len != ty.array_len {
+            return Err(format!(
+                "array length mismatch: {} expected, but {} given",
+                ty.array_len.unwrap(),
+                len.unwrap()
+            ));
+        }
+
+        for annotation in &annotations {
+            let dims = match annotation {
+                Expr::Ident(s) if s == "output_var" => None,
+                Expr::Call(s, args) if s == "output_array" => {
+                    let dims = match &args[..] {
+                        [Expr::Array(dims)] => dims
+                            .iter()
+                            .map(|d| match d {
+                                &Expr::Set(IntSet::Range(low, high)) => Ok((low, high)),
+                                _ => Err(String::from("invalid index set in output_array")),
+                            })
+                            .collect::<TranslateResult<Vec<_>>>()?,
+                        _ => return Err(String::from("invalid arguments of output_array")),
+                    };
+                    Some(dims)
+                }
+                _ => continue,
+            };
+            let terms = match &value {
+                Value::Bool(x) if dims.is_none() => vec![Term::Bool(x.clone())],
+                Value::Int(x) if dims.is_none() => vec![Term::Int(x.clone())],
+                Value::BoolArray(xs) if dims.is_some() => {
+                    xs.iter().map(|x| Term::Bool(x.clone())).collect()
+                }
+                Value::IntArray(xs) if dims.is_some() => {
+                    xs.iter().map(|x| Term::Int(x.clone())).collect()
+                }
+                _ => return Err(format!("{} cannot be an output", name)),
+            };
+            self.outputs.push(Output {
+                name: name.clone(),
+                dims,
+                terms,
+            });
+        }
+
+        self.values.insert(name, value);
+        Ok(())
+    }
+
+    /// Translates a constraint which can be reified, or returns `None` if `name` is unknown.
+    fn constraint_expr(&self, name: &str, args: &[Expr]) -> TranslateResult<Option<BoolExpr>> {
+        let expr = match name {
+            "int_eq" | "int_ne" | "int_le" | "int_lt" => {
+                check_args(args, 2)?;
+                let a = self.int_arg(&args[0])?;
+                let b = self.int_arg(&args[1])?;
+                match name {
+                    "int_eq" => a.eq(b),
+                    "int_ne" => a.ne(b),
+                    "int_le" => a.le(b),
+                    "int_lt" => a.lt(b),
+                    _ => unreachable!(),
+                }
+            }
+            "int_lin_eq" | "int_lin_ne" | "int_lin_le" => {
+                check_args(args, 3)?;
+                let sum = linear_sum(
+                    self.const_int_array_arg(&args[0])?,
+                    self.int_array_arg(&args[1])?,
+                )?;
+                let c = IntExpr::Const(self.const_int_arg(&args[2])?);
+                match name {
+                    "int_lin_eq" => sum.eq(c),
+                    "int_lin_ne" => sum.ne(c),
+                    "int_lin_le" => sum.le(c),
+                    _ => unreachable!(),
+                }
+            }
+            "int_plus" | "int_times" | "int_div" | "int_mod" | "int_min" | "int_max" => {
+                check_args(args, 3)?;
+                let a = self.int_arg(&args[0])?;
+                let b = self.int_arg(&args[1])?;
+                let c = self.int_arg(&args[2])?;
+                match name {
+                    "int_plus" => (a + b).eq(c),
+                    "int_times" => (a * b).eq(c),
+                    // Division by 0 is undefined in FlatZinc, while `IntExpr::Div` and
+                    // `IntExpr::Mod` are total.
+                    "int_div" => b.clone().ne(IntExpr::Const(0)) & (a / b).eq(c),
+                    "int_mod" => b.clone().ne(IntExpr::Const(0)) & (a % b).eq(c),
+                    "int_min" => IntExpr::Min(Box::new(a), Box::new(b)).eq(c),
+                    "int_max" => IntExpr::Max(Box::new(a), Box::new(b)).eq(c),
+                    _ => unreachable!(),
+                }
+            }
+            "int_abs" => {
+                check_args(args, 2)?;
+                self.int_arg(&args[0])?.abs().eq(self.int_arg(&args[1])?)
+            }
+            "array_int_element" | "array_var_int_element" => {
+                check_args(args, 3)?;
+                let index = self.int_arg(&args[0])?;
+                let array = self.int_array_arg(&args[1])?;
+                let c = self.int_arg(&args[2])?;
+                let n = array.len() as i32;
+                IntSet::Range(1, n).contains(index.clone())
+                    & IntExpr::element(index - IntExpr::Const(1), array).eq(c)
+            }
+            "array_bool_element" | "array_var_bool_element" => {
+                check_args(args, 3)?;
+                let index = self.int_arg(&args[0])?;
+                let array = self.bool_array_arg(&args[1])?;
+                let c = self.bool_arg(&args[2])?;
+                let n = array.len() as i32;
+                let mut exprs = vec![Box::new(IntSet::Range(1, n).contains(index.clone()))];
+                for (i, x) in array.into_iter().enumerate() {
+                    exprs.push(Box::new(
+                        index
+                            .clone()
+                            .eq(IntExpr::Const(i as i32 + 1))
+                            .imp(x.iff(c.clone())),
+                    ));
+                }
+                BoolExpr::And(exprs)
+            }
+            "set_in" => {
+                check_args(args, 2)?;
+                self.set_arg(&args[1])?.contains(self.int_arg(&args[0])?)
+            }
+            "bool_eq" | "bool_not" | "bool_le" | "bool_lt" => {
+                check_args(args, 2)?;
+                let a = self.bool_arg(&args[0])?;
+                let b = self.bool_arg(&args[1])?;
+                match name {
+                    "bool_eq" => a.iff(b),
+                    "bool_not" => a ^ b,
+                    "bool_le" => a.imp(b),
+                    "bool_lt" => !a & b,
+                    _ => unreachable!(),
+                }
+            }
+            // `bool_and(a, b, r)` means `r <-> (a /\ b)`, while the 2-argument form is the plain
+            // conjunction (which also serves as the base of `bool_and_imp`).
+            "bool_and" | "bool_or" | "bool_xor" => {
+                if args.len() != 3 {
+                    check_args(args, 2)?;
+                }
+                let a = self.bool_arg(&args[0])?;
+                let b = self.bool_arg(&args[1])?;
+                let expr = match name {
+                    "bool_and" => a & b,
+                    "bool_or" => a | b,
+                    "bool_xor" => a ^ b,
+                    _ => unreachable!(),
+                };
+                if args.len() == 3 {
+                    self.bool_arg(&args[2])?.iff(expr)
+                } else {
+                    expr
+                }
+            }
+            "array_bool_and" | "array_bool_or" => {
+                if args.len() != 2 {
+                    check_args(args, 1)?;
+                }
+                let exprs = self
+                    .bool_array_arg(&args[0])?
+                    .into_iter()
+                    .map(Box::new)
+                    .collect();
+                let expr = if name == "array_bool_and" {
+                    BoolExpr::And(exprs)
+                } else {
+                    BoolExpr::Or(exprs)
+                };
+                if args.len() == 2 {
+                    self.bool_arg(&args[1])?.iff(expr)
+                } else {
+                    expr
+                }
+            }
+            "array_bool_xor" => {
+                check_args(args, 1)?;
+                self.bool_array_arg(&args[0])?
+                    .into_iter()
+                    .fold(BoolExpr::Const(false), |acc, x| acc ^ x)
+            }
+            "bool_clause" => {
+                check_args(args, 2)?;
+                let mut exprs = vec![];
+                for x in self.bool_array_arg(&args[0])? {
+                    exprs.push(Box::new(x));
+                }
+                for x in self.bool_array_arg(&args[1])? {
+                    exprs.push(Box::new(!x));
+                }
+                BoolExpr::Or(exprs)
+            }
+            "bool2int" => {
+                check_args(args, 2)?;
+                bool_to_int(self.bool_arg(&args[0])?).eq(self.int_arg(&args[1])?)
+            }
+            "bool_lin_eq" | "bool_lin_le" => {
+                check_args(args, 3)?;
+                let sum = linear_sum(
+                    self.const_int_array_arg(&args[0])?,
+                    self.bool_array_arg(&args[1])?
+                        .into_iter()
+                        .map(bool_to_int)
+                        .collect(),
+                )?;
+                let c = self.int_arg(&args[2])?;
+                if name == "bool_lin_eq" {
+                    sum.eq(c)
+                } else {
+                    sum.le(c)
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(expr))
+    }
+
+    fn add_constraint(&mut self, name: &str, args: &[Expr]) -> TranslateResult<()> {
+        match name {
+            "all_different_int" | "fzn_all_different_int" => {
+                check_args(args, 1)?;
+                let exprs = self.int_array_arg(&args[0])?;
+                self.solver.add_constraint(Stmt::AllDifferent(exprs));
+                return Ok(());
+            }
+            _ => (),
+        }
+
+        if let Some(expr) = self.constraint_expr(name, args)? {
+            self.solver.add_expr(expr);
+            return Ok(());
+        }
+        for (suffix, is_reif) in [("_reif", true), ("_imp", false)] {
+            let Some(base) = name.strip_suffix(suffix) else {
+                continue;
+            };
+            let Some((r, base_args)) = args.split_last() else {
+                continue;
+            };
+            if let Some(expr) = self.constraint_expr(base, base_args)? {
+                let r = self.bool_arg(r)?;
+                self.solver
+                    .add_expr(if is_reif { r.iff(expr) } else { r.imp(expr) });
+                return Ok(());
+            }
+        }
+        Err(format!("unsupported constraint: {}", name))
+    }
+
+    fn translate(&mut self, item: Item) -> TranslateResult<()> {
+        match item {
+            Item::Decl {
+                ty,
+                name,
+                annotations,
+                value,
+            } => self.declare(ty, name, annotations, value),
+            Item::Constraint { name, args } => self.add_constraint(&name, &args),
+            Item::Solve(objective) => {
+                if self.has_solve_item {
+                    return Err(String::from("solve item is specified more than once"));
+                }
+                self.has_solve_item = true;
+                if let Some((sense, expr)) = objective {
+                    self.objective = Some((sense, self.int_arg(&expr)?));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads a FlatZinc model from `input` and adds its variables and constraints to `solver`.
+pub fn parse_flatzinc(
+    solver: &mut IntegratedSolver,
+    input: &str,
+) -> Result<FlatZincModel, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        idx: 0,
+    };
+    let mut translator = Translator {
+        solver,
+        values: BTreeMap::new(),
+        outputs: vec![],
+        objective: None,
+        has_solve_item: false,
+    };
+    while let Some((item, position)) = parser.parse_item()? {
+        translator
+            .translate(item)
+            .map_err(|message| position.error(message))?;
+    }
+
+    Ok(FlatZincModel {
+        outputs: translator.outputs,
+        objective: translator.objective,
+    })
+}
+
+/// Options of `flatzinc_cli`, corresponding to the standard options of FlatZinc solvers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlatZincOptions {
+    /// Print all solutions (`-a`). Only the optimal one is printed for optimization problems.
+    pub all_solutions: bool,
+    /// Stop after printing this number of solutions (`-n`).
+    pub num_solutions: Option<usize>,
+}
+
+const SOLUTION_SEPARATOR: &str = "----------";
+const SEARCH_COMPLETE: &str = "==========";
+const UNSATISFIABLE: &str = "=====UNSATISFIABLE=====";
+const UNKNOWN: &str = "=====UNKNOWN=====";
+
+pub fn flatzinc_cli(
+    input: &str,
+    config: Config,
+    backend_config: &BackendConfig,
+    options: &FlatZincOptions,
+) -> Result<String, CliError> {
+    let mut solver = IntegratedSolver::with_backend_config(config, backend_config);
+    let model = parse_flatzinc(&mut solver, input).map_err(CliError::Parse)?;
+
+    // clear the flags left by the solvers used before in this thread
+    take_resource_limit_exceeded();
+    take_sat_solver_error();
+
+    let mut ret = String::new();
+
+    if let Some((sense, expr)) = &model.objective {
+        let result = match sense {
+            ObjectiveSense::Minimize => solver.try_minimize_under(expr.clone(), &[]),
+            ObjectiveSense::Maximize => solver.try_maximize_under(expr.clone(), &[]),
+        };
+        match result {
+            SolveOutcome::Sat((_, m)) => {
+                ret.push_str(&model.format_solution(&model.output_assignment(&m)));
+                writeln!(&mut ret, "{}", SOLUTION_SEPARATOR).unwrap();
+                writeln!(&mut ret, "{}", SEARCH_COMPLETE).unwrap();
+            }
+            SolveOutcome::Unsat => writeln!(&mut ret, "{}", UNSATISFIABLE).unwrap(),
+            SolveOutcome::Unknown => writeln!(&mut ret, "{}", UNKNOWN).unwrap(),
+            SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
+        }
+        if let Some(e) = take_sat_solver_error() {
+            return Err(CliError::Solver(e));
+        }
+        return Ok(ret);
+    }
+
+    if !options.all_solutions && options.num_solutions.is_none() {
+        match solver.try_solve() {
+            SolveOutcome::Sat(m) => {
+                ret.push_str(&model.format_solution(&model.output_assignment(&m)));
+                writeln!(&mut ret, "{}", SOLUTION_SEPARATOR).unwrap();
+            }
+            SolveOutcome::Unsat => writeln!(&mut ret, "{}", UNSATISFIABLE).unwrap(),
+            SolveOutcome::Unknown => writeln!(&mut ret, "{}", UNKNOWN).unwrap(),
+            SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
+        }
+        if let Some(e) = take_sat_solver_error() {
+            return Err(CliError::Solver(e));
+        }
+        return Ok(ret);
+    }
+
+    let limit = if options.all_solutions {
+        options.num_solutions.unwrap_or(usize::MAX)
+    } else {
+        options.num_solutions.unwrap()
+    };
+    let (bool_vars, int_vars) = model.output_vars();
+    let mut num_found = 0;
+    let mut answer_iter = solver.answer_iter(&bool_vars, &int_vars);
+    let mut unknown = false;
+    while num_found < limit {
+        match answer_iter.try_next() {
+            SolveOutcome::Sat(assignment) => {
+                ret.push_str(&model.format_solution(&assignment));
+                writeln!(&mut ret, "{}", SOLUTION_SEPARATOR).unwrap();
+                num_found += 1;
+            }
+            SolveOutcome::Unsat => break,
+            SolveOutcome::Unknown => {
+                unknown = true;
+                break;
+            }
+            SolveOutcome::Overflow(e) => return Err(CliError::Overflow(e)),
+        }
+    }
+    if unknown {
+        if num_found == 0 {
+            writeln!(&mut ret, "{}", UNKNOWN).unwrap();
+        }
+    } else if num_found == 0 {
+        writeln!(&mut ret, "{}", UNSATISFIABLE).unwrap();
+    } else if num_found < limit {
+        writeln!(&mut ret, "{}", SEARCH_COMPLETE).unwrap();
+    }
+
+    if let Some(e) = take_sat_solver_error() {
+        return Err(CliError::Solver(e));
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(input: &str, options: &FlatZincOptions) -> String {
+        flatzinc_cli(input, Config::default(), &BackendConfig::default(), options).unwrap()
+    }
+
+    fn parse_error(input: &str) -> (usize, usize, String) {
+        let mut solver = IntegratedSolver::new();
+        match parse_flatzinc(&mut solver, input) {
+            Ok(_) => panic!("error expected for {}", input),
+            Err(e) => (e.line, e.column, e.message),
+        }
+    }
+
+    #[test]
+    fn test_flatzinc_tokenize() {
+        let tokens = tokenize("var 1..-3: x_1 :: output_var; % comment\n\"a\\\"b\"")
+            .unwrap()
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident(String::from("var")),
+                Token::Int(1),
+                Token::Symbol(".."),
+                Token::Int(-3),
+                Token::Symbol(":"),
+                Token::Ident(String::from("x_1")),
+                Token::Symbol("::"),
+                Token::Ident(String::from("output_var")),
+                Token::Symbol(";"),
+                Token::Str(String::from("a\"b")),
+                Token::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatzinc_satisfy() {
+        let input = "\
+predicate my_predicate(var int: x);
+array [1..2] of int: coefs = [1, -1];
+var 1..3: x :: output_var;
+var 1..3: y :: output_var;
+var bool: b :: output_var;
+constraint int_lin_eq(coefs, [x, y], 2) :: domain;
+constraint int_le_reif(x, 2, b);
+solve :: int_search([x, y], input_order, indomain_min, complete) satisfy;
+";
+        assert_eq!(
+            run(input, &FlatZincOptions::default()),
+            "x = 3;\ny = 1;\nb = false;\n----------\n"
+        );
+    }
+
+    #[test]
+    fn test_flatzinc_all_solutions() {
+        let input = "\
+var 1..3: x;
+var 1..3: y;
+var 1..3: z;
+array [1..3] of var int: xs :: output_array([1..3]) = [x, y, z];
+constraint fzn_all_different_int(xs);
+constraint int_lt(x, y);
+solve satisfy;
+";
+        let options = FlatZincOptions {
+            all_solutions: true,
+            num_solutions: None,
+        };
+        let output = run(input, &options);
+        let mut solutions = output
+            .split("----------\n")
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(solutions.pop(), Some(String::from("==========\n")));
+        solutions.sort();
+        assert_eq!(
+            solutions,
+            vec![
+                "xs = array1d(1..3, [1, 2, 3]);\n",
+                "xs = array1d(1..3, [1, 3, 2]);\n",
+                "xs = array1d(1..3, [2, 3, 1]);\n",
+            ]
+        );
+
+        let options = FlatZincOptions {
+            all_solutions: false,
+            num_solutions: Some(2),
+        };
+        assert_eq!(run(input, &options).matches("----------").count(), 2);
+        assert!(!run(input, &options).contains("=========="));
+    }
+
+    #[test]
+    fn test_flatzinc_unsorted_set_domain() {
+        let input = "\
+var {5, 1, 3, 1}: x :: output_var;
+constraint int_ne(x, 3);
+solve satisfy;
+";
+        let options = FlatZincOptions {
+            all_solutions: true,
+            num_solutions: None,
+        };
+        let output = run(input, &options);
+        let mut solutions = output
+            .split("----------\n")
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(solutions.pop(), Some(String::from("==========\n")));
+        solutions.sort();
+        assert_eq!(solutions, vec!["x = 1;\n", "x = 5;\n"]);
+    }
+
+    #[test]
+    fn test_flatzinc_optimize() {
+        let input = "\
+var 0..10: x :: output_var;
+var 0..10: y :: output_var;
+var bool: b;
+array [1..2] of var bool: bs :: output_array([1..1, 1..2]) = [b, true];
+constraint int_lin_le([2, 3], [x, y], 12);
+constraint bool_clause([b], []);
+solve maximize x;
+";
+        assert_eq!(
+            run(input, &FlatZincOptions::default()),
+            "x = 6;\ny = 0;\nbs = array2d(1..1, 1..2, [true, true]);\n----------\n==========\n"
+        );
+    }
+
+    #[test]
+    fn test_flatzinc_unsat() {
+        let input = "\
+var 0..5: x :: output_var;
+var -2..2: y;
+constraint int_div(x, y, 0);
+constraint int_le(1, x);
+constraint int_eq(y, 0);
+solve satisfy;
+";
+        assert_eq!(
+            run(input, &FlatZincOptions::default()),
+            "=====UNSATISFIABLE=====\n"
+        );
+    }
+
+    #[test]
+    fn test_flatzinc_parse_error() {
+        assert_eq!(
+            parse_error("var 1..3: x;\nvar 1..3: x;\n"),
+            (2, 1, String::from("redeclaration of x"))
+        );
+        assert_eq!(
+            parse_error("var 1..3: x;\nconstraint int_le(x, y);\n"),
+            (2, 1, String::from("unknown identifier: y"))
+        );
+        assert_eq!(
+            parse_error("var 1..3: x;\nconstraint foo(x);\n"),
+            (2, 1, String::from("unsupported constraint: foo"))
+        );
+        assert_eq!(
+            parse_error("var 1..3: x;\nconstraint int_le(x);\n"),
+            (2, 1, String::from("2 argument(s) expected, but 1 given"))
+        );
+        assert_eq!(
+            parse_error("var 1..3: x\nsolve satisfy;\n"),
+            (2, 1, String::from("`;` expected, but `solve` found"))
+        );
+        assert_eq!(
+            parse_error("var int: x;\n"),
+            (
+                1,
+                1,
+                String::from("unbounded int variables are not supported: x")
+            )
+        );
+        assert_eq!(
+            parse_error("var 1.0..2.0: x;\n"),
+            (1, 5, String::from("floats are not supported"))
+        );
+    }
+}
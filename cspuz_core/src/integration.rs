@@ -1,16 +1,121 @@
-use crate::arithmetic::CheckedInt;
+use crate::arithmetic::{CheckedInt, OverflowError};
 use crate::normalizer::ConvertedBoolVar;
 
-use super::config::Config;
+use super::config::{BackendConfig, Config};
 use super::csp::{
     Assignment, BoolExpr, BoolVar, BoolVarStatus, IntExpr, IntVar, IntVarStatus, Stmt, CSP,
 };
 use super::encoder::{encode, EncodeMap};
+use super::export::{BoolVarMapping, CnfVarMap, ExportError, IntVarMapping};
 use super::norm_csp::NormCSP;
 use super::normalizer::{normalize, NormalizeMap};
-use super::sat::{SATModel, SAT};
+use super::proof::lit_to_dimacs;
+use super::proof::{Proof, REFUTED_BEFORE_SAT_SOLVING};
+use super::sat::{Lit, ResourceLimit, SATModel, SolveResult, SAT};
 use crate::domain::Domain;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Instant;
+
+thread_local! {
+    static RESOURCE_LIMIT_EXCEEDED: Cell<bool> = const { Cell::new(false) };
+    static SAT_SOLVER_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Returns whether any solver in the current thread gave up due to `Config::resource_limit`
+/// since the last call of this function, and clears the flag.
+/// The flag is also set when the SAT solver fails (see `take_sat_solver_error`).
+///
+/// This is useful to tell timeouts from unsatisfiability through APIs which return `Option`.
+pub fn take_resource_limit_exceeded() -> bool {
+    RESOURCE_LIMIT_EXCEEDED.with(|f| f.replace(false))
+}
+
+/// Returns the last failure of the SAT solver in the current thread since the last call of this function
+/// (e.g. the external solver could not be run or printed an invalid answer), and clears it.
+pub fn take_sat_solver_error() -> Option<String> {
+    SAT_SOLVER_ERROR.with(|f| f.borrow_mut().take())
+}
+
+/// The result of solving a problem under a resource limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveOutcome<T> {
+    Sat(T),
+    Unsat,
+    /// The resource limit was reached before the problem was solved, or the SAT solver failed
+    /// (see `take_sat_solver_error`).
+    Unknown,
+    /// The problem involves values which do not fit in the internal arithmetic (see `CSP::check_overflow`).
+    Overflow(OverflowError),
+}
+
+impl<T> SolveOutcome<T> {
+    /// Returns the content of `Sat`, or `None` for both `Unsat` and `Unknown`.
+    ///
+    /// # Panics
+    ///
+    /// Panics on `Overflow`, which cannot be reported as `None`.
+    pub fn sat(self) -> Option<T> {
+        match self {
+            SolveOutcome::Sat(x) => Some(x),
+            SolveOutcome::Unsat | SolveOutcome::Unknown => None,
+            SolveOutcome::Overflow(e) => panic!("overflow: {}", e),
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, SolveOutcome::Unknown)
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> SolveOutcome<U> {
+        match self {
+            SolveOutcome::Sat(x) => SolveOutcome::Sat(f(x)),
+            SolveOutcome::Unsat => SolveOutcome::Unsat,
+            SolveOutcome::Unknown => SolveOutcome::Unknown,
+            SolveOutcome::Overflow(e) => SolveOutcome::Overflow(e),
+        }
+    }
+}
+
+/// Resources consumed by an `IntegratedSolver` so far, which are counted against `Config::resource_limit`.
+#[derive(Clone, Copy, Default)]
+struct ResourceUsage {
+    start: Option<Instant>,
+    conflicts: u64,
+    propagations: u64,
+}
+
+impl ResourceUsage {
+    /// Returns the limit for the next SAT solver call, or `None` if some resource is already exhausted.
+    /// The deadline is folded into the time limit of the returned value.
+    fn remaining(&self, limit: &ResourceLimit) -> Option<ResourceLimit> {
+        let time = match (limit.time, self.start) {
+            (Some(t), Some(start)) => Some(t.checked_sub(start.elapsed())?),
+            (t, _) => t,
+        };
+        let time = match limit.deadline {
+            Some(deadline) => {
+                let until_deadline = deadline.checked_duration_since(Instant::now())?;
+                Some(time.map_or(until_deadline, |t| t.min(until_deadline)))
+            }
+            None => time,
+        };
+        let conflicts = match limit.conflicts {
+            Some(c) => Some(c.checked_sub(self.conflicts).filter(|&c| c > 0)?),
+            None => None,
+        };
+        let propagations = match limit.propagations {
+            Some(p) => Some(p.checked_sub(self.propagations).filter(|&p| p > 0)?),
+            None => None,
+        };
+        Some(ResourceLimit {
+            time,
+            conflicts,
+            propagations,
+            deadline: None,
+        })
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct PerfStats {
@@ -65,6 +170,28 @@ impl PerfStats {
     }
 }
 
+#[cfg(feature = "portfolio")]
+fn new_sat(config: &Config, backend_config: &BackendConfig) -> SAT {
+    let primary = SAT::new_with_backend(config.backend, backend_config);
+    if config.portfolio_size <= 1 || config.produce_proof {
+        // Proofs are written only by a single solver.
+        return primary;
+    }
+    let workers = crate::backend::portfolio::WorkerConfig::diversified(
+        config.portfolio_size - 1,
+        config.glucose_random_seed.unwrap_or(0.0),
+    );
+    SAT::new_portfolio(primary, &workers)
+}
+
+#[cfg(not(feature = "portfolio"))]
+fn new_sat(config: &Config, backend_config: &BackendConfig) -> SAT {
+    if config.portfolio_size > 1 {
+        panic!("portfolio feature is not enabled");
+    }
+    SAT::new_with_backend(config.backend, backend_config)
+}
+
 pub struct IntegratedSolver<'a> {
     csp: CSP,
     normalize_map: NormalizeMap,
@@ -72,8 +199,14 @@ pub struct IntegratedSolver<'a> {
     encode_map: EncodeMap,
     sat: SAT,
     already_used: bool,
+    assumption_vars: BTreeSet<BoolVar>,
+    // indicator variables of non-literal assumptions, reused when the same assumption is given again
+    assumption_indicators: HashMap<BoolExpr, BoolVar>,
     config: Config,
     perf_stats: Option<&'a PerfStats>,
+    resource_usage: ResourceUsage,
+    // once an overflow is found, the problem is no longer encoded
+    overflow: Option<OverflowError>,
 }
 
 impl<'a> IntegratedSolver<'a> {
@@ -81,17 +214,43 @@ impl<'a> IntegratedSolver<'a> {
         IntegratedSolver::with_config(Config::default())
     }
 
+    /// Creates a solver with `config`.
+    ///
+    /// If `config.produce_proof` is set, constraints are encoded into clauses rather than native propagators
+    /// wherever possible, so that the proofs do not depend on propagations which cannot be checked (see `crate::proof`).
+    /// The SAT backend is configured by the default `BackendConfig` of the current thread.
     pub fn with_config(config: Config) -> IntegratedSolver<'a> {
+        IntegratedSolver::with_backend_config(config, &BackendConfig::default())
+    }
+
+    /// Same as `with_config`, but the SAT backend is configured by `backend_config`.
+    pub fn with_backend_config(
+        mut config: Config,
+        backend_config: &BackendConfig,
+    ) -> IntegratedSolver<'a> {
+        if config.produce_proof {
+            config.native_linear_encoding_terms = 0;
+            config.use_native_extension_supports = false;
+            config.use_native_all_different = false;
+        }
         let mut ret = IntegratedSolver {
             csp: CSP::new(),
             normalize_map: NormalizeMap::new(),
             norm: NormCSP::new(),
             encode_map: EncodeMap::new(),
-            sat: SAT::new_with_backend(config.backend),
+            sat: new_sat(&config, backend_config),
             already_used: false,
+            assumption_vars: BTreeSet::new(),
+            assumption_indicators: HashMap::new(),
             config,
             perf_stats: None,
+            resource_usage: ResourceUsage::default(),
+            overflow: None,
         };
+        if ret.config.produce_proof {
+            // If the proof cannot be written, `proof` reports that the proof is not enabled.
+            ret.sat.enable_proof();
+        }
         ret.sat.set_rnd_init_act(ret.config.glucose_rnd_init_act);
         ret.sat
             .set_dump_analysis_info(ret.config.dump_analysis_info);
@@ -129,9 +288,40 @@ impl<'a> IntegratedSolver<'a> {
         self.add_constraint(Stmt::Expr(expr))
     }
 
+    /// Checks that the constraints added so far (and not encoded yet) do not overflow the internal arithmetic.
+    /// This check is also done by `try_encode`, so calling this is necessary only to detect overflows early.
+    pub fn check_overflow(&self) -> Result<(), OverflowError> {
+        self.csp.check_overflow()
+    }
+
+    /// Same as `try_encode`, but panics on overflow.
     pub fn encode(&mut self) -> bool {
+        match self.try_encode() {
+            Ok(b) => b,
+            Err(e) => panic!("overflow: {}", e),
+        }
+    }
+
+    /// Encodes the constraints added since the last call into the SAT solver.
+    /// Returns `Ok(false)` if the problem is found to be unsatisfiable.
+    ///
+    /// The constraints are checked by `check_overflow` first, and an `OverflowError` is returned if they may overflow.
+    /// After an error is returned, the problem is no longer encoded and every call returns the same error.
+    pub fn try_encode(&mut self) -> Result<bool, OverflowError> {
+        if let Some(e) = self.overflow {
+            return Err(e);
+        }
+        if let Err(e) = self.csp.check_overflow() {
+            self.overflow = Some(e);
+            return Err(e);
+        }
+
         let is_first = !self.already_used;
         self.already_used = true;
+        if is_first {
+            // The time limit covers the encoding as well as the SAT solving.
+            self.resource_usage.start = Some(Instant::now());
+        }
 
         if self.config.use_constant_folding {
             self.csp.optimize(
@@ -141,11 +331,11 @@ impl<'a> IntegratedSolver<'a> {
         }
 
         if self.csp.is_inconsistent() {
-            return false;
+            return Ok(false);
         }
 
         let start = std::time::Instant::now();
-        normalize(
+        let normalized = normalize(
             &mut self.csp,
             &mut self.norm,
             &mut self.normalize_map,
@@ -156,12 +346,16 @@ impl<'a> IntegratedSolver<'a> {
                 .time_normalize
                 .set(perf_stats.time_normalize() + start.elapsed().as_secs_f64());
         }
+        if let Err(e) = normalized {
+            self.overflow = Some(e);
+            return Err(e);
+        }
 
         if is_first && self.config.use_norm_domain_refinement {
             self.norm.refine_domain();
         }
         if self.norm.is_inconsistent() {
-            return false;
+            return Ok(false);
         }
 
         let start = std::time::Instant::now();
@@ -176,6 +370,9 @@ impl<'a> IntegratedSolver<'a> {
                 .time_encode
                 .set(perf_stats.time_encode() + start.elapsed().as_secs_f64());
         }
+        if self.norm.is_inconsistent() {
+            return Ok(false);
+        }
         let solver_stats = self.sat.stats();
         if let Some(perf_stats) = self.perf_stats {
             if let Some(decisions) = solver_stats.decisions {
@@ -188,19 +385,208 @@ impl<'a> IntegratedSolver<'a> {
                 perf_stats.conflicts.set(conflicts);
             }
         }
-        true
+        Ok(true)
     }
 
     pub fn solve(&mut self) -> Option<Model<'_>> {
-        if !self.encode() {
-            return None;
+        self.try_solve().sat()
+    }
+
+    /// Same as `solve`, but distinguishes the case where `Config::resource_limit` is reached (`Unknown`)
+    /// from unsatisfiability.
+    pub fn try_solve(&mut self) -> SolveOutcome<Model<'_>> {
+        match self.try_encode() {
+            Ok(true) => (),
+            Ok(false) => return SolveOutcome::Unsat,
+            Err(e) => return SolveOutcome::Overflow(e),
         }
-        let start = std::time::Instant::now();
-        let solver_result = if self.sat.solve_without_model() {
-            Some(unsafe { self.sat.model() })
+        self.run_sat_solver(&[])
+    }
+
+    /// Solve the problem assuming that all of `assumptions` hold.
+    /// Unlike constraints added by `add_expr`, assumptions are effective only for this call,
+    /// so the solver can be queried repeatedly under different assumptions without rebuilding it.
+    ///
+    /// An assumption which is not a (possibly negated) variable is given to the SAT solver through an
+    /// indicator variable implying it. The indicator is added permanently but never forced to be true,
+    /// and it is reused when the same assumption is given again.
+    pub fn solve_under(&mut self, assumptions: &[BoolExpr]) -> Option<Model<'_>> {
+        self.try_solve_under(assumptions).sat()
+    }
+
+    /// Same as `solve_under`, but distinguishes the case where `Config::resource_limit` is reached.
+    pub fn try_solve_under(&mut self, assumptions: &[BoolExpr]) -> SolveOutcome<Model<'_>> {
+        let assumptions = assumptions
+            .iter()
+            .map(|expr| self.assumption_var(expr))
+            .collect::<Vec<_>>();
+
+        match self.try_encode() {
+            Ok(true) => (),
+            Ok(false) => return SolveOutcome::Unsat,
+            Err(e) => return SolveOutcome::Overflow(e),
+        }
+
+        let mut sat_assumptions = vec![];
+        for (var, negated) in assumptions {
+            match self.normalize_map.get_bool_var_raw(var) {
+                ConvertedBoolVar::Lit(norm_lit) => {
+                    let sat_lit = self
+                        .encode_map
+                        .get_bool_lit(norm_lit)
+                        .expect("assumption variable is not encoded");
+                    sat_assumptions.push(if negated { !sat_lit } else { sat_lit });
+                }
+                ConvertedBoolVar::Removed => match self.csp.get_bool_var_status(var) {
+                    BoolVarStatus::Fixed(b) => {
+                        if b == negated {
+                            return SolveOutcome::Unsat;
+                        }
+                    }
+                    _ => unreachable!(),
+                },
+                ConvertedBoolVar::NotConverted => unreachable!(),
+            }
+        }
+        self.run_sat_solver(&sat_assumptions)
+    }
+
+    /// Finds an assignment which minimizes the value of `objective`.
+    /// Returns the optimal value together with a model attaining it, or `None` if the problem is unsatisfiable.
+    pub fn minimize(&mut self, objective: IntExpr) -> Option<(i32, Model<'_>)> {
+        self.minimize_under(objective, &[])
+    }
+
+    /// Finds an assignment which maximizes the value of `objective`.
+    /// Returns the optimal value together with a model attaining it, or `None` if the problem is unsatisfiable.
+    pub fn maximize(&mut self, objective: IntExpr) -> Option<(i32, Model<'_>)> {
+        self.maximize_under(objective, &[])
+    }
+
+    /// Same as `minimize`, but only assignments satisfying all of `assumptions` are considered.
+    ///
+    /// The optimum is found by a binary search over the objective value, where each candidate bound is
+    /// passed to the SAT solver as an assumption. Thus the solutions are not restricted after the search,
+    /// although the definition of an auxiliary variable for `objective` and the indicator variables of
+    /// the bounds (see `solve_under`) remain in the problem.
+    /// If `Config::resource_limit` is reached during the search, `None` is returned.
+    pub fn minimize_under(
+        &mut self,
+        objective: IntExpr,
+        assumptions: &[BoolExpr],
+    ) -> Option<(i32, Model<'_>)> {
+        self.try_minimize_under(objective, assumptions).sat()
+    }
+
+    /// Same as `minimize_under`, but distinguishes the case where `Config::resource_limit` is reached.
+    /// In this case `Unknown` is returned rather than the best solution found so far, which may not be optimal.
+    pub fn try_minimize_under(
+        &mut self,
+        objective: IntExpr,
+        assumptions: &[BoolExpr],
+    ) -> SolveOutcome<(i32, Model<'_>)> {
+        let objective = match objective {
+            IntExpr::Var(v) => v,
+            _ => {
+                let range = self.csp.int_expr_range(&objective);
+                let v = self
+                    .csp
+                    .new_int_var(Domain::range_from_checked(range.low, range.high));
+                self.add_expr(v.expr().eq(objective));
+                v
+            }
+        };
+
+        let mut assumptions = assumptions.to_vec();
+        let mut best = match self
+            .try_solve_under(&assumptions)
+            .map(|model| model.get_int(objective))
+        {
+            SolveOutcome::Sat(value) => value,
+            SolveOutcome::Unsat => return SolveOutcome::Unsat,
+            SolveOutcome::Unknown => return SolveOutcome::Unknown,
+            SolveOutcome::Overflow(e) => return SolveOutcome::Overflow(e),
+        };
+        // `solve_under` may have refined the domain of `objective`
+        let mut low = self.csp.int_expr_range(&objective.expr()).low.get();
+
+        while low < best {
+            let mid = ((low as i64 + best as i64).div_euclid(2)) as i32;
+            assumptions.push(objective.expr().le(IntExpr::Const(mid)));
+            let value = self
+                .try_solve_under(&assumptions)
+                .map(|model| model.get_int(objective));
+            assumptions.pop();
+
+            match value {
+                SolveOutcome::Sat(value) => best = value,
+                SolveOutcome::Unsat => low = mid + 1,
+                SolveOutcome::Unknown => return SolveOutcome::Unknown,
+                SolveOutcome::Overflow(e) => return SolveOutcome::Overflow(e),
+            }
+        }
+
+        assumptions.push(objective.expr().le(IntExpr::Const(best)));
+        self.try_solve_under(&assumptions)
+            .map(|model| (best, model))
+    }
+
+    /// Same as `maximize`, but only assignments satisfying all of `assumptions` are considered.
+    pub fn maximize_under(
+        &mut self,
+        objective: IntExpr,
+        assumptions: &[BoolExpr],
+    ) -> Option<(i32, Model<'_>)> {
+        self.try_maximize_under(objective, assumptions).sat()
+    }
+
+    /// Same as `maximize_under`, but distinguishes the case where `Config::resource_limit` is reached.
+    pub fn try_maximize_under(
+        &mut self,
+        objective: IntExpr,
+        assumptions: &[BoolExpr],
+    ) -> SolveOutcome<(i32, Model<'_>)> {
+        self.try_minimize_under(
+            IntExpr::Linear(vec![(Box::new(objective), -1)]),
+            assumptions,
+        )
+        .map(|(value, model)| (-value, model))
+    }
+
+    /// Returns a variable `v` and a flag `negated` such that `v ^ negated` implies `expr`.
+    /// An indicator variable is introduced unless `expr` is a (possibly negated) variable or it has been
+    /// introduced for the same expression before.
+    fn assumption_var(&mut self, expr: &BoolExpr) -> (BoolVar, bool) {
+        let (var, negated) = if let Some(var) = expr.as_var() {
+            (var, false)
+        } else if let Some(var) = match expr {
+            BoolExpr::Not(e) => e.as_var(),
+            _ => None,
+        } {
+            (var, true)
+        } else if let Some(&var) = self.assumption_indicators.get(expr) {
+            (var, false)
         } else {
-            None
+            let var = self.new_bool_var();
+            self.add_expr(var.expr().imp(expr.clone()));
+            self.assumption_indicators.insert(expr.clone(), var);
+            (var, false)
         };
+        if self.assumption_vars.insert(var) {
+            self.add_prenormalize_var(var);
+        }
+        (var, negated)
+    }
+
+    fn run_sat_solver(&mut self, assumptions: &[Lit]) -> SolveOutcome<Model<'_>> {
+        let Some(limit) = self.resource_usage.remaining(&self.config.resource_limit) else {
+            RESOURCE_LIMIT_EXCEEDED.with(|f| f.set(true));
+            return SolveOutcome::Unknown;
+        };
+
+        let start = std::time::Instant::now();
+        let stats_before = self.sat.stats();
+        let result = self.sat.solve_under_limited(assumptions, &limit);
         if let Some(perf_stats) = self.perf_stats {
             perf_stats
                 .time_sat_solver
@@ -218,16 +604,36 @@ impl<'a> IntegratedSolver<'a> {
                 perf_stats.conflicts.set(conflicts);
             }
         }
+        if let (Some(before), Some(after)) = (stats_before.conflicts, solver_stats.conflicts) {
+            self.resource_usage.conflicts += after - before;
+        }
+        if let (Some(before), Some(after)) = (stats_before.propagations, solver_stats.propagations)
+        {
+            self.resource_usage.propagations += after - before;
+        }
 
-        match solver_result {
-            Some(model) => Some(Model {
+        match result {
+            SolveResult::Sat => SolveOutcome::Sat(Model {
                 csp: &self.csp,
                 normalize_map: &self.normalize_map,
                 norm_csp: &self.norm,
                 encode_map: &self.encode_map,
-                model,
+                model: unsafe { self.sat.model() },
             }),
-            None => None,
+            SolveResult::Unsat => SolveOutcome::Unsat,
+            SolveResult::Unknown => {
+                RESOURCE_LIMIT_EXCEEDED.with(|f| f.set(true));
+                // An external solver may answer UNKNOWN by itself, which should not be mistaken for a timeout.
+                let error = self.sat.last_error().or_else(|| {
+                    limit.is_unlimited().then(|| {
+                        String::from("the SAT solver gave up without reaching the resource limit")
+                    })
+                });
+                if let Some(e) = error {
+                    SAT_SOLVER_ERROR.with(|f| *f.borrow_mut() = Some(e));
+                }
+                SolveOutcome::Unknown
+            }
         }
     }
 
@@ -247,13 +653,49 @@ impl<'a> IntegratedSolver<'a> {
     }
 
     pub fn decide_irrefutable_facts(
-        mut self,
+        self,
         bool_vars: &[BoolVar],
         int_vars: &[IntVar],
     ) -> Option<Assignment> {
+        self.try_decide_irrefutable_facts(bool_vars, int_vars).sat()
+    }
+
+    /// Same as `decide_irrefutable_facts`, but distinguishes the case where `Config::resource_limit` is reached.
+    /// In this case `Unknown` is returned rather than a partial result, since facts which are not refuted yet
+    /// are not necessarily irrefutable.
+    pub fn try_decide_irrefutable_facts(
+        mut self,
+        bool_vars: &[BoolVar],
+        int_vars: &[IntVar],
+    ) -> SolveOutcome<Assignment> {
+        self.decide_irrefutable_facts_impl(bool_vars, int_vars)
+    }
+
+    /// Same as `decide_irrefutable_facts`, but also returns a proof of the irrefutability of the facts.
+    /// `Config::produce_proof` must be set.
+    ///
+    /// The proof refutes the problem together with a clause stating that at least one of the facts does not hold,
+    /// that is, it shows that every assignment satisfying the problem agrees with the facts.
+    pub fn decide_irrefutable_facts_with_proof(
+        mut self,
+        bool_vars: &[BoolVar],
+        int_vars: &[IntVar],
+    ) -> Option<(Assignment, Result<Proof, String>)> {
+        let assignment = self
+            .decide_irrefutable_facts_impl(bool_vars, int_vars)
+            .sat()?;
+        let proof = self.proof();
+        Some((assignment, proof))
+    }
+
+    fn decide_irrefutable_facts_impl(
+        &mut self,
+        bool_vars: &[BoolVar],
+        int_vars: &[IntVar],
+    ) -> SolveOutcome<Assignment> {
         let mut assignment = Assignment::new();
-        match self.solve() {
-            Some(model) => {
+        match self.try_solve() {
+            SolveOutcome::Sat(model) => {
                 for &var in bool_vars {
                     assignment.set_bool(var, model.get_bool(var));
                 }
@@ -261,7 +703,9 @@ impl<'a> IntegratedSolver<'a> {
                     assignment.set_int(var, model.get_int(var));
                 }
             }
-            None => return None,
+            SolveOutcome::Unsat => return SolveOutcome::Unsat,
+            SolveOutcome::Unknown => return SolveOutcome::Unknown,
+            SolveOutcome::Overflow(e) => return SolveOutcome::Overflow(e),
         }
         let mut iterations = 1;
         loop {
@@ -302,8 +746,8 @@ impl<'a> IntegratedSolver<'a> {
             }
 
             iterations += 1;
-            match self.solve() {
-                Some(model) => {
+            match self.try_solve() {
+                SolveOutcome::Sat(model) => {
                     let bool_erased = assignment
                         .bool_iter()
                         .filter_map(|(&v, &b)| {
@@ -326,7 +770,9 @@ impl<'a> IntegratedSolver<'a> {
                         .iter()
                         .for_each(|&v| assert!(assignment.remove_int(v).is_some()));
                 }
-                None => break,
+                SolveOutcome::Unsat => break,
+                SolveOutcome::Unknown => return SolveOutcome::Unknown,
+                SolveOutcome::Overflow(e) => return SolveOutcome::Overflow(e),
             }
         }
 
@@ -334,7 +780,116 @@ impl<'a> IntegratedSolver<'a> {
             perf_stats.iterations.set(iterations);
         }
 
-        Some(assignment)
+        SolveOutcome::Sat(assignment)
+    }
+
+    /// Returns a DRAT proof of the unsatisfiability of the problem, which is valid if the last call of `solve`
+    /// (or `try_solve`) reported unsatisfiability. `Config::produce_proof` must be set.
+    ///
+    /// If the unsatisfiability was detected before the problem reached the SAT solver (e.g. by the normalizer),
+    /// no proof is available and an error is returned.
+    pub fn proof(&self) -> Result<Proof, String> {
+        if self.csp.is_inconsistent() || self.norm.is_inconsistent() {
+            return Err(String::from(REFUTED_BEFORE_SAT_SOLVING));
+        }
+        self.sat.proof()
+    }
+
+    /// Encodes the problem into CNF without solving it, and writes the CNF to `out` in the DIMACS format.
+    /// Returns the correspondence between the variables of the CNF and the CSP variables.
+    ///
+    /// Constraints are encoded into clauses rather than native propagators wherever possible (as with
+    /// `Config::produce_proof`), falling back to the clausal encodings of the external backend for connectivity
+    /// and graph division constraints. If the problem contains a constraint which cannot be expressed by clauses
+    /// (graph division with extra options or custom constraints), `ExportError::Unsupported` is returned.
+    /// If the problem may overflow (see `try_encode`), `ExportError::Overflow` is returned.
+    /// If the problem has already been solved, `ExportError::AlreadySolved` is returned.
+    pub fn export_cnf<W: std::io::Write>(mut self, out: &mut W) -> Result<CnfVarMap, ExportError> {
+        if self.already_used {
+            return Err(ExportError::AlreadySolved);
+        }
+        self.config.native_linear_encoding_terms = 0;
+        self.config.use_native_extension_supports = false;
+        self.config.use_native_all_different = false;
+        self.sat = SAT::new_external();
+
+        if !self.try_encode()? {
+            self.sat.add_clause(&[]);
+        }
+        let sat = self.sat.as_external().unwrap();
+        if let Some(name) = sat.unsupported() {
+            return Err(ExportError::Unsupported(name));
+        }
+        sat.write_dimacs(out, &[])?;
+
+        let bool_vars = (0..self.csp.num_bool_vars())
+            .map(|i| {
+                let var = BoolVar::new(i);
+                match self.normalize_map.get_bool_var_raw(var) {
+                    ConvertedBoolVar::Lit(norm_lit) => match self.encode_map.get_bool_lit(norm_lit)
+                    {
+                        Some(lit) => BoolVarMapping::Lit(lit_to_dimacs(lit)),
+                        None => BoolVarMapping::Unused,
+                    },
+                    ConvertedBoolVar::Removed => match self.csp.get_bool_var_status(var) {
+                        BoolVarStatus::Fixed(b) => BoolVarMapping::Fixed(b),
+                        _ => BoolVarMapping::Unused,
+                    },
+                    ConvertedBoolVar::NotConverted => BoolVarMapping::Unused,
+                }
+            })
+            .collect();
+
+        let int_vars = (0..self.csp.num_int_vars())
+            .map(|i| -> Result<IntVarMapping, OverflowError> {
+                let var = IntVar::new(i);
+                let Some(norm_var) = self.normalize_map.get_int_var(var) else {
+                    let lower_bound = match self.csp.get_int_var_status(var) {
+                        IntVarStatus::Fixed(v) | IntVarStatus::Unfixed(v) => v.get(),
+                        // The CNF is unsatisfiable in this case.
+                        IntVarStatus::Infeasible => 0,
+                    };
+                    return Ok(IntVarMapping {
+                        lower_bound,
+                        ..IntVarMapping::default()
+                    });
+                };
+                let lower_bound = self
+                    .norm
+                    .vars
+                    .int_var(norm_var)
+                    .lower_bound_checked()
+                    .to_i32_checked()?;
+                Ok(match self.encode_map.get_int_var_lits(norm_var)? {
+                    Some(lits) => IntVarMapping {
+                        lower_bound,
+                        order: lits
+                            .order
+                            .into_iter()
+                            .map(|(v, l)| (v, lit_to_dimacs(l)))
+                            .collect(),
+                        direct: lits
+                            .direct
+                            .into_iter()
+                            .map(|(v, l)| (v, lit_to_dimacs(l)))
+                            .collect(),
+                        log: lits.log.map(|(offset, bits)| {
+                            (offset, bits.into_iter().map(lit_to_dimacs).collect())
+                        }),
+                    },
+                    None => IntVarMapping {
+                        lower_bound,
+                        ..IntVarMapping::default()
+                    },
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CnfVarMap {
+            num_vars: self.sat.num_var(),
+            bool_vars,
+            int_vars,
+        })
     }
 
     pub fn answer_iter(self, bool_vars: &[BoolVar], int_vars: &[IntVar]) -> AnswerIterator<'a> {
@@ -360,30 +915,41 @@ pub struct AnswerIterator<'a> {
     key_int: Vec<IntVar>,
 }
 
+impl AnswerIterator<'_> {
+    /// Same as `next`, but distinguishes the case where `Config::resource_limit` is reached (`Unknown`)
+    /// from the end of the answers (`Unsat`).
+    pub fn try_next(&mut self) -> SolveOutcome<Assignment> {
+        let model = match self.solver.try_solve() {
+            SolveOutcome::Sat(model) => model,
+            SolveOutcome::Unsat => return SolveOutcome::Unsat,
+            SolveOutcome::Unknown => return SolveOutcome::Unknown,
+            SolveOutcome::Overflow(e) => return SolveOutcome::Overflow(e),
+        };
+        let mut ret = Assignment::new();
+        let mut refutation = vec![];
+        for &var in &self.key_bool {
+            let b = model.get_bool(var);
+            ret.set_bool(var, b);
+            refutation.push(Box::new(if b { !var.expr() } else { var.expr() }));
+        }
+        for &var in &self.key_int {
+            let n = model.get_int(var);
+            ret.set_int(var, n);
+            refutation.push(Box::new(var.expr().ne(IntExpr::Const(n))));
+        }
+        self.solver.add_expr(BoolExpr::Or(refutation));
+
+        SolveOutcome::Sat(ret)
+    }
+}
+
 impl Iterator for AnswerIterator<'_> {
     type Item = Assignment;
 
+    /// Returns the next answer, or `None` if there are no more answers or `Config::resource_limit` is reached
+    /// (use `try_next` to tell them apart). Panics on overflow (see `SolveOutcome::sat`).
     fn next(&mut self) -> Option<Assignment> {
-        let model = self.solver.solve();
-        if let Some(model) = &model {
-            let mut ret = Assignment::new();
-            let mut refutation = vec![];
-            for &var in &self.key_bool {
-                let b = model.get_bool(var);
-                ret.set_bool(var, b);
-                refutation.push(Box::new(if b { !var.expr() } else { var.expr() }));
-            }
-            for &var in &self.key_int {
-                let n = model.get_int(var);
-                ret.set_int(var, n);
-                refutation.push(Box::new(var.expr().ne(IntExpr::Const(n))));
-            }
-            self.solver.add_expr(BoolExpr::Or(refutation));
-
-            Some(ret)
-        } else {
-            None
-        }
+        self.try_next().sat()
     }
 }
 
@@ -1,6 +1,7 @@
 use super::IntegrationTester;
 use crate::arithmetic::CmpOp;
 use crate::csp::Stmt;
+use crate::dfa::Dfa;
 use crate::integration::*;
 use std::collections::VecDeque;
 use std::env;
@@ -61,9 +62,16 @@ impl Fuzzer {
         log_encoding_mode: FuzzerLogEncodingMode,
         encode_only: bool,
     ) {
+        let use_native_all_different =
+            cfg!(feature = "csp-extra-constraints") && self.next_u32(2) == 0;
+        let use_pseudo_boolean_encoding = self.next_u32(2) == 0;
+        let force_pseudo_boolean_encoding = use_pseudo_boolean_encoding && self.next_u32(2) == 0;
         let mut tester = IntegrationTester::with_config(Config {
             use_log_encoding: !matches!(log_encoding_mode, FuzzerLogEncodingMode::Never),
             force_use_log_encoding: matches!(log_encoding_mode, FuzzerLogEncodingMode::Force),
+            use_native_all_different,
+            use_pseudo_boolean_encoding,
+            force_pseudo_boolean_encoding,
             ..Config::default()
         });
 
@@ -130,18 +138,20 @@ impl Fuzzer {
         int_vars: &[IntVar],
         max_complexity: u32,
     ) -> Stmt {
-        // Weights: 0,1 = AllDifferent, 2 = ActiveVerticesConnected,
-        // 3 (feature-gated) = ExtensionSupports, rest = BoolExpr
+        // Weights: 0,1 = AllDifferent, 2 = ActiveVerticesConnected, 3 = Regular,
+        // 4 = Cardinality / PseudoBoolean, 5 (feature-gated) = ExtensionSupports, rest = BoolExpr
         #[cfg(feature = "csp-extra-constraints")]
-        let mode = self.next_u32(11);
+        let mode = self.next_u32(13);
         #[cfg(not(feature = "csp-extra-constraints"))]
-        let mode = self.next_u32(10);
+        let mode = self.next_u32(12);
 
         match mode {
             0 | 1 => self.random_alldifferent_stmt(bool_vars, int_vars, max_complexity),
             2 => self.random_active_vertices_connected_stmt(bool_vars, int_vars, max_complexity),
+            3 => self.random_regular_stmt(bool_vars, int_vars, max_complexity),
+            4 => self.random_pseudo_boolean_stmt(bool_vars, int_vars, max_complexity),
             #[cfg(feature = "csp-extra-constraints")]
-            3 => self.random_extension_supports_stmt(bool_vars, int_vars),
+            5 => self.random_extension_supports_stmt(bool_vars, int_vars),
             _ => {
                 let complexity = self.next_u32(max_complexity);
                 Stmt::Expr(self.random_bool_expr(bool_vars, int_vars, complexity))
@@ -199,6 +209,73 @@ impl Fuzzer {
         Stmt::ActiveVerticesConnected(vertex_exprs, edges)
     }
 
+    fn random_regular_stmt(
+        &mut self,
+        bool_vars: &[BoolVar],
+        int_vars: &[IntVar],
+        max_complexity: u32,
+    ) -> Stmt {
+        // 0 to 4 expressions
+        let n = self.next_u32(5) as usize;
+        let exprs: Vec<IntExpr> = (0..n)
+            .map(|_| {
+                let c = self.next_u32(max_complexity / 2 + 1);
+                self.random_int_expr(bool_vars, int_vars, c)
+            })
+            .collect();
+
+        // 1 to 4 states; symbols in -3..=3 to match int var domains
+        let num_states = self.next_u32(4) as usize + 1;
+        let mut dfa = Dfa::new(num_states, self.next_u32(num_states as u32) as usize);
+        for s in 0..num_states {
+            if self.next_u32(2) == 0 {
+                dfa.set_accepting(s, true);
+            }
+            for symbol in -3..=3 {
+                if self.next_u32(3) != 0 {
+                    let t = self.next_u32(num_states as u32) as usize;
+                    dfa.add_transition(s, symbol, t);
+                }
+            }
+        }
+        Stmt::Regular(exprs, dfa)
+    }
+
+    fn random_pseudo_boolean_stmt(
+        &mut self,
+        bool_vars: &[BoolVar],
+        int_vars: &[IntVar],
+        max_complexity: u32,
+    ) -> Stmt {
+        // 0 to 6 terms
+        let n = self.next_u32(7) as usize;
+        let exprs: Vec<BoolExpr> = (0..n)
+            .map(|_| {
+                let c = self.next_u32(max_complexity / 2 + 1);
+                self.random_bool_expr(bool_vars, int_vars, c)
+            })
+            .collect();
+        let op = [
+            CmpOp::Eq,
+            CmpOp::Ne,
+            CmpOp::Le,
+            CmpOp::Lt,
+            CmpOp::Ge,
+            CmpOp::Gt,
+        ][self.next_u32(6) as usize];
+        if self.next_u32(2) == 0 {
+            let rhs = self.next_i32(-1, n as i32 + 2);
+            Stmt::Cardinality(exprs, op, rhs)
+        } else {
+            let terms = exprs
+                .into_iter()
+                .map(|e| (e, self.next_i32(-3, 4)))
+                .collect();
+            let rhs = self.next_i32(-4, 5);
+            Stmt::PseudoBoolean(terms, op, rhs)
+        }
+    }
+
     #[cfg(feature = "csp-extra-constraints")]
     fn random_extension_supports_stmt(
         &mut self,
@@ -311,9 +388,9 @@ impl Fuzzer {
         }
 
         #[cfg(feature = "csp-extra-constraints")]
-        let mode = self.next_u32(5);
+        let mode = self.next_u32(8);
         #[cfg(not(feature = "csp-extra-constraints"))]
-        let mode = self.next_u32(4);
+        let mode = self.next_u32(6);
 
         match mode {
             0 => {
@@ -351,7 +428,6 @@ impl Fuzzer {
 
                 IntExpr::Linear(vec![(t1, scale1), (t2, scale2)])
             }
-            #[cfg(feature = "csp-extra-constraints")]
             4 => {
                 let left_complexity = self.next_u32(complexity);
                 let right_complexity = complexity - left_complexity - 1;
@@ -359,8 +435,53 @@ impl Fuzzer {
                 let lhs = Box::new(self.random_int_expr(bool_vars, int_vars, left_complexity));
                 let rhs = Box::new(self.random_int_expr(bool_vars, int_vars, right_complexity));
 
+                if self.next_u32(2) == 0 {
+                    IntExpr::Min(lhs, rhs)
+                } else {
+                    IntExpr::Max(lhs, rhs)
+                }
+            }
+            5 => {
+                let num_values = self.next_i32(1, 4) as usize;
+                let mut rest_complexity = complexity - 1;
+
+                let index_complexity = self.next_u32(rest_complexity + 1);
+                rest_complexity -= index_complexity;
+                let index = Box::new(self.random_int_expr(bool_vars, int_vars, index_complexity));
+
+                let mut values = vec![];
+                for _ in 0..num_values {
+                    let value_complexity = self.next_u32(rest_complexity + 1);
+                    rest_complexity -= value_complexity;
+                    values.push(self.random_int_expr(bool_vars, int_vars, value_complexity));
+                }
+
+                IntExpr::Element(index, values)
+            }
+            #[cfg(feature = "csp-extra-constraints")]
+            6 => {
+                let left_complexity = self.next_u32(complexity);
+                let right_complexity = complexity - left_complexity - 1;
+
+                let lhs = Box::new(self.random_int_expr(bool_vars, int_vars, left_complexity));
+                let rhs = Box::new(self.random_int_expr(bool_vars, int_vars, right_complexity));
+
                 IntExpr::Mul(lhs, rhs)
             }
+            #[cfg(feature = "csp-extra-constraints")]
+            7 => {
+                let left_complexity = self.next_u32(complexity);
+                let right_complexity = complexity - left_complexity - 1;
+
+                let lhs = Box::new(self.random_int_expr(bool_vars, int_vars, left_complexity));
+                let rhs = Box::new(self.random_int_expr(bool_vars, int_vars, right_complexity));
+
+                if self.next_u32(2) == 0 {
+                    IntExpr::Div(lhs, rhs)
+                } else {
+                    IntExpr::Mod(lhs, rhs)
+                }
+            }
             _ => unreachable!(),
         }
     }
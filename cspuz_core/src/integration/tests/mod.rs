@@ -1,4 +1,6 @@
+use crate::arithmetic::CmpOp;
 use crate::csp::*;
+use crate::dfa::Dfa;
 use crate::domain::Domain;
 use crate::integration::*;
 use crate::propagators::graph_division::GraphDivisionOptions;
@@ -349,6 +351,563 @@ fn test_integration_solve_twice_propagation() {
     }
 }
 
+#[test]
+fn test_integration_solve_under_bool() {
+    let mut solver = IntegratedSolver::new();
+
+    let x = solver.new_bool_var();
+    let y = solver.new_bool_var();
+    let z = solver.new_bool_var();
+    solver.add_expr(x.expr() | y.expr());
+    solver.add_expr(!x.expr() | z.expr());
+
+    {
+        let model = solver.solve_under(&[!y.expr()]);
+        assert!(model.is_some());
+        let model = model.unwrap();
+        assert_eq!(model.get_bool(x), true);
+        assert_eq!(model.get_bool(y), false);
+        assert_eq!(model.get_bool(z), true);
+    }
+    {
+        let model = solver.solve_under(&[!y.expr(), !z.expr()]);
+        assert!(model.is_none());
+    }
+    {
+        // assumptions do not persist across calls
+        let model = solver.solve_under(&[!z.expr()]);
+        assert!(model.is_some());
+        let model = model.unwrap();
+        assert_eq!(model.get_bool(x), false);
+        assert_eq!(model.get_bool(y), true);
+    }
+    {
+        let model = solver.solve_under(&[x.expr() ^ y.expr(), !(y.expr() & z.expr())]);
+        assert!(model.is_some());
+        let model = model.unwrap();
+        assert_ne!(model.get_bool(x), model.get_bool(y));
+        assert!(!(model.get_bool(y) && model.get_bool(z)));
+    }
+    {
+        let model = solver.solve();
+        assert!(model.is_some());
+    }
+}
+
+#[test]
+fn test_integration_solve_under_int() {
+    let mut solver = IntegratedSolver::new();
+
+    let a = solver.new_int_var(Domain::range(0, 5));
+    let b = solver.new_int_var(Domain::range(0, 5));
+    solver.add_expr((a.expr() + b.expr()).eq(IntExpr::Const(6)));
+
+    {
+        let model = solver.solve_under(&[
+            a.expr().ge(IntExpr::Const(4)),
+            b.expr().ge(IntExpr::Const(2)),
+        ]);
+        assert!(model.is_some());
+        let model = model.unwrap();
+        assert_eq!(model.get_int(a), 4);
+        assert_eq!(model.get_int(b), 2);
+    }
+    {
+        let model = solver.solve_under(&[
+            a.expr().ge(IntExpr::Const(4)),
+            b.expr().ge(IntExpr::Const(3)),
+        ]);
+        assert!(model.is_none());
+    }
+
+    solver.add_expr(a.expr().le(IntExpr::Const(3)));
+    {
+        let model = solver.solve_under(&[a.expr().ge(IntExpr::Const(4))]);
+        assert!(model.is_none());
+    }
+    {
+        let model = solver.solve_under(&[b.expr().ge(IntExpr::Const(3))]);
+        assert!(model.is_some());
+    }
+}
+
+#[test]
+fn test_integration_solve_under_reuses_indicators() {
+    let mut solver = IntegratedSolver::new();
+
+    let a = solver.new_int_var(Domain::range(0, 5));
+    let b = solver.new_int_var(Domain::range(0, 5));
+    solver.add_expr((a.expr() + b.expr()).eq(IntExpr::Const(6)));
+
+    let assumption = a.expr().ge(IntExpr::Const(4));
+    assert!(solver.solve_under(&[assumption.clone()]).is_some());
+    let num_bool_vars = solver.csp.num_bool_vars();
+
+    for _ in 0..3 {
+        assert!(solver.solve_under(&[assumption.clone()]).is_some());
+        assert!(solver
+            .solve_under(&[assumption.clone(), b.expr().ge(IntExpr::Const(3))])
+            .is_none());
+    }
+    assert_eq!(solver.csp.num_bool_vars(), num_bool_vars + 1);
+}
+
+#[test]
+fn test_integration_solve_under_fixed_var() {
+    let mut solver = IntegratedSolver::new();
+
+    let x = solver.new_bool_var();
+    let y = solver.new_bool_var();
+    solver.add_expr(x.expr());
+    solver.add_expr(x.expr().imp(y.expr()));
+
+    assert!(solver.solve_under(&[x.expr(), y.expr()]).is_some());
+    assert!(solver.solve_under(&[!y.expr()]).is_none());
+    assert!(solver.solve_under(&[]).is_some());
+}
+
+#[test]
+fn test_integration_minimize() {
+    let mut solver = IntegratedSolver::new();
+
+    let a = solver.new_int_var(Domain::range(0, 10));
+    let b = solver.new_int_var(Domain::range(0, 10));
+    solver.add_expr((a.expr() + b.expr()).ge(IntExpr::Const(7)));
+    solver.add_expr(a.expr().ne(b.expr()));
+
+    {
+        let (value, model) = solver.minimize(a.expr() * 2 + b.expr()).unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(model.get_int(a), 0);
+        assert_eq!(model.get_int(b), 7);
+    }
+    {
+        let (value, model) = solver.minimize(b.expr()).unwrap();
+        assert_eq!(value, 0);
+        assert_eq!(model.get_int(b), 0);
+        assert!(model.get_int(a) >= 7);
+    }
+    {
+        let (value, model) = solver.maximize(a.expr() - b.expr()).unwrap();
+        assert_eq!(value, 10);
+        assert_eq!(model.get_int(a), 10);
+        assert_eq!(model.get_int(b), 0);
+    }
+    {
+        let (value, model) = solver
+            .minimize_under(a.expr() + b.expr(), &[a.expr().ge(IntExpr::Const(4))])
+            .unwrap();
+        assert_eq!(value, 7);
+        assert!(model.get_int(a) >= 4);
+    }
+
+    // bounds used in the search must not remain
+    solver.add_expr(b.expr().eq(IntExpr::Const(10)));
+    assert!(solver.solve().is_some());
+}
+
+#[test]
+fn test_integration_minimize_unsat() {
+    let mut solver = IntegratedSolver::new();
+
+    let a = solver.new_int_var(Domain::range(0, 3));
+    let x = solver.new_bool_var();
+    solver.add_expr(x.expr().imp(a.expr().ge(IntExpr::Const(2))));
+    solver.add_expr(x.expr().imp(a.expr().le(IntExpr::Const(1))));
+
+    assert!(solver.maximize_under(a.expr(), &[x.expr()]).is_none());
+    let (value, _) = solver.maximize(a.expr()).unwrap();
+    assert_eq!(value, 3);
+}
+
+fn add_pigeonhole(solver: &mut IntegratedSolver, pigeons: usize, holes: usize) {
+    let vars = (0..pigeons)
+        .map(|_| {
+            (0..holes)
+                .map(|_| solver.new_bool_var())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    for row in &vars {
+        solver.add_expr(BoolExpr::Or(
+            row.iter().map(|v| Box::new(v.expr())).collect(),
+        ));
+    }
+    for h in 0..holes {
+        for i in 0..pigeons {
+            for j in (i + 1)..pigeons {
+                solver.add_expr(!(vars[i][h].expr() & vars[j][h].expr()));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_integration_resource_limit() {
+    take_resource_limit_exceeded();
+
+    let mut config = Config::default();
+    config.resource_limit.conflicts = Some(1);
+    let mut solver = IntegratedSolver::with_config(config);
+    add_pigeonhole(&mut solver, 7, 6);
+    assert!(solver.try_solve().is_unknown());
+    assert!(take_resource_limit_exceeded());
+    assert!(!take_resource_limit_exceeded());
+
+    // the budget is shared by all the solver calls
+    assert!(solver.try_solve().is_unknown());
+    assert!(take_resource_limit_exceeded());
+
+    let mut solver = IntegratedSolver::new();
+    add_pigeonhole(&mut solver, 7, 6);
+    assert_eq!(solver.try_solve().map(|_| ()), SolveOutcome::Unsat);
+    assert!(!take_resource_limit_exceeded());
+}
+
+#[test]
+fn test_integration_deadline_multi_solve() {
+    take_resource_limit_exceeded();
+
+    let mut config = Config::default();
+    config.resource_limit.deadline = Some(std::time::Instant::now());
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let a = solver.new_int_var(Domain::range(0, 5));
+    assert!(solver
+        .try_minimize_under(a.expr(), &[])
+        .map(|_| ())
+        .is_unknown());
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let x = solver.new_bool_var();
+    assert!(solver.try_decide_irrefutable_facts(&[x], &[]).is_unknown());
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let x = solver.new_bool_var();
+    let mut iter = solver.answer_iter(&[x], &[]);
+    assert!(iter.try_next().is_unknown());
+    assert!(take_resource_limit_exceeded());
+}
+
+#[cfg(not(feature = "wide-int"))]
+#[test]
+fn test_integration_overflow() {
+    let mut solver = IntegratedSolver::new();
+    let x = solver.new_int_var(Domain::range(-2000, 2000));
+    let y = solver.new_int_var(Domain::range(0, 2000));
+    let z = solver.new_int_var(Domain::range(-2000, 0));
+    solver.add_expr(x.expr().ge(y.expr()));
+    assert!(solver.try_solve().sat().is_some());
+
+    // overflows are detected without calling `check_overflow`
+    let xyz = IntExpr::Mul(
+        Box::new(IntExpr::Mul(Box::new(x.expr()), Box::new(y.expr()))),
+        Box::new(z.expr()),
+    );
+    solver.add_expr(xyz.ne(IntExpr::Const(1)));
+    let outcome = solver.try_solve().map(|_| ());
+    assert!(matches!(outcome, SolveOutcome::Overflow(_)));
+
+    // the problem is no longer solved after an overflow
+    assert_eq!(solver.try_solve().map(|_| ()), outcome);
+    assert!(matches!(
+        solver.try_encode(),
+        Err(e) if SolveOutcome::<()>::Overflow(e) == outcome
+    ));
+}
+
+#[cfg(feature = "portfolio")]
+#[test]
+fn test_integration_portfolio() {
+    let mut config = Config::default();
+    config.portfolio_size = 3;
+
+    let mut solver = IntegratedSolver::with_config(config);
+    add_pigeonhole(&mut solver, 6, 5);
+    assert!(solver.solve().is_none());
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let a = solver.new_int_var(Domain::range(0, 5));
+    let b = solver.new_int_var(Domain::range(0, 5));
+    let x = solver.new_bool_var();
+    solver.add_expr((a.expr() + b.expr()).eq(IntExpr::Const(5)));
+    solver.add_expr(x.expr().iff(a.expr().ge(IntExpr::Const(3))));
+    solver.add_expr(b.expr().ge(IntExpr::Const(3)));
+
+    // refutations added in `decide_irrefutable_facts` must reach all the solvers
+    let facts = solver.decide_irrefutable_facts(&[x], &[a, b]).unwrap();
+    assert_eq!(facts.get_bool(x), Some(false));
+    assert_eq!(facts.get_int(a), None);
+    assert_eq!(facts.get_int(b), None);
+}
+
+fn check_proof_pigeonhole(backend: crate::sat::Backend) {
+    let mut config = Config::default();
+    config.backend = backend;
+    config.produce_proof = true;
+
+    let mut solver = IntegratedSolver::with_config(config);
+    add_pigeonhole(&mut solver, 6, 5);
+    assert!(solver.solve().is_none());
+
+    let proof = solver.proof().unwrap();
+    assert_eq!(proof.check(), Ok(()));
+    // the formula is not refuted by unit propagation alone
+    assert!(crate::proof::check_drat(proof.num_vars(), &proof.formula(), "").is_err());
+}
+
+#[test]
+fn test_integration_proof_glucose() {
+    check_proof_pigeonhole(crate::sat::Backend::Glucose);
+}
+
+#[cfg(feature = "backend-cadical")]
+#[test]
+fn test_integration_proof_cadical() {
+    check_proof_pigeonhole(crate::sat::Backend::CaDiCaL);
+}
+
+fn check_proof_connectivity(backend: crate::sat::Backend) {
+    let mut config = Config::default();
+    config.backend = backend;
+    config.produce_proof = true;
+
+    // The opposite corners of the top row of a 2x4 grid cannot be connected with at most 3 cells.
+    let mut solver = IntegratedSolver::with_config(config);
+    let cells = (0..8).map(|_| solver.new_bool_var()).collect::<Vec<_>>();
+    let mut edges = vec![];
+    for y in 0..2 {
+        for x in 0..4 {
+            if x < 3 {
+                edges.push((y * 4 + x, y * 4 + x + 1));
+            }
+            if y < 1 {
+                edges.push((y * 4 + x, (y + 1) * 4 + x));
+            }
+        }
+    }
+    solver.add_constraint(Stmt::ActiveVerticesConnected(
+        cells.iter().map(|v| v.expr()).collect(),
+        edges,
+    ));
+    solver.add_expr(cells[0].expr());
+    solver.add_expr(cells[3].expr());
+    solver.add_expr(
+        IntExpr::Linear(
+            cells
+                .iter()
+                .map(|v| {
+                    (
+                        Box::new(v.expr().ite(IntExpr::Const(1), IntExpr::Const(0))),
+                        1,
+                    )
+                })
+                .collect(),
+        )
+        .le(IntExpr::Const(3)),
+    );
+    assert!(solver.solve().is_none());
+
+    let proof = solver.proof().unwrap();
+    assert!(!proof.theory_lemmas().is_empty());
+    assert_eq!(proof.check(), Ok(()));
+}
+
+#[test]
+fn test_integration_proof_connectivity_glucose() {
+    check_proof_connectivity(crate::sat::Backend::Glucose);
+}
+
+#[cfg(feature = "backend-cadical")]
+#[test]
+fn test_integration_proof_connectivity_cadical() {
+    check_proof_connectivity(crate::sat::Backend::CaDiCaL);
+}
+
+#[test]
+fn test_integration_proof_refuted_before_sat_solving() {
+    let mut config = Config::default();
+    config.produce_proof = true;
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let a = solver.new_int_var(Domain::range(0, 2));
+    solver.add_expr(a.expr().ge(IntExpr::Const(3)));
+    assert!(solver.solve().is_none());
+    assert!(solver.proof().is_err());
+}
+
+#[test]
+fn test_integration_irrefutable_facts_with_proof() {
+    let mut config = Config::default();
+    config.produce_proof = true;
+
+    let mut solver = IntegratedSolver::with_config(config);
+    let a = solver.new_int_var(Domain::range(0, 2));
+    let b = solver.new_int_var(Domain::range(0, 2));
+    let x = solver.new_bool_var();
+    solver.add_expr((a.expr() + b.expr()).eq(IntExpr::Const(3)));
+    solver.add_expr(x.expr().iff(a.expr().ge(IntExpr::Const(1))));
+
+    let (facts, proof) = solver
+        .decide_irrefutable_facts_with_proof(&[x], &[a, b])
+        .unwrap();
+    assert_eq!(facts.get_bool(x), Some(true));
+    assert_eq!(facts.get_int(a), None);
+    assert_eq!(facts.get_int(b), None);
+    assert_eq!(proof.unwrap().check(), Ok(()));
+}
+
+#[test]
+fn test_integration_export_cnf() {
+    use crate::export::BoolVarMapping;
+
+    let mut solver = IntegratedSolver::new();
+    let a = solver.new_int_var(Domain::range(0, 3));
+    let b = solver.new_int_var(Domain::range(0, 3));
+    let x = solver.new_bool_var();
+    let y = solver.new_bool_var();
+    let unused = solver.new_bool_var();
+    solver.add_expr((a.expr() + b.expr()).eq(IntExpr::Const(3)));
+    solver.add_expr(x.expr().iff(a.expr().ge(IntExpr::Const(2))));
+    solver.add_expr(y.expr());
+
+    let mut dimacs = vec![];
+    let map = solver.export_cnf(&mut dimacs).unwrap();
+    assert_eq!(map.bool_var(y), &BoolVarMapping::Fixed(true));
+    assert_eq!(map.bool_var(unused), &BoolVarMapping::Unused);
+    let mut json = vec![];
+    map.write_json(&mut json).unwrap();
+    assert!(String::from_utf8(json)
+        .unwrap()
+        .contains("\"bool_vars\": ["));
+
+    // Load the CNF into another solver and check that it agrees with the constraints.
+    let mut cnf_solver = IntegratedSolver::new();
+    let cnf_vars = (0..map.num_vars)
+        .map(|_| cnf_solver.new_bool_var())
+        .collect::<Vec<_>>();
+    let lit = |n: i32, val: bool| {
+        let e = cnf_vars[n.unsigned_abs() as usize - 1].expr();
+        if (n > 0) == val {
+            e
+        } else {
+            !e
+        }
+    };
+    let dimacs = String::from_utf8(dimacs).unwrap();
+    let mut lines = dimacs.lines();
+    assert_eq!(
+        lines.next(),
+        Some(format!("p cnf {} {}", map.num_vars, dimacs.lines().count() - 1).as_str())
+    );
+    for line in lines {
+        let clause = line
+            .split(' ')
+            .map(|t| t.parse::<i32>().unwrap())
+            .take_while(|&n| n != 0)
+            .map(|n| Box::new(lit(n, true)))
+            .collect();
+        cnf_solver.add_expr(BoolExpr::Or(clause));
+    }
+
+    for va in 0..=3 {
+        for vb in 0..=3 {
+            for vx in [false, true] {
+                let mut assumptions = vec![];
+                match map.bool_var(x) {
+                    &BoolVarMapping::Lit(l) => assumptions.push(lit(l, vx)),
+                    _ => panic!(),
+                }
+                for (var, val) in [(a, va), (b, vb)] {
+                    let mapping = map.int_var(var);
+                    assert!(mapping.lower_bound <= val);
+                    for &(v, l) in &mapping.order {
+                        assumptions.push(lit(l, val >= v));
+                    }
+                    for &(v, l) in &mapping.direct {
+                        assumptions.push(lit(l, val == v));
+                    }
+                }
+                let expected = va + vb == 3 && vx == (va >= 2);
+                assert_eq!(
+                    cnf_solver.solve_under(&assumptions).is_some(),
+                    expected,
+                    "a = {}, b = {}, x = {}",
+                    va,
+                    vb,
+                    vx
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_integration_export_cnf_unsupported() {
+    let mut solver = IntegratedSolver::new();
+    let xs = (0..2)
+        .map(|_| solver.new_bool_var().expr())
+        .collect::<Vec<_>>();
+    solver.add_constraint(Stmt::GraphDivision(
+        vec![None; 3],
+        vec![(0, 1), (1, 2)],
+        xs,
+        GraphDivisionOptions {
+            allow_extra_walls: true,
+            ..Default::default()
+        },
+    ));
+
+    let mut dimacs = vec![];
+    assert!(matches!(
+        solver.export_cnf(&mut dimacs),
+        Err(crate::export::ExportError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_integration_export_cnf_after_solve() {
+    let mut solver = IntegratedSolver::new();
+    let x = solver.new_bool_var();
+    solver.add_expr(x.expr());
+    assert!(solver.solve().is_some());
+
+    let mut dimacs = vec![];
+    assert!(matches!(
+        solver.export_cnf(&mut dimacs),
+        Err(crate::export::ExportError::AlreadySolved)
+    ));
+}
+
+#[cfg(all(feature = "backend-external", unix))]
+#[test]
+fn test_integration_external_unknown_without_limit() {
+    use crate::sat::{Backend, BackendConfig, ExternalSolverConfig};
+
+    let mut config = Config::default();
+    config.backend = Backend::External;
+    let backend_config = BackendConfig {
+        external_solver: ExternalSolverConfig {
+            command: Some(String::from("sh")),
+            args: vec![
+                String::from("-c"),
+                String::from("cat > /dev/null; echo 's UNKNOWN'"),
+            ],
+            use_input_file: false,
+        },
+        ipasir_library: None,
+    };
+    let mut solver = IntegratedSolver::with_backend_config(config, &backend_config);
+    let x = solver.new_bool_var();
+    let y = solver.new_bool_var();
+    solver.add_expr(x.expr() | y.expr());
+
+    take_sat_solver_error();
+    assert!(matches!(solver.try_solve(), SolveOutcome::Unknown));
+    // The solver gave up by itself, which is reported as a failure rather than a timeout.
+    assert!(take_sat_solver_error().is_some());
+}
+
 #[test]
 fn test_integration_bool_lit_after_decomposition() {
     let mut config = Config::default();
@@ -906,6 +1465,78 @@ fn test_integration_exhaustive_alldifferent() {
     tester.check();
 }
 
+#[cfg(feature = "csp-extra-constraints")]
+#[test]
+fn test_integration_exhaustive_alldifferent_native() {
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_all_different = use_native;
+        let mut tester = IntegrationTester::with_config(config);
+
+        let a = tester.new_int_var(Domain::range(0, 3));
+        let b = tester.new_int_var(Domain::range(1, 2));
+        let c = tester.new_int_var(Domain::range(1, 3));
+        let d = tester.new_int_var_from_list(vec![0, 2, 4]);
+        tester.add_constraint(Stmt::AllDifferent(vec![
+            a.expr(),
+            b.expr(),
+            c.expr(),
+            d.expr(),
+        ]));
+        // makes `a`, `b` and `c` order-encoded
+        tester.add_expr((a.expr() + b.expr() + c.expr()).ge(IntExpr::Const(5)));
+
+        tester.check();
+    }
+}
+
+#[cfg(feature = "csp-extra-constraints")]
+#[test]
+fn test_integration_exhaustive_alldifferent_native_hall_interval() {
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_all_different = use_native;
+        let mut tester = IntegrationTester::with_config(config);
+
+        let x = tester.new_bool_var();
+        let a = tester.new_int_var(Domain::range(0, 4));
+        let b = tester.new_int_var(Domain::range(0, 4));
+        let c = tester.new_int_var(Domain::range(0, 4));
+        let d = tester.new_int_var(Domain::range(0, 4));
+        tester.add_constraint(Stmt::AllDifferent(vec![
+            a.expr(),
+            b.expr(),
+            c.expr(),
+            d.expr(),
+        ]));
+        tester.add_expr(x.expr().imp(a.expr().le(IntExpr::Const(1))));
+        tester.add_expr(x.expr().imp(b.expr().le(IntExpr::Const(1))));
+        tester.add_expr(c.expr().ne(IntExpr::Const(3)) | d.expr().ne(IntExpr::Const(4)));
+
+        tester.check();
+    }
+}
+
+#[cfg(feature = "csp-extra-constraints")]
+#[test]
+fn test_integration_alldifferent_native_infeasible() {
+    let mut config = Config::default();
+    config.use_native_all_different = true;
+    let mut solver = IntegratedSolver::with_config(config);
+
+    // The native propagator finds the conflict when it is added to the SAT solver.
+    let a = solver.new_int_var(Domain::range(0, 1));
+    let b = solver.new_int_var(Domain::range(0, 1));
+    let c = solver.new_int_var(Domain::range(0, 1));
+    solver.add_constraint(Stmt::AllDifferent(vec![a.expr(), b.expr(), c.expr()]));
+    assert!(solver.solve().is_none());
+
+    // The problem stays unsatisfiable after more constraints are added.
+    let x = solver.new_bool_var();
+    solver.add_expr(x.expr());
+    assert!(solver.solve().is_none());
+}
+
 #[test]
 fn test_integration_domain_list1() {
     let mut tester = IntegrationTester::new();
@@ -968,38 +1599,46 @@ fn test_integration_many_terms_not_equal() {
 #[cfg(feature = "csp-extra-constraints")]
 #[test]
 fn test_integration_exhaustive_circuit1() {
-    let mut tester = IntegrationTester::new();
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_single_cycle = use_native;
+        let mut tester = IntegrationTester::with_config(config);
 
-    let a = tester.new_int_var(Domain::range(0, 5));
-    let b = tester.new_int_var(Domain::range(-1, 3));
-    let c = tester.new_int_var(Domain::range(0, 3));
-    let d = tester.new_int_var(Domain::range(1, 3));
+        let a = tester.new_int_var(Domain::range(0, 5));
+        let b = tester.new_int_var(Domain::range(-1, 3));
+        let c = tester.new_int_var(Domain::range(0, 3));
+        let d = tester.new_int_var(Domain::range(1, 3));
 
-    tester.add_constraint(Stmt::Circuit(vec![a.expr(), b.expr(), c.expr(), d.expr()]));
+        tester.add_constraint(Stmt::Circuit(vec![a.expr(), b.expr(), c.expr(), d.expr()]));
 
-    tester.check();
+        tester.check();
+    }
 }
 
 #[cfg(feature = "csp-extra-constraints")]
 #[test]
 fn test_integration_exhaustive_circuit2() {
-    let mut tester = IntegrationTester::new();
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_single_cycle = use_native;
+        let mut tester = IntegrationTester::with_config(config);
 
-    let a = tester.new_int_var_from_list(vec![1, 2, 3, 4]);
-    let b = tester.new_int_var_from_list(vec![0, 2, 3, 4]);
-    let c = tester.new_int_var_from_list(vec![0, 1, 4]);
-    let d = tester.new_int_var_from_list(vec![0, 2, 4]);
-    let e = tester.new_int_var_from_list(vec![0, 1, 2, 3]);
+        let a = tester.new_int_var_from_list(vec![1, 2, 3, 4]);
+        let b = tester.new_int_var_from_list(vec![0, 2, 3, 4]);
+        let c = tester.new_int_var_from_list(vec![0, 1, 4]);
+        let d = tester.new_int_var_from_list(vec![0, 2, 4]);
+        let e = tester.new_int_var_from_list(vec![0, 1, 2, 3]);
 
-    tester.add_constraint(Stmt::Circuit(vec![
-        a.expr(),
-        b.expr(),
-        c.expr(),
-        d.expr(),
-        e.expr(),
-    ]));
+        tester.add_constraint(Stmt::Circuit(vec![
+            a.expr(),
+            b.expr(),
+            c.expr(),
+            d.expr(),
+            e.expr(),
+        ]));
 
-    tester.check();
+        tester.check();
+    }
 }
 
 #[cfg(feature = "csp-extra-constraints")]
@@ -1043,6 +1682,40 @@ fn test_integration_exhaustive_extension_supports1() {
     }
 }
 
+#[test]
+fn test_integration_exhaustive_regular() {
+    // sequences over {0, 1, 2} in which no two adjacent values are equal and the last value is 2
+    let mut dfa = Dfa::new(4, 3);
+    for s in 0..4 {
+        for c in 0..3 {
+            if s != c as usize {
+                dfa.add_transition(s, c, c as usize);
+            }
+        }
+    }
+    dfa.set_accepting(2, true);
+
+    let mut tester = IntegrationTester::new();
+
+    let a = tester.new_int_var(Domain::range(0, 2));
+    let b = tester.new_int_var(Domain::range(-1, 2));
+    let c = tester.new_int_var(Domain::range(0, 3));
+    let d = tester.new_int_var(Domain::range(0, 2));
+    let x = tester.new_bool_var();
+
+    tester.add_constraint(Stmt::Regular(
+        vec![
+            a.expr(),
+            b.expr(),
+            x.expr().ite(c.expr(), d.expr()),
+            d.expr(),
+        ],
+        dfa,
+    ));
+
+    tester.check();
+}
+
 #[test]
 fn test_integration_active_vertices_connected1() {
     let mut tester = IntegrationTester::new();
@@ -1109,6 +1782,181 @@ fn test_integration_active_vertices_connected2() {
     }
 }
 
+#[test]
+fn test_integration_exhaustive_active_edges_single_cycle1() {
+    // 3x3 grid graph
+    let edges = vec![
+        (0, 1),
+        (1, 2),
+        (3, 4),
+        (4, 5),
+        (6, 7),
+        (7, 8),
+        (0, 3),
+        (1, 4),
+        (2, 5),
+        (3, 6),
+        (4, 7),
+        (5, 8),
+    ];
+
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_single_cycle = use_native;
+        let mut tester = IntegrationTester::with_config(config);
+        let vars = (0..edges.len())
+            .map(|_| tester.new_bool_var().expr())
+            .collect::<Vec<_>>();
+        tester.add_constraint(Stmt::ActiveEdgesSingleCycle(vars, edges.clone()));
+
+        tester.check();
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_active_edges_single_cycle2() {
+    // two triangles sharing vertex 2, with parallel edges between 3 and 4
+    let edges = vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (3, 4)];
+
+    for use_native in [false, true] {
+        let mut config = Config::default();
+        config.use_native_single_cycle = use_native;
+        let mut tester = IntegrationTester::with_config(config);
+        let x = tester.new_bool_var();
+        let mut vars = (0..edges.len())
+            .map(|_| tester.new_bool_var().expr())
+            .collect::<Vec<_>>();
+        vars[1] = x.expr();
+        vars[5] = !x.expr();
+        tester.add_constraint(Stmt::ActiveEdgesSingleCycle(vars, edges.clone()));
+
+        tester.check();
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_cardinality() {
+    for op in [
+        CmpOp::Eq,
+        CmpOp::Le,
+        CmpOp::Lt,
+        CmpOp::Ge,
+        CmpOp::Gt,
+        CmpOp::Ne,
+    ] {
+        for use_pseudo_boolean_encoding in [false, true] {
+            let mut config = Config::default();
+            config.use_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+            config.force_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+            let mut tester = IntegrationTester::with_config(config);
+
+            let a = tester.new_int_var(Domain::range(0, 3));
+            let mut exprs = vec![];
+            for _ in 0..4 {
+                exprs.push(tester.new_bool_var().expr());
+            }
+            exprs.push(!exprs[0].clone());
+            exprs.push(exprs[1].clone());
+            exprs.push(a.expr().ge(IntExpr::Const(2)));
+            tester.add_constraint(Stmt::Cardinality(exprs, op, 3));
+
+            tester.check();
+        }
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_pseudo_boolean() {
+    for op in [CmpOp::Eq, CmpOp::Le, CmpOp::Lt, CmpOp::Ge, CmpOp::Gt] {
+        for rhs in [-2, 1, 3] {
+            for use_pseudo_boolean_encoding in [false, true] {
+                let mut config = Config::default();
+                config.use_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+                config.force_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+                let mut tester = IntegrationTester::with_config(config);
+
+                let x = tester.new_bool_var();
+                let mut terms = vec![];
+                for coef in [3, -2, 2, 1] {
+                    terms.push((tester.new_bool_var().expr(), coef));
+                }
+                terms.push((!x.expr(), -1));
+                terms.push((x.expr(), 2));
+                tester.add_constraint(Stmt::PseudoBoolean(terms, op, rhs));
+
+                tester.check();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_count_true() {
+    for use_pseudo_boolean_encoding in [false, true] {
+        let mut config = Config::default();
+        config.use_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+        config.force_pseudo_boolean_encoding = use_pseudo_boolean_encoding;
+        let mut tester = IntegrationTester::with_config(config);
+
+        let vars = (0..6)
+            .map(|_| tester.new_bool_var().expr())
+            .collect::<Vec<_>>();
+        let count = IntExpr::Linear(
+            vars.iter()
+                .map(|v| {
+                    (
+                        Box::new(v.clone().ite(IntExpr::Const(1), IntExpr::Const(0))),
+                        1,
+                    )
+                })
+                .collect(),
+        );
+        tester.add_expr(count.eq(IntExpr::Const(2)));
+        tester.add_expr(
+            (vars[0].clone().ite(IntExpr::Const(2), IntExpr::Const(-1))
+                + vars[2].clone().ite(IntExpr::Const(1), IntExpr::Const(0))
+                + vars[3].clone().ite(IntExpr::Const(0), IntExpr::Const(1))
+                + vars[4].clone().ite(IntExpr::Const(3), IntExpr::Const(0)))
+            .le(IntExpr::Const(2)),
+        );
+
+        tester.check();
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_lex_less_eq() {
+    for (n, m) in [(3, 3), (3, 2), (1, 2)] {
+        let mut tester = IntegrationTester::new();
+
+        let x = tester.new_bool_var();
+        let mut lhs = vec![];
+        for _ in 0..n {
+            lhs.push(tester.new_int_var(Domain::range(0, 2)).expr());
+        }
+        let mut rhs = vec![];
+        for _ in 0..m {
+            rhs.push(tester.new_int_var(Domain::range(0, 2)).expr());
+        }
+        rhs[0] = x.expr().ite(rhs[0].clone(), IntExpr::Const(1));
+        tester.add_constraint(Stmt::LexLessEq(lhs, rhs));
+
+        tester.check();
+    }
+}
+
+#[test]
+fn test_integration_exhaustive_value_precede_chain() {
+    let mut tester = IntegrationTester::new();
+
+    let exprs = (0..5)
+        .map(|_| tester.new_int_var(Domain::range(0, 3)).expr())
+        .collect::<Vec<_>>();
+    tester.add_constraint(Stmt::ValuePrecedeChain(exprs, vec![0, 2, 1]));
+
+    tester.check();
+}
+
 #[test]
 fn test_integration_graph_division1() {
     let mut tester = IntegrationTester::new();
@@ -1213,4 +2061,88 @@ fn test_integration_binary_var2() {
     tester.check();
 }
 
+#[cfg(feature = "backend-cadical")]
+#[test]
+fn test_integration_cadical_native_constraints() {
+    // These constraints are handled by custom propagators in the CaDiCaL backend.
+    let mut config = Config::default();
+    config.backend = crate::sat::Backend::CaDiCaL;
+    config.native_linear_encoding_terms = 4;
+    config.native_linear_encoding_domain_product_threshold = 1;
+    config.use_native_extension_supports = true;
+
+    {
+        let mut tester = IntegrationTester::with_config(config);
+        let a = tester.new_int_var(Domain::range(0, 3));
+        let b = tester.new_int_var(Domain::range(-1, 4));
+        let c = tester.new_int_var(Domain::range(1, 3));
+        tester.add_expr(
+            (a.expr() * 2 + b.expr() - c.expr() * 3)
+                .ge(IntExpr::Const(1))
+                .imp(a.expr().ne(b.expr())),
+        );
+        tester.add_expr((a.expr() + b.expr() + c.expr()).le(IntExpr::Const(5)));
+        tester.check();
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    {
+        let mut tester = IntegrationTester::with_config(config);
+        let a = tester.new_int_var_from_list(vec![0, 2, 3]);
+        let b = tester.new_int_var(Domain::range(0, 3));
+        let c = tester.new_int_var(Domain::range(0, 2));
+        tester.add_constraint(Stmt::ExtensionSupports(
+            vec![a.expr(), b.expr(), c.expr()],
+            vec![
+                vec![Some(0), Some(0), Some(1)],
+                vec![Some(0), None, Some(2)],
+                vec![Some(2), Some(1), None],
+                vec![Some(3), Some(3), Some(0)],
+                vec![Some(1), Some(2), Some(2)],
+            ],
+        ));
+        tester.check();
+    }
+
+    {
+        let mut tester = IntegrationTester::with_config(config);
+        let mut vars = vec![];
+        for _ in 0..12 {
+            vars.push(tester.new_bool_var().expr());
+        }
+        let a = tester.new_int_var(Domain::range(2, 3));
+        let b = tester.new_int_var(Domain::range(3, 5));
+        tester.add_constraint(Stmt::GraphDivision(
+            vec![
+                Some(a.expr()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(b.expr()),
+            ],
+            vec![
+                (0, 1),
+                (1, 2),
+                (3, 4),
+                (4, 5),
+                (6, 7),
+                (7, 8),
+                (0, 3),
+                (1, 4),
+                (2, 5),
+                (3, 6),
+                (4, 7),
+                (5, 8),
+            ],
+            vars,
+            GraphDivisionOptions::default(),
+        ));
+        tester.check();
+    }
+}
+
 mod fuzz;
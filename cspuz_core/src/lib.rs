@@ -2,18 +2,24 @@ pub mod arithmetic;
 pub mod config;
 pub mod csp;
 pub mod custom_constraints;
+pub mod dfa;
 pub mod domain;
 
 pub mod backend;
 pub mod encoder;
+pub mod export;
 pub mod integration;
 pub mod norm_csp;
 pub mod normalizer;
+pub mod proof;
 pub mod propagators;
 
 #[cfg(feature = "parser")]
 pub mod csugar_cli;
 
+#[cfg(feature = "parser")]
+pub mod flatzinc;
+
 #[cfg(feature = "parser")]
 pub mod parser;
 
@@ -8,7 +8,7 @@ use std::ops::Not;
 
 use super::domain::Domain;
 use crate::arithmetic::{CheckedInt, CmpOp, Range};
-use crate::custom_constraints::PropagatorGenerator;
+use crate::custom_constraints::{PropagatorGenerator, SimpleCustomIntConstraint};
 use crate::propagators::graph_division::GraphDivisionOptions;
 use crate::util::{ConvertMapIndex, UpdateStatus};
 
@@ -135,10 +135,10 @@ impl Constraint {
                 if i > 0 {
                     write!(out, "+")?;
                 }
-                write!(out, "<ni{}>*{}", var.id(), coef.get())?;
+                write!(out, "<ni{}>*{}", var.id(), coef.get_repr())?;
             }
 
-            write!(out, "+{}{}0", lit.sum.constant.get(), lit.op)?;
+            write!(out, "+{}{}0", lit.sum.constant.get_repr(), lit.op)?;
         }
         write!(out, "]")?;
         Ok(())
@@ -266,7 +266,7 @@ impl NormCSPVars {
                     v_false,
                     v_true,
                 } => {
-                    let dom = Domain::range(v_false.get(), v_true.get());
+                    let dom = Domain::range_from_checked(*v_false, *v_true);
                     ret = ret + dom * *coef;
                 }
             }
@@ -367,8 +367,15 @@ impl NormCSPVars {
 
 pub enum ExtraConstraint {
     ActiveVerticesConnected(Vec<BoolLit>, Vec<(usize, usize)>),
+    /// The active edges are connected and each vertex is incident to at most two active edges
+    /// (constraints excluding vertices incident to exactly one active edge are added separately).
+    ActiveEdgesSingleCycle(Vec<BoolLit>, Vec<(usize, usize)>),
     Mul(IntVar, IntVar, IntVar),
     ExtensionSupports(Vec<IntVar>, Vec<Vec<Option<CheckedInt>>>),
+    AllDifferent(Vec<IntVar>),
+    /// The sum of the coefficients of the true literals satisfies `op` (which is not `Ne`) against
+    /// the constant.
+    PseudoBoolean(Vec<(BoolLit, CheckedInt)>, CmpOp, CheckedInt),
     GraphDivision(
         Vec<Option<IntVar>>,
         Vec<(usize, usize)>,
@@ -376,6 +383,7 @@ pub enum ExtraConstraint {
         GraphDivisionOptions,
     ),
     CustomConstraint(Vec<BoolLit>, Box<dyn PropagatorGenerator>),
+    CustomIntConstraint(Vec<IntVar>, Box<dyn SimpleCustomIntConstraint>),
 }
 
 pub struct NormCSP {
@@ -383,7 +391,7 @@ pub struct NormCSP {
     pub(super) constraints: Vec<Constraint>,
     pub(super) extra_constraints: Vec<ExtraConstraint>,
     pub(super) num_encoded_vars: usize,
-    inconsistent: bool,
+    pub(super) inconsistent: bool,
     pub(super) prenormalize_vars: Vec<BoolVar>,
 }
 
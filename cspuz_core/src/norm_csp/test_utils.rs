@@ -18,6 +18,17 @@ pub fn is_norm_csp_satisfied(assignment: &Assignment, norm_csp: &NormCSP) -> boo
                     return false;
                 }
             }
+            ExtraConstraint::ActiveEdgesSingleCycle(is_active, edges) => {
+                let is_active = is_active
+                    .iter()
+                    .map(|&v| assignment.get_bool(v.var).unwrap() ^ v.negated)
+                    .collect::<Vec<_>>();
+                if !crate::test_utils::check_graph_active_edges_single_path_or_cycle(
+                    &is_active, edges,
+                ) {
+                    return false;
+                }
+            }
             &ExtraConstraint::Mul(x, y, m) => {
                 let val_x = assignment.get_int(x).unwrap();
                 let val_y = assignment.get_int(y).unwrap();
@@ -51,8 +62,29 @@ pub fn is_norm_csp_satisfied(assignment: &Assignment, norm_csp: &NormCSP) -> boo
                     return false;
                 }
             }
+            ExtraConstraint::AllDifferent(vars) => {
+                let mut values = vars
+                    .iter()
+                    .map(|&v| assignment.get_int(v).unwrap())
+                    .collect::<Vec<_>>();
+                values.sort();
+                if values.windows(2).any(|w| w[0] == w[1]) {
+                    return false;
+                }
+            }
+            ExtraConstraint::PseudoBoolean(terms, op, rhs) => {
+                let sum = terms
+                    .iter()
+                    .filter(|(l, _)| assignment.get_bool(l.var).unwrap() ^ l.negated)
+                    .map(|&(_, coef)| coef)
+                    .fold(CheckedInt::new(0), |a, b| a + b);
+                if !op.compare(sum, *rhs) {
+                    return false;
+                }
+            }
             ExtraConstraint::GraphDivision(_, _, _, _) => todo!(),
             ExtraConstraint::CustomConstraint(_, _) => todo!(),
+            ExtraConstraint::CustomIntConstraint(_, _) => todo!(),
         }
     }
     true
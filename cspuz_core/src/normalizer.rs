@@ -3,8 +3,10 @@ use super::csp::{BoolExpr, BoolVar, BoolVarStatus, CSPVars, IntExpr, IntVar, Stm
 use super::norm_csp::BoolLit as NBoolLit;
 use super::norm_csp::IntVar as NIntVar;
 use super::norm_csp::{Constraint, ExtraConstraint, LinearLit, LinearSum, NormCSP};
-use crate::arithmetic::{CheckedInt, CmpOp};
+use crate::arithmetic::{CheckedInt, CmpOp, OverflowError, Range};
+use crate::dfa::Dfa;
 use crate::domain::Domain;
+use crate::encoder::is_pseudo_boolean_encoding_smaller;
 use crate::norm_csp::IntVarRepresentation;
 use crate::util::ConvertMap;
 
@@ -138,6 +140,8 @@ struct NormalizerEnv<'a, 'b, 'c, 'd> {
     norm: &'b mut NormCSP,
     map: &'c mut NormalizeMap,
     config: &'d Config,
+    /// The first overflow found during the normalization.
+    overflow: Option<OverflowError>,
 }
 
 impl NormalizerEnv<'_, '_, '_, '_> {
@@ -151,12 +155,22 @@ impl NormalizerEnv<'_, '_, '_, '_> {
 }
 
 /// Normalize constraints in `csp`. Existing constraints in `csp` are cleared.
-pub fn normalize(csp: &mut CSP, norm: &mut NormCSP, map: &mut NormalizeMap, config: &Config) {
+/// Normalizes `csp` into `norm`.
+///
+/// Returns an `OverflowError` if some value which must be given as `i32` does not fit in `i32`
+/// (possible only with the `wide-int` feature). In this case, `norm` is incomplete.
+pub fn normalize(
+    csp: &mut CSP,
+    norm: &mut NormCSP,
+    map: &mut NormalizeMap,
+    config: &Config,
+) -> Result<(), OverflowError> {
     let mut env = NormalizerEnv {
         csp_vars: &mut csp.vars,
         norm,
         map,
         config,
+        overflow: None,
     };
 
     if config.merge_equivalent_variables {
@@ -212,6 +226,10 @@ pub fn normalize(csp: &mut CSP, norm: &mut NormCSP, map: &mut NormalizeMap, conf
     }
 
     for &var in &csp.prenormalize_vars {
+        if let ConvertedBoolVar::Removed = env.map.bool_map[var] {
+            // `var` is fixed by constant propagation and no longer needs to be normalized
+            continue;
+        }
         let var = env.convert_bool_var(var).var;
         env.norm.add_prenormalize_var(var);
     }
@@ -221,7 +239,11 @@ pub fn normalize(csp: &mut CSP, norm: &mut NormCSP, map: &mut NormalizeMap, conf
 
     for stmt in stmts {
         normalize_stmt(&mut env, stmt);
+        if let Some(e) = env.overflow {
+            return Err(e);
+        }
     }
+    Ok(())
 }
 
 fn equivalent_bool_lit(env: &mut NormalizerEnv, expr: BoolExpr) -> NBoolLit {
@@ -260,6 +282,33 @@ fn equivalent_int_var(env: &mut NormalizerEnv, expr: &IntExpr) -> NIntVar {
     }
 }
 
+/// Returns an `IntExpr` equivalent to `sum` which is either `Const` or `NVar`.
+fn linear_sum_to_atom(env: &mut NormalizerEnv, sum: LinearSum) -> IntExpr {
+    if sum.is_constant() {
+        if let Some(c) = sum.constant.to_i32() {
+            return IntExpr::Const(c);
+        }
+    }
+    if let Some(&v) = sum.as_singleton() {
+        return IntExpr::NVar(v);
+    }
+    let dom = env.norm.get_domain_linear_sum(&sum);
+    let v = env.norm.new_int_var(dom);
+    let mut c = Constraint::new();
+    c.add_linear(LinearLit::new(sum - LinearSum::singleton(v), CmpOp::Eq));
+    env.norm.add_constraint(c);
+    IntExpr::NVar(v)
+}
+
+fn linear_sum_range(env: &NormalizerEnv, sum: &LinearSum) -> Range {
+    let dom = env.norm.get_domain_linear_sum(sum);
+    if dom.is_empty() {
+        Range::empty()
+    } else {
+        Range::new(dom.lower_bound_checked(), dom.upper_bound_checked())
+    }
+}
+
 fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
     if env.config.verbose {
         let mut buf = Vec::<u8>::new();
@@ -270,12 +319,28 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
     let num_constrs_before_norm = env.norm.constraints.len();
 
     match stmt {
-        Stmt::Expr(expr) => normalize_and_register_expr(env, expr),
+        Stmt::Expr(expr) => match extract_pseudo_boolean(&expr) {
+            Some((terms, op, rhs))
+                if is_pseudo_boolean_encoding_applicable(env, &terms, op, rhs) =>
+            {
+                normalize_pseudo_boolean(env, terms, op, rhs)
+            }
+            _ => normalize_and_register_expr(env, expr),
+        },
         Stmt::AllDifferent(_exprs) => {
-            for i in 0.._exprs.len() {
-                for j in (i + 1).._exprs.len() {
-                    let diff_expr = _exprs[i].clone().ne(_exprs[j].clone());
-                    normalize_and_register_expr(env, diff_expr);
+            if cfg!(feature = "csp-extra-constraints") && env.config.use_native_all_different {
+                let vars = _exprs
+                    .iter()
+                    .map(|e| equivalent_int_var(env, e))
+                    .collect::<Vec<_>>();
+                env.norm
+                    .add_extra_constraint(ExtraConstraint::AllDifferent(vars));
+            } else {
+                for i in 0.._exprs.len() {
+                    for j in (i + 1).._exprs.len() {
+                        let diff_expr = _exprs[i].clone().ne(_exprs[j].clone());
+                        normalize_and_register_expr(env, diff_expr);
+                    }
                 }
             }
             let is_all_var = _exprs.iter().all(|e| matches!(e, IntExpr::Var(_)));
@@ -299,14 +364,15 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
                 if isok {
                     let domain = domain.unwrap();
                     if domain.len() == _exprs.len() {
-                        for &value in &domain {
+                        // the values of CSP variables always fit in `i32`
+                        for value in domain.iter().filter_map(|x| x.to_i32()) {
                             let e = _exprs
                                 .iter()
                                 .map(|e| {
                                     Box::new(BoolExpr::Cmp(
                                         CmpOp::Eq,
                                         Box::new(e.clone()),
-                                        Box::new(IntExpr::Const(value.get())),
+                                        Box::new(IntExpr::Const(value)),
                                     ))
                                 })
                                 .collect::<Vec<_>>();
@@ -327,6 +393,13 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
                     edges,
                 ));
         }
+        Stmt::ActiveEdgesSingleCycle(edge_exprs, edges) => {
+            let edge_lits = edge_exprs
+                .into_iter()
+                .map(|e| equivalent_bool_lit(env, e))
+                .collect::<Vec<_>>();
+            normalize_active_edges_single_cycle(env, edge_lits, edges);
+        }
         Stmt::Circuit(exprs) => {
             let exprs_converted = exprs
                 .into_iter()
@@ -334,6 +407,38 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
                 .collect::<Vec<_>>();
             normalize_circuit(env, exprs_converted)
         }
+        Stmt::Cardinality(exprs, op, rhs) => {
+            let terms = exprs
+                .into_iter()
+                .map(|e| (e, CheckedInt::new(1)))
+                .collect::<Vec<_>>();
+            normalize_pseudo_boolean(env, terms, op, CheckedInt::new(rhs))
+        }
+        Stmt::PseudoBoolean(terms, op, rhs) => {
+            let terms = terms
+                .into_iter()
+                .map(|(e, coef)| (e, CheckedInt::new(coef)))
+                .collect::<Vec<_>>();
+            normalize_pseudo_boolean(env, terms, op, CheckedInt::new(rhs))
+        }
+        Stmt::LexLessEq(lhs, rhs) => {
+            let lhs_converted = lhs
+                .into_iter()
+                .map(|e| equivalent_int_var(env, &e))
+                .collect::<Vec<_>>();
+            let rhs_converted = rhs
+                .into_iter()
+                .map(|e| equivalent_int_var(env, &e))
+                .collect::<Vec<_>>();
+            normalize_lex_less_eq(env, lhs_converted, rhs_converted)
+        }
+        Stmt::ValuePrecedeChain(exprs, values) => {
+            let exprs_converted = exprs
+                .into_iter()
+                .map(|e| equivalent_int_var(env, &e))
+                .collect::<Vec<_>>();
+            normalize_value_precede_chain(env, exprs_converted, &values)
+        }
         Stmt::ExtensionSupports(exprs, supports) => {
             let exprs_converted = exprs
                 .into_iter()
@@ -341,6 +446,13 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
                 .collect::<Vec<_>>();
             normalize_extension_supports(env, exprs_converted, supports)
         }
+        Stmt::Regular(exprs, dfa) => {
+            let exprs_converted = exprs
+                .into_iter()
+                .map(|e| equivalent_int_var(env, &e))
+                .collect::<Vec<_>>();
+            normalize_regular(env, exprs_converted, &dfa)
+        }
         Stmt::GraphDivision(sizes, edges, edge_lits, opts) => {
             let sizes = sizes
                 .into_iter()
@@ -367,6 +479,14 @@ fn normalize_stmt(env: &mut NormalizerEnv, stmt: Stmt) {
             env.norm
                 .add_extra_constraint(ExtraConstraint::CustomConstraint(inputs_as_lit, constr));
         }
+        Stmt::CustomIntConstraint(inputs, constr) => {
+            let inputs_as_var = inputs
+                .iter()
+                .map(|e| equivalent_int_var(env, e))
+                .collect::<Vec<_>>();
+            env.norm
+                .add_extra_constraint(ExtraConstraint::CustomIntConstraint(inputs_as_var, constr));
+        }
     }
     if env.config.verbose {
         for i in num_constrs_before_norm..env.norm.constraints.len() {
@@ -452,10 +572,20 @@ fn tseitin_transformation_int(
             tseitin_transformation_int(env, extra, f);
         }
         IntExpr::Abs(x) => tseitin_transformation_int(env, extra, x),
-        IntExpr::Mul(x, y) => {
+        IntExpr::Mul(x, y)
+        | IntExpr::Div(x, y)
+        | IntExpr::Mod(x, y)
+        | IntExpr::Min(x, y)
+        | IntExpr::Max(x, y) => {
             tseitin_transformation_int(env, extra, x);
             tseitin_transformation_int(env, extra, y);
         }
+        IntExpr::Element(index, values) => {
+            tseitin_transformation_int(env, extra, index);
+            values
+                .iter_mut()
+                .for_each(|e| tseitin_transformation_int(env, extra, e));
+        }
     }
 }
 
@@ -799,6 +929,382 @@ fn normalize_int_expr(env: &mut NormalizerEnv, expr: &IntExpr) -> LinearSum {
                 .add_extra_constraint(ExtraConstraint::Mul(xvar, yvar, zvar));
             LinearSum::singleton(zvar)
         }
+        IntExpr::Div(x, y) | IntExpr::Mod(x, y) => {
+            if let Some(&v) = env.map.int_expr_equivalence.get(expr) {
+                return LinearSum::singleton(v);
+            }
+            let is_div = matches!(expr, IntExpr::Div(_, _));
+
+            let x_sum = normalize_int_expr(env, x);
+            let y_sum = normalize_int_expr(env, y);
+            let x_range = linear_sum_range(env, &x_sum);
+            let y_range = linear_sum_range(env, &y_sum);
+
+            if y_sum.is_constant() && y_sum.constant == 0 {
+                return if is_div {
+                    LinearSum::constant(CheckedInt::new(0))
+                } else {
+                    x_sum
+                };
+            }
+
+            let x_atom = linear_sum_to_atom(env, x_sum);
+            let y_atom = linear_sum_to_atom(env, y_sum);
+            let q_range = x_range.div_trunc(y_range);
+            let r_range = x_range.rem_trunc(y_range);
+            let q = env
+                .norm
+                .new_int_var(Domain::range_from_checked(q_range.low, q_range.high));
+            let r = env
+                .norm
+                .new_int_var(Domain::range_from_checked(r_range.low, r_range.high));
+
+            // x = q * y + r, where r has the same sign as x
+            normalize_and_register_expr(
+                env,
+                x_atom
+                    .clone()
+                    .eq(IntExpr::NVar(q) * y_atom.clone() + IntExpr::NVar(r)),
+            );
+            normalize_and_register_expr(
+                env,
+                x_atom
+                    .clone()
+                    .ge(IntExpr::Const(0))
+                    .imp(IntExpr::NVar(r).ge(IntExpr::Const(0))),
+            );
+            normalize_and_register_expr(
+                env,
+                x_atom
+                    .le(IntExpr::Const(0))
+                    .imp(IntExpr::NVar(r).le(IntExpr::Const(0))),
+            );
+
+            // If y is a constant, |r| < |y| is already ensured by the domain of r.
+            if !matches!(y_atom, IntExpr::Const(_)) {
+                let r_bound = IntExpr::NVar(r).abs().lt(y_atom.clone().abs());
+                if y_range.contains(CheckedInt::new(0)) {
+                    normalize_and_register_expr(
+                        env,
+                        y_atom
+                            .clone()
+                            .eq(IntExpr::Const(0))
+                            .imp(IntExpr::NVar(q).eq(IntExpr::Const(0))),
+                    );
+                    normalize_and_register_expr(env, y_atom.ne(IntExpr::Const(0)).imp(r_bound));
+                } else {
+                    normalize_and_register_expr(env, r_bound);
+                }
+            }
+
+            let (x, y) = (x.clone(), y.clone());
+            env.map
+                .int_expr_equivalence
+                .insert(IntExpr::Div(x.clone(), y.clone()), q);
+            env.map.int_expr_equivalence.insert(IntExpr::Mod(x, y), r);
+
+            LinearSum::singleton(if is_div { q } else { r })
+        }
+        IntExpr::Min(x, y) | IntExpr::Max(x, y) => {
+            let xvar = equivalent_int_var(env, x);
+            let yvar = equivalent_int_var(env, y);
+            let cond = if matches!(expr, IntExpr::Min(_, _)) {
+                IntExpr::NVar(xvar).le(IntExpr::NVar(yvar))
+            } else {
+                IntExpr::NVar(xvar).ge(IntExpr::NVar(yvar))
+            };
+            let aux_expr = IntExpr::If(
+                Box::new(cond),
+                Box::new(IntExpr::NVar(xvar)),
+                Box::new(IntExpr::NVar(yvar)),
+            );
+            normalize_int_expr(env, &aux_expr)
+        }
+        IntExpr::Element(index, values) => {
+            if let Some(&v) = env.map.int_expr_equivalence.get(expr) {
+                return LinearSum::singleton(v);
+            }
+
+            let index = normalize_int_expr(env, index);
+            let index_dom = env.norm.get_domain_linear_sum(&index);
+            let n = values.len() as i32;
+
+            let mut cands = vec![];
+            for (i, e) in values.iter().enumerate() {
+                if index_dom.contains(CheckedInt::new(i as i32)) {
+                    cands.push((i as i32, normalize_int_expr(env, e)));
+                }
+            }
+            let out_of_range = !index_dom.is_empty()
+                && (index_dom.lower_bound_checked() < 0 || index_dom.upper_bound_checked() >= n);
+
+            if !out_of_range && cands.len() == 1 {
+                return cands.remove(0).1;
+            }
+
+            let mut dom = if out_of_range {
+                Domain::range(0, 0)
+            } else {
+                Domain::empty()
+            };
+            for (_, e) in &cands {
+                dom = dom | env.norm.get_domain_linear_sum(e);
+            }
+            let v = env.norm.new_int_var(dom);
+
+            for (i, e) in cands {
+                let mut c = Constraint::new();
+                c.add_linear(LinearLit::new(index.clone() + (-i), CmpOp::Ne));
+                c.add_linear(LinearLit::new(e - LinearSum::singleton(v), CmpOp::Eq));
+                env.norm.add_constraint(c);
+            }
+            if out_of_range {
+                for lit in [
+                    LinearLit::new(index.clone(), CmpOp::Ge),
+                    LinearLit::new(index.clone() + (-(n - 1)), CmpOp::Le),
+                ] {
+                    let mut c = Constraint::new();
+                    c.add_linear(lit);
+                    c.add_linear(LinearLit::new(LinearSum::singleton(v), CmpOp::Eq));
+                    env.norm.add_constraint(c);
+                }
+            }
+
+            env.map.int_expr_equivalence.insert(expr.clone(), v);
+
+            LinearSum::singleton(v)
+        }
+    }
+}
+
+fn normalize_active_edges_single_cycle(
+    env: &mut NormalizerEnv,
+    edge_lits: Vec<NBoolLit>,
+    edges: Vec<(usize, usize)>,
+) {
+    let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let mut adj_edges: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        assert_ne!(u, v);
+        adj_edges[u].push(i);
+        adj_edges[v].push(i);
+    }
+
+    // No vertex is incident to exactly one active edge
+    for adj in &adj_edges {
+        for &e in adj {
+            let mut bool_lit = vec![!edge_lits[e]];
+            bool_lit.extend(adj.iter().filter(|&&f| f != e).map(|&f| edge_lits[f]));
+            env.norm.add_constraint(Constraint {
+                bool_lit,
+                linear_lit: vec![],
+            });
+        }
+    }
+
+    if !env.config.use_native_single_cycle {
+        // No vertex is incident to three or more active edges
+        for adj in &adj_edges {
+            for i in 0..adj.len() {
+                for j in (i + 1)..adj.len() {
+                    for k in (j + 1)..adj.len() {
+                        env.norm.add_constraint(Constraint {
+                            bool_lit: vec![
+                                !edge_lits[adj[i]],
+                                !edge_lits[adj[j]],
+                                !edge_lits[adj[k]],
+                            ],
+                            linear_lit: vec![],
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    add_active_edges_connected(env, edge_lits, edges, &adj_edges);
+}
+
+/// Requires that the active edges are connected, assuming that each vertex is incident to at
+/// most two active edges (which is enforced by the native propagator by itself).
+/// `adj_edges[u]` is the list of the edges incident to vertex `u`.
+fn add_active_edges_connected(
+    env: &mut NormalizerEnv,
+    edge_lits: Vec<NBoolLit>,
+    edges: Vec<(usize, usize)>,
+    adj_edges: &[Vec<usize>],
+) {
+    if env.config.use_native_single_cycle {
+        env.norm
+            .add_extra_constraint(ExtraConstraint::ActiveEdgesSingleCycle(edge_lits, edges));
+        return;
+    }
+
+    let mut line_graph_edges: Vec<(usize, usize)> = vec![];
+    for adj in adj_edges {
+        for j in 0..adj.len() {
+            for k in 0..j {
+                line_graph_edges.push((adj[j], adj[k]));
+            }
+        }
+    }
+    env.norm
+        .add_extra_constraint(ExtraConstraint::ActiveVerticesConnected(
+            edge_lits,
+            line_graph_edges,
+        ));
+}
+
+/// `(terms, op, rhs)` representing `sum coef * [e] op rhs` for `(e, coef)` in `terms`.
+type PseudoBooleanConstraint = (Vec<(BoolExpr, CheckedInt)>, CmpOp, CheckedInt);
+
+/// Returns the pseudo-Boolean constraint equivalent to `expr` if `expr` is a comparison of linear
+/// sums of `(if c t f)` with constant `t` and `f` (for example, `count_true(...) == n`).
+fn extract_pseudo_boolean(expr: &BoolExpr) -> Option<PseudoBooleanConstraint> {
+    fn collect_terms(
+        expr: &IntExpr,
+        coef: CheckedInt,
+        terms: &mut Vec<(BoolExpr, CheckedInt)>,
+        constant: &mut CheckedInt,
+    ) -> bool {
+        match expr {
+            &IntExpr::Const(c) => {
+                *constant += coef * CheckedInt::new(c);
+                true
+            }
+            IntExpr::Linear(es) => es
+                .iter()
+                .all(|(e, c)| collect_terms(e, coef * CheckedInt::new(*c), terms, constant)),
+            IntExpr::If(c, t, f) => match (t.as_ref(), f.as_ref()) {
+                (&IntExpr::Const(t), &IntExpr::Const(f)) => {
+                    // (if c t f) == f + (t - f) * [c]
+                    *constant += coef * CheckedInt::new(f);
+                    let w = coef * (CheckedInt::new(t) - CheckedInt::new(f));
+                    if w != 0 {
+                        terms.push((c.as_ref().clone(), w));
+                    }
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    let BoolExpr::Cmp(op, lhs, rhs) = expr else {
+        return None;
+    };
+    let mut terms = vec![];
+    let mut constant = CheckedInt::new(0);
+    if !collect_terms(lhs, CheckedInt::new(1), &mut terms, &mut constant)
+        || !collect_terms(rhs, CheckedInt::new(-1), &mut terms, &mut constant)
+    {
+        return None;
+    }
+    Some((terms, *op, -constant))
+}
+
+fn is_pseudo_boolean_encoding_applicable(
+    env: &NormalizerEnv,
+    terms: &[(BoolExpr, CheckedInt)],
+    op: CmpOp,
+    rhs: CheckedInt,
+) -> bool {
+    if op == CmpOp::Ne {
+        return false;
+    }
+    if env.config.force_pseudo_boolean_encoding {
+        return true;
+    }
+    if !env.config.use_pseudo_boolean_encoding
+        || terms.len() < env.config.pseudo_boolean_encoding_min_terms
+    {
+        return false;
+    }
+    let coefs = terms.iter().map(|&(_, coef)| coef).collect::<Vec<_>>();
+    is_pseudo_boolean_encoding_smaller(&coefs, op, rhs)
+}
+
+fn normalize_pseudo_boolean(
+    env: &mut NormalizerEnv,
+    terms: Vec<(BoolExpr, CheckedInt)>,
+    op: CmpOp,
+    rhs: CheckedInt,
+) {
+    if !is_pseudo_boolean_encoding_applicable(env, &terms, op, rhs) {
+        let converted = terms
+            .into_iter()
+            .map(|(e, coef)| {
+                coef.to_i32_checked()
+                    .map(|coef| (Box::new(e.ite(IntExpr::Const(1), IntExpr::Const(0))), coef))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|sum| Ok((sum, rhs.to_i32_checked()?)));
+        let (sum, rhs) = match converted {
+            Ok(x) => x,
+            Err(e) => {
+                env.overflow.get_or_insert(e);
+                return;
+            }
+        };
+        normalize_and_register_expr(
+            env,
+            BoolExpr::Cmp(
+                op,
+                Box::new(IntExpr::Linear(sum)),
+                Box::new(IntExpr::Const(rhs)),
+            ),
+        );
+        return;
+    }
+
+    let terms = terms
+        .into_iter()
+        .map(|(e, coef)| (equivalent_bool_lit(env, e), coef))
+        .collect::<Vec<_>>();
+    env.norm
+        .add_extra_constraint(ExtraConstraint::PseudoBoolean(terms, op, rhs));
+}
+
+fn normalize_lex_less_eq(env: &mut NormalizerEnv, lhs: Vec<NIntVar>, rhs: Vec<NIntVar>) {
+    let n = lhs.len().min(rhs.len());
+
+    // `prefix_eq` is forced to be true if `lhs[..i]` and `rhs[..i]` are equal
+    let mut prefix_eq = BoolExpr::Const(true);
+    for (i, (&l, &r)) in lhs.iter().zip(&rhs).enumerate() {
+        let x = IntExpr::NVar(l);
+        let y = IntExpr::NVar(r);
+        normalize_and_register_expr(env, prefix_eq.clone().imp(x.clone().le(y.clone())));
+        if i + 1 == n && lhs.len() <= rhs.len() {
+            break;
+        }
+        let next = BoolExpr::NVar(env.norm.new_bool_var());
+        normalize_and_register_expr(env, (prefix_eq & x.ge(y)).imp(next.clone()));
+        prefix_eq = next;
+    }
+    if lhs.len() > rhs.len() {
+        // `rhs` is a proper prefix of `lhs`
+        normalize_and_register_expr(env, !prefix_eq);
+    }
+}
+
+fn normalize_value_precede_chain(env: &mut NormalizerEnv, vars: Vec<NIntVar>, values: &[i32]) {
+    for w in values.windows(2) {
+        let s = IntExpr::Const(w[0]);
+        let t = IntExpr::Const(w[1]);
+
+        // `seen` is true iff `s` appears in `vars[..i]`
+        let mut seen = BoolExpr::Const(false);
+        for (i, &v) in vars.iter().enumerate() {
+            let x = IntExpr::NVar(v);
+            normalize_and_register_expr(env, x.clone().eq(t.clone()).imp(seen.clone()));
+            if i + 1 == vars.len() {
+                break;
+            }
+            let next = BoolExpr::NVar(env.norm.new_bool_var());
+            normalize_and_register_expr(env, next.clone().iff(seen | x.eq(s.clone())));
+            seen = next;
+        }
     }
 }
 
@@ -921,29 +1427,14 @@ fn normalize_circuit(env: &mut NormalizerEnv, vars: Vec<NIntVar>) {
             }
         }
     }
+    // Each vertex has at most two undirected edges since its in-degree and out-degree are at most 1
+    let (edges_undir, edge_lits): (Vec<_>, Vec<_>) = edges_undir_dedup.into_iter().unzip();
     let mut adj_edges: Vec<Vec<usize>> = vec![vec![]; n];
-    for (i, &((u, v), _)) in edges_undir_dedup.iter().enumerate() {
+    for (i, &(u, v)) in edges_undir.iter().enumerate() {
         adj_edges[u].push(i);
         adj_edges[v].push(i);
     }
-    let mut line_graph_edges: Vec<(usize, usize)> = vec![];
-    for i in 0..n {
-        for j in 0..adj_edges[i].len() {
-            for k in 0..j {
-                line_graph_edges.push((adj_edges[i][j], adj_edges[i][k]));
-            }
-        }
-    }
-    let line_graph_vertices = edges_undir_dedup
-        .iter()
-        .map(|&(_, l)| l)
-        .collect::<Vec<_>>();
-
-    env.norm
-        .add_extra_constraint(ExtraConstraint::ActiveVerticesConnected(
-            line_graph_vertices,
-            line_graph_edges,
-        ));
+    add_active_edges_connected(env, edge_lits, edges_undir, &adj_edges);
 }
 
 #[cfg(not(feature = "csp-extra-constraints"))]
@@ -1097,6 +1588,71 @@ fn normalize_extension_supports(
     }
 }
 
+/// Encodes `Regular` with an integer variable representing the state of `dfa` after reading each prefix of `vars`.
+/// Only the states which are reachable and from which an accepting state is reachable are kept.
+fn normalize_regular(env: &mut NormalizerEnv, vars: Vec<NIntVar>, dfa: &Dfa) {
+    let candidates = vars
+        .iter()
+        .map(|&v| {
+            env.norm
+                .get_domain_linear_sum(&LinearSum::singleton(v))
+                .enumerate()
+                .into_iter()
+                .map(|c| c.get())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let live = dfa.live_states(&candidates);
+
+    let mut states = vec![];
+    for layer in &live {
+        let cands = (0..dfa.num_states())
+            .filter(|&s| layer[s])
+            .map(|s| s as i32)
+            .collect::<Vec<_>>();
+        if cands.is_empty() {
+            env.norm.add_constraint(Constraint::new());
+            return;
+        }
+        if cands.len() == 1 {
+            states.push(LinearSum::constant(CheckedInt::new(cands[0])));
+        } else {
+            let v = env.norm.new_int_var(Domain::enumerative(cands));
+            states.push(LinearSum::singleton(v));
+        }
+    }
+
+    // (state[i] == s && vars[i] == c) => state[i + 1] == next_state(s, c)
+    for i in 0..vars.len() {
+        for s in 0..dfa.num_states() {
+            if !live[i][s] {
+                continue;
+            }
+            for &c in &candidates[i] {
+                let mut constraint = Constraint::new();
+                if !states[i].is_constant() {
+                    constraint
+                        .add_linear(LinearLit::new(states[i].clone() + (-(s as i32)), CmpOp::Ne));
+                }
+                constraint.add_linear(LinearLit::new(
+                    LinearSum::singleton(vars[i]) + (-c),
+                    CmpOp::Ne,
+                ));
+                if let Some(t) = dfa.next_state(s, c).filter(|&t| live[i + 1][t]) {
+                    if states[i + 1].is_constant() {
+                        continue;
+                    }
+                    constraint.add_linear(LinearLit::new(
+                        states[i + 1].clone() + (-(t as i32)),
+                        CmpOp::Eq,
+                    ));
+                }
+                env.norm.add_constraint(constraint);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -1154,7 +1710,7 @@ mod tests {
         fn check(&mut self) {
             let csp_assignments = crate::csp::test_utils::csp_all_assignments(&self.csp);
 
-            normalize(&mut self.csp, &mut self.norm, &mut self.map, &self.config);
+            normalize(&mut self.csp, &mut self.norm, &mut self.map, &self.config).unwrap();
 
             let mut norm_csp_assignments =
                 crate::norm_csp::test_utils::norm_csp_all_assignments(&self.norm);
@@ -1312,7 +1868,7 @@ mod tests {
 
         let mut norm_csp = NormCSP::new();
         let mut map = NormalizeMap::new();
-        normalize(&mut csp, &mut norm_csp, &mut map, &Config::default());
+        normalize(&mut csp, &mut norm_csp, &mut map, &Config::default()).unwrap();
         assert!(norm_csp.constraints.len() <= 200);
     }
 
@@ -1446,7 +2002,7 @@ mod tests {
 
         csp.optimize(true, false);
 
-        normalize(&mut csp, &mut norm_csp, &mut map, &config);
+        normalize(&mut csp, &mut norm_csp, &mut map, &config).unwrap();
 
         assert!(map.bool_map[v].is_not_converted());
         assert!(map.bool_map[w].is_removed());
@@ -1488,6 +2044,100 @@ mod tests {
         tester.check();
     }
 
+    #[test]
+    fn test_normalization_active_edges_single_cycle() {
+        for use_native in [false, true] {
+            let mut tester = NormalizerTester::new();
+            tester.config.use_native_single_cycle = use_native;
+
+            let edges = vec![(0, 1), (1, 2), (2, 0), (2, 3), (3, 0)];
+            let vars = (0..edges.len())
+                .map(|_| tester.new_bool_var().expr())
+                .collect::<Vec<_>>();
+            tester.add_constraint(Stmt::ActiveEdgesSingleCycle(vars, edges));
+            tester.check();
+        }
+    }
+
+    #[cfg(feature = "csp-extra-constraints")]
+    #[test]
+    fn test_normalization_alldifferent_native() {
+        let mut tester = NormalizerTester::new();
+        tester.config.use_native_all_different = true;
+
+        let a = tester.new_int_var(Domain::range(0, 3));
+        let b = tester.new_int_var(Domain::range(0, 3));
+        let c = tester.new_int_var(Domain::range(1, 4));
+        tester.add_constraint(Stmt::AllDifferent(vec![
+            a.expr(),
+            b.expr() + c.expr(),
+            c.expr(),
+        ]));
+        tester.check();
+    }
+
+    #[test]
+    fn test_normalization_pseudo_boolean() {
+        let mut tester = NormalizerTester::new();
+        tester.config.force_pseudo_boolean_encoding = true;
+
+        let x = tester.new_bool_var();
+        let y = tester.new_bool_var();
+        let z = tester.new_bool_var();
+        let a = tester.new_int_var(Domain::range(0, 2));
+        tester.add_constraint(Stmt::PseudoBoolean(
+            vec![
+                (x.expr(), 2),
+                (!y.expr(), -1),
+                (z.expr(), 1),
+                (a.expr().ge(IntExpr::Const(1)), 3),
+            ],
+            CmpOp::Ge,
+            2,
+        ));
+        tester.add_expr(
+            (x.expr().ite(IntExpr::Const(1), IntExpr::Const(0))
+                + y.expr().ite(IntExpr::Const(1), IntExpr::Const(0))
+                + z.expr().ite(IntExpr::Const(0), IntExpr::Const(1))
+                + a.expr()
+                    .eq(IntExpr::Const(2))
+                    .ite(IntExpr::Const(1), IntExpr::Const(0)))
+            .le(IntExpr::Const(2)),
+        );
+        tester.check();
+    }
+
+    #[test]
+    fn test_normalization_lex_less_eq() {
+        for (n, m) in [(3, 3), (3, 2), (2, 3)] {
+            let mut tester = NormalizerTester::new();
+
+            let lhs = (0..n)
+                .map(|_| tester.new_int_var(Domain::range(0, 2)).expr())
+                .collect::<Vec<_>>();
+            let rhs = (0..m)
+                .map(|_| tester.new_int_var(Domain::range(0, 2)).expr())
+                .collect::<Vec<_>>();
+            tester.add_constraint(Stmt::LexLessEq(lhs, rhs));
+            tester.check();
+        }
+    }
+
+    #[test]
+    fn test_normalization_value_precede_chain() {
+        let mut tester = NormalizerTester::new();
+
+        let a = tester.new_int_var(Domain::range(0, 2));
+        let b = tester.new_int_var(Domain::range(0, 2));
+        let c = tester.new_int_var(Domain::range(1, 3));
+        let d = tester.new_int_var(Domain::range(0, 2));
+        tester.add_constraint(Stmt::ValuePrecedeChain(
+            vec![a.expr(), b.expr(), c.expr() - IntExpr::Const(1), d.expr()],
+            vec![0, 1, 2],
+        ));
+        tester.check();
+    }
+
     #[cfg(feature = "csp-extra-constraints")]
     #[test]
     fn test_normalization_extension_supports_1() {
@@ -1555,6 +2205,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_normalization_regular() {
+        // blocks of 1's with lengths 2 and 1, in this order
+        let mut dfa = Dfa::new(5, 0);
+        dfa.add_transition(0, 0, 0);
+        dfa.add_transition(0, 1, 1);
+        dfa.add_transition(1, 1, 2);
+        dfa.add_transition(2, 0, 3);
+        dfa.add_transition(3, 0, 3);
+        dfa.add_transition(3, 1, 4);
+        dfa.add_transition(4, 0, 4);
+        dfa.set_accepting(4, true);
+
+        let mut tester = NormalizerTester::new();
+        let vars = (0..6)
+            .map(|_| tester.new_int_var(Domain::range(0, 1)).expr())
+            .collect::<Vec<_>>();
+        tester.add_constraint(Stmt::Regular(vars, dfa));
+        tester.check();
+    }
+
     #[cfg(feature = "csp-extra-constraints")]
     #[test]
     fn test_normalization_circuit_1() {
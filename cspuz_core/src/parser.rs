@@ -22,42 +22,116 @@ enum SyntaxTree<'a> {
     Node(Vec<SyntaxTree<'a>>),
 }
 
+/// An error in parsing a line of the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number. `parse` always reports 1 since it takes a single line.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error found in a syntax tree, located by the address of the corresponding part of the input
+/// (if known).
+struct TreeError {
+    address: Option<usize>,
+    message: String,
+}
+
+impl TreeError {
+    /// Locates the error at `tree` if it is not located yet.
+    fn or_at(mut self, tree: &SyntaxTree) -> TreeError {
+        if self.address.is_none() {
+            self.address = tree.address();
+        }
+        self
+    }
+}
+
+type TreeResult<T> = Result<T, TreeError>;
+
+fn error_at<T>(tree: &SyntaxTree, message: String) -> TreeResult<T> {
+    Err(TreeError {
+        address: tree.address(),
+        message,
+    })
+}
+
 impl<'a> SyntaxTree<'a> {
-    fn as_op_name(&self) -> &'a str {
+    /// Returns the address of the first character of this tree in the input, if it is known.
+    fn address(&self) -> Option<usize> {
+        match self {
+            SyntaxTree::Ident(s) => Some(s.as_ptr() as usize),
+            SyntaxTree::Int(_) => None,
+            // The first child follows the opening parenthesis immediately
+            SyntaxTree::Node(child) => child.first()?.address().map(|a| a - 1),
+        }
+    }
+
+    fn as_op_name(&self) -> TreeResult<&'a str> {
         match self {
-            &SyntaxTree::Ident(s) => s,
-            _ => panic!("op name expected"),
+            &SyntaxTree::Ident(s) => Ok(s),
+            _ => error_at(self, String::from("op name expected")),
         }
     }
 
-    fn as_ident(&self) -> &'a str {
+    fn as_ident(&self) -> TreeResult<&'a str> {
         match self {
-            &SyntaxTree::Ident(s) => s,
-            _ => panic!("identifier expected"),
+            &SyntaxTree::Ident(s) => Ok(s),
+            _ => error_at(self, String::from("identifier expected")),
         }
     }
 
-    fn as_int(&self) -> i32 {
+    fn as_int(&self) -> TreeResult<i32> {
         match self {
-            &SyntaxTree::Int(n) => n,
-            _ => panic!("int expected"),
+            &SyntaxTree::Int(n) => Ok(n),
+            _ => error_at(self, String::from("int expected")),
         }
     }
 
-    fn as_usize(&self) -> usize {
-        let n = self.as_int();
-        assert!(n >= 0);
-        n as usize
+    fn as_usize(&self) -> TreeResult<usize> {
+        let n = self.as_int()?;
+        if n < 0 {
+            return error_at(
+                self,
+                format!("non-negative int expected, but {} is given", n),
+            );
+        }
+        Ok(n as usize)
     }
 
-    fn as_node(&self) -> &Vec<SyntaxTree<'a>> {
+    fn as_node(&self) -> TreeResult<&Vec<SyntaxTree<'a>>> {
         match self {
-            SyntaxTree::Node(ch) => ch,
-            _ => panic!("node expected"),
+            SyntaxTree::Node(ch) => Ok(ch),
+            _ => error_at(self, String::from("node expected")),
         }
     }
 }
 
+/// Checks that `child` (the children of `tree`) consists of `expected` elements including the operator.
+fn check_len(tree: &SyntaxTree, child: &[SyntaxTree], expected: usize) -> TreeResult<()> {
+    if child.len() != expected {
+        return error_at(
+            tree,
+            format!(
+                "{} operand(s) expected, but {} given",
+                expected - 1,
+                child.len() - 1
+            ),
+        );
+    }
+    Ok(())
+}
+
 fn is_ident_char(c: char) -> bool {
     c.is_alphanumeric() || c == '[' || c == ']' || c == '_'
 }
@@ -87,6 +161,8 @@ fn parse_to_tree<'a>(input: &str) -> Result<SyntaxTree<'_>, nom::error::Error<&s
             tag("+"),
             tag("-"),
             tag("*"),
+            tag("/"),
+            tag("%"),
         ));
         alt((
             delimited(
@@ -115,12 +191,19 @@ pub enum Var {
     Int(IntVar),
 }
 
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ObjectiveSense {
+    Minimize,
+    Maximize,
+}
+
 #[derive(Debug)]
 pub enum ParseResult<'a> {
     BoolVarDecl(&'a str),
     IntVarDecl(&'a str, Domain),
     IntVarWithListDomDecl(&'a str, Vec<i32>),
     Stmt(Stmt),
+    Objective(ObjectiveSense, IntExpr),
 }
 
 pub struct VarMap(BTreeMap<String, Var>);
@@ -147,94 +230,156 @@ impl VarMap {
     }
 }
 
-pub fn parse<'a>(var_map: &VarMap, input: &'a str) -> ParseResult<'a> {
-    // TODO: return error info
-    let tree = parse_to_tree(input).unwrap();
-    let child = match &tree {
+/// Parses a line of the csugar-like input.
+pub fn parse<'a>(var_map: &VarMap, input: &'a str) -> Result<ParseResult<'a>, ParseError> {
+    let to_column =
+        |address: usize| input[..(address - input.as_ptr() as usize)].chars().count() + 1;
+
+    let tree = match parse_to_tree(input) {
+        Ok(tree) => tree,
+        Err(e) => {
+            return Err(ParseError {
+                line: 1,
+                column: to_column(e.input.as_ptr() as usize),
+                message: String::from("syntax error"),
+            })
+        }
+    };
+    parse_tree(var_map, &tree).map_err(|e| ParseError {
+        line: 1,
+        column: e.address.map_or(1, to_column),
+        message: e.message,
+    })
+}
+
+/// Parses `tree`. Errors which cannot be located more precisely are located at `tree`.
+fn parse_tree<'a>(var_map: &VarMap, tree: &SyntaxTree<'a>) -> TreeResult<ParseResult<'a>> {
+    parse_tree_inner(var_map, tree).map_err(|e| e.or_at(tree))
+}
+
+fn parse_tree_inner<'a>(var_map: &VarMap, tree: &SyntaxTree<'a>) -> TreeResult<ParseResult<'a>> {
+    let child = match tree {
         SyntaxTree::Node(child) => child,
-        _ => return ParseResult::Stmt(Stmt::Expr(parse_bool_expr(var_map, &tree))),
+        _ => {
+            return Ok(ParseResult::Stmt(Stmt::Expr(parse_bool_expr(
+                var_map, tree,
+            )?)))
+        }
+    };
+    if child.is_empty() {
+        return error_at(tree, String::from("empty node"));
+    }
+    let op_name = child[0].as_op_name()?;
+    let check_undeclared = |t: &SyntaxTree<'a>| -> TreeResult<&'a str> {
+        let var_name = t.as_ident()?;
+        if var_map.get_var(var_name).is_some() {
+            return error_at(t, format!("variable {} is already declared", var_name));
+        }
+        Ok(var_name)
     };
-    assert!(!child.is_empty());
-    let op_name = child[0].as_op_name();
 
-    if op_name == "bool" {
-        assert_eq!(child.len(), 2);
-        let var_name = child[1].as_ident();
+    let ret = if op_name == "bool" {
+        check_len(tree, child, 2)?;
+        let var_name = check_undeclared(&child[1])?;
         ParseResult::BoolVarDecl(var_name)
     } else if op_name == "int" {
         if child.len() == 4 {
-            let var_name = child[1].as_ident();
-            let low = child[2].as_int();
-            let high = child[3].as_int();
+            let var_name = check_undeclared(&child[1])?;
+            let low = child[2].as_int()?;
+            let high = child[3].as_int()?;
             ParseResult::IntVarDecl(var_name, Domain::range(low, high))
         } else if child.len() == 3 {
-            let var_name = child[1].as_ident();
+            let var_name = check_undeclared(&child[1])?;
             match &child[2] {
-                SyntaxTree::Node(child) => {
-                    let domain = child.iter().map(|x| x.as_int()).collect::<Vec<_>>();
+                SyntaxTree::Node(domain_child) => {
+                    let domain = domain_child
+                        .iter()
+                        .map(|x| x.as_int())
+                        .collect::<TreeResult<Vec<_>>>()
+                        .map_err(|e| e.or_at(&child[2]))?;
+                    if domain.is_empty() {
+                        return error_at(tree, String::from("empty domain"));
+                    }
                     ParseResult::IntVarWithListDomDecl(var_name, domain)
                 }
-                _ => panic!(),
+                t => return error_at(t, String::from("list of values expected")),
             }
         } else {
-            panic!();
+            return error_at(
+                tree,
+                String::from(
+                    "int declaration must be (int name low high) or (int name (values...))",
+                ),
+            );
         }
+    } else if op_name == "objective" {
+        check_len(tree, child, 3)?;
+        let sense = match child[1].as_ident()? {
+            "minimize" => ObjectiveSense::Minimize,
+            "maximize" => ObjectiveSense::Maximize,
+            s => return error_at(&child[1], format!("unknown objective sense: {}", s)),
+        };
+        ParseResult::Objective(sense, parse_int_expr(var_map, &child[2])?)
     } else if op_name == "alldifferent" {
         let exprs = child[1..]
             .iter()
             .map(|c| parse_int_expr(var_map, c))
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         ParseResult::Stmt(Stmt::AllDifferent(exprs))
     } else if op_name == "circuit" {
         let exprs = child[1..]
             .iter()
             .map(|c| parse_int_expr(var_map, c))
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         ParseResult::Stmt(Stmt::Circuit(exprs))
     } else if op_name == "graph-active-vertices-connected" {
-        let num_vertices = child[1].as_usize();
-        let num_edges = child[2].as_usize();
-        let num_vertices = num_vertices as usize;
-        let num_edges = num_edges as usize;
-        assert_eq!(child.len(), 3 + num_vertices + num_edges * 2);
+        if child.len() < 3 {
+            return error_at(tree, String::from("number of vertices and edges expected"));
+        }
+        let num_vertices = child[1].as_usize()?;
+        let num_edges = child[2].as_usize()?;
+        check_len(tree, child, 3 + num_vertices + num_edges * 2)?;
 
         let vertices = (0..num_vertices)
             .map(|i| parse_bool_expr(var_map, &child[i + 3]))
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         let edges = (0..num_edges)
             .map(|i| {
-                (
-                    child[i * 2 + 3 + num_vertices].as_usize(),
-                    child[i * 2 + 4 + num_vertices].as_usize(),
-                )
+                Ok((
+                    child[i * 2 + 3 + num_vertices].as_usize()?,
+                    child[i * 2 + 4 + num_vertices].as_usize()?,
+                ))
             })
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         ParseResult::Stmt(Stmt::ActiveVerticesConnected(vertices, edges))
     } else if op_name == "graph-division" {
-        let num_vertices = child[1].as_usize();
-        let num_edges = child[2].as_usize();
-        assert_eq!(child.len(), 3 + num_vertices + num_edges * 3);
+        if child.len() < 3 {
+            return error_at(tree, String::from("number of vertices and edges expected"));
+        }
+        let num_vertices = child[1].as_usize()?;
+        let num_edges = child[2].as_usize()?;
+        check_len(tree, child, 3 + num_vertices + num_edges * 3)?;
 
         let vertices = (0..num_vertices)
             .map(|i| {
                 if child[i + 3] == SyntaxTree::Ident("*") {
-                    None
+                    Ok(None)
                 } else {
-                    Some(parse_int_expr(var_map, &child[i + 3]))
+                    Ok(Some(parse_int_expr(var_map, &child[i + 3])?))
                 }
             })
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         let edges = (0..num_edges)
             .map(|i| {
-                (
-                    child[i * 2 + 3 + num_vertices].as_usize(),
-                    child[i * 2 + 4 + num_vertices].as_usize(),
-                )
+                Ok((
+                    child[i * 2 + 3 + num_vertices].as_usize()?,
+                    child[i * 2 + 4 + num_vertices].as_usize()?,
+                ))
             })
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         let edge_exprs = (0..num_edges)
             .map(|i| parse_bool_expr(var_map, &child[i + 3 + num_vertices + num_edges * 2]))
-            .collect::<Vec<_>>();
+            .collect::<TreeResult<Vec<_>>>()?;
         ParseResult::Stmt(Stmt::GraphDivision(
             vertices,
             edges,
@@ -242,155 +387,231 @@ pub fn parse<'a>(var_map: &VarMap, input: &'a str) -> ParseResult<'a> {
             GraphDivisionOptions::default(),
         ))
     } else if op_name == "extension-supports" {
-        assert_eq!(child.len(), 3);
+        check_len(tree, child, 3)?;
         let mut exprs = vec![];
-        for c in child[1].as_node() {
-            exprs.push(parse_int_expr(var_map, c));
+        for c in child[1].as_node()? {
+            exprs.push(parse_int_expr(var_map, c)?);
         }
         let mut supports = vec![];
-        for s in child[2].as_node() {
+        for s in child[2].as_node()? {
             let mut support = vec![];
-            for v in s.as_node() {
+            for v in s.as_node()? {
                 match v {
                     SyntaxTree::Int(v) => support.push(Some(*v)),
                     SyntaxTree::Ident("*") => support.push(None),
-                    _ => panic!(),
+                    _ => return error_at(v, String::from("int or * expected")),
                 }
             }
+            if support.len() != exprs.len() {
+                return error_at(
+                    s,
+                    format!(
+                        "support of length {} expected, but {} given",
+                        exprs.len(),
+                        support.len()
+                    ),
+                );
+            }
             supports.push(support);
         }
         ParseResult::Stmt(Stmt::ExtensionSupports(exprs, supports))
     } else {
-        ParseResult::Stmt(Stmt::Expr(parse_bool_expr(var_map, &tree)))
-    }
+        ParseResult::Stmt(Stmt::Expr(parse_bool_expr(var_map, tree)?))
+    };
+    Ok(ret)
 }
 
-fn parse_bool_expr(var_map: &VarMap, tree: &SyntaxTree) -> BoolExpr {
+fn parse_bool_expr(var_map: &VarMap, tree: &SyntaxTree) -> TreeResult<BoolExpr> {
     match tree {
         &SyntaxTree::Ident(id) => {
             if id == "true" {
-                return BoolExpr::Const(true);
+                return Ok(BoolExpr::Const(true));
             } else if id == "false" {
-                return BoolExpr::Const(false);
+                return Ok(BoolExpr::Const(false));
             }
 
-            let var = var_map.get_var(id).unwrap();
-            match var {
-                Var::Bool(b) => b.expr(),
-                Var::Int(_) => panic!("int var is given while bool expr is expected"),
+            match var_map.get_var(id) {
+                Some(Var::Bool(b)) => Ok(b.expr()),
+                Some(Var::Int(_)) => error_at(
+                    tree,
+                    format!("int var {} is given while bool expr is expected", id),
+                ),
+                None => error_at(tree, format!("unknown variable: {}", id)),
             }
         }
-        &SyntaxTree::Int(_) => panic!("int constant is given while bool expr is expected"),
+        &SyntaxTree::Int(_) => error_at(
+            tree,
+            String::from("int constant is given while bool expr is expected"),
+        ),
         SyntaxTree::Node(child) => {
-            let op_name = child[0].as_op_name();
-            if op_name == "not" || op_name == "!" {
-                assert_eq!(child.len(), 2);
-                !parse_bool_expr(var_map, &child[1])
-            } else if op_name == "and" || op_name == "&&" {
-                BoolExpr::And(
-                    child[1..]
-                        .iter()
-                        .map(|t| Box::new(parse_bool_expr(var_map, t)))
-                        .collect(),
-                )
-            } else if op_name == "or" || op_name == "||" {
-                BoolExpr::Or(
-                    child[1..]
-                        .iter()
-                        .map(|t| Box::new(parse_bool_expr(var_map, t)))
-                        .collect(),
-                )
-            } else if op_name == "xor" || op_name == "^" {
-                assert_eq!(child.len(), 3);
-                parse_bool_expr(var_map, &child[1]) ^ parse_bool_expr(var_map, &child[2])
-            } else if op_name == "iff" {
-                assert_eq!(child.len(), 3);
-                parse_bool_expr(var_map, &child[1]).iff(parse_bool_expr(var_map, &child[2]))
-            } else if op_name == "imp" || op_name == "=>" {
-                assert_eq!(child.len(), 3);
-                parse_bool_expr(var_map, &child[1]).imp(parse_bool_expr(var_map, &child[2]))
-            } else if op_name == "=" || op_name == "==" || op_name == "eq" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).eq(parse_int_expr(var_map, &child[2]))
+            parse_bool_expr_node(var_map, tree, child).map_err(|e| e.or_at(tree))
+        }
+    }
+}
+
+fn parse_bool_expr_node(
+    var_map: &VarMap,
+    tree: &SyntaxTree,
+    child: &[SyntaxTree],
+) -> TreeResult<BoolExpr> {
+    if child.is_empty() {
+        return error_at(tree, String::from("empty node"));
+    }
+    let op_name = child[0].as_op_name()?;
+    let bool_operands = || {
+        child[1..]
+            .iter()
+            .map(|t| Ok(Box::new(parse_bool_expr(var_map, t)?)))
+            .collect::<TreeResult<Vec<_>>>()
+    };
+    let ret = if op_name == "not" || op_name == "!" {
+        check_len(tree, child, 2)?;
+        !parse_bool_expr(var_map, &child[1])?
+    } else if op_name == "and" || op_name == "&&" {
+        BoolExpr::And(bool_operands()?)
+    } else if op_name == "or" || op_name == "||" {
+        BoolExpr::Or(bool_operands()?)
+    } else if op_name == "xor" || op_name == "^" {
+        check_len(tree, child, 3)?;
+        parse_bool_expr(var_map, &child[1])? ^ parse_bool_expr(var_map, &child[2])?
+    } else if op_name == "iff" {
+        check_len(tree, child, 3)?;
+        parse_bool_expr(var_map, &child[1])?.iff(parse_bool_expr(var_map, &child[2])?)
+    } else if op_name == "imp" || op_name == "=>" {
+        check_len(tree, child, 3)?;
+        parse_bool_expr(var_map, &child[1])?.imp(parse_bool_expr(var_map, &child[2])?)
+    } else {
+        let cmp: fn(IntExpr, IntExpr) -> BoolExpr =
+            if op_name == "=" || op_name == "==" || op_name == "eq" {
+                IntExpr::eq
             } else if op_name == "!=" || op_name == "ne" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).ne(parse_int_expr(var_map, &child[2]))
+                IntExpr::ne
             } else if op_name == "<=" || op_name == "le" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).le(parse_int_expr(var_map, &child[2]))
+                IntExpr::le
             } else if op_name == "<" || op_name == "lt" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).lt(parse_int_expr(var_map, &child[2]))
+                IntExpr::lt
             } else if op_name == ">=" || op_name == "ge" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).ge(parse_int_expr(var_map, &child[2]))
+                IntExpr::ge
             } else if op_name == ">" || op_name == "gt" {
-                assert_eq!(child.len(), 3);
-                parse_int_expr(var_map, &child[1]).gt(parse_int_expr(var_map, &child[2]))
+                IntExpr::gt
             } else {
-                panic!("unknown operator: {}", op_name);
-            }
-        }
-    }
+                return error_at(&child[0], format!("unknown operator: {}", op_name));
+            };
+        check_len(tree, child, 3)?;
+        cmp(
+            parse_int_expr(var_map, &child[1])?,
+            parse_int_expr(var_map, &child[2])?,
+        )
+    };
+    Ok(ret)
 }
 
-fn parse_int_expr(var_map: &VarMap, tree: &SyntaxTree) -> IntExpr {
+fn parse_int_expr(var_map: &VarMap, tree: &SyntaxTree) -> TreeResult<IntExpr> {
     match tree {
         &SyntaxTree::Ident(id) => {
             if id == "true" || id == "false" {
-                panic!("bool constant is given while int expr is expected");
+                return error_at(
+                    tree,
+                    String::from("bool constant is given while int expr is expected"),
+                );
             }
 
-            let var = var_map.get_var(id).unwrap();
-            match var {
-                Var::Bool(_) => panic!("int var is given while bool expr is expected"),
-                Var::Int(i) => i.expr(),
+            match var_map.get_var(id) {
+                Some(Var::Bool(_)) => error_at(
+                    tree,
+                    format!("bool var {} is given while int expr is expected", id),
+                ),
+                Some(Var::Int(i)) => Ok(i.expr()),
+                None => error_at(tree, format!("unknown variable: {}", id)),
             }
         }
-        &SyntaxTree::Int(n) => IntExpr::Const(n),
+        &SyntaxTree::Int(n) => Ok(IntExpr::Const(n)),
         SyntaxTree::Node(child) => {
-            let op_name = child[0].as_op_name();
-            if op_name == "+" || op_name == "add" {
-                IntExpr::Linear(
-                    child[1..]
-                        .iter()
-                        .map(|t| (Box::new(parse_int_expr(var_map, t)), 1))
-                        .collect(),
-                )
-            } else if op_name == "-" || op_name == "sub" {
-                assert!(child.len() == 2 || child.len() == 3);
-                if child.len() == 2 {
-                    IntExpr::Linear(vec![(Box::new(parse_int_expr(var_map, &child[1])), -1)])
-                } else {
-                    parse_int_expr(var_map, &child[1]) - parse_int_expr(var_map, &child[2])
-                }
-            } else if op_name == "*" || op_name == "mul" {
-                assert!(child.len() == 3);
-                let lhs = parse_int_expr(var_map, &child[1]);
-                let rhs = parse_int_expr(var_map, &child[2]);
-                if let IntExpr::Const(c) = lhs {
-                    IntExpr::Linear(vec![(Box::new(rhs), c)])
-                } else if let IntExpr::Const(c) = rhs {
-                    IntExpr::Linear(vec![(Box::new(lhs), c)])
-                } else {
-                    IntExpr::Mul(Box::new(lhs), Box::new(rhs))
-                }
-            } else if op_name == "if" {
-                assert_eq!(child.len(), 4);
-                parse_bool_expr(var_map, &child[1]).ite(
-                    parse_int_expr(var_map, &child[2]),
-                    parse_int_expr(var_map, &child[3]),
-                )
-            } else if op_name == "abs" {
-                assert_eq!(child.len(), 2);
-                parse_int_expr(var_map, &child[1]).abs()
-            } else {
-                panic!("unknown operator: {}", op_name);
-            }
+            parse_int_expr_node(var_map, tree, child).map_err(|e| e.or_at(tree))
         }
     }
 }
 
+fn parse_int_expr_node(
+    var_map: &VarMap,
+    tree: &SyntaxTree,
+    child: &[SyntaxTree],
+) -> TreeResult<IntExpr> {
+    if child.is_empty() {
+        return error_at(tree, String::from("empty node"));
+    }
+    let op_name = child[0].as_op_name()?;
+    let ret = if op_name == "+" || op_name == "add" {
+        IntExpr::Linear(
+            child[1..]
+                .iter()
+                .map(|t| Ok((Box::new(parse_int_expr(var_map, t)?), 1)))
+                .collect::<TreeResult<Vec<_>>>()?,
+        )
+    } else if op_name == "-" || op_name == "sub" {
+        if child.len() == 2 {
+            IntExpr::Linear(vec![(Box::new(parse_int_expr(var_map, &child[1])?), -1)])
+        } else {
+            check_len(tree, child, 3)?;
+            parse_int_expr(var_map, &child[1])? - parse_int_expr(var_map, &child[2])?
+        }
+    } else if op_name == "*" || op_name == "mul" {
+        check_len(tree, child, 3)?;
+        let lhs = parse_int_expr(var_map, &child[1])?;
+        let rhs = parse_int_expr(var_map, &child[2])?;
+        if let IntExpr::Const(c) = lhs {
+            IntExpr::Linear(vec![(Box::new(rhs), c)])
+        } else if let IntExpr::Const(c) = rhs {
+            IntExpr::Linear(vec![(Box::new(lhs), c)])
+        } else {
+            IntExpr::Mul(Box::new(lhs), Box::new(rhs))
+        }
+    } else if op_name == "if" {
+        check_len(tree, child, 4)?;
+        parse_bool_expr(var_map, &child[1])?.ite(
+            parse_int_expr(var_map, &child[2])?,
+            parse_int_expr(var_map, &child[3])?,
+        )
+    } else if op_name == "abs" {
+        check_len(tree, child, 2)?;
+        parse_int_expr(var_map, &child[1])?.abs()
+    } else if op_name == "/" || op_name == "div" {
+        check_len(tree, child, 3)?;
+        parse_int_expr(var_map, &child[1])? / parse_int_expr(var_map, &child[2])?
+    } else if op_name == "%" || op_name == "mod" {
+        check_len(tree, child, 3)?;
+        parse_int_expr(var_map, &child[1])? % parse_int_expr(var_map, &child[2])?
+    } else if op_name == "min" || op_name == "max" {
+        if child.len() < 2 {
+            return error_at(tree, format!("{} requires at least one operand", op_name));
+        }
+        let mut ret = parse_int_expr(var_map, &child[1])?;
+        for t in &child[2..] {
+            let e = parse_int_expr(var_map, t)?;
+            ret = if op_name == "min" {
+                ret.min(e)
+            } else {
+                ret.max(e)
+            };
+        }
+        ret
+    } else if op_name == "element" {
+        if child.len() < 2 {
+            return error_at(tree, String::from("element requires an index"));
+        }
+        IntExpr::element(
+            parse_int_expr(var_map, &child[1])?,
+            child[2..]
+                .iter()
+                .map(|t| parse_int_expr(var_map, t))
+                .collect::<TreeResult<Vec<_>>>()?,
+        )
+    } else {
+        return error_at(&child[0], format!("unknown operator: {}", op_name));
+    };
+    Ok(ret)
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::integration::IntegratedSolver;
@@ -437,7 +658,7 @@ mod tests {
         let mut var_map = VarMap::new();
         let mut solver = IntegratedSolver::new();
 
-        let result = parse(&var_map, "(bool foo)");
+        let result = parse(&var_map, "(bool foo)").unwrap();
         match result {
             ParseResult::BoolVarDecl(name) => {
                 assert_eq!(name, "foo");
@@ -447,7 +668,7 @@ mod tests {
         let foo = solver.new_bool_var();
         var_map.add_bool_var("foo", foo);
 
-        let result = parse(&var_map, "(bool bar)");
+        let result = parse(&var_map, "(bool bar)").unwrap();
         match result {
             ParseResult::BoolVarDecl(name) => {
                 assert_eq!(name, "bar");
@@ -457,7 +678,7 @@ mod tests {
         let bar = solver.new_bool_var();
         var_map.add_bool_var("bar", bar);
 
-        let result = parse(&var_map, "(|| (xor foo bar) bar)");
+        let result = parse(&var_map, "(|| (xor foo bar) bar)").unwrap();
         match result {
             ParseResult::Stmt(Stmt::Expr(expr)) => {
                 assert_eq!(expr, (foo.expr() ^ bar.expr()) | bar.expr());
@@ -465,7 +686,46 @@ mod tests {
             _ => panic!(),
         }
 
-        let result = parse(&var_map, "foo");
+        let result = parse(&var_map, "(objective maximize (if foo 1 0))").unwrap();
+        match result {
+            ParseResult::Objective(ObjectiveSense::Maximize, expr) => {
+                assert_eq!(expr, foo.expr().ite(IntExpr::Const(1), IntExpr::Const(0)));
+            }
+            _ => panic!(),
+        }
+
+        let result = parse(&var_map, "(int x 0 5)").unwrap();
+        match result {
+            ParseResult::IntVarDecl(name, _) => {
+                assert_eq!(name, "x");
+            }
+            _ => panic!(),
+        }
+        let x = solver.new_int_var(Domain::range(0, 5));
+        var_map.add_int_var("x", x);
+
+        let result = parse(
+            &var_map,
+            "(= (max (div x 2) (mod x 3) 1) (element x 4 x 2))",
+        )
+        .unwrap();
+        match result {
+            ParseResult::Stmt(Stmt::Expr(expr)) => {
+                assert_eq!(
+                    expr,
+                    (x.expr() / IntExpr::Const(2))
+                        .max(x.expr() % IntExpr::Const(3))
+                        .max(IntExpr::Const(1))
+                        .eq(IntExpr::element(
+                            x.expr(),
+                            vec![IntExpr::Const(4), x.expr(), IntExpr::Const(2)]
+                        ))
+                );
+            }
+            _ => panic!(),
+        }
+
+        let result = parse(&var_map, "foo").unwrap();
         match result {
             ParseResult::Stmt(Stmt::Expr(expr)) => {
                 assert_eq!(expr, foo.expr());
@@ -473,4 +733,57 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn test_parser_error() {
+        let mut var_map = VarMap::new();
+        let mut solver = IntegratedSolver::new();
+        var_map.add_bool_var("foo", solver.new_bool_var());
+        var_map.add_int_var("x", solver.new_int_var(Domain::range(0, 5)));
+
+        let error = |input: &str| match parse(&var_map, input) {
+            Ok(_) => panic!("error expected for {}", input),
+            Err(e) => (e.column, e.message),
+        };
+
+        assert_eq!(error("(&& foo))"), (9, String::from("syntax error")));
+        assert_eq!(
+            error("(&& foo bar)"),
+            (9, String::from("unknown variable: bar"))
+        );
+        assert_eq!(
+            error("(|| foo (foo x 1))"),
+            (10, String::from("unknown operator: foo"))
+        );
+        assert_eq!(
+            error("(= x (if foo 1))"),
+            (6, String::from("3 operand(s) expected, but 2 given"))
+        );
+        assert_eq!(
+            error("(&& foo x)"),
+            (
+                9,
+                String::from("int var x is given while bool expr is expected")
+            )
+        );
+        assert_eq!(
+            error("(&& foo 1)"),
+            (
+                1,
+                String::from("int constant is given while bool expr is expected")
+            )
+        );
+        assert_eq!(
+            error("(int foo 0 1)"),
+            (6, String::from("variable foo is already declared"))
+        );
+        assert_eq!(
+            error("(objective minimize (+ x foo))"),
+            (
+                26,
+                String::from("bool var foo is given while int expr is expected")
+            )
+        );
+        assert_eq!(error("(bool 1)"), (1, String::from("identifier expected")));
+    }
 }
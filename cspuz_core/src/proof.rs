@@ -0,0 +1,603 @@
+//! DRAT proofs of unsatisfiability and a checker for them.
+//!
+//! When `Config::produce_proof` is set, the SAT solver writes the clauses it learns to a DRAT
+//! proof, which can be retrieved together with the CNF formula passed to the solver by
+//! `IntegratedSolver::proof` after the solver reported unsatisfiability.
+//!
+//! Custom propagators (such as graph division) are not expressed as clauses in the formula.
+//! Instead, the reason clause of every propagation and conflict they produce is recorded as a
+//! *theory lemma*, which is appended to the formula when the proof is checked. Theory lemmas are
+//! trusted, that is, the checker does not verify that they are implied by the constraints of the
+//! propagators. Since SAT solvers do not ask for the reasons of the propagations at the top level,
+//! the backends compute the reasons as soon as the propagations are made.
+//!
+//! If the unsatisfiability is detected before the SAT solver starts the search (e.g. by the
+//! normalizer, or by a propagator on its initialization), no proof is available.
+
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::fs::OpenOptions;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::sat::{CustomPropagator, Lit, SolverManipulator};
+
+/// Converts `lit` to the DIMACS representation, in which variables are 1-indexed.
+pub fn lit_to_dimacs(lit: Lit) -> i32 {
+    let var = lit.var().0 + 1;
+    if lit.is_negated() {
+        -var
+    } else {
+        var
+    }
+}
+
+/// A DRAT proof of the unsatisfiability of a CNF formula.
+pub struct Proof {
+    num_vars: usize,
+    clauses: Vec<Vec<Lit>>,
+    theory_lemmas: Vec<Vec<Lit>>,
+    drat: String,
+}
+
+impl Proof {
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// The clauses passed to the SAT solver.
+    pub fn clauses(&self) -> &[Vec<Lit>] {
+        &self.clauses
+    }
+
+    /// The reason clauses produced by custom propagators.
+    pub fn theory_lemmas(&self) -> &[Vec<Lit>] {
+        &self.theory_lemmas
+    }
+
+    /// The proof in the textual DRAT format.
+    pub fn drat(&self) -> &str {
+        &self.drat
+    }
+
+    /// Returns the formula to be refuted, i.e. the clauses followed by the theory lemmas, in the DIMACS representation.
+    pub fn formula(&self) -> Vec<Vec<i32>> {
+        self.clauses
+            .iter()
+            .chain(self.theory_lemmas.iter())
+            .map(|clause| clause.iter().map(|&l| lit_to_dimacs(l)).collect())
+            .collect()
+    }
+
+    /// Writes the formula returned by `formula` in the DIMACS CNF format.
+    pub fn write_dimacs<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "c {} clauses and {} theory lemmas",
+            self.clauses.len(),
+            self.theory_lemmas.len()
+        )?;
+        writeln!(
+            out,
+            "p cnf {} {}",
+            self.num_vars,
+            self.clauses.len() + self.theory_lemmas.len()
+        )?;
+        for clause in self.formula() {
+            for l in clause {
+                write!(out, "{} ", l)?;
+            }
+            writeln!(out, "0")?;
+        }
+        Ok(())
+    }
+
+    pub fn write_drat<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        out.write_all(self.drat.as_bytes())
+    }
+
+    /// Checks the proof with `check_drat`.
+    pub fn check(&self) -> Result<(), String> {
+        check_drat(self.num_vars, &self.formula(), &self.drat)
+    }
+}
+
+/// The error message of `proof` when the unsatisfiability is detected without the SAT solver.
+pub(crate) const REFUTED_BEFORE_SAT_SOLVING: &str =
+    "unsatisfiability detected before SAT solving; no proof available";
+
+static PROOF_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Records what is needed to build a `Proof` in a SAT solver backend.
+///
+/// The backend writes the DRAT proof to the file at `path`, which is removed when the log is dropped.
+pub(crate) struct ProofLog {
+    path: PathBuf,
+    clauses: Vec<Vec<Lit>>,
+    theory_lemmas: Rc<RefCell<Vec<Vec<Lit>>>>,
+    refuted_before_solving: bool,
+}
+
+impl ProofLog {
+    /// Creates an empty file for the proof in the temporary directory.
+    ///
+    /// The file name contains a random part, and the file is created only if it does not exist yet,
+    /// so that other users cannot make the backend write the proof to a file of their choice.
+    pub(crate) fn new() -> std::io::Result<ProofLog> {
+        let id = PROOF_FILE_ID.fetch_add(1, Ordering::Relaxed);
+        loop {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_usize(id);
+            let path = std::env::temp_dir().join(format!(
+                "cspuz_core_proof_{}_{}_{:016x}.drat",
+                std::process::id(),
+                id,
+                hasher.finish()
+            ));
+
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            match options.open(&path) {
+                Ok(_) => {
+                    return Ok(ProofLog {
+                        path,
+                        clauses: vec![],
+                        theory_lemmas: Rc::new(RefCell::new(vec![])),
+                        refuted_before_solving: false,
+                    })
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn add_clause(&mut self, clause: &[Lit]) {
+        self.clauses.push(clause.to_vec());
+    }
+
+    /// Marks that the unsatisfiability was detected without the search of the SAT solver (e.g. a
+    /// propagator found a conflict on its initialization). After this, `proof` always fails.
+    pub(crate) fn mark_refuted_before_solving(&mut self) {
+        self.refuted_before_solving = true;
+    }
+
+    /// Wraps `propagator` so that the reason clauses it produces are recorded as theory lemmas.
+    pub(crate) fn wrap_propagator<T: SolverManipulator + 'static>(
+        &self,
+        propagator: Box<dyn CustomPropagator<T>>,
+    ) -> Box<dyn CustomPropagator<T>> {
+        Box::new(ReasonLogger {
+            inner: propagator,
+            theory_lemmas: self.theory_lemmas.clone(),
+        })
+    }
+
+    /// Builds a `Proof` from the proof written so far.
+    /// The backend must flush the proof file before calling this.
+    pub(crate) fn proof(&self, num_vars: usize) -> Result<Proof, String> {
+        if self.refuted_before_solving {
+            return Err(String::from(REFUTED_BEFORE_SAT_SOLVING));
+        }
+        let drat = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("failed to read the proof: {}", e))?;
+        Ok(Proof {
+            num_vars,
+            clauses: self.clauses.clone(),
+            theory_lemmas: self.theory_lemmas.borrow().clone(),
+            drat,
+        })
+    }
+}
+
+impl Drop for ProofLog {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+struct ReasonLogger<T: SolverManipulator> {
+    inner: Box<dyn CustomPropagator<T>>,
+    theory_lemmas: Rc<RefCell<Vec<Vec<Lit>>>>,
+}
+
+unsafe impl<T: SolverManipulator> CustomPropagator<T> for ReasonLogger<T> {
+    fn initialize(&mut self, solver: &mut T) -> bool {
+        self.inner.initialize(solver)
+    }
+
+    fn propagate(&mut self, solver: &mut T, p: Lit, num_pending_propagations: i32) -> bool {
+        self.inner.propagate(solver, p, num_pending_propagations)
+    }
+
+    fn calc_reason(&mut self, solver: &mut T, p: Option<Lit>, extra: Option<Lit>) -> Vec<Lit> {
+        let reason = self.inner.calc_reason(solver, p, extra);
+
+        // `reason` implies `p` (or a conflict if `p` is `None`).
+        let mut lemma = vec![];
+        if let Some(p) = p {
+            lemma.push(p);
+        }
+        lemma.extend(reason.iter().map(|&l| !l));
+        self.theory_lemmas.borrow_mut().push(lemma);
+
+        reason
+    }
+
+    fn undo(&mut self, solver: &mut T, p: Lit) {
+        self.inner.undo(solver, p)
+    }
+}
+
+/// Checks that `proof`, a proof in the textual DRAT format, refutes the CNF formula `formula`
+/// whose literals are in the DIMACS representation.
+///
+/// Lemmas are checked in the forward direction: each lemma must be RUP (reverse unit propagation)
+/// or RAT (resolution asymmetric tautology) on its first literal with respect to the formula and the
+/// lemmas before it. After all the lemmas are added, the empty clause must follow by unit propagation.
+/// Deletions of unit clauses are ignored, as in DRAT-trim.
+pub fn check_drat(num_vars: usize, formula: &[Vec<i32>], proof: &str) -> Result<(), String> {
+    let mut checker = DratChecker::new(num_vars);
+    for clause in formula {
+        checker.add_clause(clause);
+    }
+
+    for (line_no, line) in proof.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('c') {
+            continue;
+        }
+        let (is_deletion, body) = match line.strip_prefix('d') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        let mut lits = vec![];
+        let mut terminated = false;
+        for token in body.split_whitespace() {
+            if terminated {
+                return Err(format!("line {}: extra tokens after 0", line_no + 1));
+            }
+            let lit = token
+                .parse::<i32>()
+                .map_err(|_| format!("line {}: invalid literal `{}`", line_no + 1, token))?;
+            if lit == 0 {
+                terminated = true;
+            } else {
+                lits.push(lit);
+            }
+        }
+        if !terminated {
+            return Err(format!(
+                "line {}: clause is not terminated by 0",
+                line_no + 1
+            ));
+        }
+
+        if is_deletion {
+            checker.delete_clause(&lits);
+        } else {
+            if !checker.is_implied(&lits) {
+                return Err(format!(
+                    "line {}: lemma is neither RUP nor RAT: {:?}",
+                    line_no + 1,
+                    lits
+                ));
+            }
+            checker.add_clause(&lits);
+        }
+    }
+
+    if checker.is_implied(&[]) {
+        Ok(())
+    } else {
+        Err(String::from("the proof does not derive the empty clause"))
+    }
+}
+
+fn lit_index(lit: i32) -> usize {
+    ((lit.unsigned_abs() as usize - 1) << 1) | (lit < 0) as usize
+}
+
+/// A clause database with unit propagation based on two watched literals.
+///
+/// Assignments derived from the clauses are kept at the "top level", while assignments made for checking
+/// a lemma are undone after the check.
+struct DratChecker {
+    clauses: Vec<Vec<i32>>,
+    deleted: Vec<bool>,
+    // Indices of the clauses watched by each literal; a clause is watched by its first two literals.
+    watches: Vec<Vec<usize>>,
+    // Indices of the (non-tautological) clauses keyed by their sorted literals, for deletion.
+    clause_ids: std::collections::HashMap<Vec<i32>, Vec<usize>>,
+    value: Vec<Option<bool>>,
+    trail: Vec<i32>,
+    propagated: usize,
+    inconsistent: bool,
+}
+
+impl DratChecker {
+    fn new(num_vars: usize) -> DratChecker {
+        let mut ret = DratChecker {
+            clauses: vec![],
+            deleted: vec![],
+            watches: vec![],
+            clause_ids: std::collections::HashMap::new(),
+            value: vec![],
+            trail: vec![],
+            propagated: 0,
+            inconsistent: false,
+        };
+        ret.reserve_vars(num_vars);
+        ret
+    }
+
+    fn reserve_vars(&mut self, num_vars: usize) {
+        if self.value.len() < num_vars {
+            self.value.resize(num_vars, None);
+            self.watches.resize(num_vars * 2, vec![]);
+        }
+    }
+
+    fn lit_value(&self, lit: i32) -> Option<bool> {
+        self.value[lit.unsigned_abs() as usize - 1].map(|v| v == (lit > 0))
+    }
+
+    fn assign(&mut self, lit: i32) {
+        self.value[lit.unsigned_abs() as usize - 1] = Some(lit > 0);
+        self.trail.push(lit);
+    }
+
+    fn normalize(&mut self, clause: &[i32]) -> Option<Vec<i32>> {
+        let mut clause = clause.to_vec();
+        clause.sort_unstable();
+        clause.dedup();
+        for i in 1..clause.len() {
+            if clause[i - 1] == -clause[i] {
+                return None;
+            }
+        }
+        if let Some(max_var) = clause.iter().map(|l| l.unsigned_abs() as usize).max() {
+            self.reserve_vars(max_var);
+        }
+        Some(clause)
+    }
+
+    /// Adds `clause` at the top level.
+    fn add_clause(&mut self, clause: &[i32]) {
+        let Some(mut clause) = self.normalize(clause) else {
+            // tautologies never take part in propagation
+            return;
+        };
+        if self.inconsistent {
+            return;
+        }
+
+        let id = self.clauses.len();
+        self.clause_ids.entry(clause.clone()).or_default().push(id);
+
+        // Move non-false literals to the front so that they are watched.
+        clause.sort_by_key(|&l| self.lit_value(l) == Some(false));
+        let num_non_false = clause
+            .iter()
+            .filter(|&&l| self.lit_value(l) != Some(false))
+            .count();
+
+        if clause.len() >= 2 {
+            self.watches[lit_index(-clause[0])].push(id);
+            self.watches[lit_index(-clause[1])].push(id);
+        }
+        let first = clause.first().copied();
+        self.clauses.push(clause);
+        self.deleted.push(false);
+
+        match (num_non_false, first) {
+            (0, _) => self.inconsistent = true,
+            (1, Some(lit)) => {
+                if self.lit_value(lit).is_none() {
+                    self.assign(lit);
+                }
+                if !self.propagate() {
+                    self.inconsistent = true;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn delete_clause(&mut self, clause: &[i32]) {
+        let Some(clause) = self.normalize(clause) else {
+            return;
+        };
+        if clause.len() <= 1 {
+            return;
+        }
+        if let Some(ids) = self.clause_ids.get_mut(&clause) {
+            if let Some(id) = ids.pop() {
+                self.deleted[id] = true;
+            }
+        }
+    }
+
+    /// Propagates the assignments in `trail` from `propagated`. Returns `false` on a conflict.
+    fn propagate(&mut self) -> bool {
+        while self.propagated < self.trail.len() {
+            let lit = self.trail[self.propagated];
+            self.propagated += 1;
+
+            // Clauses watching `!lit`, which is now false
+            let watchers = std::mem::take(&mut self.watches[lit_index(lit)]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = false;
+
+            for (i, &id) in watchers.iter().enumerate() {
+                if conflict {
+                    kept.extend_from_slice(&watchers[i..]);
+                    break;
+                }
+                if self.deleted[id] {
+                    continue;
+                }
+                let clause = &mut self.clauses[id];
+                if clause[0] == -lit {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+                if self.lit_value(other) == Some(true) {
+                    kept.push(id);
+                    continue;
+                }
+
+                let clause = &self.clauses[id];
+                let replacement =
+                    (2..clause.len()).find(|&j| self.lit_value(clause[j]) != Some(false));
+                if let Some(j) = replacement {
+                    let clause = &mut self.clauses[id];
+                    clause.swap(1, j);
+                    let new_watch = clause[1];
+                    self.watches[lit_index(-new_watch)].push(id);
+                    continue;
+                }
+
+                kept.push(id);
+                match self.lit_value(other) {
+                    None => self.assign(other),
+                    Some(false) => conflict = true,
+                    Some(true) => unreachable!(),
+                }
+            }
+
+            self.watches[lit_index(lit)] = kept;
+            if conflict {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Undoes the assignments after the first `trail_size` ones.
+    fn backtrack(&mut self, trail_size: usize) {
+        while self.trail.len() > trail_size {
+            let lit = self.trail.pop().unwrap();
+            self.value[lit.unsigned_abs() as usize - 1] = None;
+        }
+        self.propagated = trail_size;
+    }
+
+    /// Returns whether assuming the negation of all literals in `clause` leads to a conflict.
+    fn is_rup(&mut self, clause: &[i32]) -> bool {
+        if self.inconsistent {
+            return true;
+        }
+        let trail_size = self.trail.len();
+        let mut conflict = false;
+        for &lit in clause {
+            match self.lit_value(lit) {
+                Some(true) => {
+                    conflict = true;
+                    break;
+                }
+                Some(false) => (),
+                None => self.assign(-lit),
+            }
+        }
+        if !conflict {
+            conflict = !self.propagate();
+        }
+        self.backtrack(trail_size);
+        conflict
+    }
+
+    fn is_implied(&mut self, clause: &[i32]) -> bool {
+        // The pivot of RAT is the first literal in the original order, which is lost by `normalize`.
+        let pivot = clause.first().copied();
+        let Some(clause) = self.normalize(clause) else {
+            return true;
+        };
+        if self.is_rup(&clause) {
+            return true;
+        }
+        match pivot {
+            Some(pivot) => self.is_rat(&clause, pivot),
+            None => false,
+        }
+    }
+
+    fn is_rat(&mut self, clause: &[i32], pivot: i32) -> bool {
+        let candidates = (0..self.clauses.len())
+            .filter(|&id| !self.deleted[id] && self.clauses[id].contains(&-pivot))
+            .collect::<Vec<_>>();
+        for id in candidates {
+            let mut resolvent = clause.to_vec();
+            resolvent.extend(self.clauses[id].iter().filter(|&&l| l != -pivot));
+            match self.normalize(&resolvent) {
+                None => continue,
+                Some(resolvent) => {
+                    if !self.is_rup(&resolvent) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pigeonhole_2_1() -> Vec<Vec<i32>> {
+        // two pigeons (1, 2) in one hole
+        vec![vec![1], vec![2], vec![-1, -2]]
+    }
+
+    #[test]
+    fn test_check_drat_trivial() {
+        assert!(check_drat(2, &pigeonhole_2_1(), "").is_ok());
+        assert!(check_drat(2, &pigeonhole_2_1(), "0\n").is_ok());
+    }
+
+    #[test]
+    fn test_check_drat_rup() {
+        // (x1 | x2), (x1 | !x2), (!x1 | x2), (!x1 | !x2)
+        let formula = vec![vec![1, 2], vec![1, -2], vec![-1, 2], vec![-1, -2]];
+        assert!(check_drat(2, &formula, "1 0\n0\n").is_ok());
+        assert!(check_drat(2, &formula, "1 0\nd 1 2 0\nd 1 -2 0\n0\n").is_ok());
+        assert!(check_drat(2, &formula, "").is_err());
+
+        // After deleting (x1 | !x2), `x1` is no longer RUP.
+        assert!(check_drat(2, &formula, "d 1 -2 0\n1 0\n0\n").is_err());
+    }
+
+    #[test]
+    fn test_check_drat_rat() {
+        let mut checker = DratChecker::new(3);
+        checker.add_clause(&[1, 2]);
+        checker.add_clause(&[-1, -2]);
+
+        // `x3` does not occur in the formula, so `(x3 | x1)` is RAT on `x3` (but not on `x1`).
+        assert!(!checker.is_rup(&[1, 3]));
+        assert!(checker.is_implied(&[3, 1]));
+        assert!(!checker.is_implied(&[1, 3]));
+    }
+
+    #[test]
+    fn test_check_drat_invalid() {
+        let formula = vec![vec![1, 2], vec![-1, 2], vec![1, -2]];
+        // The formula is satisfiable (x1 = x2 = true), so no proof is accepted.
+        assert!(check_drat(2, &formula, "2 0\n1 0\n0\n").is_err());
+        assert!(check_drat(2, &formula, "-1 0\n").is_err());
+        assert!(check_drat(2, &formula, "1 2").is_err());
+        assert!(check_drat(2, &formula, "1 x 0").is_err());
+    }
+}
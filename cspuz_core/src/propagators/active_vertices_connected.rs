@@ -0,0 +1,211 @@
+use crate::sat::{CustomPropagator, Lit, SolverManipulator};
+
+enum UndoEntry {
+    Assigned(usize),
+    Reason,
+}
+
+/// Propagator requiring that the active vertices of a graph are connected.
+///
+/// This only checks the connectivity through the vertices which are not inactive, and forces the
+/// vertices unreachable from the active ones to be inactive. Unlike the C++ propagator, it does not
+/// detect articulation points, so it is weaker but simple enough to give the reason of every
+/// propagation at any time (which is needed for proofs).
+pub struct ActiveVerticesConnected {
+    lits: Vec<Lit>,
+    adj: Vec<Vec<usize>>,
+    // watched literals with the vertex id and the value of the vertex when the literal becomes true
+    watches: Vec<(Lit, usize, bool)>,
+    state: Vec<Option<bool>>,
+    undo_list: Vec<Option<UndoEntry>>,
+    reasons: Vec<(Lit, Vec<Lit>)>,
+    propagation_failure: Option<Vec<Lit>>,
+}
+
+impl ActiveVerticesConnected {
+    pub fn new(lits: Vec<Lit>, edges: Vec<(usize, usize)>) -> ActiveVerticesConnected {
+        let num_vertices = lits.len();
+        let mut adj = vec![vec![]; num_vertices];
+        for &(u, v) in &edges {
+            assert!(u < num_vertices && v < num_vertices);
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+
+        let mut watches = vec![];
+        for (i, &lit) in lits.iter().enumerate() {
+            watches.push((lit, i, true));
+            watches.push((!lit, i, false));
+        }
+        watches.sort();
+
+        ActiveVerticesConnected {
+            lits,
+            adj,
+            watches,
+            state: vec![None; num_vertices],
+            undo_list: vec![],
+            reasons: vec![],
+            propagation_failure: None,
+        }
+    }
+
+    /// The literal (which is currently true) representing the current state of vertex `v`.
+    fn state_lit(&self, v: usize) -> Lit {
+        match self.state[v] {
+            Some(true) => self.lits[v],
+            Some(false) => !self.lits[v],
+            None => panic!(),
+        }
+    }
+
+    /// Enqueues the inactivity of vertex `v` because of `reason`.
+    /// Returns `false` if the vertex is already active.
+    fn enqueue_inactive<T: SolverManipulator>(
+        &mut self,
+        solver: &mut T,
+        v: usize,
+        reason: &[Lit],
+    ) -> bool {
+        let lit = !self.lits[v];
+        if unsafe { solver.value(lit) } == Some(true) {
+            return true;
+        }
+        if !unsafe { solver.enqueue(lit) } {
+            self.propagation_failure = Some(reason.to_vec());
+            return false;
+        }
+        self.reasons.push((lit, reason.to_vec()));
+        self.undo_list.push(Some(UndoEntry::Reason));
+        true
+    }
+
+    /// Checks that all the active vertices can be connected through vertices which are not inactive.
+    fn check<T: SolverManipulator>(&mut self, solver: &mut T) -> bool {
+        let num_vertices = self.lits.len();
+        let Some(root) = (0..num_vertices).find(|&v| self.state[v] == Some(true)) else {
+            return true;
+        };
+
+        let mut reached = vec![false; num_vertices];
+        let mut stack = vec![root];
+        reached[root] = true;
+        while let Some(u) = stack.pop() {
+            for &v in &self.adj[u] {
+                if self.state[v] != Some(false) && !reached[v] {
+                    reached[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+
+        let unreached = (0..num_vertices)
+            .filter(|&v| !reached[v])
+            .collect::<Vec<_>>();
+        if unreached.is_empty() {
+            return true;
+        }
+
+        // The component of `root` is separated from the other vertices by the inactive vertices around it.
+        let mut reason = vec![self.state_lit(root)];
+        for u in (0..num_vertices).filter(|&u| reached[u]) {
+            for &v in &self.adj[u] {
+                if self.state[v] == Some(false) {
+                    reason.push(self.state_lit(v));
+                }
+            }
+        }
+        reason.sort();
+        reason.dedup();
+
+        for v in unreached {
+            match self.state[v] {
+                Some(true) => {
+                    reason.push(self.state_lit(v));
+                    self.propagation_failure = Some(reason);
+                    return false;
+                }
+                Some(false) => (),
+                None => {
+                    if !self.enqueue_inactive(solver, v, &reason) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+unsafe impl<T: SolverManipulator> CustomPropagator<T> for ActiveVerticesConnected {
+    fn initialize(&mut self, solver: &mut T) -> bool {
+        for i in 0..self.watches.len() {
+            let lit = self.watches[i].0;
+            if i > 0 && self.watches[i - 1].0 == lit {
+                continue;
+            }
+            unsafe {
+                solver.add_watch(lit);
+            }
+        }
+
+        for &(lit, v, value) in &self.watches {
+            if unsafe { solver.value(lit) } == Some(true) {
+                self.state[v] = Some(value);
+            }
+        }
+
+        self.check(solver)
+    }
+
+    fn propagate(&mut self, solver: &mut T, p: Lit, num_pending_propagations: i32) -> bool {
+        self.undo_list.push(None);
+        self.propagation_failure = None;
+
+        let mut idx = self.watches.partition_point(|&(lit, _, _)| lit < p);
+        while idx < self.watches.len() && self.watches[idx].0 == p {
+            let (_, v, value) = self.watches[idx];
+            idx += 1;
+            match self.state[v] {
+                Some(s) => assert_eq!(s, value),
+                None => {
+                    self.state[v] = Some(value);
+                    self.undo_list.push(Some(UndoEntry::Assigned(v)));
+                }
+            }
+        }
+
+        if num_pending_propagations > 0 {
+            return true;
+        }
+        self.check(solver)
+    }
+
+    fn calc_reason(&mut self, _: &mut T, p: Option<Lit>, extra: Option<Lit>) -> Vec<Lit> {
+        let mut ret = if let Some(p) = p {
+            self.reasons
+                .iter()
+                .rev()
+                .find(|(lit, _)| *lit == p)
+                .unwrap()
+                .1
+                .clone()
+        } else {
+            self.propagation_failure.clone().unwrap()
+        };
+        ret.extend(extra);
+        ret
+    }
+
+    fn undo(&mut self, _: &mut T, _: Lit) {
+        while let Some(entry) = self.undo_list.pop().unwrap() {
+            match entry {
+                UndoEntry::Assigned(v) => self.state[v] = None,
+                UndoEntry::Reason => {
+                    self.reasons.pop();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use crate::sat::{CustomPropagator, Lit, SolverManipulator};
+
+enum UndoEntry {
+    Excluded(usize, usize),
+    Reason,
+}
+
+/// Propagator for all-different constraints over direct-encoded variables.
+///
+/// `vars[i][j]` is the literal meaning that the `i`-th variable takes the value `values[i][j]`.
+/// Filtering is based on Hall intervals: if the candidates of `k` variables are contained in an
+/// interval of `k` values, the other variables cannot take any value in the interval.
+/// On each propagation, only the intervals containing the candidates of a variable whose candidates
+/// were reduced since the last check are examined, since other intervals cannot become Hall intervals.
+pub struct DirectEncodingAllDifferent {
+    vars: Vec<Vec<Lit>>,
+    // index of `values[i][j]` in the sorted list of all values
+    value_idx: Vec<Vec<usize>>,
+    num_values: usize,
+    lits: Vec<(Lit, usize, usize)>,
+    excluded: Vec<Vec<bool>>,
+    // whether a candidate of each variable was excluded since the last check
+    touched: Vec<bool>,
+    undo_list: Vec<Option<UndoEntry>>,
+    reasons: Vec<(Lit, Vec<Lit>)>,
+    propagation_failure: Option<Vec<Lit>>,
+}
+
+impl DirectEncodingAllDifferent {
+    pub fn new(vars: Vec<Vec<Lit>>, values: Vec<Vec<i32>>) -> DirectEncodingAllDifferent {
+        assert_eq!(vars.len(), values.len());
+        for i in 0..vars.len() {
+            assert_eq!(vars[i].len(), values[i].len());
+        }
+
+        let mut all_values = values.iter().flatten().copied().collect::<Vec<_>>();
+        all_values.sort();
+        all_values.dedup();
+        let value_idx = values
+            .iter()
+            .map(|v| {
+                v.iter()
+                    .map(|x| all_values.binary_search(x).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut lits = vec![];
+        for (i, v) in vars.iter().enumerate() {
+            for (j, &lit) in v.iter().enumerate() {
+                lits.push((lit, i, j));
+            }
+        }
+        lits.sort();
+
+        let excluded = vars.iter().map(|v| vec![false; v.len()]).collect();
+        let touched = vec![false; vars.len()];
+
+        DirectEncodingAllDifferent {
+            vars,
+            value_idx,
+            num_values: all_values.len(),
+            lits,
+            excluded,
+            touched,
+            undo_list: vec![],
+            reasons: vec![],
+            propagation_failure: None,
+        }
+    }
+
+    /// Returns the smallest and the largest value indices of the remaining candidates of each variable.
+    fn candidate_bounds(&self) -> Vec<Option<(usize, usize)>> {
+        (0..self.vars.len())
+            .map(|i| {
+                let mut ret: Option<(usize, usize)> = None;
+                for j in 0..self.vars[i].len() {
+                    if self.excluded[i][j] {
+                        continue;
+                    }
+                    let v = self.value_idx[i][j];
+                    ret = Some(match ret {
+                        Some((lo, hi)) => (lo.min(v), hi.max(v)),
+                        None => (v, v),
+                    });
+                }
+                ret
+            })
+            .collect()
+    }
+
+    /// Collects the literals (which are currently true) excluding the candidates of the variables in
+    /// `hall_set` outside the interval `[lo, hi]` of value indices.
+    fn reason_hall_set(&self, hall_set: &[usize], lo: usize, hi: usize) -> Vec<Lit> {
+        let mut ret = vec![];
+        for &i in hall_set {
+            for j in 0..self.vars[i].len() {
+                let v = self.value_idx[i][j];
+                if self.excluded[i][j] && (v < lo || hi < v) {
+                    ret.push(!self.vars[i][j]);
+                }
+            }
+        }
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// Checks the intervals containing the candidates of the touched variables (all the variables if `full`).
+    fn check<T: SolverManipulator>(&mut self, solver: &mut T, full: bool) -> bool {
+        let bounds = self.candidate_bounds();
+        let targets = (0..self.vars.len())
+            .filter(|&i| full || self.touched[i])
+            .collect::<Vec<_>>();
+        self.touched.fill(false);
+
+        if let Some(&i) = targets.iter().find(|&&i| bounds[i].is_none()) {
+            self.propagation_failure = Some(self.vars[i].iter().map(|&lit| !lit).collect());
+            return false;
+        }
+
+        // min_hi[lo]: the smallest `hi` such that [lo, hi] contains the candidates of a target
+        let mut min_hi: Vec<Option<usize>> = vec![None; self.num_values + 1];
+        for &i in &targets {
+            let (l, h) = bounds[i].unwrap();
+            min_hi[l] = Some(min_hi[l].map_or(h, |x| x.min(h)));
+        }
+        for lo in (0..self.num_values).rev() {
+            if let Some(h) = min_hi[lo + 1] {
+                min_hi[lo] = Some(min_hi[lo].map_or(h, |x| x.min(h)));
+            }
+        }
+
+        for (lo, &start) in min_hi[..self.num_values].iter().enumerate() {
+            let Some(start) = start else {
+                continue;
+            };
+            for hi in start..self.num_values {
+                let hall_set = (0..self.vars.len())
+                    .filter(|&i| match bounds[i] {
+                        Some((l, h)) => lo <= l && h <= hi,
+                        None => false,
+                    })
+                    .collect::<Vec<_>>();
+                let width = hi - lo + 1;
+                if hall_set.len() > width {
+                    self.propagation_failure = Some(self.reason_hall_set(&hall_set, lo, hi));
+                    return false;
+                }
+                if hall_set.len() < width {
+                    continue;
+                }
+
+                let mut reason = None;
+                for i in 0..self.vars.len() {
+                    if hall_set.binary_search(&i).is_ok() {
+                        continue;
+                    }
+                    for j in 0..self.vars[i].len() {
+                        let v = self.value_idx[i][j];
+                        if self.excluded[i][j] || v < lo || hi < v {
+                            continue;
+                        }
+                        let lit = !self.vars[i][j];
+                        if unsafe { solver.value(lit) } == Some(true) {
+                            continue;
+                        }
+                        let reason = reason
+                            .get_or_insert_with(|| self.reason_hall_set(&hall_set, lo, hi))
+                            .clone();
+                        if !unsafe { solver.enqueue(lit) } {
+                            self.propagation_failure = Some(reason);
+                            return false;
+                        }
+                        self.reasons.push((lit, reason));
+                        self.undo_list.push(Some(UndoEntry::Reason));
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+unsafe impl<T: SolverManipulator> CustomPropagator<T> for DirectEncodingAllDifferent {
+    fn initialize(&mut self, solver: &mut T) -> bool {
+        let mut unique_watchers = self
+            .lits
+            .iter()
+            .map(|&(lit, _, _)| !lit)
+            .collect::<Vec<_>>();
+        unique_watchers.sort();
+        unique_watchers.dedup();
+
+        for &lit in &unique_watchers {
+            unsafe {
+                solver.add_watch(lit);
+            }
+        }
+
+        for &(lit, i, j) in &self.lits {
+            if unsafe { solver.value(!lit) } == Some(true) {
+                self.excluded[i][j] = true;
+            }
+        }
+
+        self.check(solver, true)
+    }
+
+    fn propagate(&mut self, solver: &mut T, p: Lit, num_pending_propagations: i32) -> bool {
+        self.undo_list.push(None);
+        self.propagation_failure = None;
+
+        let mut idx = self.lits.partition_point(|&(lit, _, _)| lit < !p);
+        while idx < self.lits.len() && self.lits[idx].0 == !p {
+            let (_, i, j) = self.lits[idx];
+            idx += 1;
+            if !self.excluded[i][j] {
+                self.excluded[i][j] = true;
+                self.touched[i] = true;
+                self.undo_list.push(Some(UndoEntry::Excluded(i, j)));
+            }
+        }
+
+        if num_pending_propagations > 0 {
+            return true;
+        }
+        self.check(solver, false)
+    }
+
+    fn calc_reason(&mut self, _: &mut T, p: Option<Lit>, extra: Option<Lit>) -> Vec<Lit> {
+        let mut ret = if let Some(p) = p {
+            self.reasons
+                .iter()
+                .rev()
+                .find(|(lit, _)| *lit == p)
+                .unwrap()
+                .1
+                .clone()
+        } else {
+            self.propagation_failure.clone().unwrap()
+        };
+        ret.extend(extra);
+        ret
+    }
+
+    fn undo(&mut self, _: &mut T, _: Lit) {
+        while let Some(entry) = self.undo_list.pop().unwrap() {
+            match entry {
+                UndoEntry::Excluded(i, j) => self.excluded[i][j] = false,
+                UndoEntry::Reason => {
+                    self.reasons.pop();
+                }
+            }
+        }
+    }
+}
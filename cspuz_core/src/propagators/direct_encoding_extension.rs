@@ -0,0 +1,193 @@
+use crate::sat::{CustomPropagator, Lit, SolverManipulator};
+
+/// Propagator for extension (supports) constraints over direct-encoded variables.
+///
+/// `vars[i][j]` is the literal meaning that the `i`-th variable takes its `j`-th value.
+/// Each support specifies the index of the value for each variable, where `None` matches any value.
+pub struct DirectEncodingExtensionSupports {
+    vars: Vec<Vec<Lit>>,
+    supports: Vec<Vec<Option<usize>>>,
+    lits: Vec<(Lit, usize, usize)>,
+    excluded: Vec<Vec<bool>>,
+    undo_list: Vec<Option<(usize, usize)>>,
+    propagation_failure: Option<(usize, usize)>,
+}
+
+impl DirectEncodingExtensionSupports {
+    pub fn new(
+        vars: Vec<Vec<Lit>>,
+        supports: Vec<Vec<Option<usize>>>,
+    ) -> DirectEncodingExtensionSupports {
+        for support in &supports {
+            assert_eq!(support.len(), vars.len());
+            for i in 0..vars.len() {
+                if let Some(j) = support[i] {
+                    assert!(j < vars[i].len());
+                }
+            }
+        }
+
+        let mut lits = vec![];
+        for (i, v) in vars.iter().enumerate() {
+            for (j, &lit) in v.iter().enumerate() {
+                lits.push((lit, i, j));
+            }
+        }
+        lits.sort();
+
+        let excluded = vars.iter().map(|v| vec![false; v.len()]).collect();
+
+        DirectEncodingExtensionSupports {
+            vars,
+            supports,
+            lits,
+            excluded,
+            undo_list: vec![],
+            propagation_failure: None,
+        }
+    }
+
+    /// Returns the literal (which is currently true) that excludes the `s`-th support, if any.
+    fn excluding_lit(&self, s: usize) -> Option<Lit> {
+        let support = &self.supports[s];
+        for i in 0..self.vars.len() {
+            if let Some(j) = support[i] {
+                if self.excluded[i][j] {
+                    return Some(!self.vars[i][j]);
+                }
+            }
+        }
+        None
+    }
+
+    fn is_compatible(&self, s: usize, i: usize, j: usize) -> bool {
+        match self.supports[s][i] {
+            Some(k) => j == k,
+            None => true,
+        }
+    }
+
+    /// Collects the literals excluding all the supports compatible with the `j`-th value of
+    /// the `i`-th variable (or all the supports if `target` is `None`).
+    fn reason_excluded(&self, target: Option<(usize, usize)>) -> Vec<Lit> {
+        let mut ret = vec![];
+        for s in 0..self.supports.len() {
+            if let Some((i, j)) = target {
+                if !self.is_compatible(s, i, j) {
+                    continue;
+                }
+            }
+            ret.push(self.excluding_lit(s).unwrap());
+        }
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+}
+
+unsafe impl<T: SolverManipulator> CustomPropagator<T> for DirectEncodingExtensionSupports {
+    fn initialize(&mut self, solver: &mut T) -> bool {
+        let mut unique_watchers = self
+            .lits
+            .iter()
+            .map(|&(lit, _, _)| !lit)
+            .collect::<Vec<_>>();
+        unique_watchers.sort();
+        unique_watchers.dedup();
+
+        for &lit in &unique_watchers {
+            unsafe {
+                solver.add_watch(lit);
+            }
+        }
+
+        for lit in unique_watchers {
+            if unsafe { solver.value(lit) } == Some(true) && !self.propagate(solver, lit, 0) {
+                return false;
+            }
+        }
+
+        (0..self.supports.len()).any(|s| self.excluding_lit(s).is_none())
+    }
+
+    fn propagate(&mut self, solver: &mut T, p: Lit, _num_pending_propagations: i32) -> bool {
+        self.undo_list.push(None);
+        self.propagation_failure = None;
+
+        let mut idx = self.lits.partition_point(|&(lit, _, _)| lit < !p);
+        while idx < self.lits.len() && self.lits[idx].0 == !p {
+            let (_, i, j) = self.lits[idx];
+            idx += 1;
+            if !self.excluded[i][j] {
+                self.excluded[i][j] = true;
+                self.undo_list.push(Some((i, j)));
+            }
+        }
+
+        let mut supported = self
+            .vars
+            .iter()
+            .map(|v| vec![false; v.len()])
+            .collect::<Vec<_>>();
+        let mut has_alive_support = false;
+        for s in 0..self.supports.len() {
+            if self.excluding_lit(s).is_some() {
+                continue;
+            }
+            has_alive_support = true;
+            for i in 0..self.vars.len() {
+                match self.supports[s][i] {
+                    Some(j) => supported[i][j] = true,
+                    None => supported[i].iter_mut().for_each(|x| *x = true),
+                }
+            }
+        }
+        if !has_alive_support {
+            return false;
+        }
+
+        for i in 0..self.vars.len() {
+            for j in 0..self.vars[i].len() {
+                if supported[i][j] || self.excluded[i][j] {
+                    continue;
+                }
+                if !unsafe { solver.enqueue(!self.vars[i][j]) } {
+                    self.propagation_failure = Some((i, j));
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn calc_reason(&mut self, _: &mut T, p: Option<Lit>, extra: Option<Lit>) -> Vec<Lit> {
+        let mut ret = if let Some(p) = p {
+            let mut idx = self.lits.partition_point(|&(lit, _, _)| lit < !p);
+            let mut target = None;
+            while idx < self.lits.len() && self.lits[idx].0 == !p {
+                let (_, i, j) = self.lits[idx];
+                idx += 1;
+                if (0..self.supports.len())
+                    .all(|s| !self.is_compatible(s, i, j) || self.excluding_lit(s).is_some())
+                {
+                    target = Some((i, j));
+                    break;
+                }
+            }
+            self.reason_excluded(Some(target.unwrap()))
+        } else if let Some((i, j)) = self.propagation_failure {
+            self.reason_excluded(Some((i, j)))
+        } else {
+            self.reason_excluded(None)
+        };
+        ret.extend(extra);
+        ret
+    }
+
+    fn undo(&mut self, _: &mut T, _: Lit) {
+        while let Some((i, j)) = self.undo_list.pop().unwrap() {
+            self.excluded[i][j] = false;
+        }
+    }
+}
@@ -1,2 +1,8 @@
+pub mod active_vertices_connected;
+#[cfg(feature = "csp-extra-constraints")]
+pub mod all_different;
+#[cfg(feature = "csp-extra-constraints")]
+pub mod direct_encoding_extension;
 pub mod graph_division;
 pub mod order_encoding_linear;
+pub mod single_cycle;
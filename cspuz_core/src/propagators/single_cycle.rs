@@ -0,0 +1,338 @@
+use crate::sat::{CustomPropagator, Lit, SolverManipulator};
+
+enum UndoEntry {
+    Assigned(usize),
+    Reason,
+}
+
+/// Propagator requiring that the active edges of a graph are connected and that each vertex is
+/// incident to at most two active edges, that is, the active edges form a single path or cycle.
+/// Combined with constraints forbidding vertices of degree 1, this means a single cycle.
+///
+/// Besides the connectivity through non-inactive edges, this detects the cycles which are closed
+/// while other edges are active (subtours) and forbids edges closing a path prematurely.
+pub struct ActiveEdgesSingleCycle {
+    edges: Vec<(usize, usize)>,
+    edge_lits: Vec<Lit>,
+    // (edge id, the other endpoint) for each vertex
+    adj: Vec<Vec<(usize, usize)>>,
+    // watched literals with the edge id and the value of the edge when the literal becomes true
+    watches: Vec<(Lit, usize, bool)>,
+    state: Vec<Option<bool>>,
+    undo_list: Vec<Option<UndoEntry>>,
+    reasons: Vec<(Lit, Vec<Lit>)>,
+    propagation_failure: Option<Vec<Lit>>,
+}
+
+impl ActiveEdgesSingleCycle {
+    pub fn new(edge_lits: Vec<Lit>, edges: Vec<(usize, usize)>) -> ActiveEdgesSingleCycle {
+        assert_eq!(edge_lits.len(), edges.len());
+
+        let num_vertices = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+        let mut adj = vec![vec![]; num_vertices];
+        for (i, &(u, v)) in edges.iter().enumerate() {
+            assert_ne!(u, v);
+            adj[u].push((i, v));
+            adj[v].push((i, u));
+        }
+
+        let mut watches = vec![];
+        for (i, &lit) in edge_lits.iter().enumerate() {
+            watches.push((lit, i, true));
+            watches.push((!lit, i, false));
+        }
+        watches.sort();
+
+        let num_edges = edges.len();
+        ActiveEdgesSingleCycle {
+            edges,
+            edge_lits,
+            adj,
+            watches,
+            state: vec![None; num_edges],
+            undo_list: vec![],
+            reasons: vec![],
+            propagation_failure: None,
+        }
+    }
+
+    /// The literal (which is currently true) representing the current state of edge `e`.
+    fn state_lit(&self, e: usize) -> Lit {
+        match self.state[e] {
+            Some(true) => self.edge_lits[e],
+            Some(false) => !self.edge_lits[e],
+            None => panic!(),
+        }
+    }
+
+    /// Enqueues the inactivity of edge `e` because of `reason`.
+    /// Returns `false` if the edge is already active.
+    fn enqueue_inactive<T: SolverManipulator>(
+        &mut self,
+        solver: &mut T,
+        e: usize,
+        reason: &[Lit],
+    ) -> bool {
+        let lit = !self.edge_lits[e];
+        if unsafe { solver.value(lit) } == Some(true) {
+            return true;
+        }
+        if !unsafe { solver.enqueue(lit) } {
+            self.propagation_failure = Some(reason.to_vec());
+            return false;
+        }
+        self.reasons.push((lit, reason.to_vec()));
+        self.undo_list.push(Some(UndoEntry::Reason));
+        true
+    }
+
+    fn check_degree<T: SolverManipulator>(&mut self, solver: &mut T) -> bool {
+        for u in 0..self.adj.len() {
+            let active = self.adj[u]
+                .iter()
+                .filter(|&&(e, _)| self.state[e] == Some(true))
+                .map(|&(e, _)| e)
+                .collect::<Vec<_>>();
+            if active.len() > 2 {
+                self.propagation_failure =
+                    Some(active[..3].iter().map(|&e| self.state_lit(e)).collect());
+                return false;
+            }
+            if active.len() < 2 {
+                continue;
+            }
+            let reason = active
+                .iter()
+                .map(|&e| self.state_lit(e))
+                .collect::<Vec<_>>();
+            for i in 0..self.adj[u].len() {
+                let e = self.adj[u][i].0;
+                if self.state[e].is_none() && !self.enqueue_inactive(solver, e, &reason) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Checks the components formed by active edges. Assumes that no vertex has three or more
+    /// active edges.
+    fn check_components<T: SolverManipulator>(&mut self, solver: &mut T) -> bool {
+        let num_edges = self.edges.len();
+        let mut component = vec![None; num_edges];
+        let mut components: Vec<Vec<usize>> = vec![];
+
+        for e in 0..num_edges {
+            if self.state[e] != Some(true) || component[e].is_some() {
+                continue;
+            }
+            let id = components.len();
+            let mut members = vec![e];
+            component[e] = Some(id);
+            let mut idx = 0;
+            while idx < members.len() {
+                let (u, v) = self.edges[members[idx]];
+                idx += 1;
+                for w in [u, v] {
+                    for &(f, _) in &self.adj[w] {
+                        if self.state[f] == Some(true) && component[f].is_none() {
+                            component[f] = Some(id);
+                            members.push(f);
+                        }
+                    }
+                }
+            }
+            components.push(members);
+        }
+
+        for id in 0..components.len() {
+            let members = &components[id];
+            let other_active = (0..components.len())
+                .find(|&j| j != id)
+                .map(|j| self.state_lit(components[j][0]));
+
+            // endpoints of the path (vertices with only one active edge)
+            let mut endpoints = vec![];
+            for &e in members {
+                let (u, v) = self.edges[e];
+                for w in [u, v] {
+                    let deg = self.adj[w]
+                        .iter()
+                        .filter(|&&(f, _)| self.state[f] == Some(true))
+                        .count();
+                    if deg == 1 {
+                        endpoints.push(w);
+                    }
+                }
+            }
+
+            let mut reason = members
+                .iter()
+                .map(|&e| self.state_lit(e))
+                .collect::<Vec<_>>();
+            if endpoints.is_empty() {
+                // closed cycle: no other edge can be active
+                if let Some(lit) = other_active {
+                    reason.push(lit);
+                    self.propagation_failure = Some(reason);
+                    return false;
+                }
+                for e in 0..num_edges {
+                    if self.state[e].is_none() && !self.enqueue_inactive(solver, e, &reason) {
+                        return false;
+                    }
+                }
+            } else if let Some(lit) = other_active {
+                // closing the path would leave the other active edges disconnected
+                assert_eq!(endpoints.len(), 2);
+                reason.push(lit);
+                let (a, b) = (endpoints[0], endpoints[1]);
+                for i in 0..self.adj[a].len() {
+                    let (e, w) = self.adj[a][i];
+                    if w == b
+                        && self.state[e].is_none()
+                        && !self.enqueue_inactive(solver, e, &reason)
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Checks that all the active edges can be connected through edges which are not inactive.
+    fn check_connectivity<T: SolverManipulator>(&mut self, solver: &mut T) -> bool {
+        let num_edges = self.edges.len();
+        let Some(root) = (0..num_edges).find(|&e| self.state[e] == Some(true)) else {
+            return true;
+        };
+
+        let mut reached = vec![false; self.adj.len()];
+        let mut stack = vec![self.edges[root].0, self.edges[root].1];
+        reached[self.edges[root].0] = true;
+        reached[self.edges[root].1] = true;
+        while let Some(u) = stack.pop() {
+            for &(e, v) in &self.adj[u] {
+                if self.state[e] != Some(false) && !reached[v] {
+                    reached[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+
+        let unreached = (0..num_edges)
+            .filter(|&e| !reached[self.edges[e].0] && !reached[self.edges[e].1])
+            .collect::<Vec<_>>();
+        if unreached.is_empty() {
+            return true;
+        }
+
+        let mut reason = vec![self.state_lit(root)];
+        for e in 0..num_edges {
+            let (u, v) = self.edges[e];
+            if self.state[e] == Some(false) && reached[u] != reached[v] {
+                reason.push(self.state_lit(e));
+            }
+        }
+
+        for e in unreached {
+            match self.state[e] {
+                Some(true) => {
+                    reason.push(self.state_lit(e));
+                    self.propagation_failure = Some(reason);
+                    return false;
+                }
+                Some(false) => (),
+                None => {
+                    if !self.enqueue_inactive(solver, e, &reason) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    fn check<T: SolverManipulator>(&mut self, solver: &mut T) -> bool {
+        self.check_degree(solver)
+            && self.check_components(solver)
+            && self.check_connectivity(solver)
+    }
+}
+
+unsafe impl<T: SolverManipulator> CustomPropagator<T> for ActiveEdgesSingleCycle {
+    fn initialize(&mut self, solver: &mut T) -> bool {
+        for i in 0..self.watches.len() {
+            let lit = self.watches[i].0;
+            if i > 0 && self.watches[i - 1].0 == lit {
+                continue;
+            }
+            unsafe {
+                solver.add_watch(lit);
+            }
+        }
+
+        for &(lit, e, value) in &self.watches {
+            if unsafe { solver.value(lit) } == Some(true) {
+                self.state[e] = Some(value);
+            }
+        }
+
+        self.check(solver)
+    }
+
+    fn propagate(&mut self, solver: &mut T, p: Lit, num_pending_propagations: i32) -> bool {
+        self.undo_list.push(None);
+        self.propagation_failure = None;
+
+        let mut idx = self.watches.partition_point(|&(lit, _, _)| lit < p);
+        while idx < self.watches.len() && self.watches[idx].0 == p {
+            let (_, e, value) = self.watches[idx];
+            idx += 1;
+            match self.state[e] {
+                Some(v) => assert_eq!(v, value),
+                None => {
+                    self.state[e] = Some(value);
+                    self.undo_list.push(Some(UndoEntry::Assigned(e)));
+                }
+            }
+        }
+
+        if num_pending_propagations > 0 {
+            return true;
+        }
+        self.check(solver)
+    }
+
+    fn calc_reason(&mut self, _: &mut T, p: Option<Lit>, extra: Option<Lit>) -> Vec<Lit> {
+        let mut ret = if let Some(p) = p {
+            self.reasons
+                .iter()
+                .rev()
+                .find(|(lit, _)| *lit == p)
+                .unwrap()
+                .1
+                .clone()
+        } else {
+            self.propagation_failure.clone().unwrap()
+        };
+        ret.sort();
+        ret.dedup();
+        ret.extend(extra);
+        ret
+    }
+
+    fn undo(&mut self, _: &mut T, _: Lit) {
+        while let Some(entry) = self.undo_list.pop().unwrap() {
+            match entry {
+                UndoEntry::Assigned(e) => self.state[e] = None,
+                UndoEntry::Reason => {
+                    self.reasons.pop();
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,23 @@
 use std::ops::Not;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "backend-cadical")]
 use crate::backend::cadical;
-#[cfg(feature = "backend-external")]
+use crate::backend::clausal::ClauseSink;
 use crate::backend::external;
 use crate::backend::glucose;
 #[cfg(feature = "experimental-backend-glucose-rs")]
 use crate::backend::glucose_rs;
+#[cfg(feature = "backend-ipasir")]
+use crate::backend::ipasir;
+#[cfg(feature = "portfolio")]
+use crate::backend::portfolio;
 
 use crate::custom_constraints::PropagatorGenerator;
+use crate::proof::Proof;
 use crate::propagators::graph_division::GraphDivisionOptions;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -60,19 +68,24 @@ pub enum SAT {
     Glucose(glucose::Solver),
     #[cfg(feature = "experimental-backend-glucose-rs")]
     GlucoseRs(glucose_rs::Solver),
-    #[cfg(feature = "backend-external")]
     External(external::Solver),
+    #[cfg(feature = "backend-ipasir")]
+    Ipasir(ipasir::Solver),
     #[cfg(feature = "backend-cadical")]
     CaDiCaL(cadical::Solver),
+    #[cfg(feature = "portfolio")]
+    Portfolio(portfolio::Solver),
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Backend {
     Glucose,
     #[cfg(feature = "experimental-backend-glucose-rs")]
     GlucoseRs,
     External,
     CaDiCaL,
+    /// An IPASIR-compliant SAT solver loaded from the shared library `BackendConfig::ipasir_library`.
+    Ipasir,
 }
 
 impl FromStr for Backend {
@@ -85,11 +98,44 @@ impl FromStr for Backend {
             "glucose_rs" => Ok(Backend::GlucoseRs),
             "external" => Ok(Backend::External),
             "cadical" => Ok(Backend::CaDiCaL),
+            "ipasir" => Ok(Backend::Ipasir),
             _ => Err(format!("Unknown backend: {}", s)),
         }
     }
 }
 
+impl Backend {
+    /// Parses a backend, where `ipasir:PATH` also specifies the shared library of the IPASIR backend.
+    pub fn parse_with_library(s: &str) -> Result<(Backend, Option<PathBuf>), String> {
+        match s.strip_prefix("ipasir:") {
+            Some(path) if !path.is_empty() => Ok((Backend::Ipasir, Some(PathBuf::from(path)))),
+            _ => s.parse().map(|backend| (backend, None)),
+        }
+    }
+}
+
+/// How the external backend runs the SAT solver.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExternalSolverConfig {
+    /// The solver command. If `None`, the command is taken from the environment variable
+    /// `ENIGMA_CSP_EXTERNAL_SOLVER`.
+    pub command: Option<String>,
+    /// Additional arguments passed to the solver.
+    pub args: Vec<String>,
+    /// If `true`, the problem is written to a temporary file whose path is passed as the last argument.
+    /// Otherwise, the problem is given from the standard input.
+    pub use_input_file: bool,
+}
+
+/// Settings of the SAT backends which are not `Copy` and thus kept out of `Config`.
+/// The default of the current thread is given by `BackendConfig::default` (see `crate::config`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendConfig {
+    pub external_solver: ExternalSolverConfig,
+    /// The shared library of the SAT solver for `Backend::Ipasir`.
+    pub ipasir_library: Option<PathBuf>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum OrderEncodingLinearMode {
     Cpp,
@@ -116,6 +162,43 @@ pub enum GraphDivisionMode {
     Rust,
 }
 
+/// Limits on the resources which the SAT solver may consume.
+///
+/// `None` means that the corresponding resource is not limited.
+/// The propagation limit is honored only by the Glucose backend, since CaDiCaL does not report the number of propagations.
+///
+/// `time`, `conflicts` and `propagations` are counted per `IntegratedSolver`, over all the SAT solver calls made by it.
+/// `deadline` is an absolute point in time, so a copy of the limit can bound several solvers working on the same task
+/// (e.g. uniqueness checks of a puzzle) at once.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceLimit {
+    pub time: Option<Duration>,
+    pub conflicts: Option<u64>,
+    pub propagations: Option<u64>,
+    pub deadline: Option<Instant>,
+}
+
+impl ResourceLimit {
+    pub fn unlimited() -> ResourceLimit {
+        ResourceLimit::default()
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.time.is_none()
+            && self.conflicts.is_none()
+            && self.propagations.is_none()
+            && self.deadline.is_none()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveResult {
+    Sat,
+    Unsat,
+    /// The solver gave up because the resource limit was reached.
+    Unknown,
+}
+
 pub unsafe trait SolverManipulator {
     /// # Safety
     /// `lit` must be a valid literal associated with the solver.
@@ -213,29 +296,60 @@ impl SAT {
         SAT::GlucoseRs(glucose_rs::Solver::new())
     }
 
-    #[cfg(feature = "backend-external")]
     pub fn new_external() -> SAT {
         SAT::External(external::Solver::new())
     }
 
+    pub fn new_external_with_config(config: ExternalSolverConfig) -> SAT {
+        SAT::External(external::Solver::with_config(config))
+    }
+
+    /// Creates a solver by the IPASIR library at `path`.
+    #[cfg(feature = "backend-ipasir")]
+    pub fn new_ipasir(path: &std::path::Path) -> Result<SAT, ipasir::IpasirError> {
+        ipasir::Solver::new(path).map(SAT::Ipasir)
+    }
+
     #[cfg(feature = "backend-cadical")]
     pub fn new_cadical() -> SAT {
         SAT::CaDiCaL(cadical::Solver::new())
     }
 
-    pub fn new_with_backend(backend: Backend) -> SAT {
+    /// Creates a portfolio consisting of `primary` and solvers configured by `workers`, each of which runs in its own thread.
+    #[cfg(feature = "portfolio")]
+    pub fn new_portfolio(primary: SAT, workers: &[portfolio::WorkerConfig]) -> SAT {
+        SAT::Portfolio(portfolio::Solver::new(primary, workers))
+    }
+
+    #[cfg_attr(
+        not(any(feature = "backend-external", feature = "backend-ipasir")),
+        allow(unused_variables)
+    )]
+    pub fn new_with_backend(backend: Backend, backend_config: &BackendConfig) -> SAT {
         match backend {
             Backend::Glucose => SAT::new_glucose(),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             Backend::GlucoseRs => SAT::new_glucose_rs(),
             #[cfg(feature = "backend-external")]
-            Backend::External => SAT::new_external(),
+            Backend::External => {
+                SAT::new_external_with_config(backend_config.external_solver.clone())
+            }
             #[cfg(not(feature = "backend-external"))]
             Backend::External => panic!("external backend is not enabled"),
             #[cfg(feature = "backend-cadical")]
             Backend::CaDiCaL => SAT::new_cadical(),
             #[cfg(not(feature = "backend-cadical"))]
             Backend::CaDiCaL => panic!("CaDiCaL backend is not enabled"),
+            #[cfg(feature = "backend-ipasir")]
+            Backend::Ipasir => {
+                let path = backend_config
+                    .ipasir_library
+                    .as_ref()
+                    .expect("the library of the IPASIR backend is not specified");
+                SAT::new_ipasir(path).unwrap_or_else(|e| panic!("{}", e))
+            }
+            #[cfg(not(feature = "backend-ipasir"))]
+            Backend::Ipasir => panic!("IPASIR backend is not enabled"),
         }
     }
 
@@ -244,10 +358,13 @@ impl SAT {
             SAT::Glucose(_) => Backend::Glucose,
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(_) => Backend::GlucoseRs,
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => Backend::External,
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => Backend::Ipasir,
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(_) => Backend::CaDiCaL,
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.primary().get_backend(),
         }
     }
 
@@ -256,10 +373,13 @@ impl SAT {
             SAT::Glucose(solver) => solver.num_var() as usize,
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.num_var() as usize,
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => solver.num_var() as usize,
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.num_var() as usize,
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => solver.num_var() as usize,
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.primary().num_var(),
         }
     }
 
@@ -268,10 +388,13 @@ impl SAT {
             SAT::Glucose(solver) => solver.all_vars(),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.all_vars(),
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => solver.all_vars(),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.all_vars(),
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => solver.all_vars(),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.primary().all_vars(),
         }
     }
 
@@ -282,7 +405,11 @@ impl SAT {
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.new_named_var(name),
             SAT::External(_) => panic!("new_var is not supported in external backend"),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => panic!("new_var is not supported in IPASIR backend"),
             SAT::CaDiCaL(_) => panic!("new_var is not supported in cadical backend"),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.new_named_var(name),
         }
     }
 
@@ -292,10 +419,13 @@ impl SAT {
             SAT::Glucose(solver) => solver.new_var(),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.new_var(),
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => solver.new_var(),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.new_var(),
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => solver.new_var(),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.new_var(),
         }
     }
 
@@ -334,14 +464,17 @@ impl SAT {
             SAT::Glucose(solver) => solver.set_polarity(var, polarity),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.set_polarity(var, polarity),
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => {
                 panic!("set_polarity is not supported in external backend")
             }
-            #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => {
-                panic!("set_polarity is not supported in cadical backend")
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => {
+                panic!("set_polarity is not supported in IPASIR backend")
             }
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.set_polarity(var, polarity),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.set_polarity(var, polarity),
         }
     }
 
@@ -354,14 +487,21 @@ impl SAT {
             SAT::GlucoseRs(solver) => {
                 solver.add_clause(clause);
             }
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => {
                 solver.add_clause(clause);
             }
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => {
+                solver.add_clause(clause);
+            }
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => {
                 solver.add_clause(clause);
             }
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                solver.add_clause(clause);
+            }
         }
     }
 
@@ -381,12 +521,21 @@ impl SAT {
             SAT::GlucoseRs(solver) => {
                 solver.add_order_encoding_linear(&lits, &domain, &coefs, constant, mode)
             }
-            #[cfg(feature = "backend-external")]
-            SAT::External(_) => {
-                panic!("add_order_encoding_linear is not supported in external backend")
+            SAT::External(solver) => {
+                solver.add_order_encoding_linear(&lits, &domain, &coefs, constant)
+            }
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => {
+                solver.add_order_encoding_linear(&lits, &domain, &coefs, constant)
             }
             #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => todo!(),
+            SAT::CaDiCaL(solver) => {
+                solver.add_order_encoding_linear(&lits, &domain, &coefs, constant, mode)
+            }
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                solver.add_order_encoding_linear(lits, domain, coefs, constant, mode)
+            }
         }
     }
 
@@ -399,15 +548,34 @@ impl SAT {
             SAT::Glucose(solver) => solver.add_active_vertices_connected(&lits, &edges),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.add_active_vertices_connected(&lits, &edges),
-            #[cfg(feature = "backend-external")]
-            SAT::External(_) => {
-                panic!("add_active_vertices_connected is not supported in external backend")
-            }
+            SAT::External(solver) => solver.add_active_vertices_connected(&lits, &edges),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.add_active_vertices_connected(&lits, &edges),
             #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(solver) => {
-                solver.add_active_vertices_connected(&lits, &edges);
-                true
-            }
+            SAT::CaDiCaL(solver) => solver.add_active_vertices_connected(&lits, &edges),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.add_active_vertices_connected(lits, edges),
+        }
+    }
+
+    /// Adds a constraint that the active edges (`lits`) are connected and each vertex is incident to
+    /// at most two active edges.
+    pub fn add_active_edges_single_cycle(
+        &mut self,
+        lits: Vec<Lit>,
+        edges: Vec<(usize, usize)>,
+    ) -> bool {
+        match self {
+            SAT::Glucose(solver) => solver.add_active_edges_single_cycle(&lits, &edges),
+            #[cfg(feature = "experimental-backend-glucose-rs")]
+            SAT::GlucoseRs(solver) => solver.add_active_edges_single_cycle(&lits, &edges),
+            SAT::External(solver) => solver.add_active_edges_single_cycle(&lits, &edges),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.add_active_edges_single_cycle(&lits, &edges),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.add_active_edges_single_cycle(&lits, &edges),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.add_active_edges_single_cycle(lits, edges),
         }
     }
 
@@ -430,12 +598,40 @@ impl SAT {
             SAT::Glucose(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
-            #[cfg(feature = "backend-external")]
-            SAT::External(_) => panic!(
-                "add_direct_encoding_extension_supports is not supported in external backend"
-            ),
+            SAT::External(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.add_direct_encoding_extension_supports(vars, supports),
+        }
+    }
+
+    #[cfg(not(feature = "csp-extra-constraints"))]
+    pub fn add_direct_encoding_all_different(&mut self, _: &[Vec<Lit>], _: &[Vec<i32>]) -> bool {
+        panic!("feature not enabled");
+    }
+
+    /// Adds an all-different constraint over direct-encoded variables.
+    /// `vars[i][j]` is the literal meaning that the `i`-th variable takes the value `values[i][j]`.
+    #[cfg(feature = "csp-extra-constraints")]
+    pub fn add_direct_encoding_all_different(
+        &mut self,
+        vars: &[Vec<Lit>],
+        values: &[Vec<i32>],
+    ) -> bool {
+        match self {
+            SAT::Glucose(solver) => solver.add_direct_encoding_all_different(vars, values),
+            #[cfg(feature = "experimental-backend-glucose-rs")]
+            SAT::GlucoseRs(solver) => solver.add_direct_encoding_all_different(vars, values),
+            SAT::External(solver) => solver.add_direct_encoding_all_different(vars, values),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.add_direct_encoding_all_different(vars, values),
             #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => todo!(),
+            SAT::CaDiCaL(solver) => solver.add_direct_encoding_all_different(vars, values),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.add_direct_encoding_all_different(vars, values),
         }
     }
 
@@ -456,10 +652,21 @@ impl SAT {
             SAT::GlucoseRs(solver) => {
                 solver.add_graph_division(domains, dom_lits, edges, edge_lits, mode, opts)
             }
-            #[cfg(feature = "backend-external")]
-            SAT::External(_) => panic!("add_graph_division is not supported in external backend"),
+            SAT::External(solver) => {
+                solver.add_graph_division(domains, dom_lits, edges, edge_lits, opts)
+            }
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => {
+                solver.add_graph_division(domains, dom_lits, edges, edge_lits, opts)
+            }
             #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => todo!(),
+            SAT::CaDiCaL(solver) => {
+                solver.add_graph_division(domains, dom_lits, edges, edge_lits, opts)
+            }
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                solver.add_graph_division(domains, dom_lits, edges, edge_lits, mode, opts)
+            }
         }
     }
 
@@ -468,7 +675,6 @@ impl SAT {
         inputs: Vec<Lit>,
         constr: Box<dyn PropagatorGenerator>,
     ) -> bool {
-        #[allow(unreachable_patterns)]
         match self {
             SAT::Glucose(solver) => {
                 let propagator = constr.generate(inputs);
@@ -479,7 +685,23 @@ impl SAT {
                 let propagator = constr.generate_for_glucose_rs(inputs);
                 solver.add_custom_constraint(propagator)
             }
-            _ => todo!("add_custom_constraint is supported only in Glucose/GlucoseRs backend"),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => {
+                let propagator = constr.generate_for_cadical(inputs);
+                solver.add_custom_constraint(propagator)
+            }
+            SAT::External(solver) => {
+                solver.mark_unsupported("add_custom_constraint");
+                true
+            }
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => {
+                solver.mark_unsupported("add_custom_constraint");
+                true
+            }
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver
+                .add_custom_constraint(|primary| primary.add_custom_constraint(inputs, constr)),
         }
     }
 
@@ -488,10 +710,13 @@ impl SAT {
             SAT::Glucose(solver) => solver.set_seed(seed),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.set_seed(seed),
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => (), // TODO: add warning
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => (), // TODO: add warning
             #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => (), // TODO
+            SAT::CaDiCaL(solver) => solver.set_seed(seed),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.set_seed(seed),
         }
     }
 
@@ -500,10 +725,13 @@ impl SAT {
             SAT::Glucose(solver) => solver.set_rnd_init_act(rnd_init_act),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.set_rnd_init_act(rnd_init_act),
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => (), // TODO: add warning
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => (), // TODO: add warning
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(_) => (), // TODO
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.set_rnd_init_act(rnd_init_act),
         }
     }
 
@@ -512,10 +740,99 @@ impl SAT {
             SAT::Glucose(solver) => solver.set_dump_analysis_info(dump_analysis_info),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.set_dump_analysis_info(dump_analysis_info),
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => (), // TODO: add warning
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => (), // TODO: add warning
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(_) => (), // TODO: add warning
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.set_dump_analysis_info(dump_analysis_info),
+        }
+    }
+
+    /// Makes the solver export the learnt clauses with at most `max_len` literals (see `take_learnt_clauses`).
+    /// Glucose exports only the literals fixed at the root level, regardless of `max_len`.
+    #[cfg(feature = "portfolio")]
+    pub(crate) fn set_max_export_len(&mut self, max_len: usize) {
+        #[allow(unreachable_patterns)]
+        match self {
+            SAT::Glucose(_) => (),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.set_max_export_len(max_len),
+            _ => panic!("exporting learnt clauses is not supported in this backend"),
+        }
+    }
+
+    /// Returns the clauses learnt in the solves so far which are to be shared with the other solvers
+    /// of a portfolio. They are consequences of the clauses and the constraints added to this solver.
+    /// This must be called between solves.
+    #[cfg(feature = "portfolio")]
+    pub(crate) fn take_learnt_clauses(&mut self) -> Vec<Vec<Lit>> {
+        #[allow(unreachable_patterns)]
+        match self {
+            SAT::Glucose(solver) => solver
+                .root_level_lits()
+                .into_iter()
+                .map(|lit| vec![lit])
+                .collect(),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.take_exported_clauses(),
+            _ => panic!("exporting learnt clauses is not supported in this backend"),
+        }
+    }
+
+    /// Makes the solver write a DRAT proof, which is available from `proof`.
+    /// This must be called before any variable is created.
+    /// Only the Glucose and CaDiCaL backends support proofs.
+    pub fn enable_proof(&mut self) -> bool {
+        #[allow(unreachable_patterns)]
+        match self {
+            SAT::Glucose(solver) => solver.enable_proof(),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.enable_proof(),
+            _ => panic!("proof is not supported in this backend"),
+        }
+    }
+
+    /// Returns the proof of unsatisfiability of the clauses added so far.
+    /// The proof is valid only if the last solve without assumptions reported unsatisfiability.
+    pub fn proof(&self) -> Result<Proof, String> {
+        #[allow(unreachable_patterns)]
+        match self {
+            SAT::Glucose(solver) => solver.proof(),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.proof(),
+            _ => Err(String::from("proof is not supported in this backend")),
+        }
+    }
+
+    /// Returns the underlying solver if this is the external backend, which keeps all the clauses added so far.
+    pub(crate) fn as_external(&self) -> Option<&external::Solver> {
+        match self {
+            SAT::External(solver) => Some(solver),
+            _ => None,
+        }
+    }
+
+    /// Returns why the last solve returned `SolveResult::Unknown` other than reaching the resource limit.
+    /// Only the external and IPASIR backends can fail in this way.
+    pub fn last_error(&self) -> Option<String> {
+        match self {
+            SAT::External(solver) => solver.last_error().map(|e| e.to_string()),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.last_error().map(|e| e.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns `false` if the backend accepts only clauses, in which case constraints other than clauses
+    /// are encoded by the clausal fallbacks in `crate::backend::clausal` (or not supported at all).
+    pub fn has_native_propagators(&self) -> bool {
+        match self {
+            SAT::External(_) => false,
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => false,
+            _ => true,
         }
     }
 
@@ -524,10 +841,19 @@ impl SAT {
             SAT::Glucose(solver) => solver.solve().map(SATModel::Glucose),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.solve().map(SATModel::GlucoseRs),
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => solver.solve().map(SATModel::External),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.solve().map(SATModel::Ipasir),
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => solver.solve().map(SATModel::CaDiCaL),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(_) => {
+                if self.solve_without_model() {
+                    Some(unsafe { self.model() })
+                } else {
+                    None
+                }
+            }
         }
     }
 
@@ -536,10 +862,104 @@ impl SAT {
             SAT::Glucose(solver) => solver.solve_without_model(),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => solver.solve_without_model(),
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => solver.solve_without_model(),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.solve_without_model(),
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => solver.solve_without_model(),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                solver.solve_under_limited(&[], &ResourceLimit::unlimited()) == SolveResult::Sat
+            }
+        }
+    }
+
+    pub fn solve_under(&mut self, assumptions: &[Lit]) -> Option<SATModel<'_>> {
+        match self {
+            SAT::Glucose(solver) => solver.solve_under(assumptions).map(SATModel::Glucose),
+            #[cfg(feature = "experimental-backend-glucose-rs")]
+            SAT::GlucoseRs(_) => panic!("solve_under is not supported in glucose_rs backend"),
+            SAT::External(solver) => solver.solve_under(assumptions).map(SATModel::External),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.solve_under(assumptions).map(SATModel::Ipasir),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.solve_under(assumptions).map(SATModel::CaDiCaL),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(_) => {
+                if self.solve_under_without_model(assumptions) {
+                    Some(unsafe { self.model() })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn solve_under_without_model(&mut self, assumptions: &[Lit]) -> bool {
+        match self {
+            SAT::Glucose(solver) => solver.solve_under_without_model(assumptions),
+            #[cfg(feature = "experimental-backend-glucose-rs")]
+            SAT::GlucoseRs(_) => panic!("solve_under is not supported in glucose_rs backend"),
+            SAT::External(solver) => solver.solve_under_without_model(assumptions),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.solve_under_without_model(assumptions),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.solve_under_without_model(assumptions),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                solver.solve_under_limited(assumptions, &ResourceLimit::unlimited())
+                    == SolveResult::Sat
+            }
+        }
+    }
+
+    /// Same as `solve_under_without_model`, but the solver gives up when `limit` is reached.
+    /// The external and IPASIR backends honor only the time limit, and they also return
+    /// `SolveResult::Unknown` when the solver fails (see `last_error`).
+    pub fn solve_under_limited(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+    ) -> SolveResult {
+        if let SAT::External(solver) = self {
+            return solver.solve_under_limited(assumptions, limit);
+        }
+        #[cfg(feature = "backend-ipasir")]
+        if let SAT::Ipasir(solver) = self {
+            return solver.solve_under_limited(assumptions, limit, None);
+        }
+        if limit.is_unlimited() {
+            return if self.solve_under_without_model(assumptions) {
+                SolveResult::Sat
+            } else {
+                SolveResult::Unsat
+            };
+        }
+        self.solve_under_interruptible(assumptions, limit, None)
+    }
+
+    /// Same as `solve_under_limited`, but the solver also gives up when `interrupt` is set
+    /// (typically by another thread).
+    pub(crate) fn solve_under_interruptible(
+        &mut self,
+        assumptions: &[Lit],
+        limit: &ResourceLimit,
+        interrupt: Option<&AtomicBool>,
+    ) -> SolveResult {
+        match self {
+            SAT::Glucose(solver) => solver.solve_under_limited(assumptions, limit, interrupt),
+            #[cfg(feature = "experimental-backend-glucose-rs")]
+            SAT::GlucoseRs(_) => panic!("resource limit is not supported in glucose_rs backend"),
+            SAT::External(solver) => solver.solve_under_limited(assumptions, limit),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => solver.solve_under_limited(assumptions, limit, interrupt),
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => solver.solve_under_limited(assumptions, limit, interrupt),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => {
+                assert!(interrupt.is_none(), "nested portfolio is not supported");
+                solver.solve_under_limited(assumptions, limit)
+            }
         }
     }
 
@@ -548,10 +968,13 @@ impl SAT {
             SAT::Glucose(solver) => SATModel::Glucose(solver.model()),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SAT::GlucoseRs(solver) => SATModel::GlucoseRs(solver.model()),
-            #[cfg(feature = "backend-external")]
             SAT::External(solver) => SATModel::External(solver.model()),
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(solver) => SATModel::Ipasir(solver.model()),
             #[cfg(feature = "backend-cadical")]
             SAT::CaDiCaL(solver) => SATModel::CaDiCaL(solver.model()),
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => SATModel::Portfolio(solver.model()),
         }
     }
 
@@ -568,18 +991,25 @@ impl SAT {
                 propagations: Some(solver.stats_propagations()),
                 conflicts: Some(solver.stats_conflicts()),
             },
-            #[cfg(feature = "backend-external")]
             SAT::External(_) => SATSolverStats {
                 decisions: None,
                 propagations: None,
                 conflicts: None,
             },
-            #[cfg(feature = "backend-cadical")]
-            SAT::CaDiCaL(_) => SATSolverStats {
+            #[cfg(feature = "backend-ipasir")]
+            SAT::Ipasir(_) => SATSolverStats {
                 decisions: None,
                 propagations: None,
                 conflicts: None,
+            },
+            #[cfg(feature = "backend-cadical")]
+            SAT::CaDiCaL(solver) => SATSolverStats {
+                decisions: None,
+                propagations: None,
+                conflicts: Some(solver.stats_conflicts()),
             }, // TODO
+            #[cfg(feature = "portfolio")]
+            SAT::Portfolio(solver) => solver.stats(),
         }
     }
 }
@@ -588,10 +1018,13 @@ pub enum SATModel<'a> {
     Glucose(glucose::Model<'a>),
     #[cfg(feature = "experimental-backend-glucose-rs")]
     GlucoseRs(glucose_rs::Model<'a>),
-    #[cfg(feature = "backend-external")]
     External(external::Model<'a>),
+    #[cfg(feature = "backend-ipasir")]
+    Ipasir(ipasir::Model<'a>),
     #[cfg(feature = "backend-cadical")]
     CaDiCaL(cadical::Model<'a>),
+    #[cfg(feature = "portfolio")]
+    Portfolio(portfolio::Model<'a>),
 }
 
 impl SATModel<'_> {
@@ -600,10 +1033,13 @@ impl SATModel<'_> {
             SATModel::Glucose(model) => model.assignment(var),
             #[cfg(feature = "experimental-backend-glucose-rs")]
             SATModel::GlucoseRs(model) => model.assignment(var),
-            #[cfg(feature = "backend-external")]
             SATModel::External(model) => model.assignment(var),
+            #[cfg(feature = "backend-ipasir")]
+            SATModel::Ipasir(model) => model.assignment(var),
             #[cfg(feature = "backend-cadical")]
             SATModel::CaDiCaL(model) => model.assignment(var),
+            #[cfg(feature = "portfolio")]
+            SATModel::Portfolio(model) => model.assignment(var),
         }
     }
 
@@ -61,6 +61,49 @@ pub fn check_graph_active_vertices_connected(is_active: &[bool], edges: &[(usize
     n_connected_components <= 1
 }
 
+/// Checks that the active edges are connected and each vertex is incident to at most two of them.
+pub fn check_graph_active_edges_single_path_or_cycle(
+    is_active: &[bool],
+    edges: &[(usize, usize)],
+) -> bool {
+    let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let mut degree = vec![0; n];
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        if is_active[i] {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+    }
+    if degree.iter().any(|&d| d > 2) {
+        return false;
+    }
+
+    let mut line_graph_edges = vec![];
+    for i in 0..edges.len() {
+        for j in 0..i {
+            let (a, b) = edges[i];
+            let (c, d) = edges[j];
+            if a == c || a == d || b == c || b == d {
+                line_graph_edges.push((i, j));
+            }
+        }
+    }
+    check_graph_active_vertices_connected(is_active, &line_graph_edges)
+}
+
+pub fn check_graph_active_edges_single_cycle(is_active: &[bool], edges: &[(usize, usize)]) -> bool {
+    let n = edges.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let mut degree = vec![0; n];
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        if is_active[i] {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+    }
+    degree.iter().all(|&d| d == 0 || d == 2)
+        && check_graph_active_edges_single_path_or_cycle(is_active, edges)
+}
+
 pub fn check_circuit(values: &[i32]) -> bool {
     let n = values.len();
     if values.iter().any(|&x| x < 0 || x >= n as i32) {
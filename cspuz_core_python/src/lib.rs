@@ -1,17 +1,23 @@
 use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
 
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyOverflowError, PyRuntimeError, PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
 
-use cspuz_core::config::Config;
-use cspuz_core::csugar_cli::csugar_cli;
+use cspuz_core::config::{BackendConfig, Config};
+use cspuz_core::csugar_cli::{csugar_cli, CliError};
+use cspuz_core::integration::{take_resource_limit_exceeded, PerfStats};
 use cspuz_core::sat::{Backend, OrderEncodingLinearMode};
+use std::time::Duration;
+
+mod modeling;
+mod puzzles;
 
 #[pyclass(name = "Config")]
 #[derive(Clone)]
 struct PyConfig {
     config: Config,
+    backend_config: BackendConfig,
 }
 
 #[pymethods]
@@ -20,6 +26,7 @@ impl PyConfig {
     fn new() -> PyConfig {
         PyConfig {
             config: Config::initial_default(),
+            backend_config: BackendConfig::initial_default(),
         }
     }
 
@@ -171,11 +178,15 @@ impl PyConfig {
 
     #[getter]
     fn get_backend(&self) -> PyResult<String> {
-        let mode = match self.config.backend {
+        let mode = match &self.config.backend {
             Backend::Glucose => "glucose",
             Backend::GlucoseRs => "glucose_rs",
             Backend::CaDiCaL => "cadical",
             Backend::External => "external",
+            Backend::Ipasir => match &self.backend_config.ipasir_library {
+                Some(path) => return Ok(format!("ipasir:{}", path.display())),
+                None => "ipasir",
+            },
         };
         Ok(mode.to_owned())
     }
@@ -190,6 +201,9 @@ impl PyConfig {
             self.config.backend = Backend::CaDiCaL;
         } else if backend == "external" {
             self.config.backend = Backend::External;
+        } else if let Some(path) = backend.strip_prefix("ipasir:") {
+            self.config.backend = Backend::Ipasir;
+            self.backend_config.ipasir_library = Some(path.into());
         } else {
             return Err(PyErr::new::<PyValueError, _>(format!(
                 "unknown backend: {}",
@@ -236,6 +250,68 @@ impl PyConfig {
         self.config.optimize_polarity = value;
         Ok(())
     }
+
+    #[getter]
+    fn get_time_limit(&self) -> PyResult<Option<f64>> {
+        Ok(self.config.resource_limit.time.map(|t| t.as_secs_f64()))
+    }
+
+    #[setter]
+    fn set_time_limit(&mut self, value: Option<f64>) -> PyResult<()> {
+        self.config.resource_limit.time = match value {
+            Some(value) => Some(Duration::try_from_secs_f64(value).map_err(|_| {
+                PyErr::new::<PyValueError, _>(format!("invalid time limit: {}", value))
+            })?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    #[getter]
+    fn get_conflict_limit(&self) -> PyResult<Option<u64>> {
+        Ok(self.config.resource_limit.conflicts)
+    }
+
+    #[setter]
+    fn set_conflict_limit(&mut self, value: Option<u64>) -> PyResult<()> {
+        self.config.resource_limit.conflicts = value;
+        Ok(())
+    }
+
+    /// The maximum number of propagations in SAT solving. Only the Glucose backend honors this limit;
+    /// the other backends ignore it.
+    #[getter]
+    fn get_propagation_limit(&self) -> PyResult<Option<u64>> {
+        Ok(self.config.resource_limit.propagations)
+    }
+
+    #[setter]
+    fn set_propagation_limit(&mut self, value: Option<u64>) -> PyResult<()> {
+        self.config.resource_limit.propagations = value;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_portfolio_size(&self) -> PyResult<i32> {
+        Ok(self.config.portfolio_size as i32)
+    }
+
+    #[setter]
+    fn set_portfolio_size(&mut self, value: i32) -> PyResult<()> {
+        if value < 1 {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "invalid portfolio size: {}",
+                value
+            )));
+        }
+        if value > 1 && !cfg!(feature = "portfolio") {
+            return Err(PyErr::new::<PyValueError, _>(
+                "portfolio feature is not enabled",
+            ));
+        }
+        self.config.portfolio_size = value as usize;
+        Ok(())
+    }
 }
 
 static GLOBAL_CONFIG: OnceLock<Mutex<PyConfig>> = OnceLock::new();
@@ -249,19 +325,39 @@ fn set_config(config: PyConfig) {
     *get_global_config().lock().unwrap() = config;
 }
 
-#[pyfunction]
-fn solver(input: String) -> String {
+/// Runs `csugar_cli`, raising `ValueError` if the input is malformed and `TimeoutError` if the resource limit
+/// is exceeded.
+fn run_csugar_cli(input: &str) -> PyResult<(String, PerfStats)> {
     let mut bytes = input.as_bytes();
-    let config = get_global_config().lock().unwrap().config.clone();
-    let (res, _) = csugar_cli(&mut bytes, config);
-    res
+    let (config, backend_config) = {
+        let global = get_global_config().lock().unwrap();
+        (global.config, global.backend_config.clone())
+    };
+    let ret = csugar_cli(&mut bytes, config, &backend_config).map_err(|e| match e {
+        CliError::Parse(e) => PyErr::new::<PyValueError, _>(format!(
+            "line {}, column {}: {}",
+            e.line, e.column, e.message
+        )),
+        CliError::Overflow(e) => PyErr::new::<PyOverflowError, _>(e.to_string()),
+        CliError::Solver(e) => PyErr::new::<PyRuntimeError, _>(e),
+        e => PyErr::new::<PyValueError, _>(e.to_string()),
+    })?;
+    // `csugar_cli` clears the flag on start, and failures of the SAT solver are reported as `CliError::Solver`.
+    if take_resource_limit_exceeded() {
+        return Err(PyErr::new::<PyTimeoutError, _>("resource limit exceeded"));
+    }
+    Ok(ret)
 }
 
 #[pyfunction]
-fn solver_with_perf(input: String) -> (String, HashMap<String, f64>) {
-    let mut bytes = input.as_bytes();
-    let config = get_global_config().lock().unwrap().config.clone();
-    let (res, perf) = csugar_cli(&mut bytes, config);
+fn solver(input: String) -> PyResult<String> {
+    let (res, _) = run_csugar_cli(&input)?;
+    Ok(res)
+}
+
+#[pyfunction]
+fn solver_with_perf(input: String) -> PyResult<(String, HashMap<String, f64>)> {
+    let (res, perf) = run_csugar_cli(&input)?;
 
     let mut perf_map = HashMap::<String, f64>::new();
     perf_map.insert(String::from("time_normalize"), perf.time_normalize());
@@ -271,7 +367,7 @@ fn solver_with_perf(input: String) -> (String, HashMap<String, f64>) {
     perf_map.insert(String::from("propagations"), perf.propagations() as f64);
     perf_map.insert(String::from("conflicts"), perf.conflicts() as f64);
 
-    (res, perf_map)
+    Ok((res, perf_map))
 }
 
 #[pymodule(name = "cspuz_core")]
@@ -280,6 +376,8 @@ pub fn cspuz_core_python_module(_py: Python, m: &Bound<'_, PyModule>) -> PyResul
     m.add_function(wrap_pyfunction!(solver_with_perf, m)?)?;
     m.add_function(wrap_pyfunction!(set_config, m)?)?;
     m.add_class::<PyConfig>()?;
+    modeling::register(m)?;
+    puzzles::register(m)?;
 
     Ok(())
 }
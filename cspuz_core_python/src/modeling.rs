@@ -0,0 +1,738 @@
+//! Python classes for building CSP problems directly on `IntegratedSolver`, without going through
+//! the csugar text format.
+
+use pyo3::exceptions::{
+    PyOverflowError, PyRuntimeError, PyTimeoutError, PyTypeError, PyValueError,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyString, PyTuple};
+
+use cspuz_core::csp::{Assignment, BoolExpr, BoolVar, IntExpr, IntVar, Stmt};
+use cspuz_core::domain::Domain;
+use cspuz_core::integration::{
+    take_resource_limit_exceeded, take_sat_solver_error, AnswerIterator, IntegratedSolver,
+    SolveOutcome,
+};
+use cspuz_core::propagators::graph_division::GraphDivisionOptions;
+
+use crate::{get_global_config, PyConfig};
+
+fn pretty_print<F: FnOnce(&mut Vec<u8>) -> std::io::Result<()>>(f: F) -> String {
+    let mut out = vec![];
+    f(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// Source of `PySolver::id`. Variables and expressions remember the id of the solver they belong to,
+/// since variables of different solvers may share the same index.
+static NEXT_SOLVER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the solver shared by `ids`, ignoring `None` (expressions without variables).
+fn merge_solver_ids(ids: impl IntoIterator<Item = Option<usize>>) -> PyResult<Option<usize>> {
+    let mut ret = None;
+    for id in ids.into_iter().flatten() {
+        match ret {
+            Some(r) if r != id => {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "cannot mix variables of different solvers",
+                ))
+            }
+            _ => ret = Some(id),
+        }
+    }
+    Ok(ret)
+}
+
+#[pyclass(name = "BoolExpr", subclass, frozen)]
+#[derive(Clone)]
+pub struct PyBoolExpr {
+    expr: BoolExpr,
+    solver_id: Option<usize>,
+}
+
+#[pyclass(name = "IntExpr", subclass, frozen)]
+#[derive(Clone)]
+pub struct PyIntExpr {
+    expr: IntExpr,
+    solver_id: Option<usize>,
+}
+
+/// A Boolean variable, which can be used as a `BoolExpr`.
+#[pyclass(name = "BoolVar", extends = PyBoolExpr, frozen)]
+pub struct PyBoolVar {
+    var: BoolVar,
+    solver_id: usize,
+}
+
+/// An integer variable, which can be used as an `IntExpr`.
+#[pyclass(name = "IntVar", extends = PyIntExpr, frozen)]
+pub struct PyIntVar {
+    var: IntVar,
+    solver_id: usize,
+}
+
+/// A Python value which can be converted into a `BoolExpr`.
+#[derive(FromPyObject)]
+enum BoolOperand {
+    Expr(PyBoolExpr),
+    Const(bool),
+}
+
+impl BoolOperand {
+    fn solver_id(&self) -> Option<usize> {
+        match self {
+            BoolOperand::Expr(e) => e.solver_id,
+            BoolOperand::Const(_) => None,
+        }
+    }
+
+    fn into_expr(self) -> BoolExpr {
+        match self {
+            BoolOperand::Expr(e) => e.expr,
+            BoolOperand::Const(b) => BoolExpr::Const(b),
+        }
+    }
+}
+
+/// A Python value which can be converted into an `IntExpr`.
+#[derive(FromPyObject)]
+enum IntOperand {
+    Expr(PyIntExpr),
+    Const(i32),
+}
+
+impl IntOperand {
+    fn solver_id(&self) -> Option<usize> {
+        match self {
+            IntOperand::Expr(e) => e.solver_id,
+            IntOperand::Const(_) => None,
+        }
+    }
+
+    fn into_expr(self) -> IntExpr {
+        match self {
+            IntOperand::Expr(e) => e.expr,
+            IntOperand::Const(n) => IntExpr::Const(n),
+        }
+    }
+}
+
+fn bool_expr(expr: BoolExpr, solver_id: Option<usize>) -> PyBoolExpr {
+    PyBoolExpr { expr, solver_id }
+}
+
+fn int_expr(expr: IntExpr, solver_id: Option<usize>) -> PyIntExpr {
+    PyIntExpr { expr, solver_id }
+}
+
+impl PyBoolExpr {
+    fn with_bool(
+        &self,
+        rhs: BoolOperand,
+        f: impl FnOnce(BoolExpr, BoolExpr) -> BoolExpr,
+    ) -> PyResult<PyBoolExpr> {
+        let solver_id = merge_solver_ids([self.solver_id, rhs.solver_id()])?;
+        Ok(bool_expr(f(self.expr.clone(), rhs.into_expr()), solver_id))
+    }
+}
+
+#[pymethods]
+impl PyBoolExpr {
+    fn __invert__(&self) -> PyBoolExpr {
+        bool_expr(!self.expr.clone(), self.solver_id)
+    }
+
+    fn __and__(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(rhs, |a, b| a & b)
+    }
+
+    fn __rand__(&self, lhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(lhs, |a, b| b & a)
+    }
+
+    fn __or__(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(rhs, |a, b| a | b)
+    }
+
+    fn __ror__(&self, lhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(lhs, |a, b| b | a)
+    }
+
+    fn __xor__(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(rhs, |a, b| a ^ b)
+    }
+
+    fn __rxor__(&self, lhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(lhs, |a, b| b ^ a)
+    }
+
+    fn __eq__(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.iff(rhs)
+    }
+
+    fn __ne__(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.__xor__(rhs)
+    }
+
+    fn __bool__(&self) -> PyResult<bool> {
+        Err(PyErr::new::<PyTypeError, _>(
+            "BoolExpr cannot be converted to bool",
+        ))
+    }
+
+    fn implies(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(rhs, |a, b| a.imp(b))
+    }
+
+    fn iff(&self, rhs: BoolOperand) -> PyResult<PyBoolExpr> {
+        self.with_bool(rhs, |a, b| a.iff(b))
+    }
+
+    /// Returns `t` if this expression is true, and `f` otherwise.
+    fn ite(&self, t: IntOperand, f: IntOperand) -> PyResult<PyIntExpr> {
+        let solver_id = merge_solver_ids([self.solver_id, t.solver_id(), f.solver_id()])?;
+        Ok(int_expr(
+            self.expr.clone().ite(t.into_expr(), f.into_expr()),
+            solver_id,
+        ))
+    }
+
+    /// Returns 1 if this expression is true, and 0 otherwise.
+    fn as_int(&self) -> PyIntExpr {
+        int_expr(
+            self.expr.clone().ite(IntExpr::Const(1), IntExpr::Const(0)),
+            self.solver_id,
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        pretty_print(|out| self.expr.pretty_print(out))
+    }
+}
+
+impl PyIntExpr {
+    fn with_int<T>(
+        &self,
+        rhs: IntOperand,
+        f: impl FnOnce(IntExpr, IntExpr) -> T,
+    ) -> PyResult<(T, Option<usize>)> {
+        let solver_id = merge_solver_ids([self.solver_id, rhs.solver_id()])?;
+        Ok((f(self.expr.clone(), rhs.into_expr()), solver_id))
+    }
+
+    fn int_op(
+        &self,
+        rhs: IntOperand,
+        f: impl FnOnce(IntExpr, IntExpr) -> IntExpr,
+    ) -> PyResult<PyIntExpr> {
+        let (expr, solver_id) = self.with_int(rhs, f)?;
+        Ok(int_expr(expr, solver_id))
+    }
+
+    fn cmp_op(
+        &self,
+        rhs: IntOperand,
+        f: impl FnOnce(IntExpr, IntExpr) -> BoolExpr,
+    ) -> PyResult<PyBoolExpr> {
+        let (expr, solver_id) = self.with_int(rhs, f)?;
+        Ok(bool_expr(expr, solver_id))
+    }
+}
+
+#[pymethods]
+impl PyIntExpr {
+    fn __neg__(&self) -> PyIntExpr {
+        int_expr(self.expr.clone() * -1, self.solver_id)
+    }
+
+    fn __add__(&self, rhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(rhs, |a, b| a + b)
+    }
+
+    fn __radd__(&self, lhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(lhs, |a, b| b + a)
+    }
+
+    fn __sub__(&self, rhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(rhs, |a, b| a - b)
+    }
+
+    fn __rsub__(&self, lhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(lhs, |a, b| b - a)
+    }
+
+    fn __mul__(&self, rhs: IntOperand) -> PyResult<PyIntExpr> {
+        match rhs {
+            IntOperand::Const(n) => Ok(int_expr(self.expr.clone() * n, self.solver_id)),
+            IntOperand::Expr(_) => self.int_op(rhs, |a, b| a * b),
+        }
+    }
+
+    fn __rmul__(&self, lhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.__mul__(lhs)
+    }
+
+    fn __eq__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.eq(b))
+    }
+
+    fn __ne__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.ne(b))
+    }
+
+    fn __le__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.le(b))
+    }
+
+    fn __lt__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.lt(b))
+    }
+
+    fn __ge__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.ge(b))
+    }
+
+    fn __gt__(&self, rhs: IntOperand) -> PyResult<PyBoolExpr> {
+        self.cmp_op(rhs, |a, b| a.gt(b))
+    }
+
+    fn __bool__(&self) -> PyResult<bool> {
+        Err(PyErr::new::<PyTypeError, _>(
+            "IntExpr cannot be converted to bool",
+        ))
+    }
+
+    fn __abs__(&self) -> PyIntExpr {
+        int_expr(self.expr.clone().abs(), self.solver_id)
+    }
+
+    fn min(&self, rhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(rhs, |a, b| a.min(b))
+    }
+
+    fn max(&self, rhs: IntOperand) -> PyResult<PyIntExpr> {
+        self.int_op(rhs, |a, b| a.max(b))
+    }
+
+    fn __repr__(&self) -> String {
+        pretty_print(|out| self.expr.pretty_print(out))
+    }
+}
+
+#[derive(Clone)]
+enum ConstraintKind {
+    AllDifferent(Vec<IntExpr>),
+    ActiveVerticesConnected(Vec<BoolExpr>, Vec<(usize, usize)>),
+    GraphDivision(
+        Vec<Option<IntExpr>>,
+        Vec<(usize, usize)>,
+        Vec<BoolExpr>,
+        GraphDivisionOptions,
+    ),
+}
+
+/// A constraint other than a Boolean expression, which can be added to `Solver`.
+#[pyclass(name = "Constraint", frozen)]
+#[derive(Clone)]
+pub struct PyConstraint {
+    kind: ConstraintKind,
+    solver_id: Option<usize>,
+}
+
+impl PyConstraint {
+    fn to_stmt(&self) -> Stmt {
+        match self.kind.clone() {
+            ConstraintKind::AllDifferent(exprs) => Stmt::AllDifferent(exprs),
+            ConstraintKind::ActiveVerticesConnected(exprs, edges) => {
+                Stmt::ActiveVerticesConnected(exprs, edges)
+            }
+            ConstraintKind::GraphDivision(sizes, edges, edge_exprs, opts) => {
+                Stmt::GraphDivision(sizes, edges, edge_exprs, opts)
+            }
+        }
+    }
+}
+
+fn check_edges(num_vertices: usize, edges: &[(usize, usize)]) -> PyResult<()> {
+    for &(u, v) in edges {
+        if u >= num_vertices || v >= num_vertices {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "edge ({}, {}) is out of range",
+                u, v
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// All of `exprs` take different values.
+#[pyfunction]
+fn alldifferent(exprs: Vec<IntOperand>) -> PyResult<PyConstraint> {
+    let solver_id = merge_solver_ids(exprs.iter().map(|e| e.solver_id()))?;
+    Ok(PyConstraint {
+        kind: ConstraintKind::AllDifferent(exprs.into_iter().map(|e| e.into_expr()).collect()),
+        solver_id,
+    })
+}
+
+/// The vertices `i` such that `vertices[i]` is true are connected by `edges`.
+#[pyfunction]
+fn active_vertices_connected(
+    vertices: Vec<BoolOperand>,
+    edges: Vec<(usize, usize)>,
+) -> PyResult<PyConstraint> {
+    check_edges(vertices.len(), &edges)?;
+    let solver_id = merge_solver_ids(vertices.iter().map(|e| e.solver_id()))?;
+    Ok(PyConstraint {
+        kind: ConstraintKind::ActiveVerticesConnected(
+            vertices.into_iter().map(|e| e.into_expr()).collect(),
+            edges,
+        ),
+        solver_id,
+    })
+}
+
+/// The graph is divided into regions by the edges such that `edges_disconnected[i]` is true, and the size
+/// of the region containing the vertex `i` is `sizes[i]` (if not `None`).
+#[pyfunction]
+#[pyo3(signature = (sizes, edges, edges_disconnected, allow_extra_walls = false, disallow_adjacent_same_size_regions = false))]
+fn graph_division(
+    sizes: Vec<Option<IntOperand>>,
+    edges: Vec<(usize, usize)>,
+    edges_disconnected: Vec<BoolOperand>,
+    allow_extra_walls: bool,
+    disallow_adjacent_same_size_regions: bool,
+) -> PyResult<PyConstraint> {
+    check_edges(sizes.len(), &edges)?;
+    if edges.len() != edges_disconnected.len() {
+        return Err(PyErr::new::<PyValueError, _>(
+            "edges and edges_disconnected must have the same length",
+        ));
+    }
+    let solver_id = merge_solver_ids(
+        sizes
+            .iter()
+            .flatten()
+            .map(|s| s.solver_id())
+            .chain(edges_disconnected.iter().map(|e| e.solver_id())),
+    )?;
+    Ok(PyConstraint {
+        kind: ConstraintKind::GraphDivision(
+            sizes
+                .into_iter()
+                .map(|s| s.map(|s| s.into_expr()))
+                .collect(),
+            edges,
+            edges_disconnected
+                .into_iter()
+                .map(|e| e.into_expr())
+                .collect(),
+            GraphDivisionOptions {
+                allow_extra_walls,
+                disallow_adjacent_same_size_regions,
+            },
+        ),
+        solver_id,
+    })
+}
+
+/// A variable of either type, used as a key of `Assignment`.
+#[derive(FromPyObject)]
+enum AnyVar<'py> {
+    Bool(PyRef<'py, PyBoolVar>),
+    Int(PyRef<'py, PyIntVar>),
+}
+
+impl AnyVar<'_> {
+    fn solver_id(&self) -> usize {
+        match self {
+            AnyVar::Bool(v) => v.solver_id,
+            AnyVar::Int(v) => v.solver_id,
+        }
+    }
+}
+
+#[derive(IntoPyObject)]
+enum Value {
+    Bool(bool),
+    Int(i32),
+}
+
+/// Values of (some of) the variables.
+#[pyclass(name = "Assignment", frozen)]
+pub struct PyAssignment {
+    assignment: Assignment,
+    solver_id: usize,
+}
+
+#[pymethods]
+impl PyAssignment {
+    /// Returns the value of `var`, or `None` if it is not decided.
+    fn get(&self, var: AnyVar) -> PyResult<Option<Value>> {
+        check_solver_id(self.solver_id, Some(var.solver_id()))?;
+        Ok(match var {
+            AnyVar::Bool(v) => self.assignment.get_bool(v.var).map(Value::Bool),
+            AnyVar::Int(v) => self.assignment.get_int(v.var).map(Value::Int),
+        })
+    }
+
+    fn __getitem__(&self, var: AnyVar) -> PyResult<Option<Value>> {
+        self.get(var)
+    }
+
+    fn __contains__(&self, var: AnyVar) -> PyResult<bool> {
+        Ok(self.get(var)?.is_some())
+    }
+}
+
+/// Raises `ValueError` unless `solver_id` is `None` (no variables involved) or `expected`.
+fn check_solver_id(expected: usize, solver_id: Option<usize>) -> PyResult<()> {
+    match solver_id {
+        Some(id) if id != expected => Err(PyErr::new::<PyValueError, _>(
+            "the variable belongs to another solver",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Converts the result of a solver call, raising an exception if the solver gave up.
+fn check_outcome<T>(outcome: SolveOutcome<T>) -> PyResult<Option<T>> {
+    match outcome {
+        SolveOutcome::Sat(x) => Ok(Some(x)),
+        SolveOutcome::Unsat => Ok(None),
+        SolveOutcome::Unknown => {
+            take_resource_limit_exceeded();
+            match take_sat_solver_error() {
+                Some(e) => Err(PyErr::new::<PyRuntimeError, _>(e)),
+                None => Err(PyErr::new::<PyTimeoutError, _>("resource limit exceeded")),
+            }
+        }
+        SolveOutcome::Overflow(e) => Err(PyErr::new::<PyOverflowError, _>(e.to_string())),
+    }
+}
+
+/// A CSP solver backed by `IntegratedSolver`.
+///
+/// `irrefutable_facts` and `answer_iter` consume the solver, after which it cannot be used any more.
+#[pyclass(name = "Solver", unsendable)]
+pub struct PySolver {
+    id: usize,
+    solver: Option<IntegratedSolver<'static>>,
+    bool_vars: Vec<BoolVar>,
+    int_vars: Vec<IntVar>,
+}
+
+impl PySolver {
+    fn solver(&mut self) -> PyResult<&mut IntegratedSolver<'static>> {
+        self.solver
+            .as_mut()
+            .ok_or_else(|| PyErr::new::<PyRuntimeError, _>("the solver has already been consumed"))
+    }
+
+    fn take_solver(&mut self) -> PyResult<IntegratedSolver<'static>> {
+        self.solver
+            .take()
+            .ok_or_else(|| PyErr::new::<PyRuntimeError, _>("the solver has already been consumed"))
+    }
+
+    fn add_item(&mut self, item: &Bound<'_, PyAny>) -> PyResult<()> {
+        if let Ok(constraint) = item.downcast::<PyConstraint>() {
+            let constraint = constraint.get();
+            check_solver_id(self.id, constraint.solver_id)?;
+            self.solver()?.add_constraint(constraint.to_stmt());
+        } else if let Ok(expr) = item.extract::<BoolOperand>() {
+            check_solver_id(self.id, expr.solver_id())?;
+            self.solver()?.add_expr(expr.into_expr());
+        } else if !item.is_instance_of::<PyString>() && item.try_iter().is_ok() {
+            for item in item.try_iter()? {
+                self.add_item(&item?)?;
+            }
+        } else {
+            return Err(PyErr::new::<PyTypeError, _>(format!(
+                "cannot add {} to the solver",
+                item.get_type().name()?
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PySolver {
+    /// Creates a solver with `config`, or the global config (see `set_config`) if not given.
+    #[new]
+    #[pyo3(signature = (config = None))]
+    fn new(config: Option<PyConfig>) -> PySolver {
+        let config = match config {
+            Some(config) => config,
+            None => get_global_config().lock().unwrap().clone(),
+        };
+        PySolver {
+            id: NEXT_SOLVER_ID.fetch_add(1, Ordering::Relaxed),
+            solver: Some(IntegratedSolver::with_backend_config(
+                config.config,
+                &config.backend_config,
+            )),
+            bool_vars: vec![],
+            int_vars: vec![],
+        }
+    }
+
+    fn bool_var(&mut self, py: Python) -> PyResult<Py<PyBoolVar>> {
+        let var = self.solver()?.new_bool_var();
+        self.bool_vars.push(var);
+        Py::new(
+            py,
+            PyClassInitializer::from(bool_expr(var.expr(), Some(self.id))).add_subclass(
+                PyBoolVar {
+                    var,
+                    solver_id: self.id,
+                },
+            ),
+        )
+    }
+
+    /// Creates an integer variable whose domain is `low..=high`.
+    fn int_var(&mut self, py: Python, low: i32, high: i32) -> PyResult<Py<PyIntVar>> {
+        if low > high {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "empty domain: {}..={}",
+                low, high
+            )));
+        }
+        let var = self.solver()?.new_int_var(Domain::range(low, high));
+        self.new_int_var_object(py, var)
+    }
+
+    /// Creates an integer variable whose domain is `values`.
+    fn int_var_from_domain(&mut self, py: Python, values: Vec<i32>) -> PyResult<Py<PyIntVar>> {
+        if values.is_empty() {
+            return Err(PyErr::new::<PyValueError, _>("empty domain"));
+        }
+        let var = self.solver()?.new_int_var_from_list(values);
+        self.new_int_var_object(py, var)
+    }
+
+    /// Adds constraints. Each item is a `BoolExpr`, a `bool`, a `Constraint` or an iterable of them.
+    /// Raises `ValueError` if an item involves variables of another solver.
+    #[pyo3(signature = (*items))]
+    fn add(&mut self, items: &Bound<'_, PyTuple>) -> PyResult<()> {
+        for item in items {
+            self.add_item(&item)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the values of all the variables in a solution, or `None` if there is no solution.
+    /// Raises `TimeoutError` if the resource limit is exceeded.
+    fn solve(&mut self) -> PyResult<Option<PyAssignment>> {
+        take_sat_solver_error();
+        let id = self.id;
+        let bool_vars = self.bool_vars.clone();
+        let int_vars = self.int_vars.clone();
+        let outcome = self.solver()?.try_solve().map(|model| {
+            let mut assignment = Assignment::new();
+            for &v in &bool_vars {
+                assignment.set_bool(v, model.get_bool(v));
+            }
+            for &v in &int_vars {
+                assignment.set_int(v, model.get_int(v));
+            }
+            PyAssignment {
+                assignment,
+                solver_id: id,
+            }
+        });
+        check_outcome(outcome)
+    }
+
+    /// Returns the values of `vars` which are common to all the solutions, or `None` if there is no solution.
+    /// Raises `TimeoutError` if the resource limit is exceeded.
+    fn irrefutable_facts(&mut self, vars: Vec<AnyVar>) -> PyResult<Option<PyAssignment>> {
+        take_sat_solver_error();
+        let (bool_vars, int_vars) = self.split_vars(vars)?;
+        let outcome = self
+            .take_solver()?
+            .try_decide_irrefutable_facts(&bool_vars, &int_vars);
+        Ok(check_outcome(outcome)?.map(|assignment| PyAssignment {
+            assignment,
+            solver_id: self.id,
+        }))
+    }
+
+    /// Returns an iterator over the distinct assignments of `vars` in the solutions.
+    fn answer_iter(&mut self, vars: Vec<AnyVar>) -> PyResult<PyAnswerIterator> {
+        let (bool_vars, int_vars) = self.split_vars(vars)?;
+        let iter = self.take_solver()?.answer_iter(&bool_vars, &int_vars);
+        Ok(PyAnswerIterator {
+            iter,
+            solver_id: self.id,
+        })
+    }
+}
+
+impl PySolver {
+    fn new_int_var_object(&mut self, py: Python, var: IntVar) -> PyResult<Py<PyIntVar>> {
+        self.int_vars.push(var);
+        Py::new(
+            py,
+            PyClassInitializer::from(int_expr(var.expr(), Some(self.id))).add_subclass(PyIntVar {
+                var,
+                solver_id: self.id,
+            }),
+        )
+    }
+
+    fn split_vars(&self, vars: Vec<AnyVar>) -> PyResult<(Vec<BoolVar>, Vec<IntVar>)> {
+        let mut bool_vars = vec![];
+        let mut int_vars = vec![];
+        for var in vars {
+            check_solver_id(self.id, Some(var.solver_id()))?;
+            match var {
+                AnyVar::Bool(v) => bool_vars.push(v.var),
+                AnyVar::Int(v) => int_vars.push(v.var),
+            }
+        }
+        Ok((bool_vars, int_vars))
+    }
+}
+
+#[pyclass(name = "AnswerIterator", unsendable)]
+pub struct PyAnswerIterator {
+    iter: AnswerIterator<'static>,
+    solver_id: usize,
+}
+
+#[pymethods]
+impl PyAnswerIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Raises `TimeoutError` if the resource limit is exceeded.
+    fn __next__(&mut self) -> PyResult<Option<PyAssignment>> {
+        take_sat_solver_error();
+        let outcome = self.iter.try_next();
+        Ok(check_outcome(outcome)?.map(|assignment| PyAssignment {
+            assignment,
+            solver_id: self.solver_id,
+        }))
+    }
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBoolExpr>()?;
+    m.add_class::<PyIntExpr>()?;
+    m.add_class::<PyBoolVar>()?;
+    m.add_class::<PyIntVar>()?;
+    m.add_class::<PyConstraint>()?;
+    m.add_class::<PyAssignment>()?;
+    m.add_class::<PySolver>()?;
+    m.add_class::<PyAnswerIterator>()?;
+    m.add_function(wrap_pyfunction!(alldifferent, m)?)?;
+    m.add_function(wrap_pyfunction!(active_vertices_connected, m)?)?;
+    m.add_function(wrap_pyfunction!(graph_division, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,410 @@
+//! Python bindings for `cspuz_rs_puzzles`, exposed as the `cspuz_core.puzzles` module.
+//!
+//! Problems and answers are converted into plain Python values. Edges (`GridEdges` and `InnerGridEdges`
+//! in `cspuz_rs::graph`) are represented as `(horizontal, vertical)` tuples of 2-D lists.
+
+use std::time::Instant;
+
+use pyo3::exceptions::{PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+
+use cspuz_core::config::{BackendConfig, Config};
+use cspuz_core::integration::take_resource_limit_exceeded;
+use cspuz_rs::graph;
+use cspuz_rs::items::{Arrow, NumberedArrow};
+use cspuz_rs_puzzles::puzzles::masyu::MasyuClue;
+
+use crate::get_global_config;
+
+/// Restores the default `Config` and `BackendConfig` when dropped, even if the solver panics.
+struct RestoreDefaults {
+    config: Config,
+    backend_config: BackendConfig,
+}
+
+impl Drop for RestoreDefaults {
+    fn drop(&mut self) {
+        Config::set_default(self.config);
+        BackendConfig::set_default(self.backend_config.clone());
+    }
+}
+
+/// Runs `f` with the global config, raising `TimeoutError` if the solver gave up due to the resource limit.
+///
+/// The time limit bounds `f` as a whole, even if it runs the solver many times.
+/// The GIL is released while `f` runs so that other Python threads are not blocked by the solver.
+fn run_solver<T: Send>(py: Python, f: impl FnOnce() -> T + Send) -> PyResult<T> {
+    py.allow_threads(|| {
+        let _restore = RestoreDefaults {
+            config: Config::default(),
+            backend_config: BackendConfig::default(),
+        };
+        {
+            let global = get_global_config().lock().unwrap();
+            let mut config = global.config;
+            if let Some(time) = config.resource_limit.time {
+                config.resource_limit.deadline = Some(Instant::now() + time);
+            }
+            Config::set_default(config);
+            BackendConfig::set_default(global.backend_config.clone());
+        }
+        take_resource_limit_exceeded();
+        let ret = f();
+
+        if take_resource_limit_exceeded() {
+            Err(PyErr::new::<PyTimeoutError, _>("resource limit exceeded"))
+        } else {
+            Ok(ret)
+        }
+    })
+}
+
+type PyEdges<T> = (Vec<Vec<T>>, Vec<Vec<T>>);
+
+fn edges_to_py<T>(edges: graph::GridEdges<Vec<Vec<T>>>) -> PyEdges<T> {
+    (edges.horizontal, edges.vertical)
+}
+
+fn inner_edges_to_py<T>(edges: graph::InnerGridEdges<Vec<Vec<T>>>) -> PyEdges<T> {
+    (edges.horizontal, edges.vertical)
+}
+
+fn inner_edges_from_py<T>(
+    (horizontal, vertical): PyEdges<T>,
+) -> PyResult<graph::InnerGridEdges<Vec<Vec<T>>>> {
+    let (height, width) = grid_shape(&vertical, "vertical edges")?;
+    let width = width + 1;
+    if !(horizontal.len() == height - 1 && horizontal.iter().all(|row| row.len() == width)) {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "horizontal edges must be of shape {}x{} for a grid of shape {}x{}",
+            height - 1,
+            width,
+            height,
+            width
+        )));
+    }
+    Ok(graph::InnerGridEdges {
+        horizontal,
+        vertical,
+    })
+}
+
+/// Returns the shape of `grid`, raising `ValueError` if it is empty or ragged.
+fn grid_shape<T>(grid: &[Vec<T>], name: &str) -> PyResult<(usize, usize)> {
+    if grid.is_empty() {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "{} must not be empty",
+            name
+        )));
+    }
+    let width = grid[0].len();
+    if grid.iter().any(|row| row.len() != width) {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "all rows of {} must have the same length",
+            name
+        )));
+    }
+    Ok((grid.len(), width))
+}
+
+fn grid_from_py<T>(grid: Vec<Vec<T>>) -> PyResult<Vec<Vec<T>>> {
+    grid_shape(&grid, "the grid")?;
+    Ok(grid)
+}
+
+fn masyu_clue_to_py(clue: MasyuClue) -> Option<String> {
+    match clue {
+        MasyuClue::None => None,
+        MasyuClue::White => Some(String::from("white")),
+        MasyuClue::Black => Some(String::from("black")),
+    }
+}
+
+fn masyu_clue_from_py(clue: Option<String>) -> PyResult<MasyuClue> {
+    match clue.as_deref() {
+        None => Ok(MasyuClue::None),
+        Some("white") => Ok(MasyuClue::White),
+        Some("black") => Ok(MasyuClue::Black),
+        Some(s) => Err(PyErr::new::<PyValueError, _>(format!(
+            "unknown masyu clue: {}",
+            s
+        ))),
+    }
+}
+
+fn arrow_to_py((dir, n): NumberedArrow) -> (String, i32) {
+    let dir = match dir {
+        Arrow::Unspecified => "unspecified",
+        Arrow::Up => "up",
+        Arrow::Down => "down",
+        Arrow::Left => "left",
+        Arrow::Right => "right",
+    };
+    (String::from(dir), n)
+}
+
+fn arrow_from_py((dir, n): (String, i32)) -> PyResult<NumberedArrow> {
+    let dir = match dir.as_str() {
+        "unspecified" => Arrow::Unspecified,
+        "up" => Arrow::Up,
+        "down" => Arrow::Down,
+        "left" => Arrow::Left,
+        "right" => Arrow::Right,
+        _ => {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "unknown arrow direction: {}",
+                dir
+            )))
+        }
+    };
+    Ok((dir, n))
+}
+
+fn map_grid<T, U>(grid: Vec<Vec<T>>, f: impl Fn(T) -> U) -> Vec<Vec<U>> {
+    grid.into_iter()
+        .map(|row| row.into_iter().map(&f).collect())
+        .collect()
+}
+
+fn try_map_grid<T, U>(grid: Vec<Vec<T>>, f: impl Fn(T) -> PyResult<U>) -> PyResult<Vec<Vec<U>>> {
+    grid.into_iter()
+        .map(|row| row.into_iter().map(&f).collect())
+        .collect()
+}
+
+/// Defines a Python module `cspuz_core.puzzles.<genre>` with `deserialize_problem`, `serialize_problem`
+/// and `solve_<genre>`, along with `solve_url` used by the `solve_url` of the parent module.
+///
+/// `problem` is the Python representation of the problem, and `to_py` / `from_py` convert it from / into
+/// the problem type of the genre. `solve` takes the latter and returns an `Option` of `answer`.
+macro_rules! puzzle_module {
+    (
+        $genre:ident,
+        $solve_fn:ident,
+        problem: $problem:ty,
+        to_py: $to_py:expr,
+        from_py: $from_py:expr,
+        answer: $answer:ty,
+        solve: $solve:expr $(,)?
+    ) => {
+        mod $genre {
+            use super::*;
+            use cspuz_rs_puzzles::puzzles::$genre;
+
+            #[pyfunction]
+            fn deserialize_problem(url: &str) -> PyResult<Option<$problem>> {
+                Ok($genre::deserialize_problem(url).map($to_py))
+            }
+
+            #[pyfunction]
+            fn serialize_problem(problem: $problem) -> PyResult<Option<String>> {
+                let problem = ($from_py)(problem)?;
+                Ok($genre::serialize_problem(&problem))
+            }
+
+            #[pyfunction]
+            fn $solve_fn(py: Python, problem: $problem) -> PyResult<Option<$answer>> {
+                let problem = ($from_py)(problem)?;
+                run_solver(py, || ($solve)(problem))
+            }
+
+            /// Returns `None` if `url` is not a problem of this genre, and the answer otherwise.
+            pub fn solve_url(py: Python, url: &str) -> PyResult<Option<PyObject>> {
+                match $genre::deserialize_problem(url) {
+                    Some(problem) => $solve_fn(py, ($to_py)(problem))?.into_py_any(py).map(Some),
+                    None => Ok(None),
+                }
+            }
+
+            pub fn register(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+                let m = PyModule::new(parent.py(), stringify!($genre))?;
+                m.add_function(wrap_pyfunction!(deserialize_problem, &m)?)?;
+                m.add_function(wrap_pyfunction!(serialize_problem, &m)?)?;
+                m.add_function(wrap_pyfunction!($solve_fn, &m)?)?;
+                add_submodule(parent, &m)
+            }
+        }
+    };
+}
+
+type Grid<T> = Vec<Vec<T>>;
+
+puzzle_module!(
+    akari,
+    solve_akari,
+    problem: Grid<Option<i32>>,
+    to_py: |p| p,
+    from_py: grid_from_py,
+    answer: Grid<Option<bool>>,
+    solve: |p: Grid<Option<i32>>| cspuz_rs_puzzles::puzzles::akari::solve_akari(&p),
+);
+
+puzzle_module!(
+    heyawake,
+    solve_heyawake,
+    problem: (PyEdges<bool>, Vec<Option<i32>>),
+    to_py: |(borders, clues)| (inner_edges_to_py(borders), clues),
+    from_py: |(borders, clues): (PyEdges<bool>, Vec<Option<i32>>)| {
+        let borders = inner_edges_from_py(borders)?;
+        let num_rooms = graph::borders_to_rooms(&borders).len();
+        if clues.len() != num_rooms {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "expected {} clues (one for each room), but got {}",
+                num_rooms,
+                clues.len()
+            )));
+        }
+        Ok((borders, clues))
+    },
+    answer: Grid<Option<bool>>,
+    solve: |(borders, clues): (graph::InnerGridEdges<Grid<bool>>, Vec<Option<i32>>)| {
+        cspuz_rs_puzzles::puzzles::heyawake::solve_heyawake(&borders, &clues)
+    },
+);
+
+puzzle_module!(
+    lits,
+    solve_lits,
+    problem: PyEdges<bool>,
+    to_py: inner_edges_to_py,
+    from_py: inner_edges_from_py,
+    answer: Grid<Option<bool>>,
+    solve: |borders: graph::InnerGridEdges<Grid<bool>>| {
+        cspuz_rs_puzzles::puzzles::lits::solve_lits(&borders)
+    },
+);
+
+puzzle_module!(
+    masyu,
+    solve_masyu,
+    problem: (bool, Grid<Option<String>>),
+    to_py: |(full, clues)| (full, map_grid(clues, masyu_clue_to_py)),
+    from_py: |(full, clues)| {
+        Ok::<_, PyErr>((full, try_map_grid(grid_from_py(clues)?, masyu_clue_from_py)?))
+    },
+    answer: PyEdges<Option<bool>>,
+    solve: |(full, clues): (bool, Grid<MasyuClue>)| {
+        cspuz_rs_puzzles::puzzles::masyu::solve_masyu(full, &clues).map(edges_to_py)
+    },
+);
+
+puzzle_module!(
+    nurikabe,
+    solve_nurikabe,
+    problem: Grid<Option<i32>>,
+    to_py: |p| p,
+    from_py: grid_from_py,
+    answer: Grid<Option<bool>>,
+    solve: |p: Grid<Option<i32>>| cspuz_rs_puzzles::puzzles::nurikabe::solve_nurikabe(&p),
+);
+
+puzzle_module!(
+    slitherlink,
+    solve_slitherlink,
+    problem: (bool, Grid<Option<i32>>),
+    to_py: |p| p,
+    from_py: |(full, clues)| Ok::<_, PyErr>((full, grid_from_py(clues)?)),
+    answer: PyEdges<Option<bool>>,
+    solve: |(full, clues): (bool, Grid<Option<i32>>)| {
+        cspuz_rs_puzzles::puzzles::slitherlink::solve_slitherlink(full, &clues).map(edges_to_py)
+    },
+);
+
+puzzle_module!(
+    star_battle,
+    solve_star_battle,
+    problem: (i32, PyEdges<bool>),
+    to_py: |(star_amount, borders)| (star_amount, inner_edges_to_py(borders)),
+    from_py: |(star_amount, borders)| Ok::<_, PyErr>((star_amount, inner_edges_from_py(borders)?)),
+    answer: Grid<Option<bool>>,
+    solve: |(star_amount, borders): (i32, graph::InnerGridEdges<Grid<bool>>)| {
+        cspuz_rs_puzzles::puzzles::star_battle::solve_star_battle(star_amount, &borders)
+    },
+);
+
+puzzle_module!(
+    sudoku,
+    solve_sudoku,
+    problem: Grid<Option<i32>>,
+    to_py: |p| p,
+    from_py: grid_from_py,
+    answer: Grid<Option<i32>>,
+    solve: |p: Grid<Option<i32>>| cspuz_rs_puzzles::puzzles::sudoku::solve_sudoku(&p),
+);
+
+puzzle_module!(
+    yajilin,
+    solve_yajilin,
+    problem: (bool, Grid<Option<(String, i32)>>),
+    to_py: |(outside, clues)| (outside, map_grid(clues, |c: Option<NumberedArrow>| c.map(arrow_to_py))),
+    from_py: |(outside, clues)| {
+        let clues = try_map_grid(grid_from_py(clues)?, |c: Option<(String, i32)>| {
+            c.map(arrow_from_py).transpose()
+        })?;
+        Ok::<_, PyErr>((outside, clues))
+    },
+    answer: (PyEdges<Option<bool>>, Grid<Option<bool>>),
+    solve: |(outside, clues): (bool, Grid<Option<NumberedArrow>>)| {
+        cspuz_rs_puzzles::puzzles::yajilin::solve_yajilin(outside, &clues)
+            .map(|(is_line, is_black)| (edges_to_py(is_line), is_black))
+    },
+);
+
+/// Adds `child` to `parent` and registers it in `sys.modules`, so that it can be imported by
+/// `import parent.child`.
+fn add_submodule(parent: &Bound<'_, PyModule>, child: &Bound<'_, PyModule>) -> PyResult<()> {
+    parent.add_submodule(child)?;
+    let name = format!("{}.{}", parent.name()?, child.name()?);
+    child.setattr("__name__", &name)?;
+    parent
+        .py()
+        .import("sys")?
+        .getattr("modules")?
+        .set_item(name, child)
+}
+
+type SolveUrl = fn(Python, &str) -> PyResult<Option<PyObject>>;
+
+const SOLVE_URL: [(&str, SolveUrl); 9] = [
+    ("akari", akari::solve_url),
+    ("heyawake", heyawake::solve_url),
+    ("lits", lits::solve_url),
+    ("masyu", masyu::solve_url),
+    ("nurikabe", nurikabe::solve_url),
+    ("slitherlink", slitherlink::solve_url),
+    ("star_battle", star_battle::solve_url),
+    ("sudoku", sudoku::solve_url),
+    ("yajilin", yajilin::solve_url),
+];
+
+/// Decodes a puzz.link URL of one of the genres in this module and solves it.
+/// Returns the name of the genre and the answer as returned by `<genre>.solve_<genre>`.
+#[pyfunction]
+fn solve_url(py: Python, url: &str) -> PyResult<(&'static str, PyObject)> {
+    for (genre, solve) in SOLVE_URL {
+        if let Some(answer) = solve(py, url)? {
+            return Ok((genre, answer));
+        }
+    }
+    Err(PyErr::new::<PyValueError, _>(
+        "the URL is not a problem of a supported genre",
+    ))
+}
+
+pub fn register(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+    let m = PyModule::new(parent.py(), "puzzles")?;
+    m.add_function(wrap_pyfunction!(solve_url, &m)?)?;
+    add_submodule(parent, &m)?;
+
+    akari::register(&m)?;
+    heyawake::register(&m)?;
+    lits::register(&m)?;
+    masyu::register(&m)?;
+    nurikabe::register(&m)?;
+    slitherlink::register(&m)?;
+    star_battle::register(&m)?;
+    sudoku::register(&m)?;
+    yajilin::register(&m)?;
+    Ok(())
+}
@@ -723,8 +723,7 @@ pub fn active_edges_single_cycle<T: BoolArrayLike>(
         solver.add_expr(count_true(adj_edges).eq(is_passed.at(u).ite(2, 0)));
     }
 
-    let line_graph = graph.line_graph();
-    active_vertices_connected(solver, &is_active_edge, &line_graph);
+    solver.add_active_edges_single_cycle(&is_active_edge, &graph.edges);
 
     is_passed
 }
@@ -6,6 +6,7 @@ pub mod graph;
 pub mod hex;
 pub mod items;
 pub mod polyomino;
+pub mod regex;
 pub mod serializer;
 pub mod solver;
 
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use cspuz_core::dfa::Dfa;
+
+/// A regular expression over integer symbols, which can be converted into a `Dfa` for `Solver::add_regular`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Regex {
+    /// Any one of the symbols.
+    Symbols(Vec<i32>),
+    Concat(Vec<Regex>),
+    Alt(Vec<Regex>),
+    Star(Box<Regex>),
+}
+
+impl Regex {
+    pub fn symbol(symbol: i32) -> Regex {
+        Regex::Symbols(vec![symbol])
+    }
+
+    pub fn any_of(symbols: &[i32]) -> Regex {
+        Regex::Symbols(symbols.to_vec())
+    }
+
+    /// The regex matching only the empty sequence.
+    pub fn empty() -> Regex {
+        Regex::Concat(vec![])
+    }
+
+    pub fn concat(regexes: Vec<Regex>) -> Regex {
+        Regex::Concat(regexes)
+    }
+
+    pub fn alt(regexes: Vec<Regex>) -> Regex {
+        Regex::Alt(regexes)
+    }
+
+    pub fn star(self) -> Regex {
+        Regex::Star(Box::new(self))
+    }
+
+    pub fn plus(self) -> Regex {
+        Regex::Concat(vec![self.clone(), self.star()])
+    }
+
+    pub fn optional(self) -> Regex {
+        Regex::Alt(vec![self, Regex::empty()])
+    }
+
+    /// Repeats `self` at least `min` times and at most `max` times (unbounded if `max` is `None`).
+    pub fn repeat(self, min: usize, max: Option<usize>) -> Regex {
+        let mut terms = vec![self.clone(); min];
+        match max {
+            Some(max) => {
+                assert!(min <= max);
+                terms.extend(std::iter::repeat_n(self.optional(), max - min));
+            }
+            None => terms.push(self.star()),
+        }
+        Regex::Concat(terms)
+    }
+
+    /// Parses a regex in which each symbol is a single digit.
+    ///
+    /// Supported syntax: `[...]` (set of digits), `(...)`, `|`, `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`.
+    /// Spaces are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cspuz_rs::regex::Regex;
+    /// // a nonogram row with the clue "2 1"
+    /// let dfa = Regex::parse("0*110+10*").unwrap().to_dfa();
+    /// assert!(dfa.accepts(&[0, 1, 1, 0, 0, 1]));
+    /// assert!(!dfa.accepts(&[1, 1, 1, 0, 1]));
+    /// ```
+    pub fn parse(pattern: &str) -> Result<Regex, String> {
+        let chars = pattern.chars().filter(|c| *c != ' ').collect::<Vec<_>>();
+        let mut parser = RegexParser { chars, pos: 0 };
+        let ret = parser.parse_alt()?;
+        if parser.pos < parser.chars.len() {
+            return Err(format!(
+                "unexpected character '{}' at {}",
+                parser.chars[parser.pos], parser.pos
+            ));
+        }
+        Ok(ret)
+    }
+
+    /// Converts `self` into a `Dfa` by the subset construction.
+    pub fn to_dfa(&self) -> Dfa {
+        let mut nfa = Nfa {
+            epsilon: vec![],
+            transitions: vec![],
+        };
+        let (start, end) = nfa.build(self);
+
+        let initial = nfa.closure([start].into_iter().collect());
+        let mut ids = BTreeMap::new();
+        let mut sets = vec![];
+        let mut queue = VecDeque::new();
+        ids.insert(initial.clone(), 0);
+        sets.push(initial.clone());
+        queue.push_back(initial);
+
+        let mut transitions = vec![];
+        while let Some(set) = queue.pop_front() {
+            let from = ids[&set];
+            let mut moves: BTreeMap<i32, BTreeSet<usize>> = BTreeMap::new();
+            for &s in &set {
+                for &(symbol, t) in &nfa.transitions[s] {
+                    moves.entry(symbol).or_default().insert(t);
+                }
+            }
+            for (symbol, next) in moves {
+                let next = nfa.closure(next);
+                let to = match ids.get(&next) {
+                    Some(&id) => id,
+                    None => {
+                        let id = sets.len();
+                        ids.insert(next.clone(), id);
+                        sets.push(next.clone());
+                        queue.push_back(next);
+                        id
+                    }
+                };
+                transitions.push((from, symbol, to));
+            }
+        }
+
+        let mut dfa = Dfa::new(sets.len(), 0);
+        for (i, set) in sets.iter().enumerate() {
+            dfa.set_accepting(i, set.contains(&end));
+        }
+        for (from, symbol, to) in transitions {
+            dfa.add_transition(from, symbol, to);
+        }
+        dfa
+    }
+}
+
+struct Nfa {
+    epsilon: Vec<Vec<usize>>,
+    transitions: Vec<Vec<(i32, usize)>>,
+}
+
+impl Nfa {
+    fn new_state(&mut self) -> usize {
+        self.epsilon.push(vec![]);
+        self.transitions.push(vec![]);
+        self.epsilon.len() - 1
+    }
+
+    /// Adds states for `regex` (Thompson's construction) and returns its start and end states.
+    fn build(&mut self, regex: &Regex) -> (usize, usize) {
+        match regex {
+            Regex::Symbols(symbols) => {
+                let start = self.new_state();
+                let end = self.new_state();
+                for &symbol in symbols {
+                    self.transitions[start].push((symbol, end));
+                }
+                (start, end)
+            }
+            Regex::Concat(regexes) => {
+                let start = self.new_state();
+                let mut cur = start;
+                for r in regexes {
+                    let (s, e) = self.build(r);
+                    self.epsilon[cur].push(s);
+                    cur = e;
+                }
+                (start, cur)
+            }
+            Regex::Alt(regexes) => {
+                let start = self.new_state();
+                let end = self.new_state();
+                for r in regexes {
+                    let (s, e) = self.build(r);
+                    self.epsilon[start].push(s);
+                    self.epsilon[e].push(end);
+                }
+                (start, end)
+            }
+            Regex::Star(r) => {
+                let start = self.new_state();
+                let end = self.new_state();
+                let (s, e) = self.build(r);
+                self.epsilon[start].push(s);
+                self.epsilon[start].push(end);
+                self.epsilon[e].push(s);
+                self.epsilon[e].push(end);
+                (start, end)
+            }
+        }
+    }
+
+    fn closure(&self, mut set: BTreeSet<usize>) -> BTreeSet<usize> {
+        let mut stack = set.iter().copied().collect::<Vec<_>>();
+        while let Some(s) = stack.pop() {
+            for &t in &self.epsilon[s] {
+                if set.insert(t) {
+                    stack.push(t);
+                }
+            }
+        }
+        set
+    }
+}
+
+struct RegexParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl RegexParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("'{}' expected at {}", c, self.pos))
+        }
+    }
+
+    fn parse_alt(&mut self) -> Result<Regex, String> {
+        let mut terms = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            terms.push(self.parse_concat()?);
+        }
+        if terms.len() == 1 {
+            Ok(terms.pop().unwrap())
+        } else {
+            Ok(Regex::Alt(terms))
+        }
+    }
+
+    fn parse_concat(&mut self) -> Result<Regex, String> {
+        let mut terms = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            terms.push(self.parse_repeat()?);
+        }
+        if terms.len() == 1 {
+            Ok(terms.pop().unwrap())
+        } else {
+            Ok(Regex::Concat(terms))
+        }
+    }
+
+    fn parse_repeat(&mut self) -> Result<Regex, String> {
+        let mut ret = self.parse_atom()?;
+        while let Some(c) = self.peek() {
+            match c {
+                '*' => ret = ret.star(),
+                '+' => ret = ret.plus(),
+                '?' => ret = ret.optional(),
+                '{' => {
+                    self.pos += 1;
+                    let min = self.parse_number()?;
+                    let max = if self.peek() == Some(',') {
+                        self.pos += 1;
+                        if self.peek() == Some('}') {
+                            None
+                        } else {
+                            Some(self.parse_number()?)
+                        }
+                    } else {
+                        Some(min)
+                    };
+                    if max.is_some_and(|max| max < min) {
+                        return Err(format!("invalid repetition count at {}", self.pos));
+                    }
+                    self.expect('}')?;
+                    ret = ret.repeat(min, max);
+                    continue;
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        Ok(ret)
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let ret = self.parse_alt()?;
+                self.expect(')')?;
+                Ok(ret)
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut symbols = vec![];
+                while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+                    symbols.push(d as i32);
+                    self.pos += 1;
+                }
+                self.expect(']')?;
+                Ok(Regex::Symbols(symbols))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                self.pos += 1;
+                Ok(Regex::symbol(c.to_digit(10).unwrap() as i32))
+            }
+            Some(c) => Err(format!("unexpected character '{}' at {}", c, self.pos)),
+            None => Err(String::from("unexpected end of pattern")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<usize, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse::<usize>()
+            .map_err(|_| format!("number expected at {}", start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regex_to_dfa() {
+        let dfa = Regex::concat(vec![
+            Regex::symbol(0).star(),
+            Regex::symbol(1).repeat(2, Some(2)),
+            Regex::symbol(0).plus(),
+            Regex::symbol(1),
+            Regex::symbol(0).star(),
+        ])
+        .to_dfa();
+
+        assert!(dfa.accepts(&[1, 1, 0, 1]));
+        assert!(dfa.accepts(&[0, 1, 1, 0, 0, 1, 0]));
+        assert!(!dfa.accepts(&[1, 1, 1, 0, 1]));
+        assert!(!dfa.accepts(&[1, 1, 0]));
+        assert!(!dfa.accepts(&[1, 1, 2, 1]));
+    }
+
+    #[test]
+    fn test_regex_parse() {
+        assert_eq!(
+            Regex::parse("0*1{2}"),
+            Ok(Regex::Concat(vec![
+                Regex::symbol(0).star(),
+                Regex::symbol(1).repeat(2, Some(2)),
+            ]))
+        );
+        assert_eq!(
+            Regex::parse("[12]|(3 4)?"),
+            Ok(Regex::Alt(vec![
+                Regex::any_of(&[1, 2]),
+                Regex::concat(vec![Regex::symbol(3), Regex::symbol(4)]).optional(),
+            ]))
+        );
+        assert!(Regex::parse("(1").is_err());
+        assert!(Regex::parse("1{3,2}").is_err());
+        assert!(Regex::parse("a").is_err());
+
+        let dfa = Regex::parse("(12|3){1,}").unwrap().to_dfa();
+        assert!(dfa.accepts(&[3, 1, 2, 3]));
+        assert!(!dfa.accepts(&[]));
+        assert!(!dfa.accepts(&[1, 3]));
+    }
+}
@@ -1,4 +1,4 @@
-use super::traits::{BoolArrayLike, IntArrayLike};
+use super::traits::{BoolArrayLike, IntArrayLike, Operand};
 use super::{BoolExpr, IntExpr};
 
 use cspuz_core::csp::BoolExpr as CSPBoolExpr;
@@ -23,6 +23,18 @@ pub fn sum<T: IntArrayLike>(values: T) -> IntExpr {
     IntExpr::from_raw(CSPIntExpr::Linear(terms))
 }
 
+/// Returns the `index`-th (0-based) element of `values`, or 0 if `index` is out of range.
+pub fn element<I, T>(index: I, values: T) -> IntExpr
+where
+    I: Operand<Shape = (), Value = CSPIntExpr>,
+    T: IntArrayLike,
+{
+    IntExpr::from_raw(CSPIntExpr::element(
+        index.as_ndarray().data.0,
+        values.to_vec(),
+    ))
+}
+
 pub fn count_true<T: BoolArrayLike>(values: T) -> IntExpr {
     let terms = values
         .to_vec()
@@ -3,23 +3,28 @@ mod ndarray;
 pub(crate) mod traits;
 
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 
-pub use cspuz_core::config::{Config, GraphDivisionMode};
+pub use cspuz_core::arithmetic::OverflowError;
+pub use cspuz_core::config::{Config, GraphDivisionMode, ResourceLimit};
 use cspuz_core::csp::BoolExpr as CSPBoolExpr;
 use cspuz_core::csp::BoolVar as CSPBoolVar;
 use cspuz_core::csp::IntExpr as CSPIntExpr;
 use cspuz_core::csp::IntVar as CSPIntVar;
 use cspuz_core::csp::{Assignment, Stmt};
-use cspuz_core::custom_constraints::PropagatorGenerator;
+use cspuz_core::custom_constraints::{PropagatorGenerator, SimpleCustomIntConstraint};
+pub use cspuz_core::dfa::Dfa;
 use cspuz_core::domain::Domain;
+pub use cspuz_core::export::{CnfVarMap, ExportError};
 use cspuz_core::integration::IntegratedSolver;
 use cspuz_core::integration::Model as IntegratedModel;
-pub use cspuz_core::integration::PerfStats;
+pub use cspuz_core::integration::{take_resource_limit_exceeded, PerfStats, SolveOutcome};
+pub use cspuz_core::proof::Proof;
 pub use cspuz_core::propagators::graph_division::GraphDivisionOptions;
 
 use ndarray::NdArray;
 
-use traits::{BoolArrayLike, IntArrayLike};
+use traits::{BoolArrayLike, IntArrayLike, Operand};
 
 pub type BoolVar = NdArray<(), CSPBoolVar>;
 pub type BoolExpr = NdArray<(), CSPBoolExpr>;
@@ -35,13 +40,42 @@ pub type IntExprArray1D = NdArray<(usize,), CSPIntExpr>;
 pub type IntExprArray2D = NdArray<(usize, usize), CSPIntExpr>;
 
 pub use constraints::{
-    all, any, bool_constant, consecutive_prefix_true, count_true, int_constant, sum, FALSE, TRUE,
+    all, any, bool_constant, consecutive_prefix_true, count_true, element, int_constant, sum,
+    FALSE, TRUE,
 };
 
+/// The number of answers found by `Solver::count_answers`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnswerCount {
+    /// There are exactly this many answers.
+    Exact(usize),
+    /// There are at least this many answers (the counting stopped at the limit).
+    AtLeast(usize),
+}
+
+/// A fact on an answer key variable, derived by `Solver::deduction_steps`.
+///
+/// The first field is the index of the variable in the order of registration as an answer key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fact {
+    Bool(usize, bool),
+    Int(usize, i32),
+}
+
+/// A step of the derivation returned by `Solver::deduction_steps`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeductionStep {
+    /// The fact derived in this step.
+    pub fact: Fact,
+    /// The tags of the constraint groups used to derive `fact`, in ascending order.
+    pub reasons: Vec<usize>,
+}
+
 pub struct Solver<'a> {
     solver: IntegratedSolver<'a>,
     answer_key_bool: Vec<CSPBoolVar>,
     answer_key_int: Vec<CSPIntVar>,
+    tags: BTreeMap<usize, CSPBoolVar>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -70,6 +104,7 @@ impl<'a> Solver<'a> {
             solver: IntegratedSolver::with_config(config),
             answer_key_bool: vec![],
             answer_key_int: vec![],
+            tags: BTreeMap::new(),
         }
     }
 
@@ -79,6 +114,7 @@ impl<'a> Solver<'a> {
             solver: IntegratedSolver::with_config(config),
             answer_key_bool: vec![],
             answer_key_int: vec![],
+            tags: BTreeMap::new(),
         }
     }
 
@@ -260,6 +296,33 @@ impl<'a> Solver<'a> {
             .for_each(|e| self.solver.add_expr(e));
     }
 
+    /// Adds a constraint that the specified boolean expression(s) is true, as a member of the constraint group `tag`.
+    ///
+    /// Tagged constraints are enforced just like the ones added by `add_expr`.
+    /// The difference is that, when the CSP instance is unsatisfiable, `unsat_core` can tell which groups are responsible for it.
+    /// Constraints added with the same `tag` belong to the same group.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cspuz_rs::solver::Solver;
+    /// let mut solver = Solver::new();
+    /// let x = &solver.bool_var();
+    /// let y = &solver.bool_var();
+    /// solver.add_expr_tagged(0, x | y);
+    /// solver.add_expr_tagged(1, [!x, !y]);
+    /// ```
+    pub fn add_expr_tagged<T: BoolArrayLike>(&mut self, tag: usize, exprs: T) {
+        let solver = &mut self.solver;
+        let indicator = *self
+            .tags
+            .entry(tag)
+            .or_insert_with(|| solver.new_bool_var());
+        exprs
+            .to_vec()
+            .into_iter()
+            .for_each(|e| solver.add_expr(indicator.expr().imp(e)));
+    }
+
     /// Adds a constraint that the specified integer expressions have different values.
     ///
     /// # Examples
@@ -291,6 +354,80 @@ impl<'a> Solver<'a> {
             .add_constraint(Stmt::ActiveVerticesConnected(vertices, graph.to_owned()));
     }
 
+    /// Adds a constraint that the edges of `graph` for which `exprs` are true form a single cycle,
+    /// or all of `exprs` are false.
+    pub fn add_active_edges_single_cycle<T: BoolArrayLike>(
+        &mut self,
+        exprs: T,
+        graph: &[(usize, usize)],
+    ) {
+        let edges = exprs.to_vec();
+        assert_eq!(edges.len(), graph.len());
+        for &(u, v) in graph {
+            assert_ne!(u, v);
+        }
+        self.solver
+            .add_constraint(Stmt::ActiveEdgesSingleCycle(edges, graph.to_owned()));
+    }
+
+    /// Adds a constraint that the sequence of the values of `exprs` is accepted by `dfa`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cspuz_rs::regex::Regex;
+    /// # use cspuz_rs::solver::Solver;
+    /// let mut solver = Solver::new();
+    /// let row = &solver.int_var_1d(5, 0, 1);
+    /// solver.add_regular(row, &Regex::parse("0*110+10*").unwrap().to_dfa());
+    /// ```
+    pub fn add_regular<T: IntArrayLike>(&mut self, exprs: T, dfa: &Dfa) {
+        self.solver
+            .add_constraint(Stmt::Regular(exprs.to_vec(), dfa.clone()));
+    }
+
+    /// Adds a constraint that the sequence of the values of `lhs` is lexicographically less than or
+    /// equal to that of `rhs`.
+    ///
+    /// This is useful for breaking the symmetry of interchangeable objects (e.g. pieces of the same
+    /// shape), each of which is described by a sequence of values.
+    pub fn add_lex_less_eq<T1: IntArrayLike, T2: IntArrayLike>(&mut self, lhs: T1, rhs: T2) {
+        self.solver
+            .add_constraint(Stmt::LexLessEq(lhs.to_vec(), rhs.to_vec()));
+    }
+
+    /// Adds a constraint that, for each `i`, `values[i + 1]` does not appear in `exprs` before the
+    /// first occurrence of `values[i]`.
+    pub fn add_value_precede_chain<T: IntArrayLike>(&mut self, exprs: T, values: &[i32]) {
+        for i in 0..values.len() {
+            for j in (i + 1)..values.len() {
+                assert_ne!(values[i], values[j]);
+            }
+        }
+        self.solver
+            .add_constraint(Stmt::ValuePrecedeChain(exprs.to_vec(), values.to_vec()));
+    }
+
+    /// Rules out the assignments which are obtained from another one by permuting the values
+    /// `low..=high` of `labels`, assuming that these values are interchangeable (e.g. ids of
+    /// groups). Specifically, the first occurrences of `low, low + 1, ..., high` must appear in this
+    /// order.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cspuz_rs::solver::{AnswerCount, Solver};
+    /// let mut solver = Solver::new();
+    /// let labels = &solver.int_var_1d(4, 0, 1);
+    /// solver.add_answer_key_int(labels);
+    /// solver.add_interchangeable_labels(labels, 0, 1);
+    ///
+    /// // the 16 assignments are reduced to the 8 divisions of 4 items into at most 2 groups
+    /// assert_eq!(solver.count_answers(100), AnswerCount::Exact(8));
+    /// ```
+    pub fn add_interchangeable_labels<T: IntArrayLike>(&mut self, labels: T, low: i32, high: i32) {
+        let values = (low..=high).collect::<Vec<_>>();
+        self.add_value_precede_chain(labels, &values);
+    }
+
     pub fn add_graph_division<T: BoolArrayLike>(
         &mut self,
         sizes: &[Option<IntExpr>],
@@ -333,6 +470,22 @@ impl<'a> Solver<'a> {
             .add_constraint(Stmt::CustomConstraint(vars.to_vec(), constraint));
     }
 
+    pub fn add_custom_int_constraint<T: IntArrayLike>(
+        &mut self,
+        constraint: Box<dyn SimpleCustomIntConstraint>,
+        vars: T,
+    ) {
+        self.solver
+            .add_constraint(Stmt::CustomIntConstraint(vars.to_vec(), constraint));
+    }
+
+    /// Checks that the constraints added so far do not overflow the internal arithmetic.
+    /// Overflows are also detected when the problem is solved (`SolveOutcome::Overflow` is returned by
+    /// the `try_*` methods, and the others panic), so this is necessary only to detect them early.
+    pub fn check_overflow(&self) -> Result<(), OverflowError> {
+        self.solver.check_overflow()
+    }
+
     pub fn set_perf_stats<'b: 'a>(&mut self, perf_stats: &'b PerfStats) {
         self.solver.set_perf_stats(perf_stats);
     }
@@ -422,7 +575,350 @@ impl<'a> Solver<'a> {
             return None;
         }
 
-        self.solver.solve().map(|model| Model { model })
+        if self.tags.is_empty() {
+            self.solver.solve().map(|model| Model { model })
+        } else {
+            let assumptions = self.tag_assumptions();
+            self.solver
+                .solve_under(&assumptions)
+                .map(|model| Model { model })
+        }
+    }
+
+    /// Solves the CSP instance under the assumption that all of `assumptions` hold,
+    /// and returns a model if it exists.
+    ///
+    /// Unlike constraints added by `add_expr`, assumptions only affect this call.
+    /// This is useful for asking many "what if" questions to the same `Solver` instance without rebuilding it.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::Solver;
+    /// let mut solver = Solver::new();
+    /// let x = &solver.bool_var();
+    /// let y = &solver.bool_var();
+    ///
+    /// solver.add_expr(x | y);
+    ///
+    /// assert!(solver.solve_under([!x, !y]).is_none());
+    ///
+    /// let model = solver.solve_under(!x);
+    /// assert!(model.is_some());
+    /// assert_eq!(model.unwrap().get(y), true);
+    ///
+    /// // The assumptions in the previous calls are no longer effective.
+    /// assert!(solver.solve_under(!y).is_some());
+    /// ```
+    pub fn solve_under<'b, T: BoolArrayLike>(&'b mut self, assumptions: T) -> Option<Model<'b>> {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return None;
+        }
+
+        let mut assumptions = assumptions.to_vec();
+        assumptions.extend(self.tag_assumptions());
+        self.solver
+            .solve_under(&assumptions)
+            .map(|model| Model { model })
+    }
+
+    /// Solves the CSP instance so that the value of `objective` is minimized.
+    ///
+    /// Returns the optimal value together with a model attaining it, or `None` if the CSP instance is unsatisfiable.
+    /// The bounds on `objective` examined during the search are not kept, so this `Solver` can still be used
+    /// for other queries afterwards.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::{count_true, Solver};
+    /// let mut solver = Solver::new();
+    /// let x = &solver.bool_var_1d(5);
+    /// for i in 0..4 {
+    ///     solver.add_expr(x.at(i) | x.at(i + 1));
+    /// }
+    ///
+    /// let (value, model) = solver.minimize(count_true(x)).unwrap();
+    /// assert_eq!(value, 2);
+    /// assert_eq!(model.get(x), vec![false, true, false, true, false]);
+    /// ```
+    pub fn minimize<'b, T>(&'b mut self, objective: T) -> Option<(i32, Model<'b>)>
+    where
+        T: Operand<Shape = (), Value = CSPIntExpr>,
+    {
+        self.try_minimize(objective).sat()
+    }
+
+    /// Same as `minimize`, but distinguishes the case where `Config::resource_limit` is reached (`Unknown`)
+    /// from unsatisfiability.
+    pub fn try_minimize<'b, T>(&'b mut self, objective: T) -> SolveOutcome<(i32, Model<'b>)>
+    where
+        T: Operand<Shape = (), Value = CSPIntExpr>,
+    {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return SolveOutcome::Unsat;
+        }
+
+        let assumptions = self.tag_assumptions();
+        self.solver
+            .try_minimize_under(objective.as_ndarray().data.0, &assumptions)
+            .map(|(value, model)| (value, Model { model }))
+    }
+
+    /// Solves the CSP instance so that the value of `objective` is maximized.
+    ///
+    /// Returns the optimal value together with a model attaining it, or `None` if the CSP instance is unsatisfiable.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::Solver;
+    /// let mut solver = Solver::new();
+    /// let x = &solver.int_var(0, 10);
+    /// let y = &solver.int_var(0, 10);
+    /// solver.add_expr((x + y).le(11));
+    /// solver.add_expr(x.ge(y + 5));
+    ///
+    /// let (value, model) = solver.maximize(y).unwrap();
+    /// assert_eq!(value, 3);
+    /// assert_eq!(model.get(x), 8);
+    /// ```
+    pub fn maximize<'b, T>(&'b mut self, objective: T) -> Option<(i32, Model<'b>)>
+    where
+        T: Operand<Shape = (), Value = CSPIntExpr>,
+    {
+        self.try_maximize(objective).sat()
+    }
+
+    /// Same as `maximize`, but distinguishes the case where `Config::resource_limit` is reached.
+    pub fn try_maximize<'b, T>(&'b mut self, objective: T) -> SolveOutcome<(i32, Model<'b>)>
+    where
+        T: Operand<Shape = (), Value = CSPIntExpr>,
+    {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return SolveOutcome::Unsat;
+        }
+
+        let assumptions = self.tag_assumptions();
+        self.solver
+            .try_maximize_under(objective.as_ndarray().data.0, &assumptions)
+            .map(|(value, model)| (value, Model { model }))
+    }
+
+    /// Returns a minimal set of tags such that the constraints in the corresponding groups (added by `add_expr_tagged`),
+    /// together with all the untagged constraints, are unsatisfiable.
+    ///
+    /// If the CSP instance is satisfiable, this method returns `None`.
+    /// If the untagged constraints are unsatisfiable by themselves, the returned set is empty.
+    ///
+    /// The returned set is minimal in the sense that removing any tag from it makes the constraints satisfiable,
+    /// but it is not necessarily the smallest one. This method calls the SAT solver once per tag in the worst case.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::Solver;
+    /// let mut solver = Solver::new();
+    /// let x = &solver.int_var(0, 5);
+    ///
+    /// solver.add_expr_tagged(0, x.ge(2));
+    /// solver.add_expr_tagged(1, x.le(4));
+    /// solver.add_expr_tagged(2, x.ne(3));
+    /// solver.add_expr_tagged(3, x.le(1));
+    ///
+    /// assert_eq!(solver.unsat_core(), Some(vec![0, 3]));
+    /// ```
+    pub fn unsat_core(&mut self) -> Option<Vec<usize>> {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return None;
+        }
+
+        let assumptions = self.tag_assumptions();
+        if self.solver.solve_under(&assumptions).is_some() {
+            return None;
+        }
+
+        let all_tags = self.tags.keys().copied().collect();
+        Some(self.minimize_core(&[], all_tags))
+    }
+
+    /// Returns a minimal subset of `core` (a set of tags) which, together with `hard` and all the untagged constraints,
+    /// are unsatisfiable. The caller must make sure that `hard` is unsatisfiable under the tags in `core`.
+    fn minimize_core(&mut self, hard: &[CSPBoolExpr], core: Vec<usize>) -> Vec<usize> {
+        let mut core = core
+            .into_iter()
+            .map(|t| (t, self.tags[&t]))
+            .collect::<Vec<_>>();
+
+        // Deletion-based minimization: drop each group in turn and keep it dropped if the rest is still unsatisfiable.
+        let mut i = 0;
+        while i < core.len() {
+            let mut assumptions = hard.to_vec();
+            assumptions.extend(
+                core.iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, &(_, v))| v.expr()),
+            );
+            if self.solver.solve_under(&assumptions).is_none() {
+                core.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        core.into_iter().map(|(t, _)| t).collect()
+    }
+
+    /// Derives the irrefutable facts on the answer key variables one by one, in the order of "easiness",
+    /// and returns the trace of the derivation.
+    ///
+    /// In each step, among the facts not derived yet, this method picks the one which can be proven from
+    /// the smallest set of tagged constraint groups (see `add_expr_tagged`), assuming the untagged constraints and
+    /// all the facts derived in the previous steps. Ties are broken by the order of registration of answer keys.
+    /// Each fact refers to an answer key by its index in the order of registration by
+    /// `add_answer_key_bool` (for `Fact::Bool`) or `add_answer_key_int` (for `Fact::Int`).
+    ///
+    /// If the CSP instance is unsatisfiable, this method returns `None`.
+    ///
+    /// The set of tag groups found for a fact remains valid in the later steps, so it is only shrunk further in them
+    /// (and a fact provable without tags is not examined again). Still, this method calls the SAT solver many times
+    /// (roughly the number of facts squared, times the size of the sets of tags), so it is intended for presenting
+    /// hints on small instances rather than for solving.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::{DeductionStep, Fact, Solver};
+    /// let mut solver = Solver::new();
+    /// let x = &solver.bool_var();
+    /// let y = &solver.bool_var();
+    /// let z = &solver.bool_var();
+    /// solver.add_answer_key_bool([x, y, z]);
+    ///
+    /// solver.add_expr(x.imp(y));
+    /// solver.add_expr_tagged(0, y.imp(z));
+    /// solver.add_expr_tagged(1, x);
+    ///
+    /// let steps = solver.deduction_steps().unwrap();
+    /// assert_eq!(
+    ///     steps,
+    ///     vec![
+    ///         DeductionStep { fact: Fact::Bool(0, true), reasons: vec![1] },
+    ///         DeductionStep { fact: Fact::Bool(1, true), reasons: vec![] },
+    ///         DeductionStep { fact: Fact::Bool(2, true), reasons: vec![0] },
+    ///     ]
+    /// );
+    /// ```
+    pub fn deduction_steps(mut self) -> Option<Vec<DeductionStep>> {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return None;
+        }
+
+        let tag_assumptions = self.tag_assumptions();
+
+        // Candidates of the facts: the values of the answer keys in an arbitrary model, each with the set of tags
+        // from which it was proven in the previous steps (if any). The set stays valid as more facts are derived.
+        let mut candidates: Vec<(Fact, Option<Vec<usize>>)> = {
+            let model = self.solver.solve_under(&tag_assumptions)?;
+            let mut candidates = vec![];
+            for (i, &v) in self.answer_key_bool.iter().enumerate() {
+                candidates.push((Fact::Bool(i, model.get_bool(v)), None));
+            }
+            for (i, &v) in self.answer_key_int.iter().enumerate() {
+                candidates.push((Fact::Int(i, model.get_int(v)), None));
+            }
+            candidates
+        };
+
+        let mut derived = vec![];
+        let mut steps = vec![];
+
+        loop {
+            let mut best: Option<(usize, Vec<usize>)> = None;
+            let mut i = 0;
+            while i < candidates.len() {
+                let mut assumptions = derived.clone();
+                assumptions.push(!self.fact_expr(candidates[i].0));
+
+                let reasons = match candidates[i].1.take() {
+                    Some(core) if core.is_empty() => core,
+                    Some(core) => self.minimize_core(&assumptions, core),
+                    None => {
+                        let mut with_tags = assumptions.clone();
+                        with_tags.extend(tag_assumptions.iter().cloned());
+                        if let Some(model) = self.solver.solve_under(&with_tags) {
+                            // `model` is a genuine answer, so any candidate it violates is not an irrefutable fact.
+                            let answer_key_bool = &self.answer_key_bool;
+                            let answer_key_int = &self.answer_key_int;
+                            candidates.retain(|&(fact, _)| match fact {
+                                Fact::Bool(j, b) => model.get_bool(answer_key_bool[j]) == b,
+                                Fact::Int(j, n) => model.get_int(answer_key_int[j]) == n,
+                            });
+                            continue;
+                        }
+                        let all_tags = self.tags.keys().copied().collect();
+                        self.minimize_core(&assumptions, all_tags)
+                    }
+                };
+                candidates[i].1 = Some(reasons.clone());
+
+                let is_better = match &best {
+                    Some((_, r)) => reasons.len() < r.len(),
+                    None => true,
+                };
+                if is_better {
+                    let is_trivial = reasons.is_empty();
+                    best = Some((i, reasons));
+                    if is_trivial {
+                        break;
+                    }
+                }
+                i += 1;
+            }
+
+            let Some((i, reasons)) = best else {
+                break;
+            };
+            let (fact, _) = candidates.remove(i);
+            derived.push(self.fact_expr(fact));
+            steps.push(DeductionStep { fact, reasons });
+        }
+
+        Some(steps)
+    }
+
+    fn fact_expr(&self, fact: Fact) -> CSPBoolExpr {
+        match fact {
+            Fact::Bool(i, b) => {
+                let e = self.answer_key_bool[i].expr();
+                if b {
+                    e
+                } else {
+                    !e
+                }
+            }
+            Fact::Int(i, n) => self.answer_key_int[i].expr().eq(CSPIntExpr::Const(n)),
+        }
+    }
+
+    fn tag_assumptions(&self) -> Vec<CSPBoolExpr> {
+        self.tags.values().map(|v| v.expr()).collect()
+    }
+
+    fn enforce_tags(&mut self) {
+        let assumptions = self.tag_assumptions();
+        assumptions
+            .into_iter()
+            .for_each(|e| self.solver.add_expr(e));
+    }
+
+    /// Writes the CNF formula encoding the problem in the DIMACS format instead of solving it,
+    /// and returns the correspondence between its variables and the CSP variables.
+    /// See `IntegratedSolver::export_cnf` for the details.
+    pub fn export_cnf<W: std::io::Write>(mut self, out: &mut W) -> Result<CnfVarMap, ExportError> {
+        self.enforce_tags();
+        self.solver.export_cnf(out)
     }
 
     /// Returns a partial model containing each answer key variable whose value is the same across all possible models
@@ -459,16 +955,37 @@ impl<'a> Solver<'a> {
     /// assert_eq!(partial_model.get(z), None);
     /// ```
     pub fn irrefutable_facts(self) -> Option<OwnedPartialModel> {
+        self.try_irrefutable_facts().sat()
+    }
+
+    /// Same as `irrefutable_facts`, but distinguishes the case where `Config::resource_limit` is reached (`Unknown`)
+    /// from unsatisfiability. No partial result is returned in this case, since the facts which are not refuted yet
+    /// are not necessarily irrefutable.
+    pub fn try_irrefutable_facts(mut self) -> SolveOutcome<OwnedPartialModel> {
         #[cfg(any(test, feature = "test-support"))]
         if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
-            return None;
+            return SolveOutcome::Unsat;
         }
 
+        self.enforce_tags();
         self.solver
-            .decide_irrefutable_facts(&self.answer_key_bool, &self.answer_key_int)
+            .try_decide_irrefutable_facts(&self.answer_key_bool, &self.answer_key_int)
             .map(|assignment| OwnedPartialModel { assignment })
     }
 
+    /// Same as `irrefutable_facts`, but also returns a DRAT proof that every assignment satisfying
+    /// the constraints agrees with the returned facts. The solver must be created with `Config::produce_proof` set.
+    ///
+    /// The proof is `Err` if it is not available (e.g. some constraint used does not support proofs).
+    pub fn irrefutable_facts_with_proof(
+        mut self,
+    ) -> Option<(OwnedPartialModel, Result<Proof, String>)> {
+        self.enforce_tags();
+        self.solver
+            .decide_irrefutable_facts_with_proof(&self.answer_key_bool, &self.answer_key_int)
+            .map(|(assignment, proof)| (OwnedPartialModel { assignment }, proof))
+    }
+
     /// Returns an iterator that yields all possible assignments to the answer key variables.
     ///
     /// The order of assignments is implementation dependent and not guaranteed to be stable.
@@ -497,17 +1014,111 @@ impl<'a> Solver<'a> {
     /// // Note that `z` is not included in the answer key, so the value of `z` is not considered.
     /// assert_eq!(count, 3);
     /// ```
-    #[cfg_attr(not(any(test, feature = "test-support")), allow(unused_mut))]
     pub fn answer_iter(mut self) -> impl Iterator<Item = OwnedPartialModel> + 'a {
         #[cfg(any(test, feature = "test-support"))]
         if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
             self.add_expr(FALSE);
         }
 
+        self.enforce_tags();
         self.solver
             .answer_iter(&self.answer_key_bool, &self.answer_key_int)
             .map(|assignment| OwnedPartialModel { assignment })
     }
+
+    /// Same as `answer_iter`, but the iterator yields `Unknown` (and then ends) if `Config::resource_limit` is reached,
+    /// so that an incomplete enumeration can be told from a complete one.
+    pub fn try_answer_iter(mut self) -> impl Iterator<Item = SolveOutcome<OwnedPartialModel>> + 'a {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            self.add_expr(FALSE);
+        }
+
+        self.enforce_tags();
+        let mut iter = self
+            .solver
+            .answer_iter(&self.answer_key_bool, &self.answer_key_int);
+        let mut finished = false;
+        std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            match iter.try_next() {
+                SolveOutcome::Sat(assignment) => {
+                    Some(SolveOutcome::Sat(OwnedPartialModel { assignment }))
+                }
+                SolveOutcome::Unsat => None,
+                SolveOutcome::Unknown => {
+                    finished = true;
+                    Some(SolveOutcome::Unknown)
+                }
+                SolveOutcome::Overflow(e) => {
+                    finished = true;
+                    Some(SolveOutcome::Overflow(e))
+                }
+            }
+        })
+    }
+
+    /// Counts the number of distinct assignments to the answer key variables, up to `limit`.
+    ///
+    /// Returns `AnswerCount::Exact(n)` if there are exactly `n` (< `limit`) such assignments,
+    /// and `AnswerCount::AtLeast(limit)` if the counting was stopped upon reaching `limit`.
+    /// Only the answer key variables are blocked after each answer, so other variables do not multiply the count.
+    ///
+    /// This method may introduce additional constraints during search and therefore consumes the `Solver` instance.
+    ///
+    /// # Example
+    /// ```
+    /// # use cspuz_rs::solver::{AnswerCount, Solver};
+    /// let mut solver = Solver::new();
+    /// let x = &solver.bool_var();
+    /// let y = &solver.bool_var();
+    /// let z = &solver.bool_var();
+    ///
+    /// solver.add_answer_key_bool([x, y]);
+    /// solver.add_expr(x | y);
+    /// solver.add_expr(z.imp(x));
+    ///
+    /// assert_eq!(solver.count_answers(10), AnswerCount::Exact(3));
+    /// ```
+    pub fn count_answers(self, limit: usize) -> AnswerCount {
+        let (count, outcome) = self.count_answers_impl(limit);
+        if let SolveOutcome::Overflow(e) = outcome {
+            panic!("overflow: {}", e);
+        }
+        count
+    }
+
+    /// Same as `count_answers`, but returns `Unknown` if `Config::resource_limit` is reached before the counting ends.
+    /// `count_answers` reports such an interrupted count as `AnswerCount::AtLeast` the number of answers found so far.
+    pub fn try_count_answers(self, limit: usize) -> SolveOutcome<AnswerCount> {
+        let (count, outcome) = self.count_answers_impl(limit);
+        outcome.map(|()| count)
+    }
+
+    /// Counts the answers up to `limit`, and also returns how the counting ended
+    /// (`Sat` if it was not interrupted).
+    fn count_answers_impl(mut self, limit: usize) -> (AnswerCount, SolveOutcome<()>) {
+        #[cfg(any(test, feature = "test-support"))]
+        if FORCE_SOLVER_FAIL.with(|cell| cell.get()) {
+            return (AnswerCount::Exact(0), SolveOutcome::Sat(()));
+        }
+
+        self.enforce_tags();
+        let mut iter = self
+            .solver
+            .answer_iter(&self.answer_key_bool, &self.answer_key_int);
+        let mut count = 0;
+        while count < limit {
+            match iter.try_next() {
+                SolveOutcome::Sat(_) => count += 1,
+                SolveOutcome::Unsat => return (AnswerCount::Exact(count), SolveOutcome::Sat(())),
+                outcome => return (AnswerCount::AtLeast(count), outcome.map(|_| ())),
+            }
+        }
+        (AnswerCount::AtLeast(limit), SolveOutcome::Sat(()))
+    }
 }
 
 pub struct Model<'a> {
@@ -765,4 +1376,187 @@ mod tests {
         }
         assert_eq!(n_ans, 24);
     }
+
+    #[test]
+    fn test_solver_solve_under() {
+        let mut solver = Solver::new();
+        let array = &solver.int_var_1d(4, 0, 3);
+        solver.all_different(array);
+
+        {
+            let model = solver.solve_under([array.at(0).eq(3), array.at(1).ge(2)]);
+            assert!(model.is_some());
+            let model = model.unwrap();
+            assert_eq!(model.get(&array.at(0)), 3);
+            assert_eq!(model.get(&array.at(1)), 2);
+        }
+        assert!(solver.solve_under(array.ge(1)).is_none());
+        assert!(solver.solve_under(array.ge(0)).is_some());
+    }
+
+    #[test]
+    fn test_solver_unsat_core() {
+        let mut solver = Solver::new();
+        let array = &solver.bool_var_1d(4);
+        solver.add_answer_key_bool(array);
+        solver.add_expr(array.at(0) | array.at(1));
+        solver.add_expr_tagged(0, !array.at(0));
+        solver.add_expr_tagged(1, array.at(2));
+        solver.add_expr_tagged(2, !array.at(1));
+        solver.add_expr_tagged(3, [array.at(2).expr(), !array.at(3)]);
+
+        // tagged constraints are enforced in ordinary solving
+        assert!(solver.solve().is_none());
+        assert!(solver.solve_under(array.at(0)).is_none());
+
+        assert_eq!(solver.unsat_core(), Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn test_solver_unsat_core_satisfiable() {
+        let mut solver = Solver::new();
+        let array = &solver.bool_var_1d(3);
+        solver.add_answer_key_bool(array);
+        solver.add_expr_tagged(0, array.at(0) | array.at(1));
+        solver.add_expr_tagged(1, !array.at(0));
+
+        assert_eq!(solver.unsat_core(), None);
+
+        let facts = solver.irrefutable_facts().unwrap();
+        assert_eq!(facts.get(&array.at(0)), Some(false));
+        assert_eq!(facts.get(&array.at(1)), Some(true));
+        assert_eq!(facts.get(&array.at(2)), None);
+    }
+
+    #[test]
+    fn test_solver_regular() {
+        let mut solver = Solver::new();
+        let row = &solver.int_var_1d(4, 0, 1);
+        solver.add_answer_key_int(row);
+        solver.add_regular(
+            row,
+            &crate::regex::Regex::parse("0*110+10*").unwrap().to_dfa(),
+        );
+
+        let facts = solver.irrefutable_facts().unwrap();
+        assert_eq!(facts.get(row), vec![Some(1), Some(1), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_solver_lex_less_eq() {
+        let mut solver = Solver::new();
+        let x = &solver.int_var_1d(2, 0, 2);
+        let y = &solver.int_var_1d(2, 0, 2);
+        solver.add_answer_key_int(x);
+        solver.add_answer_key_int(y);
+        solver.add_lex_less_eq(x, y);
+
+        // (9 * 9 - 9) / 2 + 9 pairs of sequences in {0, 1, 2}^2
+        assert_eq!(solver.count_answers(100), AnswerCount::Exact(45));
+    }
+
+    #[test]
+    fn test_solver_interchangeable_labels() {
+        let mut solver = Solver::new();
+        let labels = &solver.int_var_1d(4, 0, 2);
+        solver.add_answer_key_int(labels);
+        solver.add_interchangeable_labels(labels, 0, 2);
+
+        // S(4, 1) + S(4, 2) + S(4, 3) divisions of 4 items into at most 3 groups
+        assert_eq!(solver.count_answers(100), AnswerCount::Exact(14));
+    }
+
+    #[test]
+    fn test_solver_optimize() {
+        let mut solver = Solver::new();
+        let x = &solver.int_var(0, 10);
+        let y = &solver.int_var(0, 10);
+        solver.add_expr((x + y).le(11));
+        solver.add_expr(x.ge(y + 5));
+
+        {
+            let (value, model) = solver.maximize(y).unwrap();
+            assert_eq!(value, 3);
+            assert_eq!(model.get(x), 8);
+        }
+        {
+            let (value, model) = solver.minimize(x - y - y).unwrap();
+            assert_eq!(value, 2);
+            assert_eq!(model.get(x), 8);
+            assert_eq!(model.get(y), 3);
+        }
+
+        solver.add_expr_tagged(0, y.ge(4));
+        assert!(solver.minimize(x).is_none());
+    }
+
+    #[test]
+    fn test_solver_count_answers() {
+        let count = |limit: usize| {
+            let mut solver = Solver::new();
+            let array = &solver.bool_var_1d(5);
+            let aux = &solver.int_var(0, 3);
+            solver.add_answer_key_bool(array);
+            solver.add_expr(array.count_true().eq(2));
+            solver.add_expr(aux.ge(array.at(0).ite(1, 0)));
+            solver.count_answers(limit)
+        };
+
+        assert_eq!(count(100), AnswerCount::Exact(10));
+        assert_eq!(count(11), AnswerCount::Exact(10));
+        assert_eq!(count(10), AnswerCount::AtLeast(10));
+        assert_eq!(count(3), AnswerCount::AtLeast(3));
+
+        let mut solver = Solver::new();
+        let x = &solver.bool_var();
+        solver.add_answer_key_bool(x);
+        solver.add_expr(x & !x);
+        assert_eq!(solver.count_answers(2), AnswerCount::Exact(0));
+    }
+
+    #[test]
+    fn test_solver_count_answers_deadline() {
+        let mut config = Config::default();
+        config.use_log_encoding = false;
+        config.resource_limit.deadline = Some(std::time::Instant::now());
+        let mut solver = Solver::with_config(config);
+        let x = &solver.bool_var();
+        solver.add_answer_key_bool(x);
+        assert!(solver.try_count_answers(10).is_unknown());
+        take_resource_limit_exceeded();
+    }
+
+    #[test]
+    fn test_solver_deduction_steps() {
+        let mut solver = Solver::new();
+        let a = &solver.int_var(0, 5);
+        let b = &solver.bool_var();
+        let c = &solver.bool_var();
+        solver.add_answer_key_bool([b, c]);
+        solver.add_answer_key_int(a);
+        solver.add_expr(b.iff(a.eq(3)));
+        solver.add_expr_tagged(0, a.ge(3));
+        solver.add_expr_tagged(1, a.le(3));
+
+        assert_eq!(
+            solver.deduction_steps(),
+            Some(vec![
+                DeductionStep {
+                    fact: Fact::Bool(0, true),
+                    reasons: vec![0, 1],
+                },
+                DeductionStep {
+                    fact: Fact::Int(0, 3),
+                    reasons: vec![],
+                },
+            ])
+        );
+
+        let mut solver = Solver::new();
+        let x = &solver.bool_var();
+        solver.add_answer_key_bool(x);
+        solver.add_expr_tagged(0, x);
+        solver.add_expr_tagged(1, !x);
+        assert_eq!(solver.deduction_steps(), None);
+    }
 }
@@ -535,6 +535,8 @@ binary_op_overload!(BitOr, bitor, CSPBoolExpr, CSPBoolExpr, |);
 binary_op_overload!(BitXor, bitxor, CSPBoolExpr, CSPBoolExpr, ^);
 binary_op_overload!(Add, add, CSPIntExpr, CSPIntExpr, +);
 binary_op_overload!(Sub, sub, CSPIntExpr, CSPIntExpr, -);
+binary_op_overload!(Div, div, CSPIntExpr, CSPIntExpr, /);
+binary_op_overload!(Rem, rem, CSPIntExpr, CSPIntExpr, %);
 
 macro_rules! binary_op {
     ($func_name:ident, $input_type:ty, $output_type:ty, $op_func:expr) => {
@@ -557,6 +559,8 @@ binary_op!(le, CSPIntExpr, CSPBoolExpr, |x, y| x.le(y));
 binary_op!(lt, CSPIntExpr, CSPBoolExpr, |x, y| x.lt(y));
 binary_op!(imp, CSPBoolExpr, CSPBoolExpr, |x, y| x.imp(y));
 binary_op!(iff, CSPBoolExpr, CSPBoolExpr, |x, y| x.iff(y));
+binary_op!(min, CSPIntExpr, CSPIntExpr, |x, y| x.min(y));
+binary_op!(max, CSPIntExpr, CSPIntExpr, |x, y| x.max(y));
 
 impl<S, A: Clone> NdArray<S, A>
 where
@@ -673,7 +677,8 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::super::Solver;
+    use super::super::{element, Solver};
+    use super::CSPIntExpr;
 
     #[test]
     fn test_ndarray_add_0d_0d() {
@@ -973,4 +978,54 @@ mod tests {
             assert_eq!(model.get(b), -3);
         }
     }
+
+    #[test]
+    fn test_ndarray_div_mod_min_max() {
+        {
+            let mut solver = Solver::new();
+            let a = &solver.int_var_1d(5, -2, 2);
+            let b = &solver.int_var(1, 3);
+            let c = a / b;
+            let d = a % 2;
+            let e = a.min(b);
+            let f = a.max(b);
+
+            assert_eq!(c.len(), 5);
+            for i in 0..5 {
+                assert_eq!(&(a.data[i].expr() / b.data.0.expr()), &c.data[i]);
+                assert_eq!(&(a.data[i].expr() % CSPIntExpr::Const(2)), &d.data[i]);
+                assert_eq!(&a.data[i].expr().min(b.data.0.expr()), &e.data[i]);
+                assert_eq!(&a.data[i].expr().max(b.data.0.expr()), &f.data[i]);
+            }
+        }
+        {
+            let mut solver = Solver::new();
+            let a = &solver.int_var(-9, 9);
+            let b = &solver.int_var(-3, 3);
+            solver.add_expr((a / b).eq(-2));
+            solver.add_expr((a % b).eq(-1));
+            solver.add_expr(a.min(b).eq(-7));
+            solver.add_expr(a.max(b).ge(3));
+
+            let model = solver.solve();
+            assert!(model.is_some());
+            let model = model.unwrap();
+            assert_eq!(model.get(a), -7);
+            assert_eq!(model.get(b), 3);
+        }
+    }
+
+    #[test]
+    fn test_element() {
+        let mut solver = Solver::new();
+        let index = &solver.int_var(-1, 3);
+        let values = &solver.int_var_1d(3, 0, 5);
+        solver.add_answer_key_int(index);
+        solver.add_expr(values.eq(4).count_true().eq(1));
+        solver.add_expr(element(index, values).eq(4));
+        solver.add_expr(values.at(1).eq(4));
+
+        let model = solver.irrefutable_facts().unwrap();
+        assert_eq!(model.get(index), Some(1));
+    }
 }
@@ -0,0 +1,53 @@
+use cspuz_rs::solver::Config;
+use std::time::{Duration, Instant};
+
+const NUM_RUNS: usize = 5;
+
+/// Linear congruential generator, so that the generated problems are identical in every run.
+#[allow(dead_code)]
+pub struct Rng(pub u64);
+
+#[allow(dead_code)]
+impl Rng {
+    pub fn next(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % n as u64) as usize
+    }
+}
+
+/// Returns the median of the running times of `NUM_RUNS` runs of `f`.
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let mut times = (0..NUM_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    times.sort();
+    times[NUM_RUNS / 2]
+}
+
+/// Runs `solve` (which must return `true`) under each of `variants`, a list of the label and
+/// the modification of the default `Config`, and prints the running times.
+pub fn compare_configs<F: FnMut() -> bool>(
+    name: &str,
+    variants: &[(&str, fn(&mut Config))],
+    mut solve: F,
+) {
+    let base_config = Config::default();
+    let mut line = format!("{:<24}", name);
+    for (label, modify) in variants {
+        let mut config = base_config;
+        modify(&mut config);
+        Config::set_default(config);
+        let time = measure(|| assert!(solve()));
+        line.push_str(&format!("  {}: {:>10.3?}", label, time));
+    }
+    Config::set_default(base_config);
+
+    println!("{}", line);
+}
@@ -0,0 +1,212 @@
+// Compares the native single-cycle propagator (`Config::use_native_single_cycle`) with the
+// connectivity constraint on the line graph on masyu and slitherlink problems.
+//
+// Run with `cargo bench -p cspuz_rs_puzzles --bench single_cycle`.
+
+mod common;
+
+use common::{compare_configs, Rng};
+use cspuz_rs::solver::Config;
+use cspuz_rs_puzzles::puzzles::masyu::{self, MasyuClue};
+use cspuz_rs_puzzles::puzzles::slitherlink;
+
+/// Whether the boundary of `region` (a set of cells in a `h` x `w` grid) is a single cycle, that is,
+/// `region` is non-empty and 4-connected, has no holes and no two cells touch only at a corner.
+fn is_simple_region(region: &[Vec<bool>]) -> bool {
+    let h = region.len();
+    let w = region[0].len();
+    let at = |y: i32, x: i32| {
+        y >= 0 && x >= 0 && (y as usize) < h && (x as usize) < w && region[y as usize][x as usize]
+    };
+
+    for y in -1..(h as i32) {
+        for x in -1..(w as i32) {
+            let a = at(y, x);
+            let b = at(y, x + 1);
+            let c = at(y + 1, x);
+            let d = at(y + 1, x + 1);
+            if a == d && b == c && a != b {
+                return false;
+            }
+        }
+    }
+
+    // both the region and its complement (including the outside of the grid) must be connected
+    for inside in [true, false] {
+        let mut visited = vec![vec![false; w + 2]; h + 2];
+        let mut stack = vec![];
+        let mut total = 0;
+        for y in 0..(h + 2) {
+            for x in 0..(w + 2) {
+                if at(y as i32 - 1, x as i32 - 1) == inside {
+                    total += 1;
+                    if stack.is_empty() {
+                        visited[y][x] = true;
+                        stack.push((y, x));
+                    }
+                }
+            }
+        }
+        let mut reached = 0;
+        while let Some((y, x)) = stack.pop() {
+            reached += 1;
+            let neighbors = [
+                (y.wrapping_sub(1), x),
+                (y + 1, x),
+                (y, x.wrapping_sub(1)),
+                (y, x + 1),
+            ];
+            for (y2, x2) in neighbors {
+                if y2 < h + 2
+                    && x2 < w + 2
+                    && !visited[y2][x2]
+                    && at(y2 as i32 - 1, x2 as i32 - 1) == inside
+                {
+                    visited[y2][x2] = true;
+                    stack.push((y2, x2));
+                }
+            }
+        }
+        if total == 0 || reached != total {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Generates a random region whose boundary is a single cycle by growing it from the center.
+fn random_region(rng: &mut Rng, h: usize, w: usize) -> Vec<Vec<bool>> {
+    let mut region = vec![vec![false; w]; h];
+    region[h / 2][w / 2] = true;
+    for _ in 0..(h * w * 4) {
+        let y = rng.next(h);
+        let x = rng.next(w);
+        if region[y][x] {
+            continue;
+        }
+        let adjacent = (y > 0 && region[y - 1][x])
+            || (y + 1 < h && region[y + 1][x])
+            || (x > 0 && region[y][x - 1])
+            || (x + 1 < w && region[y][x + 1]);
+        if !adjacent {
+            continue;
+        }
+        region[y][x] = true;
+        if !is_simple_region(&region) {
+            region[y][x] = false;
+        }
+    }
+    region
+}
+
+/// A slitherlink problem whose clues are taken from a random loop, `density` percent of them revealed.
+fn random_slitherlink(seed: u64, h: usize, w: usize, density: usize) -> Vec<Vec<Option<i32>>> {
+    let mut rng = Rng(seed);
+    let region = random_region(&mut rng, h, w);
+    let at = |y: i32, x: i32| {
+        y >= 0 && x >= 0 && (y as usize) < h && (x as usize) < w && region[y as usize][x as usize]
+    };
+
+    let mut clues = vec![vec![None; w]; h];
+    for y in 0..h {
+        for x in 0..w {
+            if rng.next(100) >= density {
+                continue;
+            }
+            let (yi, xi) = (y as i32, x as i32);
+            let c = at(yi, xi);
+            let n = [(yi - 1, xi), (yi + 1, xi), (yi, xi - 1), (yi, xi + 1)]
+                .iter()
+                .filter(|&&(y2, x2)| at(y2, x2) != c)
+                .count();
+            clues[y][x] = Some(n as i32);
+        }
+    }
+    clues
+}
+
+/// A masyu problem whose clues are taken from a random loop, `density` percent of them revealed.
+fn random_masyu(seed: u64, h: usize, w: usize, density: usize) -> Vec<Vec<MasyuClue>> {
+    // The loop of masyu passes through the centers of the cells, which are the lattice points
+    // of the region of `(h - 1) x (w - 1)` squares.
+    let mut rng = Rng(seed);
+    let region = random_region(&mut rng, h - 1, w - 1);
+    let sq = |y: i32, x: i32| {
+        y >= 0
+            && x >= 0
+            && (y as usize) < h - 1
+            && (x as usize) < w - 1
+            && region[y as usize][x as usize]
+    };
+    // (up, down, left, right)
+    let lines = |y: i32, x: i32| {
+        (
+            sq(y - 1, x - 1) != sq(y - 1, x),
+            sq(y, x - 1) != sq(y, x),
+            sq(y - 1, x - 1) != sq(y, x - 1),
+            sq(y - 1, x) != sq(y, x),
+        )
+    };
+    let is_turn = |y: i32, x: i32| {
+        let (u, d, l, r) = lines(y, x);
+        (u || d) && (l || r)
+    };
+    let is_straight = |y: i32, x: i32| {
+        let (u, d, l, r) = lines(y, x);
+        (u && d) || (l && r)
+    };
+
+    let mut clues = vec![vec![MasyuClue::None; w]; h];
+    for y in 0..h {
+        for x in 0..w {
+            let (yi, xi) = (y as i32, x as i32);
+            let (u, d, l, r) = lines(yi, xi);
+            let mut next = vec![];
+            if u {
+                next.push((yi - 1, xi));
+            }
+            if d {
+                next.push((yi + 1, xi));
+            }
+            if l {
+                next.push((yi, xi - 1));
+            }
+            if r {
+                next.push((yi, xi + 1));
+            }
+            let clue = if is_turn(yi, xi) && next.iter().all(|&(y2, x2)| is_straight(y2, x2)) {
+                MasyuClue::Black
+            } else if is_straight(yi, xi) && next.iter().any(|&(y2, x2)| is_turn(y2, x2)) {
+                MasyuClue::White
+            } else {
+                continue;
+            };
+            if rng.next(100) < density {
+                clues[y][x] = clue;
+            }
+        }
+    }
+    clues
+}
+
+const VARIANTS: &[(&str, fn(&mut Config))] = &[
+    ("line graph", |c| c.use_native_single_cycle = false),
+    ("native", |c| c.use_native_single_cycle = true),
+];
+
+fn main() {
+    for (seed, size) in [(1, 10), (2, 15), (3, 20)] {
+        let problem = random_masyu(seed, size, size, 40);
+        compare_configs(&format!("masyu {}x{}", size, size), VARIANTS, || {
+            masyu::solve_masyu(false, &problem).is_some()
+        });
+    }
+
+    for (seed, size) in [(1, 10), (2, 15), (3, 20)] {
+        let problem = random_slitherlink(seed, size, size, 50);
+        compare_configs(&format!("slitherlink {}x{}", size, size), VARIANTS, || {
+            slitherlink::solve_slitherlink(false, &problem).is_some()
+        });
+    }
+}
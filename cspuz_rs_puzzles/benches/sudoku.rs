@@ -0,0 +1,35 @@
+// Compares the native alldifferent propagator (`Config::use_native_all_different`) with the
+// clausal encoding of alldifferent constraints on sudoku problems.
+//
+// Run with `cargo bench -p cspuz_rs_puzzles --bench sudoku`.
+
+mod common;
+
+use common::compare_configs;
+use cspuz_rs::solver::Config;
+use cspuz_rs_puzzles::puzzles::sudoku;
+
+const VARIANTS: &[(&str, fn(&mut Config))] = &[
+    ("clausal", |c| c.use_native_all_different = false),
+    ("native", |c| c.use_native_all_different = true),
+];
+
+fn main() {
+    let problems = [
+        // generated by cspuz
+        (
+            "sudoku 9x9",
+            "https://puzz.link/p?sudoku/9/9/k8g1g7i2i99o2g3h75q19h5g4o83i4i6g4g5k",
+        ),
+        // "AI Escargot"
+        (
+            "sudoku 9x9 (hard)",
+            "https://puzz.link/p?sudoku/9/9/1j7g9h3h2i8h96h5j53h9i1h8i26j4i3l1h4l7h7i3h",
+        ),
+    ];
+
+    for (name, url) in problems {
+        let problem = sudoku::deserialize_problem(url).unwrap();
+        compare_configs(name, VARIANTS, || sudoku::solve_sudoku(&problem).is_some());
+    }
+}
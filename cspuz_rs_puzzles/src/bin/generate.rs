@@ -0,0 +1,142 @@
+extern crate cspuz_rs_puzzles;
+
+#[cfg(feature = "generator")]
+mod cli {
+    use cspuz_rs_puzzles::generator::{GeneratorOptions, Symmetry};
+    use cspuz_rs_puzzles::puzzles::{heyawake, masyu, nurikabe, slitherlink, star_battle, sudoku};
+    use rand::SeedableRng;
+
+    const USAGE: &str = "usage: generate <puzzle> <height> <width> [--symmetry none|horizontal|vertical|rotate180|rotate90] \
+                         [--min-clues N] [--max-clues N] [--stars N] [--count N] [--seed N]\n\
+                         puzzles: nurikabe, slitherlink, heyawake, masyu, sudoku, starbattle";
+
+    struct Args {
+        puzzle: String,
+        height: usize,
+        width: usize,
+        options: GeneratorOptions,
+        stars: i32,
+        count: usize,
+        seed: Option<u64>,
+    }
+
+    fn parse_number<T: std::str::FromStr>(value: Option<String>, name: &str) -> Result<T, String> {
+        let value = value.ok_or_else(|| format!("missing value for {}", name))?;
+        value
+            .parse()
+            .map_err(|_| format!("invalid value for {}: {}", name, value))
+    }
+
+    fn parse_args() -> Result<Args, String> {
+        let mut args = std::env::args().skip(1);
+        let puzzle = args.next().ok_or("missing puzzle")?;
+        let height = parse_number(args.next(), "height")?;
+        let width = parse_number(args.next(), "width")?;
+
+        let mut ret = Args {
+            puzzle,
+            height,
+            width,
+            options: GeneratorOptions::default(),
+            stars: 1,
+            count: 1,
+            seed: None,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--symmetry" => {
+                    ret.options.symmetry = match args.next().as_deref() {
+                        Some("none") => Symmetry::None,
+                        Some("horizontal") => Symmetry::HorizontalLine,
+                        Some("vertical") => Symmetry::VerticalLine,
+                        Some("rotate180") => Symmetry::Rotate180,
+                        Some("rotate90") => Symmetry::Rotate90,
+                        _ => return Err(String::from("invalid value for --symmetry")),
+                    };
+                }
+                "--min-clues" => ret.options.min_clues = parse_number(args.next(), &arg)?,
+                "--max-clues" => ret.options.max_clues = Some(parse_number(args.next(), &arg)?),
+                "--stars" => ret.stars = parse_number(args.next(), &arg)?,
+                "--count" => ret.count = parse_number(args.next(), &arg)?,
+                "--seed" => ret.seed = Some(parse_number(args.next(), &arg)?),
+                _ => return Err(format!("unknown option: {}", arg)),
+            }
+        }
+
+        if ret.options.symmetry == Symmetry::Rotate90 && ret.height != ret.width {
+            return Err(String::from("rotate90 symmetry requires a square grid"));
+        }
+
+        Ok(ret)
+    }
+
+    fn generate_one<R: rand::Rng>(args: &Args, rng: &mut R) -> Result<Option<String>, String> {
+        let (height, width) = (args.height, args.width);
+        let options = &args.options;
+
+        let url = match args.puzzle.as_str() {
+            "nurikabe" => nurikabe::generate_nurikabe(height, width, options, rng)
+                .and_then(|p| nurikabe::serialize_problem(&p)),
+            "slitherlink" => slitherlink::generate_slitherlink(height, width, options, rng)
+                .and_then(|p| slitherlink::serialize_problem(&p)),
+            "heyawake" => heyawake::generate_heyawake(height, width, options, rng)
+                .and_then(|p| heyawake::serialize_problem(&p)),
+            "masyu" => masyu::generate_masyu(height, width, options, rng)
+                .and_then(|p| masyu::serialize_problem(&p)),
+            "sudoku" => {
+                if height != width {
+                    return Err(String::from("sudoku requires a square grid"));
+                }
+                sudoku::generate_sudoku(height, options, rng)
+                    .and_then(|p| sudoku::serialize_problem(&p))
+            }
+            "starbattle" => {
+                if height != width {
+                    return Err(String::from("starbattle requires a square grid"));
+                }
+                star_battle::generate_star_battle(height, args.stars, rng)
+                    .and_then(|p| star_battle::serialize_problem(&p))
+            }
+            _ => return Err(format!("unknown puzzle: {}", args.puzzle)),
+        };
+
+        Ok(url)
+    }
+
+    pub fn main() {
+        let args = match parse_args() {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("error: {}\n{}", err, USAGE);
+                std::process::exit(1);
+            }
+        };
+
+        let mut rng = match args.seed {
+            Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+            None => rand::rngs::StdRng::from_entropy(),
+        };
+
+        for _ in 0..args.count {
+            match generate_one(&args, &mut rng) {
+                Ok(Some(url)) => println!("{}", url),
+                Ok(None) => eprintln!("failed to generate a problem"),
+                Err(err) => {
+                    eprintln!("error: {}\n{}", err, USAGE);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "generator")]
+fn main() {
+    cli::main();
+}
+
+#[cfg(not(feature = "generator"))]
+fn main() {
+    panic!("generator feature not enabled");
+}
@@ -0,0 +1,108 @@
+use cspuz_rs::generator::{DefaultScorableAnswer, NonDefaultValueCountable};
+use cspuz_rs::graph;
+use rand::Rng;
+
+pub use cspuz_rs::generator::Symmetry;
+
+/// Options common to the `generate_*` functions of the puzzles.
+#[derive(Clone, Copy)]
+pub struct GeneratorOptions {
+    /// The symmetry of the clue placement. Ignored for puzzles whose clues are not placed on cells.
+    pub symmetry: Symmetry,
+    /// The minimum number of clues. Problems with fewer clues tend to be harder.
+    pub min_clues: usize,
+    /// The maximum number of clues, or `None` for no limit.
+    pub max_clues: Option<usize>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            symmetry: Symmetry::None,
+            min_clues: 0,
+            max_clues: None,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    fn accepts_num_clues(&self, num_clues: usize) -> bool {
+        self.min_clues <= num_clues && self.max_clues.is_none_or(|m| num_clues <= m)
+    }
+
+    /// Returns a checker for `cspuz_rs::generator::Generator` which accepts a problem if it has a unique answer
+    /// and the number of clues (values different from `default`) is within the bounds.
+    pub(crate) fn checker<X, Y, T>(&self, default: T) -> impl Fn(&X, &Y) -> bool
+    where
+        X: NonDefaultValueCountable<T>,
+        Y: DefaultScorableAnswer,
+        T: PartialEq,
+    {
+        let options = *self;
+        move |problem, answer| {
+            answer.fully_solved()
+                && options.accepts_num_clues(problem.count_non_default_value(&default) as usize)
+        }
+    }
+}
+
+/// Returns the borders between cells with different labels.
+pub(crate) fn labels_to_borders(labels: &[Vec<usize>]) -> graph::InnerGridEdges<Vec<Vec<bool>>> {
+    let height = labels.len();
+    let width = labels[0].len();
+
+    let mut horizontal = vec![vec![false; width]; height - 1];
+    for y in 0..(height - 1) {
+        for x in 0..width {
+            horizontal[y][x] = labels[y][x] != labels[y + 1][x];
+        }
+    }
+    let mut vertical = vec![vec![false; width - 1]; height];
+    for y in 0..height {
+        for x in 0..(width - 1) {
+            vertical[y][x] = labels[y][x] != labels[y][x + 1];
+        }
+    }
+
+    graph::InnerGridEdges {
+        horizontal,
+        vertical,
+    }
+}
+
+/// Splits a `height` * `width` grid into rectangles with area at most `max_area` at random,
+/// and returns the label of the rectangle for each cell.
+pub(crate) fn random_rectangles<R: Rng>(
+    height: usize,
+    width: usize,
+    max_area: usize,
+    rng: &mut R,
+) -> Vec<Vec<usize>> {
+    let mut labels = vec![vec![0; width]; height];
+    let mut num_rectangles = 0;
+    let mut stack = vec![(0, 0, height, width)];
+
+    while let Some((y, x, h, w)) = stack.pop() {
+        if h * w <= max_area && (h * w == 1 || rng.gen_bool(0.5)) {
+            for dy in 0..h {
+                for dx in 0..w {
+                    labels[y + dy][x + dx] = num_rectangles;
+                }
+            }
+            num_rectangles += 1;
+            continue;
+        }
+
+        if h >= w {
+            let k = rng.gen_range(1..h);
+            stack.push((y, x, k, w));
+            stack.push((y + k, x, h - k, w));
+        } else {
+            let k = rng.gen_range(1..w);
+            stack.push((y, x, h, k));
+            stack.push((y, x + k, h, w - k));
+        }
+    }
+
+    labels
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "generator")]
+pub mod generator;
 pub mod penpa_editor;
 pub mod polyomino;
 pub mod puzzles;
@@ -3,7 +3,7 @@ use cspuz_rs::graph::{self, BoolGridEdges};
 use cspuz_rs::serializer::{
     strip_prefix, Choice, Combinator, Context, ContextBasedGrid, Dict, HexInt, Map, Rooms, Spaces,
 };
-use cspuz_rs::solver::{IntVarArray2D, Solver};
+use cspuz_rs::solver::{AnswerCount, IntVarArray2D, Solver};
 
 type AdjacencyEntry = Option<(usize, usize)>;
 
@@ -84,6 +84,24 @@ pub fn enumerate_answers_curvedata(
         .collect()
 }
 
+pub fn count_answers_curvedata(
+    piece_id: &[Vec<PieceId>],
+    borders: &Option<graph::InnerGridEdges<Vec<Vec<bool>>>>,
+    pieces: &[graph::GridEdges<Vec<Vec<bool>>>],
+    limit: usize,
+) -> AnswerCount {
+    let (h, w) = util::infer_shape(piece_id);
+
+    let mut solver = Solver::new();
+    let is_line = &graph::BoolGridEdges::new(&mut solver, (h - 1, w - 1));
+    solver.add_answer_key_bool(&is_line.horizontal);
+    solver.add_answer_key_bool(&is_line.vertical);
+
+    add_constraints(&mut solver, is_line, piece_id, borders, pieces);
+
+    solver.count_answers(limit)
+}
+
 pub fn add_constraints(
     solver: &mut Solver,
     is_line: &graph::BoolGridEdges,
@@ -3,7 +3,7 @@ use cspuz_rs::serializer::{
     problem_to_url_with_context, url_to_problem, Choice, Combinator, Context, Dict, HexInt,
     Optionalize, RoomsWithValues, Size, Spaces,
 };
-use cspuz_rs::solver::{count_true, BoolVarArray2D, Solver};
+use cspuz_rs::solver::{count_true, AnswerCount, BoolVarArray2D, Solver};
 
 pub fn solve_heyawake(
     borders: &graph::InnerGridEdges<Vec<Vec<bool>>>,
@@ -42,6 +42,24 @@ pub fn enumerate_answers_heyawake(
         .collect()
 }
 
+pub fn count_answers_heyawake(
+    borders: &graph::InnerGridEdges<Vec<Vec<bool>>>,
+    clues: &[Option<i32>],
+    limit: usize,
+) -> AnswerCount {
+    let h = borders.vertical.len();
+    assert!(h > 0);
+    let w = borders.vertical[0].len() + 1;
+
+    let mut solver = Solver::new();
+    let is_black = &solver.bool_var_2d((h, w));
+    solver.add_answer_key_bool(is_black);
+
+    add_constraints(&mut solver, is_black, borders, clues);
+
+    solver.count_answers(limit)
+}
+
 pub(super) fn add_constraints(
     solver: &mut Solver,
     is_black: &BoolVarArray2D,
@@ -95,6 +113,43 @@ pub(super) fn add_constraints(
     }
 }
 
+/// Generates a heyawake problem of size `height` * `width` with a unique answer.
+///
+/// The grid is first divided into random rectangular rooms, and then the clues of the rooms are generated.
+/// `options.symmetry` is ignored since the clues are placed on rooms rather than cells.
+/// Returns `None` if no problem satisfying `options` was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_heyawake<R: rand::Rng>(
+    height: usize,
+    width: usize,
+    options: &crate::generator::GeneratorOptions,
+    rng: &mut R,
+) -> Option<Problem> {
+    use crate::generator::{labels_to_borders, random_rectangles};
+    use cspuz_rs::generator::{default_scorer, Choice, Generator};
+
+    let borders = labels_to_borders(&random_rectangles(height, width, 9, rng));
+    let rooms = graph::borders_to_rooms(&borders);
+
+    let pattern = rooms
+        .iter()
+        .map(|room| {
+            let mut candidates = vec![None];
+            candidates.extend((0..=((room.len() as i32 + 1) / 2)).map(Some));
+            Choice::new(candidates, None)
+        })
+        .collect::<Vec<_>>();
+    let generated = Generator::new(
+        |clues: &Vec<Option<i32>>| solve_heyawake(&borders, clues),
+        pattern,
+        options.checker(None),
+        default_scorer(None, 2.0),
+    )
+    .generate(rng)?;
+
+    Some((borders, generated))
+}
+
 pub(super) type Problem = (graph::InnerGridEdges<Vec<Vec<bool>>>, Vec<Option<i32>>);
 
 pub(super) fn combinator() -> impl Combinator<Problem> {
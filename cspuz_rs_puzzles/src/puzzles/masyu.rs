@@ -68,6 +68,35 @@ pub fn solve_masyu(
     solver.irrefutable_facts().map(|f| f.get(is_line))
 }
 
+/// Generates a masyu problem of size `height` * `width` with a unique answer.
+///
+/// Returns `None` if no problem satisfying `options` was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_masyu<R: rand::Rng>(
+    height: usize,
+    width: usize,
+    options: &crate::generator::GeneratorOptions,
+    rng: &mut R,
+) -> Option<Problem> {
+    use cspuz_rs::generator::{default_scorer, Generator, Grid};
+
+    let pattern = Grid::new(
+        height,
+        width,
+        &[MasyuClue::None, MasyuClue::White, MasyuClue::Black],
+        MasyuClue::None,
+        options.symmetry,
+    );
+    Generator::new(
+        |problem: &Vec<Vec<MasyuClue>>| solve_masyu(false, problem),
+        pattern,
+        options.checker(MasyuClue::None),
+        default_scorer(MasyuClue::None, 5.0),
+    )
+    .generate(rng)
+    .map(|clues| (false, clues))
+}
+
 type Problem = (bool, Vec<Vec<MasyuClue>>);
 
 fn combinator() -> impl Combinator<Problem> {
@@ -3,7 +3,7 @@ use cspuz_rs::graph;
 use cspuz_rs::serializer::{
     problem_to_url, url_to_problem, Choice, Combinator, Dict, Grid, HexInt, Optionalize, Spaces,
 };
-use cspuz_rs::solver::{BoolVarArray2D, Solver};
+use cspuz_rs::solver::{AnswerCount, BoolVarArray2D, Fact, Solver};
 
 pub fn solve_nurikabe(clues: &[Vec<Option<i32>>]) -> Option<Vec<Vec<Option<bool>>>> {
     let (h, w) = util::infer_shape(clues);
@@ -36,7 +36,81 @@ pub fn enumerate_answers_nurikabe(
         .collect()
 }
 
-fn add_constraints(clues: &[Vec<Option<i32>>], solver: &mut Solver, is_black: &BoolVarArray2D) {
+pub fn count_answers_nurikabe(clues: &[Vec<Option<i32>>], limit: usize) -> AnswerCount {
+    let (h, w) = util::infer_shape(clues);
+
+    let mut solver = Solver::new();
+    let is_black = &solver.bool_var_2d((h, w));
+    solver.add_answer_key_bool(is_black);
+
+    add_constraints(clues, &mut solver, is_black);
+
+    solver.count_answers(limit)
+}
+
+/// Returns the positions of a minimal set of clues which cannot be satisfied at the same time,
+/// or `None` if the problem has an answer.
+pub fn find_conflicting_clues_nurikabe(clues: &[Vec<Option<i32>>]) -> Option<Vec<(usize, usize)>> {
+    let (h, w) = util::infer_shape(clues);
+
+    let mut solver = Solver::new();
+    let is_black = &solver.bool_var_2d((h, w));
+
+    let clue_pos = add_constraints(clues, &mut solver, is_black);
+
+    solver.unsat_core().map(|core| {
+        core.into_iter()
+            .map(|i| (clue_pos[i].0, clue_pos[i].1))
+            .collect()
+    })
+}
+
+/// Returns the cells of the answer in the order in which they can be deduced, together with
+/// the positions of the clues used in each deduction, or `None` if the problem has no answer.
+/// Cells whose color is not decided are not included.
+pub fn solve_steps_nurikabe(clues: &[Vec<Option<i32>>]) -> Option<Vec<util::CellStep<bool>>> {
+    let (h, w) = util::infer_shape(clues);
+
+    let mut solver = Solver::new();
+    let is_black = &solver.bool_var_2d((h, w));
+
+    let mut cells = vec![];
+    for y in 0..h {
+        for x in 0..w {
+            if clues[y][x].is_none() {
+                solver.add_answer_key_bool(is_black.at((y, x)));
+                cells.push((y, x));
+            }
+        }
+    }
+
+    let clue_pos = add_constraints(clues, &mut solver, is_black);
+
+    let steps = solver.deduction_steps()?;
+    Some(
+        steps
+            .into_iter()
+            .map(|step| {
+                let (i, b) = match step.fact {
+                    Fact::Bool(i, b) => (i, b),
+                    Fact::Int(..) => unreachable!(),
+                };
+                let reasons = step
+                    .reasons
+                    .into_iter()
+                    .map(|t| (clue_pos[t].0, clue_pos[t].1))
+                    .collect();
+                (cells[i], b, reasons)
+            })
+            .collect(),
+    )
+}
+
+fn add_constraints(
+    clues: &[Vec<Option<i32>>],
+    solver: &mut Solver,
+    is_black: &BoolVarArray2D,
+) -> Vec<(usize, usize, i32)> {
     let (h, w) = util::infer_shape(clues);
 
     let mut clue_pos = vec![];
@@ -73,11 +147,39 @@ fn add_constraints(clues: &[Vec<Option<i32>>], solver: &mut Solver, is_black: &B
     solver.add_expr(!is_black.conv2d_and((2, 2)));
 
     for (i, &(y, x, n)) in clue_pos.iter().enumerate() {
-        solver.add_expr(group_id.at((y, x)).eq((i + 1) as i32));
+        solver.add_expr_tagged(i, group_id.at((y, x)).eq((i + 1) as i32));
         if n > 0 {
-            solver.add_expr(group_id.eq((i + 1) as i32).count_true().eq(n));
+            solver.add_expr_tagged(i, group_id.eq((i + 1) as i32).count_true().eq(n));
         }
     }
+
+    clue_pos
+}
+
+/// Generates a nurikabe problem of size `height` * `width` with a unique answer.
+///
+/// Returns `None` if no problem satisfying `options` was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_nurikabe<R: rand::Rng>(
+    height: usize,
+    width: usize,
+    options: &crate::generator::GeneratorOptions,
+    rng: &mut R,
+) -> Option<Problem> {
+    use cspuz_rs::generator::{default_scorer, Generator, Grid};
+
+    let max_num = (height * width / 4).clamp(2, 9) as i32;
+    let mut candidates = vec![None];
+    candidates.extend((1..=max_num).map(Some));
+
+    let pattern = Grid::new(height, width, &candidates, None, options.symmetry);
+    Generator::new(
+        |problem: &Problem| solve_nurikabe(problem),
+        pattern,
+        options.checker(None),
+        default_scorer(None, 2.0),
+    )
+    .generate(rng)
 }
 
 type Problem = Vec<Vec<Option<i32>>>;
@@ -132,6 +234,12 @@ mod tests {
         assert_eq!(ans, expected);
     }
 
+    #[test]
+    fn test_nurikabe_count_answers() {
+        let problem = problem_for_tests();
+        assert_eq!(count_answers_nurikabe(&problem, 2), AnswerCount::AtLeast(2));
+    }
+
     #[test]
     fn test_nurikabe_serializer() {
         let problem = problem_for_tests();
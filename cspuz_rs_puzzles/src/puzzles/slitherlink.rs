@@ -5,7 +5,7 @@ use cspuz_rs::serializer::{
     problem_to_url_pzprxs, url_to_problem, Choice, Combinator, Dict, Grid, NumSpaces, Spaces,
     Tuple2,
 };
-use cspuz_rs::solver::Solver;
+use cspuz_rs::solver::{AnswerCount, Solver};
 
 pub fn solve_slitherlink(
     full: bool,
@@ -44,6 +44,23 @@ pub fn enumerate_answers_slitherlink(
         .collect()
 }
 
+pub fn count_answers_slitherlink(
+    full: bool,
+    clues: &[Vec<Option<i32>>],
+    limit: usize,
+) -> AnswerCount {
+    let (h, w) = util::infer_shape(clues);
+
+    let mut solver = Solver::new();
+    let is_line = &graph::BoolGridEdges::new(&mut solver, (h, w));
+    solver.add_answer_key_bool(&is_line.horizontal);
+    solver.add_answer_key_bool(&is_line.vertical);
+
+    add_constraints(&mut solver, full, is_line, clues);
+
+    solver.count_answers(limit)
+}
+
 fn add_constraints(
     solver: &mut Solver,
     full: bool,
@@ -70,6 +87,35 @@ fn add_constraints(
     }
 }
 
+/// Generates a slitherlink problem of size `height` * `width` with a unique answer.
+///
+/// Returns `None` if no problem satisfying `options` was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_slitherlink<R: rand::Rng>(
+    height: usize,
+    width: usize,
+    options: &crate::generator::GeneratorOptions,
+    rng: &mut R,
+) -> Option<Problem> {
+    use cspuz_rs::generator::{default_scorer, Generator, Grid};
+
+    let pattern = Grid::new(
+        height,
+        width,
+        &[None, Some(0), Some(1), Some(2), Some(3)],
+        None,
+        options.symmetry,
+    );
+    Generator::new(
+        |problem: &Vec<Vec<Option<i32>>>| solve_slitherlink(false, problem),
+        pattern,
+        options.checker(None),
+        default_scorer(None, 5.0),
+    )
+    .generate(rng)
+    .map(|clues| (false, clues))
+}
+
 type Problem = (bool, Vec<Vec<Option<i32>>>);
 
 pub(crate) fn combinator() -> impl Combinator<Problem> {
@@ -143,6 +189,15 @@ mod tests {
         assert_eq!(ans, expected);
     }
 
+    #[test]
+    fn test_slitherlink_count_answers() {
+        let (full, problem) = problem_for_tests1();
+        assert_eq!(
+            count_answers_slitherlink(full, &problem, 2),
+            AnswerCount::Exact(1)
+        );
+    }
+
     #[test]
     #[rustfmt::skip]
     fn test_slitherlink_problem2() {
@@ -182,4 +237,27 @@ mod tests {
             util::tests::serializer_test(problem, url, serialize_problem, deserialize_problem);
         }
     }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_slitherlink_generate() {
+        use crate::generator::{GeneratorOptions, Symmetry};
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let options = GeneratorOptions {
+            symmetry: Symmetry::Rotate180,
+            ..GeneratorOptions::default()
+        };
+        let (full, clues) = generate_slitherlink(5, 5, &options, &mut rng).unwrap();
+        for y in 0..5 {
+            for x in 0..5 {
+                assert_eq!(clues[y][x].is_some(), clues[4 - y][4 - x].is_some());
+            }
+        }
+
+        let ans = solve_slitherlink(full, &clues).unwrap();
+        assert!(ans.horizontal.iter().flatten().all(|x| x.is_some()));
+        assert!(ans.vertical.iter().flatten().all(|x| x.is_some()));
+    }
 }
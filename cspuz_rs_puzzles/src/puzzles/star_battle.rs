@@ -8,6 +8,16 @@ use cspuz_rs::solver::Solver;
 pub fn solve_star_battle(
     star_amount: i32,
     borders: &graph::InnerGridEdges<Vec<Vec<bool>>>,
+) -> Option<Vec<Vec<Option<bool>>>> {
+    solve_star_battle_impl(star_amount, borders, false)
+}
+
+// If `relaxed` is true, each room is only required to contain at least `star_amount` stars.
+// This is equivalent to the original rule when the number of rooms equals the grid size.
+fn solve_star_battle_impl(
+    star_amount: i32,
+    borders: &graph::InnerGridEdges<Vec<Vec<bool>>>,
+    relaxed: bool,
 ) -> Option<Vec<Vec<Option<bool>>>> {
     let height = borders.vertical.len();
 
@@ -35,12 +45,52 @@ pub fn solve_star_battle(
         .add_expr(!(has_star.slice((..(height - 1), 1..)) & has_star.slice((1.., ..(height - 1)))));
 
     for room in &rooms {
-        solver.add_expr(has_star.select(room).count_true().eq(star_amount));
+        if relaxed {
+            solver.add_expr(has_star.select(room).count_true().ge(star_amount));
+        } else {
+            solver.add_expr(has_star.select(room).count_true().eq(star_amount));
+        }
     }
 
     solver.irrefutable_facts().map(|f| f.get(&has_star))
 }
 
+/// Generates a star battle problem of size `size` * `size` with `star_amount` stars per row, column and region.
+///
+/// Star battle problems consist only of regions, so this function takes no `GeneratorOptions`.
+/// Returns `None` if no problem was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_star_battle<R: rand::Rng>(
+    size: usize,
+    star_amount: i32,
+    rng: &mut R,
+) -> Option<Problem> {
+    use crate::generator::labels_to_borders;
+    use cspuz_rs::generator::{DefaultScorableAnswer, Generator, Grid, Symmetry};
+
+    // Each cell is labeled with its region; regions are the connected components of cells with the same label.
+    let labels = (0..size).collect::<Vec<_>>();
+    let pattern = Grid::new(size, size, &labels, 0, Symmetry::None);
+    let generated = Generator::new(
+        |labels: &Vec<Vec<usize>>| {
+            let borders = labels_to_borders(labels);
+            if graph::borders_to_rooms(&borders).len() > size {
+                return None;
+            }
+            solve_star_battle_impl(star_amount, &borders, true)
+        },
+        pattern,
+        |labels: &Vec<Vec<usize>>, answer: &Vec<Vec<Option<bool>>>| {
+            answer.fully_solved()
+                && graph::borders_to_rooms(&labels_to_borders(labels)).len() == size
+        },
+        |_: &Vec<Vec<usize>>, answer: &Vec<Vec<Option<bool>>>| answer.score(),
+    )
+    .generate(rng)?;
+
+    Some((star_amount, labels_to_borders(&generated)))
+}
+
 pub type Problem = (i32, graph::InnerGridEdges<Vec<Vec<bool>>>);
 
 fn combinator() -> impl Combinator<Problem> {
@@ -111,4 +161,17 @@ mod tests {
         let url = "https://puzz.link/p?starbattle/6/6/1/2u9gn9c9jpmk";
         util::tests::serializer_test(problem, url, serialize_problem, deserialize_problem);
     }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_star_battle_generate() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let (star_amount, borders) = generate_star_battle(5, 1, &mut rng).unwrap();
+        assert_eq!(graph::borders_to_rooms(&borders).len(), 5);
+
+        let ans = solve_star_battle(star_amount, &borders).unwrap();
+        assert!(ans.iter().flatten().all(|x| x.is_some()));
+    }
 }
@@ -2,7 +2,7 @@ use crate::util;
 use cspuz_rs::serializer::{
     problem_to_url, url_to_problem, Choice, Combinator, Dict, Grid, HexInt, Optionalize, Spaces,
 };
-use cspuz_rs::solver::Solver;
+use cspuz_rs::solver::{Fact, IntVarArray2D, Solver};
 
 pub fn solve_sudoku(clues: &[Vec<Option<i32>>]) -> Option<Vec<Vec<Option<i32>>>> {
     let (h, w) = util::infer_shape(clues);
@@ -23,25 +23,7 @@ pub fn solve_sudoku(clues: &[Vec<Option<i32>>]) -> Option<Vec<Vec<Option<i32>>>>
     let num = &solver.int_var_2d((n, n), 1, n as i32);
     solver.add_answer_key_int(num);
 
-    for i in 0..n {
-        solver.all_different(num.slice_fixed_y((i, ..)));
-        solver.all_different(num.slice_fixed_x((.., i)));
-    }
-    for i in 0..bw {
-        for j in 0..bh {
-            solver
-                .all_different(num.slice((((i * bh)..((i + 1) * bh)), ((j * bw)..((j + 1) * bw)))));
-        }
-    }
-    for y in 0..n {
-        for x in 0..n {
-            if let Some(val) = clues[y][x] {
-                if val > 0 {
-                    solver.add_expr(num.at((y, x)).eq(val));
-                }
-            }
-        }
-    }
+    add_constraints(&mut solver, num, clues, (bh, bw));
 
     solver.irrefutable_facts().map(|f| f.get(num))
 }
@@ -82,6 +64,108 @@ pub fn solve_sudoku_as_cands(clues: &[Vec<Option<i32>>]) -> Option<Vec<Vec<Vec<b
         }
     }
 
+    add_constraints(&mut solver, num, clues, (bh, bw));
+
+    solver.irrefutable_facts().map(|f| {
+        let mut ret = vec![];
+        for y in 0..n {
+            let mut row = vec![];
+            for x in 0..n {
+                row.push(
+                    f.get(&cands[y][x])
+                        .into_iter()
+                        .map(|x| x.unwrap_or(true))
+                        .collect(),
+                );
+            }
+            ret.push(row);
+        }
+        ret
+    })
+}
+
+/// Returns the positions of a minimal set of clues which cannot be satisfied at the same time,
+/// or `None` if the problem has an answer.
+pub fn find_conflicting_clues_sudoku(clues: &[Vec<Option<i32>>]) -> Option<Vec<(usize, usize)>> {
+    let (h, w) = util::infer_shape(clues);
+    if h != w {
+        return None;
+    }
+    let n = h;
+    let (bh, bw) = match n {
+        4 => (2, 2),
+        6 => (2, 3),
+        9 => (3, 3),
+        16 => (4, 4),
+        25 => (5, 5),
+        _ => return None,
+    };
+
+    let mut solver = Solver::new();
+    let num = &solver.int_var_2d((n, n), 1, n as i32);
+    add_constraints(&mut solver, num, clues, (bh, bw));
+
+    solver
+        .unsat_core()
+        .map(|core| core.into_iter().map(|i| (i / n, i % n)).collect())
+}
+
+/// Returns the numbers in the empty cells in the order in which they can be deduced, together with
+/// the positions of the clues used in each deduction, or `None` if the problem has no answer.
+/// Cells whose number is not decided are not included.
+pub fn solve_steps_sudoku(clues: &[Vec<Option<i32>>]) -> Option<Vec<util::CellStep<i32>>> {
+    let (h, w) = util::infer_shape(clues);
+    if h != w {
+        return None;
+    }
+    let n = h;
+    let (bh, bw) = match n {
+        4 => (2, 2),
+        6 => (2, 3),
+        9 => (3, 3),
+        16 => (4, 4),
+        25 => (5, 5),
+        _ => return None,
+    };
+
+    let mut solver = Solver::new();
+    let num = &solver.int_var_2d((n, n), 1, n as i32);
+
+    let mut cells = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            if clues[y][x].is_none() {
+                solver.add_answer_key_int(num.at((y, x)));
+                cells.push((y, x));
+            }
+        }
+    }
+
+    add_constraints(&mut solver, num, clues, (bh, bw));
+
+    let steps = solver.deduction_steps()?;
+    Some(
+        steps
+            .into_iter()
+            .map(|step| {
+                let (i, v) = match step.fact {
+                    Fact::Int(i, v) => (i, v),
+                    Fact::Bool(..) => unreachable!(),
+                };
+                let reasons = step.reasons.into_iter().map(|t| (t / n, t % n)).collect();
+                (cells[i], v, reasons)
+            })
+            .collect(),
+    )
+}
+
+fn add_constraints(
+    solver: &mut Solver,
+    num: &IntVarArray2D,
+    clues: &[Vec<Option<i32>>],
+    (bh, bw): (usize, usize),
+) {
+    let n = clues.len();
     for i in 0..n {
         solver.all_different(num.slice_fixed_y((i, ..)));
         solver.all_different(num.slice_fixed_x((.., i)));
@@ -96,28 +180,39 @@ pub fn solve_sudoku_as_cands(clues: &[Vec<Option<i32>>]) -> Option<Vec<Vec<Vec<b
         for x in 0..n {
             if let Some(val) = clues[y][x] {
                 if val > 0 {
-                    solver.add_expr(num.at((y, x)).eq(val));
+                    solver.add_expr_tagged(y * n + x, num.at((y, x)).eq(val));
                 }
             }
         }
     }
+}
 
-    solver.irrefutable_facts().map(|f| {
-        let mut ret = vec![];
-        for y in 0..n {
-            let mut row = vec![];
-            for x in 0..n {
-                row.push(
-                    f.get(&cands[y][x])
-                        .into_iter()
-                        .map(|x| x.unwrap_or(true))
-                        .collect(),
-                );
-            }
-            ret.push(row);
-        }
-        ret
-    })
+/// Generates a sudoku problem of size `size` * `size` with a unique answer.
+///
+/// Returns `None` if `size` is not supported or no problem satisfying `options` was found within the iteration limit.
+#[cfg(feature = "generator")]
+pub fn generate_sudoku<R: rand::Rng>(
+    size: usize,
+    options: &crate::generator::GeneratorOptions,
+    rng: &mut R,
+) -> Option<Problem> {
+    use cspuz_rs::generator::{default_scorer, Generator, Grid};
+
+    if ![4, 6, 9, 16, 25].contains(&size) {
+        return None;
+    }
+
+    let mut candidates = vec![None];
+    candidates.extend((1..=(size as i32)).map(Some));
+
+    let pattern = Grid::new(size, size, &candidates, None, options.symmetry);
+    Generator::new(
+        |problem: &Problem| solve_sudoku(problem),
+        pattern,
+        options.checker(None),
+        default_scorer(None, 2.0),
+    )
+    .generate(rng)
 }
 
 type Problem = Vec<Vec<Option<i32>>>;
@@ -141,6 +236,7 @@ pub fn deserialize_problem(url: &str) -> Option<Problem> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cspuz_rs::solver::Config;
 
     #[rustfmt::skip]
     fn problem_for_tests() -> Problem {
@@ -178,10 +274,108 @@ mod tests {
         assert_eq!(ans, expected);
     }
 
+    #[test]
+    fn test_sudoku_conflicting_clues() {
+        let problem = problem_for_tests();
+        assert_eq!(find_conflicting_clues_sudoku(&problem), None);
+
+        let mut problem = vec![vec![None; 4]; 4];
+        problem[0][0] = Some(1);
+        problem[0][1] = Some(1);
+        problem[3][3] = Some(2);
+        assert_eq!(
+            find_conflicting_clues_sudoku(&problem),
+            Some(vec![(0, 0), (0, 1)])
+        );
+    }
+
     #[test]
     fn test_sudoku_serializer() {
         let problem = problem_for_tests();
         let url = "https://puzz.link/p?sudoku/9/9/k8g1g7i2i99o2g3h75q19h5g4o83i4i6g4g5k";
         util::tests::serializer_test(problem, url, serialize_problem, deserialize_problem);
     }
+
+    #[rustfmt::skip]
+    fn hard_problem_for_tests() -> Problem {
+        // "AI Escargot"
+        vec![
+            vec![Some(1), None, None, None, None, Some(7), None, Some(9), None],
+            vec![None, Some(3), None, None, Some(2), None, None, None, Some(8)],
+            vec![None, None, Some(9), Some(6), None, None, Some(5), None, None],
+            vec![None, None, Some(5), Some(3), None, None, Some(9), None, None],
+            vec![None, Some(1), None, None, Some(8), None, None, None, Some(2)],
+            vec![Some(6), None, None, None, None, Some(4), None, None, None],
+            vec![Some(3), None, None, None, None, None, None, Some(1), None],
+            vec![None, Some(4), None, None, None, None, None, None, Some(7)],
+            vec![None, None, Some(7), None, None, None, Some(3), None, None],
+        ]
+    }
+
+    fn solve_sudoku_with_native_all_different(
+        problem: &Problem,
+        use_native: bool,
+    ) -> Option<Vec<Vec<Option<i32>>>> {
+        let default_config = Config::default();
+        let mut config = default_config;
+        config.use_native_all_different = use_native;
+        Config::set_default(config);
+        let ret = solve_sudoku(problem);
+        Config::set_default(default_config);
+        ret
+    }
+
+    #[test]
+    fn test_sudoku_native_all_different() {
+        for problem in [problem_for_tests(), hard_problem_for_tests()] {
+            let expected = solve_sudoku_with_native_all_different(&problem, false);
+            assert!(expected.is_some());
+            assert_eq!(
+                solve_sudoku_with_native_all_different(&problem, true),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_sudoku_solve_steps() {
+        let problem = vec![
+            vec![Some(1), None, None, Some(4)],
+            vec![None, None, None, None],
+            vec![None, None, None, None],
+            vec![None, Some(3), Some(2), None],
+        ];
+        let expected = [[1, 2, 3, 4], [3, 4, 1, 2], [2, 1, 4, 3], [4, 3, 2, 1]];
+
+        let steps = solve_steps_sudoku(&problem).unwrap();
+        assert_eq!(steps.len(), 12);
+        for (i, ((y, x), v, reasons)) in steps.iter().enumerate() {
+            assert_eq!(problem[*y][*x], None);
+            assert_eq!(*v, expected[*y][*x]);
+            if i == 0 {
+                assert!(!reasons.is_empty());
+            }
+            for &(ry, rx) in reasons {
+                assert!(problem[ry][rx].is_some());
+            }
+        }
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_sudoku_generate() {
+        use crate::generator::GeneratorOptions;
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let options = GeneratorOptions {
+            max_clues: Some(8),
+            ..GeneratorOptions::default()
+        };
+        let problem = generate_sudoku(4, &options, &mut rng).unwrap();
+        assert!(problem.iter().flatten().filter(|x| x.is_some()).count() <= 8);
+
+        let ans = solve_sudoku(&problem).unwrap();
+        assert!(ans.iter().flatten().all(|x| x.is_some()));
+    }
 }
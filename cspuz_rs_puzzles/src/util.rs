@@ -9,6 +9,9 @@ pub fn infer_shape<T>(array: &[Vec<T>]) -> (usize, usize) {
     (height, width)
 }
 
+/// A step of a human-style solution: the cell, the value deduced for it, and the positions of the clues used.
+pub type CellStep<T> = ((usize, usize), T, Vec<(usize, usize)>);
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Grid<T: Clone> {
     data: Vec<T>,
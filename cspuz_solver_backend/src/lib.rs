@@ -11,11 +11,46 @@ pub mod testing;
 
 use board::Board;
 use cspuz_rs::serializer::{get_kudamono_url_info_detailed, url_to_puzzle_kind};
+use cspuz_rs::solver::{take_resource_limit_exceeded, AnswerCount, Config};
 pub use puzzle::{list_penpa_edit_puzzles, list_puzzles_for_enumerate, list_puzzles_for_solve};
 pub use uniqueness::Uniqueness;
 
 static mut SHARED_ARRAY: Vec<u8> = vec![];
 
+const TIMEOUT_RESPONSE: &str = "{\"status\":\"timeout\",\"description\":\"time limit exceeded\"}";
+
+/// Runs `f`, returning `None` if the solver gave up during it due to the time limit set by `set_time_limit`.
+///
+/// The time limit bounds `f` as a whole, even if it runs the solver many times.
+fn run_with_time_limit<T>(f: impl FnOnce() -> T) -> Option<T> {
+    let config = Config::default();
+    if let Some(time) = config.resource_limit.time {
+        let mut limited = config;
+        limited.resource_limit.deadline = Some(std::time::Instant::now() + time);
+        Config::set_default(limited);
+    }
+    take_resource_limit_exceeded();
+    let ret = f();
+    Config::set_default(config);
+    if take_resource_limit_exceeded() {
+        None
+    } else {
+        Some(ret)
+    }
+}
+
+/// Stores `s` prefixed with its length (4 bytes, little endian) in `SHARED_ARRAY`, and returns a pointer to it.
+fn to_shared_array(s: &str) -> *const u8 {
+    let len = s.len();
+    unsafe {
+        SHARED_ARRAY.clear();
+        SHARED_ARRAY.reserve(4 + len);
+        SHARED_ARRAY.extend_from_slice(&(len as u32).to_le_bytes());
+        SHARED_ARRAY.extend_from_slice(s.as_bytes());
+        SHARED_ARRAY.as_ptr()
+    }
+}
+
 fn parse_penpa_edit_special_url(url: &str) -> Option<(&str, &str)> {
     let separator = url.find("!")?;
     let kind = &url[..separator];
@@ -64,41 +99,62 @@ fn decode_and_enumerate(
         .unwrap_or(Err("unknown puzzle type"))
 }
 
+fn decode_and_count(url: &[u8], limit: usize) -> Result<AnswerCount, &'static str> {
+    let url = std::str::from_utf8(url).map_err(|_| "failed to decode URL as UTF-8")?;
+
+    let puzzle_kind = url_to_puzzle_kind(url).ok_or("puzzle type not detected")?;
+
+    puzzle::dispatch_puzz_link_count(&puzzle_kind, url, limit).unwrap_or(Err("unknown puzzle type"))
+}
+
+pub fn solve_steps(url: &[u8]) -> Result<(Board, Vec<Board>), &'static str> {
+    let url = std::str::from_utf8(url).map_err(|_| "failed to decode URL as UTF-8")?;
+
+    let puzzle_kind = url_to_puzzle_kind(url).ok_or("puzzle type not detected")?;
+
+    puzzle::dispatch_puzz_link_steps(&puzzle_kind, url).unwrap_or(Err("unknown puzzle type"))
+}
+
+/// Sets the time limit of the solver in milliseconds (0 for no limit).
+/// Exported functions return `{"status":"timeout",...}` when the limit is exceeded.
+#[no_mangle]
+fn set_time_limit(time_limit_ms: usize) {
+    let mut config = Config::default();
+    config.resource_limit.time = if time_limit_ms == 0 {
+        None
+    } else {
+        Some(std::time::Duration::from_millis(time_limit_ms as u64))
+    };
+    Config::set_default(config);
+}
+
 #[no_mangle]
 fn solve_problem(url: *const u8, len: usize) -> *const u8 {
     let url = unsafe { std::slice::from_raw_parts(url, len) };
-    let result = decode_and_solve(url);
+    let result = run_with_time_limit(|| decode_and_solve(url));
 
     let ret_string = match result {
-        Ok(board) => {
+        None => String::from(TIMEOUT_RESPONSE),
+        Some(Ok(board)) => {
             format!("{{\"status\":\"ok\",\"description\":{}}}", board.to_json())
         }
-        Err(err) => {
+        Some(Err(err)) => {
             // TODO: escape `err` if necessary
             format!("{{\"status\":\"error\",\"description\":\"{}\"}}", err)
         }
     };
 
-    let ret_len = ret_string.len();
-    unsafe {
-        SHARED_ARRAY.clear();
-        SHARED_ARRAY.reserve(4 + ret_len);
-        SHARED_ARRAY.push((ret_len & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 8) & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 16) & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 24) & 0xff) as u8);
-        SHARED_ARRAY.extend_from_slice(ret_string.as_bytes());
-        SHARED_ARRAY.as_ptr()
-    }
+    to_shared_array(&ret_string)
 }
 
 #[no_mangle]
 fn enumerate_answers_problem(url: *const u8, len: usize, num_max_answers: usize) -> *const u8 {
     let url = unsafe { std::slice::from_raw_parts(url, len) };
-    let result = decode_and_enumerate(url, num_max_answers);
+    let result = run_with_time_limit(|| decode_and_enumerate(url, num_max_answers));
 
     let ret_string = match result {
-        Ok((common, per_answer)) => {
+        None => String::from(TIMEOUT_RESPONSE),
+        Some(Ok((common, per_answer))) => {
             format!(
                 "{{\"status\":\"ok\",\"description\":{{\"common\":{},\"answers\":[{}]}}}}",
                 common.to_json(),
@@ -109,21 +165,64 @@ fn enumerate_answers_problem(url: *const u8, len: usize, num_max_answers: usize)
                     .join(",")
             )
         }
-        Err(err) => {
+        Some(Err(err)) => {
             // TODO: escape `err` if necessary
             format!("{{\"status\":\"error\",\"description\":\"{}\"}}", err)
         }
     };
 
-    let ret_len = ret_string.len();
-    unsafe {
-        SHARED_ARRAY.clear();
-        SHARED_ARRAY.reserve(4 + ret_len);
-        SHARED_ARRAY.push((ret_len & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 8) & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 16) & 0xff) as u8);
-        SHARED_ARRAY.push(((ret_len >> 24) & 0xff) as u8);
-        SHARED_ARRAY.extend_from_slice(ret_string.as_bytes());
-        SHARED_ARRAY.as_ptr()
-    }
+    to_shared_array(&ret_string)
+}
+
+#[no_mangle]
+fn count_answers_problem(url: *const u8, len: usize, limit: usize) -> *const u8 {
+    let url = unsafe { std::slice::from_raw_parts(url, len) };
+    let result = run_with_time_limit(|| decode_and_count(url, limit));
+
+    let ret_string = match result {
+        None => String::from(TIMEOUT_RESPONSE),
+        Some(Ok(count)) => {
+            let (count, is_exact) = match count {
+                AnswerCount::Exact(n) => (n, true),
+                AnswerCount::AtLeast(n) => (n, false),
+            };
+            format!(
+                "{{\"status\":\"ok\",\"description\":{{\"count\":{},\"isExact\":{}}}}}",
+                count, is_exact
+            )
+        }
+        Some(Err(err)) => {
+            // TODO: escape `err` if necessary
+            format!("{{\"status\":\"error\",\"description\":\"{}\"}}", err)
+        }
+    };
+
+    to_shared_array(&ret_string)
+}
+
+#[no_mangle]
+fn solve_steps_problem(url: *const u8, len: usize) -> *const u8 {
+    let url = unsafe { std::slice::from_raw_parts(url, len) };
+    let result = run_with_time_limit(|| solve_steps(url));
+
+    let ret_string = match result {
+        None => String::from(TIMEOUT_RESPONSE),
+        Some(Ok((base, steps))) => {
+            format!(
+                "{{\"status\":\"ok\",\"description\":{{\"base\":{},\"steps\":[{}]}}}}",
+                base.to_json(),
+                steps
+                    .iter()
+                    .map(|x| x.to_json())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        }
+        Some(Err(err)) => {
+            // TODO: escape `err` if necessary
+            format!("{{\"status\":\"error\",\"description\":\"{}\"}}", err)
+        }
+    };
+
+    to_shared_array(&ret_string)
 }
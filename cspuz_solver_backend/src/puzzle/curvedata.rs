@@ -1,5 +1,6 @@
 use crate::board::{Board, BoardKind, Item, ItemKind};
 use crate::uniqueness::{check_uniqueness, Uniqueness};
+use cspuz_rs::solver::AnswerCount;
 use cspuz_rs_puzzles::puzzles::curvedata;
 
 pub fn solve(url: &str) -> Result<Board, &'static str> {
@@ -109,6 +110,13 @@ pub fn enumerate(url: &str, num_max_answers: usize) -> Result<(Board, Vec<Board>
     Ok((board_common, board_answers))
 }
 
+pub fn count(url: &str, limit: usize) -> Result<AnswerCount, &'static str> {
+    let (piece_id, borders, pieces) = curvedata::deserialize_problem(url).ok_or("invalid url")?;
+    Ok(curvedata::count_answers_curvedata(
+        &piece_id, &borders, &pieces, limit,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::solve;
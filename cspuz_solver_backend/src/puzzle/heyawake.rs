@@ -9,6 +9,10 @@ pub fn enumerate(
     crate::puzzle::heyawake_internal::enumerate_answers_heyawake(url, num_max_answers)
 }
 
+pub fn count(url: &str, limit: usize) -> Result<cspuz_rs::solver::AnswerCount, &'static str> {
+    crate::puzzle::heyawake_internal::count_answers_heyawake(url, limit)
+}
+
 #[cfg(test)]
 mod tests {
     use super::solve;
@@ -1,6 +1,7 @@
 use crate::board::{Board, BoardKind, Item, ItemKind};
 use crate::uniqueness::{check_uniqueness, Uniqueness};
 use cspuz_rs::graph;
+use cspuz_rs::solver::AnswerCount;
 use cspuz_rs_puzzles::puzzles::{ayeheya, heyawake};
 
 pub fn solve(url: &str, is_ayeheya: bool) -> Result<Board, &'static str> {
@@ -60,6 +61,11 @@ pub fn solve(url: &str, is_ayeheya: bool) -> Result<Board, &'static str> {
     Ok(board)
 }
 
+pub fn count_answers_heyawake(url: &str, limit: usize) -> Result<AnswerCount, &'static str> {
+    let (borders, clues) = heyawake::deserialize_problem(url).ok_or("invalid url")?;
+    Ok(heyawake::count_answers_heyawake(&borders, &clues, limit))
+}
+
 pub fn enumerate_answers_heyawake(
     url: &str,
     num_max_answers: usize,
@@ -1,4 +1,5 @@
 use crate::board::Board;
+use cspuz_rs::solver::AnswerCount;
 
 macro_rules! dispatch_enumerate {
     ( $mod:ident, $aliases:expr, $puzzle_kind:expr, $url:expr, $num_max_answers:expr ) => {};
@@ -11,6 +12,17 @@ macro_rules! dispatch_enumerate {
     };
 }
 
+macro_rules! dispatch_count {
+    ( $mod:ident, $aliases:expr, $puzzle_kind:expr, $url:expr, $limit:expr ) => {};
+    ( $mod:ident, $aliases:expr, $puzzle_kind:expr, $url:expr, $limit:expr, $enumerate:ident ) => {
+        for alias in $aliases {
+            if $puzzle_kind == alias {
+                return Some(super::$mod::count($url, $limit));
+            }
+        }
+    };
+}
+
 macro_rules! dispatch_enumerate_list {
     ( $ret:expr, $en_name:expr, $ja_name:expr ) => {};
     ( $ret:expr, $en_name:expr, $ja_name:expr, $enumerable:ident) => {
@@ -53,6 +65,22 @@ macro_rules! puzzle_list {
                 None
             }
 
+            #[allow(unused)]
+            pub fn dispatch_count(
+                #[allow(unused)]
+                puzzle_kind: &str,
+                #[allow(unused)]
+                url: &str,
+                #[allow(unused)]
+                limit: usize,
+            ) -> Option<Result<cspuz_rs::solver::AnswerCount, &'static str>> {
+                $(
+                    dispatch_count!($mod, $aliases, puzzle_kind, url, limit $(, $enumerable)?);
+                )*
+
+                None
+            }
+
             pub fn list_puzzles() -> Vec<(String, String)> {
                 vec![
                     $(
@@ -342,6 +370,25 @@ pub fn dispatch_puzz_link_enumerate(
     puzz_link::dispatch_enumerate(puzzle_kind, url, num_max_answers)
 }
 
+pub fn dispatch_puzz_link_count(
+    puzzle_kind: &str,
+    url: &str,
+    limit: usize,
+) -> Option<Result<AnswerCount, &'static str>> {
+    puzz_link::dispatch_count(puzzle_kind, url, limit)
+}
+
+pub fn dispatch_puzz_link_steps(
+    puzzle_kind: &str,
+    url: &str,
+) -> Option<Result<(Board, Vec<Board>), &'static str>> {
+    match puzzle_kind {
+        "nurikabe" => Some(nurikabe::solve_steps(url)),
+        "sudoku" => Some(sudoku::solve_steps(url)),
+        _ => None,
+    }
+}
+
 pub fn dispatch_kudamono(
     puzzle_kind: &str,
     puzzle_variant: &str,
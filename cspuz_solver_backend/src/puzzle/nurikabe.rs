@@ -1,5 +1,6 @@
 use crate::board::{Board, BoardKind, Item, ItemKind};
 use crate::uniqueness::{check_uniqueness, Uniqueness};
+use cspuz_rs::solver::AnswerCount;
 use cspuz_rs_puzzles::puzzles::nurikabe;
 
 pub fn solve(url: &str) -> Result<Board, &'static str> {
@@ -9,13 +10,23 @@ pub fn solve(url: &str) -> Result<Board, &'static str> {
     let height = problem.len();
     let width = problem[0].len();
     let mut board = Board::new(BoardKind::Grid, height, width, check_uniqueness(&ans));
+    let conflicting = if ans.is_none() {
+        nurikabe::find_conflicting_clues_nurikabe(&problem).unwrap_or_default()
+    } else {
+        vec![]
+    };
     for y in 0..height {
         for x in 0..width {
             if let Some(clue) = problem[y][x] {
+                let color = if conflicting.contains(&(y, x)) {
+                    "red"
+                } else {
+                    "black"
+                };
                 if clue > 0 {
-                    board.push(Item::cell(y, x, "black", ItemKind::Num(clue)));
+                    board.push(Item::cell(y, x, color, ItemKind::Num(clue)));
                 } else {
-                    board.push(Item::cell(y, x, "black", ItemKind::Text("?")));
+                    board.push(Item::cell(y, x, color, ItemKind::Text("?")));
                 }
             } else if let Some(ans) = &ans {
                 if let Some(a) = ans[y][x] {
@@ -93,9 +104,60 @@ pub fn enumerate(url: &str, num_max_answers: usize) -> Result<(Board, Vec<Board>
     Ok((board_common, boards))
 }
 
+pub fn solve_steps(url: &str) -> Result<(Board, Vec<Board>), &'static str> {
+    let problem = nurikabe::deserialize_problem(url).ok_or("invalid url")?;
+    let steps = nurikabe::solve_steps_nurikabe(&problem).ok_or("no answer")?;
+
+    let height = problem.len();
+    let width = problem[0].len();
+    let clue_item = |y: usize, x: usize, color: &'static str| {
+        let clue = problem[y][x].unwrap();
+        if clue > 0 {
+            Item::cell(y, x, color, ItemKind::Num(clue))
+        } else {
+            Item::cell(y, x, color, ItemKind::Text("?"))
+        }
+    };
+
+    let mut board_base = Board::new(BoardKind::Grid, height, width, Uniqueness::NotApplicable);
+    for y in 0..height {
+        for x in 0..width {
+            if problem[y][x].is_some() {
+                board_base.push(clue_item(y, x, "black"));
+            }
+        }
+    }
+
+    let mut boards = vec![];
+    for ((y, x), is_black, reasons) in steps {
+        let mut board_step = Board::new(BoardKind::Empty, height, width, Uniqueness::NotApplicable);
+        board_step.push(Item::cell(
+            y,
+            x,
+            "green",
+            if is_black {
+                ItemKind::Block
+            } else {
+                ItemKind::Dot
+            },
+        ));
+        for (ry, rx) in reasons {
+            board_step.push(clue_item(ry, rx, "blue"));
+        }
+        boards.push(board_step);
+    }
+
+    Ok((board_base, boards))
+}
+
+pub fn count(url: &str, limit: usize) -> Result<AnswerCount, &'static str> {
+    let problem = nurikabe::deserialize_problem(url).ok_or("invalid url")?;
+    Ok(nurikabe::count_answers_nurikabe(&problem, limit))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::solve;
+    use super::{count, solve, solve_steps};
     use crate::board::*;
     use crate::compare_board_and_check_no_solution_case;
     use crate::uniqueness::Uniqueness;
@@ -136,4 +198,23 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(
+            count("https://puzz.link/p?nurikabe/6/6/m8n8i9u", 2),
+            Ok(cspuz_rs::solver::AnswerCount::AtLeast(2))
+        );
+    }
+
+    #[test]
+    fn test_solve_steps() {
+        let (base, steps) = solve_steps("https://puzz.link/p?nurikabe/6/6/m8n8i9u").unwrap();
+        assert_eq!(base.data.len(), 3);
+        assert_eq!(steps.len(), 19);
+        for step in &steps {
+            assert_eq!(step.data[0].color, "green");
+            assert!(step.data[1..].iter().all(|item| item.color == "blue"));
+        }
+    }
 }
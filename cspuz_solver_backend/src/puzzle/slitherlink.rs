@@ -1,5 +1,6 @@
 use crate::board::{Board, BoardKind, Item, ItemKind};
 use crate::uniqueness::{check_uniqueness, Uniqueness};
+use cspuz_rs::solver::AnswerCount;
 use cspuz_rs_puzzles::puzzles::slitherlink;
 
 pub fn solve(url: &str) -> Result<Board, &'static str> {
@@ -137,6 +138,13 @@ pub fn enumerate(url: &str, num_max_answers: usize) -> Result<(Board, Vec<Board>
     Ok((board_common, board_answers))
 }
 
+pub fn count(url: &str, limit: usize) -> Result<AnswerCount, &'static str> {
+    let (full, problem) = slitherlink::deserialize_problem(url).ok_or("invalid url")?;
+    Ok(slitherlink::count_answers_slitherlink(
+        full, &problem, limit,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::solve;
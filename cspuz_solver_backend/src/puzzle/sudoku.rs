@@ -59,10 +59,16 @@ pub fn solve(url: &str) -> Result<Board, &'static str> {
             }
         }
     } else {
+        let conflicting = sudoku::find_conflicting_clues_sudoku(&problem).unwrap_or_default();
         for y in 0..height {
             for x in 0..width {
                 if let Some(n) = problem[y][x] {
-                    board.push(Item::cell(y, x, "black", ItemKind::Num(n)));
+                    let color = if conflicting.contains(&(y, x)) {
+                        "red"
+                    } else {
+                        "black"
+                    };
+                    board.push(Item::cell(y, x, color, ItemKind::Num(n)));
                 }
             }
         }
@@ -91,12 +97,46 @@ pub fn solve(url: &str) -> Result<Board, &'static str> {
     Ok(board)
 }
 
+pub fn solve_steps(url: &str) -> Result<(Board, Vec<Board>), &'static str> {
+    let problem = sudoku::deserialize_problem(url).ok_or("invalid url")?;
+    let steps = sudoku::solve_steps_sudoku(&problem).ok_or("no answer")?;
+
+    let height = problem.len();
+    let width = problem[0].len();
+
+    let mut board_base = Board::new(BoardKind::Grid, height, width, Uniqueness::NotApplicable);
+    for y in 0..height {
+        for x in 0..width {
+            if let Some(n) = problem[y][x] {
+                board_base.push(Item::cell(y, x, "black", ItemKind::Num(n)));
+            }
+        }
+    }
+
+    let mut boards = vec![];
+    for ((y, x), n, reasons) in steps {
+        let mut board_step = Board::new(BoardKind::Empty, height, width, Uniqueness::NotApplicable);
+        board_step.push(Item::cell(y, x, "green", ItemKind::Num(n)));
+        for (ry, rx) in reasons {
+            board_step.push(Item::cell(
+                ry,
+                rx,
+                "blue",
+                ItemKind::Num(problem[ry][rx].unwrap()),
+            ));
+        }
+        boards.push(board_step);
+    }
+
+    Ok((board_base, boards))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::solve;
+    use super::{solve, solve_steps};
     use crate::board::*;
-    use crate::compare_board_and_check_no_solution_case;
     use crate::uniqueness::Uniqueness;
+    use crate::{compare_board, compare_board_and_check_no_solution_case};
 
     #[test]
     #[rustfmt::skip]
@@ -248,4 +288,47 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_solve_conflicting_clues() {
+        compare_board!(
+            solve("https://puzz.link/p?sudoku/4/4/11s2"),
+            Board {
+                kind: BoardKind::Grid,
+                height: 4,
+                width: 4,
+                data: vec![
+                    Item { y: 1, x: 1, color: "red", kind: ItemKind::Num(1) },
+                    Item { y: 1, x: 3, color: "red", kind: ItemKind::Num(1) },
+                    Item { y: 7, x: 7, color: "black", kind: ItemKind::Num(2) },
+                    Item { y: 1, x: 0, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 3, x: 0, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 5, x: 0, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 7, x: 0, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 1, x: 4, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 3, x: 4, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 5, x: 4, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 7, x: 4, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 0, x: 1, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 0, x: 3, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 0, x: 5, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 0, x: 7, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 4, x: 1, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 4, x: 3, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 4, x: 5, color: "black", kind: ItemKind::BoldWall },
+                    Item { y: 4, x: 7, color: "black", kind: ItemKind::BoldWall },
+                ],
+                uniqueness: Uniqueness::NoAnswer,
+            },
+        );
+    }
+
+    #[test]
+    fn test_solve_steps() {
+        let (base, steps) = solve_steps("https://puzz.link/p?sudoku/4/4/1h4o32g").unwrap();
+        assert_eq!(base.data.len(), 4);
+        assert_eq!(steps.len(), 12);
+        assert_eq!(steps[0].data[0].color, "green");
+    }
 }